@@ -1292,6 +1292,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(&model, cx),
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
@@ -1458,6 +1459,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(model, cx),
+            max_output_tokens: None,
             thinking_allowed: false,
         };
 
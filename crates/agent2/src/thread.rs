@@ -1869,6 +1869,7 @@ impl Thread {
             tool_choice: None,
             stop: Vec::new(),
             temperature: AgentSettings::temperature_for_model(model, cx),
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
@@ -25,6 +25,7 @@ use crate::{
     ToggleOptionsMenu,
     acp::AcpThreadView,
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
+    context_server_requests::ContextServerRequests,
     slash_command::SlashCommandCompletionProvider,
     text_thread_editor::{AgentPanelDelegate, TextThreadEditor, make_lsp_adapter_delegate},
     ui::{AgentOnboardingModal, EndTrialUpsell},
@@ -433,6 +434,7 @@ pub struct AgentPanel {
     zoomed: bool,
     pending_serialization: Option<Task<Result<()>>>,
     onboarding: Entity<AgentPanelOnboarding>,
+    context_server_requests: Entity<ContextServerRequests>,
     selected_agent: AgentType,
 }
 
@@ -661,6 +663,10 @@ impl AgentPanel {
             )
         });
 
+        let context_server_requests = cx.new(|cx| {
+            ContextServerRequests::new(project.read(cx).context_server_store(), window, cx)
+        });
+
         Self {
             active_view,
             workspace,
@@ -684,6 +690,7 @@ impl AgentPanel {
             zoomed: false,
             pending_serialization: None,
             onboarding,
+            context_server_requests,
             acp_history,
             history_store,
             selected_agent: AgentType::default(),
@@ -2469,6 +2476,7 @@ impl Render for AgentPanel {
             }))
             .child(self.render_toolbar(window, cx))
             .children(self.render_onboarding(window, cx))
+            .child(self.context_server_requests.clone())
             .map(|parent| match &self.active_view {
                 ActiveView::ExternalAgentThread { thread_view, .. } => parent
                     .child(thread_view.clone())
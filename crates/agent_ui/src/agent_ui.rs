@@ -6,6 +6,7 @@ mod agent_panel;
 mod buffer_codegen;
 mod context_picker;
 mod context_server_configuration;
+mod context_server_requests;
 mod context_strip;
 mod inline_assistant;
 mod inline_prompt_editor;
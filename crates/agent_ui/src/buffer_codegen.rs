@@ -475,6 +475,7 @@ impl CodegenAlternative {
                 stop: Vec::new(),
                 temperature,
                 messages: vec![request_message],
+                max_output_tokens: None,
                 thinking_allowed: false,
             }
         }))
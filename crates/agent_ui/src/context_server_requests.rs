@@ -0,0 +1,701 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use context_server::types::{self, ElicitAction, ElicitResult, ElicitationSchema, PrimitiveSchema};
+use futures::StreamExt as _;
+use gpui::{Entity, Subscription, Task};
+use language_model::{
+    ConfiguredModel, LanguageModelCompletionEvent, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage, Role, StopReason,
+};
+use project::context_server_store::{
+    ContextServerRequest, ContextServerRequestId, ContextServerRequestKind, ContextServerStore,
+};
+use serde_json::Value;
+use ui::{Checkbox, ToggleState, prelude::*};
+use ui_input::SingleLineInput;
+
+/// Shows the requests context servers sent to Zed, asking the user to approve
+/// the use of the language model, or to fill in the form the server needs.
+pub struct ContextServerRequests {
+    context_server_store: Entity<ContextServerStore>,
+    forms: HashMap<ContextServerRequestId, ElicitationForm>,
+    sampling_tasks: HashMap<ContextServerRequestId, Task<()>>,
+    _subscription: Subscription,
+}
+
+impl ContextServerRequests {
+    pub fn new(
+        context_server_store: Entity<ContextServerStore>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.observe_in(&context_server_store, window, |this, _, window, cx| {
+            this.sync_requests(window, cx);
+        });
+        let mut this = Self {
+            context_server_store,
+            forms: HashMap::default(),
+            sampling_tasks: HashMap::default(),
+            _subscription: subscription,
+        };
+        this.sync_requests(window, cx);
+        this
+    }
+
+    fn sync_requests(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let requests = self
+            .context_server_store
+            .read(cx)
+            .pending_requests()
+            .cloned()
+            .collect::<Vec<_>>();
+        let is_pending = |id: &ContextServerRequestId| requests.iter().any(|r| &r.id == id);
+        self.forms.retain(|id, _| is_pending(id));
+        self.sampling_tasks.retain(|id, _| is_pending(id));
+        for request in &requests {
+            if let ContextServerRequestKind::Elicitation(params) = request.kind.as_ref()
+                && !self.forms.contains_key(&request.id)
+            {
+                let form = ElicitationForm::new(&params.requested_schema, window, cx);
+                self.forms.insert(request.id, form);
+            }
+        }
+        cx.notify();
+    }
+
+    fn approve_sampling(&mut self, request: &ContextServerRequest, cx: &mut Context<Self>) {
+        let ContextServerRequestKind::Sampling(params) = request.kind.as_ref() else {
+            return;
+        };
+        let Some(ConfiguredModel { model, .. }) =
+            LanguageModelRegistry::read_global(cx).default_model()
+        else {
+            self.context_server_store.update(cx, |store, cx| {
+                store.respond_to_sampling(
+                    request.id,
+                    Err(anyhow!("no language model is configured")),
+                    cx,
+                )
+            });
+            return;
+        };
+
+        let completion_request = match sampling_completion_request(params) {
+            Ok(completion_request) => completion_request,
+            Err(error) => {
+                self.context_server_store.update(cx, |store, cx| {
+                    store.respond_to_sampling(request.id, Err(error), cx)
+                });
+                return;
+            }
+        };
+        let request_id = request.id;
+        let context_server_store = self.context_server_store.clone();
+        let task = cx.spawn(async move |_, cx| {
+            let result = async {
+                let mut events = model.stream_completion(completion_request, cx).await?;
+                let mut text = String::new();
+                let mut stop_reason = None;
+                while let Some(event) = events.next().await {
+                    match event? {
+                        LanguageModelCompletionEvent::Text(chunk) => text.push_str(&chunk),
+                        LanguageModelCompletionEvent::Stop(reason) => {
+                            stop_reason = Some(sampling_stop_reason(reason).to_string());
+                        }
+                        _ => {}
+                    }
+                }
+                anyhow::Ok(types::CreateMessageResult {
+                    role: types::Role::Assistant,
+                    content: types::MessageContent::Text {
+                        text,
+                        annotations: None,
+                    },
+                    model: model.id().0.to_string(),
+                    stop_reason,
+                })
+            }
+            .await;
+            context_server_store
+                .update(cx, |store, cx| {
+                    store.respond_to_sampling(request_id, result, cx)
+                })
+                .ok();
+        });
+        self.sampling_tasks.insert(request.id, task);
+        cx.notify();
+    }
+
+    fn deny_sampling(&mut self, request_id: ContextServerRequestId, cx: &mut Context<Self>) {
+        self.context_server_store.update(cx, |store, cx| {
+            store.respond_to_sampling(request_id, Err(anyhow!("the user denied the request")), cx)
+        });
+    }
+
+    fn submit_elicitation(&mut self, request_id: ContextServerRequestId, cx: &mut Context<Self>) {
+        let Some(form) = self.forms.get_mut(&request_id) else {
+            return;
+        };
+        match form.content(cx) {
+            Ok(content) => self.respond_to_elicitation(
+                request_id,
+                ElicitResult {
+                    action: ElicitAction::Accept,
+                    content: Some(content),
+                },
+                cx,
+            ),
+            Err(error) => {
+                form.error = Some(error.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn respond_to_elicitation(
+        &mut self,
+        request_id: ContextServerRequestId,
+        result: ElicitResult,
+        cx: &mut Context<Self>,
+    ) {
+        self.context_server_store.update(cx, |store, cx| {
+            store.respond_to_elicitation(request_id, result, cx)
+        });
+    }
+
+    fn render_sampling_request(
+        &self,
+        ix: usize,
+        request: &ContextServerRequest,
+        params: &types::CreateMessageRequest,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let is_generating = self.sampling_tasks.contains_key(&request.id);
+        let prompt = params
+            .messages
+            .iter()
+            .rev()
+            .find_map(|message| sampling_message_text(&message.content))
+            .unwrap_or_default()
+            .to_string();
+
+        request_container(cx)
+            .child(
+                Label::new(format!(
+                    "{} wants to prompt the language model",
+                    request.server_id
+                ))
+                .size(LabelSize::Small),
+            )
+            .when(!prompt.is_empty(), |this| {
+                this.child(
+                    Label::new(prompt)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .truncate(),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .when(is_generating, |this| {
+                        this.child(
+                            Label::new("Generating…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        Button::new(("deny-sampling", ix), "Deny")
+                            .label_size(LabelSize::Small)
+                            .on_click({
+                                let request_id = request.id;
+                                cx.listener(move |this, _, _, cx| {
+                                    this.deny_sampling(request_id, cx)
+                                })
+                            }),
+                    )
+                    .child(
+                        Button::new(("allow-sampling", ix), "Allow")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(is_generating)
+                            .on_click({
+                                let request = request.clone();
+                                cx.listener(move |this, _, _, cx| {
+                                    this.approve_sampling(&request, cx)
+                                })
+                            }),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn render_elicitation_request(
+        &self,
+        ix: usize,
+        request: &ContextServerRequest,
+        params: &types::ElicitParams,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(form) = self.forms.get(&request.id) else {
+            return Empty.into_any_element();
+        };
+        let request_id = request.id;
+
+        request_container(cx)
+            .child(
+                Label::new(format!("{} asks for input", request.server_id))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(params.message.clone()).size(LabelSize::Small))
+            .children(
+                form.fields.iter().enumerate().map(|(field_ix, field)| {
+                    self.render_field(ix, request_id, field_ix, field, cx)
+                }),
+            )
+            .when_some(form.error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .child(
+                        Button::new(("cancel-elicitation", ix), "Cancel")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.respond_to_elicitation(
+                                    request_id,
+                                    ElicitResult {
+                                        action: ElicitAction::Cancel,
+                                        content: None,
+                                    },
+                                    cx,
+                                )
+                            })),
+                    )
+                    .child(
+                        Button::new(("decline-elicitation", ix), "Decline")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.respond_to_elicitation(
+                                    request_id,
+                                    ElicitResult {
+                                        action: ElicitAction::Decline,
+                                        content: None,
+                                    },
+                                    cx,
+                                )
+                            })),
+                    )
+                    .child(
+                        Button::new(("submit-elicitation", ix), "Submit")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.submit_elicitation(request_id, cx)
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn render_field(
+        &self,
+        ix: usize,
+        request_id: ContextServerRequestId,
+        field_ix: usize,
+        field: &ElicitationField,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let id = format!("elicitation-{ix}-{field_ix}");
+        let description = field.description.clone().map(|description| {
+            Label::new(description)
+                .size(LabelSize::XSmall)
+                .color(Color::Muted)
+        });
+        match &field.input {
+            FieldInput::Text { input, .. } => v_flex()
+                .gap_0p5()
+                .child(input.clone())
+                .children(description)
+                .into_any_element(),
+            FieldInput::Choice { options, selected } => v_flex()
+                .gap_0p5()
+                .child(Label::new(field.label.clone()).size(LabelSize::Small))
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .flex_wrap()
+                        .children(options.iter().enumerate().map(|(option_ix, (_, title))| {
+                            Button::new(
+                                SharedString::from(format!("{id}-{option_ix}")),
+                                title.clone(),
+                            )
+                            .label_size(LabelSize::Small)
+                            .toggle_state(*selected == Some(option_ix))
+                            .on_click(cx.listener(
+                                move |this, _, _, cx| {
+                                    if let Some(form) = this.forms.get_mut(&request_id)
+                                        && let Some(FieldInput::Choice { selected, .. }) = form
+                                            .fields
+                                            .get_mut(field_ix)
+                                            .map(|field| &mut field.input)
+                                    {
+                                        *selected = Some(option_ix);
+                                        cx.notify();
+                                    }
+                                },
+                            ))
+                        })),
+                )
+                .children(description)
+                .into_any_element(),
+            FieldInput::Toggle(state) => v_flex()
+                .gap_0p5()
+                .child(
+                    Checkbox::new(SharedString::from(id), *state)
+                        .label(field.label.clone())
+                        .on_click(cx.listener(move |this, state: &ToggleState, _, cx| {
+                            if let Some(form) = this.forms.get_mut(&request_id)
+                                && let Some(field) = form.fields.get_mut(field_ix)
+                            {
+                                field.input = FieldInput::Toggle(*state);
+                                cx.notify();
+                            }
+                        })),
+                )
+                .children(description)
+                .into_any_element(),
+        }
+    }
+}
+
+impl Render for ContextServerRequests {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let requests = self
+            .context_server_store
+            .read(cx)
+            .pending_requests()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex().children(requests.iter().enumerate().map(|(ix, request)| {
+            match request.kind.as_ref() {
+                ContextServerRequestKind::Sampling(params) => {
+                    self.render_sampling_request(ix, request, params, cx)
+                }
+                ContextServerRequestKind::Elicitation(params) => {
+                    self.render_elicitation_request(ix, request, params, cx)
+                }
+            }
+        }))
+    }
+}
+
+fn request_container(cx: &App) -> Div {
+    v_flex()
+        .p_2()
+        .gap_1p5()
+        .border_b_1()
+        .border_color(cx.theme().colors().border)
+        .bg(cx.theme().colors().editor_background)
+}
+
+/// The input of the user for the properties of an elicitation schema, in their order.
+struct ElicitationForm {
+    fields: Vec<ElicitationField>,
+    error: Option<SharedString>,
+}
+
+struct ElicitationField {
+    name: String,
+    label: SharedString,
+    description: Option<SharedString>,
+    required: bool,
+    input: FieldInput,
+}
+
+enum FieldInput {
+    /// The input of string, number and integer properties.
+    Text {
+        schema: PrimitiveSchema,
+        input: Entity<SingleLineInput>,
+    },
+    /// The options of enum properties, with their titles.
+    Choice {
+        options: Vec<(String, SharedString)>,
+        selected: Option<usize>,
+    },
+    Toggle(ToggleState),
+}
+
+impl ElicitationForm {
+    fn new(schema: &ElicitationSchema, window: &mut Window, cx: &mut App) -> Self {
+        let fields = schema
+            .properties
+            .iter()
+            .map(|(name, property)| {
+                let label: SharedString =
+                    property.title().unwrap_or(name.as_str()).to_string().into();
+                let input = match property {
+                    PrimitiveSchema::String {
+                        enum_values: Some(values),
+                        enum_names,
+                        ..
+                    } => FieldInput::Choice {
+                        options: values
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, value)| {
+                                let title = enum_names
+                                    .as_ref()
+                                    .and_then(|names| names.get(ix))
+                                    .unwrap_or(value);
+                                (value.clone(), title.clone().into())
+                            })
+                            .collect(),
+                        selected: None,
+                    },
+                    PrimitiveSchema::Boolean { default, .. } => {
+                        FieldInput::Toggle(default.unwrap_or(false).into())
+                    }
+                    PrimitiveSchema::String { .. }
+                    | PrimitiveSchema::Number { .. }
+                    | PrimitiveSchema::Integer { .. } => FieldInput::Text {
+                        schema: property.clone(),
+                        input: cx
+                            .new(|cx| SingleLineInput::new(window, cx, "").label(label.clone())),
+                    },
+                };
+                ElicitationField {
+                    name: name.clone(),
+                    label,
+                    description: property
+                        .description()
+                        .map(|description| description.to_string().into()),
+                    required: schema.required.contains(name),
+                    input,
+                }
+            })
+            .collect();
+        Self {
+            fields,
+            error: None,
+        }
+    }
+
+    /// Validates the input against the schema, returning the content to send to the server.
+    fn content(&self, cx: &App) -> Result<serde_json::Map<String, Value>> {
+        let mut content = serde_json::Map::new();
+        for field in &self.fields {
+            let value = match &field.input {
+                FieldInput::Text { schema, input } => {
+                    let text = input.read(cx).text(cx);
+                    let text = text.trim();
+                    if text.is_empty() {
+                        None
+                    } else {
+                        Some(text_field_value(&field.label, schema, text)?)
+                    }
+                }
+                FieldInput::Choice { options, selected } => selected
+                    .and_then(|selected| options.get(selected))
+                    .map(|(value, _)| Value::String(value.clone())),
+                FieldInput::Toggle(state) => Some(Value::Bool(state.selected())),
+            };
+            match value {
+                Some(value) => {
+                    content.insert(field.name.clone(), value);
+                }
+                None if field.required => anyhow::bail!("{} is required", field.label),
+                None => {}
+            }
+        }
+        Ok(content)
+    }
+}
+
+fn text_field_value(label: &str, schema: &PrimitiveSchema, text: &str) -> Result<Value> {
+    match schema {
+        PrimitiveSchema::String {
+            min_length,
+            max_length,
+            ..
+        } => {
+            let len = text.chars().count() as u32;
+            anyhow::ensure!(
+                min_length.is_none_or(|min_length| len >= min_length),
+                "{label} must be at least {} characters long",
+                min_length.unwrap_or_default()
+            );
+            anyhow::ensure!(
+                max_length.is_none_or(|max_length| len <= max_length),
+                "{label} must be at most {} characters long",
+                max_length.unwrap_or_default()
+            );
+            Ok(Value::String(text.to_string()))
+        }
+        PrimitiveSchema::Number {
+            minimum, maximum, ..
+        } => {
+            let number = text
+                .parse::<f64>()
+                .with_context(|| format!("{label} must be a number"))?;
+            check_range(label, number, *minimum, *maximum)?;
+            Ok(serde_json::json!(number))
+        }
+        PrimitiveSchema::Integer {
+            minimum, maximum, ..
+        } => {
+            let number = text
+                .parse::<i64>()
+                .with_context(|| format!("{label} must be an integer"))?;
+            check_range(label, number as f64, *minimum, *maximum)?;
+            Ok(Value::from(number))
+        }
+        PrimitiveSchema::Boolean { .. } => Ok(Value::Bool(text == "true")),
+    }
+}
+
+fn check_range(label: &str, number: f64, minimum: Option<f64>, maximum: Option<f64>) -> Result<()> {
+    if let Some(minimum) = minimum {
+        anyhow::ensure!(number >= minimum, "{label} must be at least {minimum}");
+    }
+    if let Some(maximum) = maximum {
+        anyhow::ensure!(number <= maximum, "{label} must be at most {maximum}");
+    }
+    Ok(())
+}
+
+fn sampling_message_text(content: &types::MessageContent) -> Option<&str> {
+    match content {
+        types::MessageContent::Text { text, .. } => Some(text),
+        types::MessageContent::Image { .. }
+        | types::MessageContent::Audio { .. }
+        | types::MessageContent::Resource { .. } => None,
+    }
+}
+
+/// Builds the completion request of a sampling request, failing if one of its messages isn't text.
+fn sampling_completion_request(
+    params: &types::CreateMessageRequest,
+) -> Result<LanguageModelRequest> {
+    let system_message =
+        params
+            .system_prompt
+            .as_ref()
+            .map(|system_prompt| LanguageModelRequestMessage {
+                role: Role::System,
+                content: vec![system_prompt.clone().into()],
+                cache: false,
+            });
+    let messages = params
+        .messages
+        .iter()
+        .map(|message| {
+            let text = sampling_message_text(&message.content)
+                .context("only text messages can be sent to the language model")?;
+            anyhow::Ok(LanguageModelRequestMessage {
+                role: match message.role {
+                    types::Role::User => Role::User,
+                    types::Role::Assistant => Role::Assistant,
+                },
+                content: vec![text.to_string().into()],
+                cache: false,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(LanguageModelRequest {
+        messages: system_message.into_iter().chain(messages).collect(),
+        stop: params.stop_sequences.clone().unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        max_output_tokens: Some(params.max_tokens.into()),
+        ..LanguageModelRequest::default()
+    })
+}
+
+/// The name the Model Context Protocol gives to the reason the model stopped.
+fn sampling_stop_reason(stop_reason: StopReason) -> &'static str {
+    match stop_reason {
+        StopReason::EndTurn => "endTurn",
+        StopReason::MaxTokens => "maxTokens",
+        StopReason::ToolUse => "toolUse",
+        StopReason::Refusal => "refusal",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_completion_request() {
+        let params = serde_json::from_value::<types::CreateMessageRequest>(serde_json::json!({
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "Summarize this"}},
+            ],
+            "systemPrompt": "Be brief",
+            "temperature": 0.5,
+            "maxTokens": 100,
+        }))
+        .unwrap();
+        let request = sampling_completion_request(&params).unwrap();
+        assert_eq!(
+            request
+                .messages
+                .iter()
+                .map(|message| (message.role, message.string_contents()))
+                .collect::<Vec<_>>(),
+            [
+                (Role::System, "Be brief".to_string()),
+                (Role::User, "Summarize this".to_string()),
+            ]
+        );
+        assert_eq!(request.temperature, Some(0.5));
+        assert_eq!(request.max_output_tokens, Some(100));
+
+        let params = serde_json::from_value::<types::CreateMessageRequest>(serde_json::json!({
+            "messages": [
+                {"role": "user", "content": {"type": "image", "data": "", "mimeType": "image/png"}},
+            ],
+            "maxTokens": 100,
+        }))
+        .unwrap();
+        assert!(sampling_completion_request(&params).is_err());
+    }
+
+    #[test]
+    fn test_text_field_value() {
+        let integer = PrimitiveSchema::Integer {
+            title: None,
+            description: None,
+            minimum: Some(1.),
+            maximum: Some(10.),
+        };
+        assert_eq!(
+            text_field_value("Count", &integer, "3").unwrap(),
+            Value::from(3)
+        );
+        assert!(text_field_value("Count", &integer, "11").is_err());
+        assert!(text_field_value("Count", &integer, "three").is_err());
+
+        let string = PrimitiveSchema::String {
+            title: None,
+            description: None,
+            min_length: Some(2),
+            max_length: None,
+            format: None,
+            enum_values: None,
+            enum_names: None,
+        };
+        assert_eq!(
+            text_field_value("Name", &string, "Zed").unwrap(),
+            Value::from("Zed")
+        );
+        assert!(text_field_value("Name", &string, "Z").is_err());
+    }
+}
@@ -2300,6 +2300,7 @@ impl AssistantContext {
             tool_choice: None,
             stop: Vec::new(),
             temperature: model.and_then(|model| AgentSettings::temperature_for_model(model, cx)),
+            max_output_tokens: None,
             thinking_allowed: true,
         };
         for message in self.messages(cx) {
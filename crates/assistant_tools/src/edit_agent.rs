@@ -728,6 +728,7 @@ impl EditAgent {
             tools,
             stop: Vec::new(),
             temperature: None,
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
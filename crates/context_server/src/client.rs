@@ -20,7 +20,7 @@ use std::{
 use util::{ResultExt, TryFutureExt};
 
 use crate::{
-    transport::{StdioTransport, Transport},
+    transport::{HttpEndpoint, HttpTransport, StdioTransport, Transport},
     types::{CancelledParams, ClientNotification, Notification as _, notifications::Cancelled},
 };
 
@@ -51,6 +51,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -100,7 +101,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -113,7 +113,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -180,6 +179,30 @@ impl Client {
         Self::new(server_id, server_name.into(), transport, timeout, cx)
     }
 
+    /// Creates a new Client instance for a remote context server, reached over HTTP.
+    pub fn http(
+        server_id: ContextServerId,
+        endpoint: &settings::ContextServerEndpoint,
+        http_client: Arc<dyn http_client::HttpClient>,
+        cx: AsyncApp,
+    ) -> Result<Self> {
+        log::debug!("connecting to context server (url={:?})", endpoint.url);
+
+        let timeout = endpoint.timeout.map(Duration::from_millis);
+        let http_endpoint = HttpEndpoint::new(endpoint)?;
+        let server_name = http_endpoint
+            .url
+            .host_str()
+            .unwrap_or(http_endpoint.url.as_str())
+            .to_string();
+        let transport = Arc::new(HttpTransport::new(
+            http_client,
+            http_endpoint,
+            cx.background_executor().clone(),
+        ));
+        Self::new(server_id, server_name.into(), transport, timeout, cx)
+    }
+
     /// Creates a new Client instance for a context server.
     pub fn new(
         server_id: ContextServerId,
@@ -202,12 +225,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     notification_handlers,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -237,6 +262,7 @@ impl Client {
         Ok(Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -260,6 +286,7 @@ impl Client {
         notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -274,6 +301,18 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    log::debug!("unhandled request from context server: {}", request.method);
+                    let response = serde_json::to_string(&Response::<()> {
+                        jsonrpc: JSON_RPC_VERSION,
+                        id: request.id,
+                        value: CspResult::Error(Some(Error {
+                            message: format!("method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        })),
+                    })
+                    .unwrap();
+                    outbound_tx.try_send(response).log_err();
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -458,6 +497,45 @@ impl Client {
     ) {
         self.notification_handlers.lock().insert(method, f);
     }
+
+    /// Registers a handler for the requests the context server sends to the client.
+    /// Requests are answered with the outcome of the task the handler returns.
+    pub fn on_request(
+        &self,
+        method: &'static str,
+        mut f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = serde_json::from_str(params.get()).unwrap_or(Value::Null);
+                let result = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.background_spawn(async move {
+                    let value = match result.await {
+                        Ok(result) => CspResult::Ok(Some(result)),
+                        Err(error) => CspResult::Error(Some(Error {
+                            message: format!("{error:#}"),
+                            code: INTERNAL_ERROR,
+                        })),
+                    };
+                    let response = serde_json::to_string(&Response {
+                        jsonrpc: JSON_RPC_VERSION,
+                        id,
+                        value,
+                    })
+                    .unwrap();
+                    outbound_tx.send(response).await.log_err();
+                })
+                .detach();
+            }),
+        );
+    }
+
+    pub fn has_request_handler(&self, method: &str) -> bool {
+        self.request_handlers.lock().contains_key(method)
+    }
 }
 
 #[derive(Debug)]
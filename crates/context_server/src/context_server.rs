@@ -12,9 +12,13 @@ use std::{fmt::Display, path::PathBuf};
 
 use anyhow::Result;
 use client::Client;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use parking_lot::RwLock;
-pub use settings::ContextServerCommand;
+pub use settings::{ContextServerCommand, ContextServerEndpoint};
+
+/// Answers a request of the context server with the JSON result of the returned task.
+pub type RequestHandler =
+    Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp) -> Task<Result<serde_json::Value>>>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ContextServerId(pub Arc<str>);
@@ -27,6 +31,7 @@ impl Display for ContextServerId {
 
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
+    Http(ContextServerEndpoint),
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
@@ -52,6 +57,14 @@ impl ContextServer {
         }
     }
 
+    pub fn http(id: ContextServerId, endpoint: ContextServerEndpoint) -> Self {
+        Self {
+            id,
+            client: RwLock::new(None),
+            configuration: ContextServerTransport::Http(endpoint),
+        }
+    }
+
     pub fn new(id: ContextServerId, transport: Arc<dyn crate::transport::Transport>) -> Self {
         Self {
             id,
@@ -72,19 +85,24 @@ impl ContextServer {
         self.initialize(self.new_client(cx)?).await
     }
 
-    /// Starts the context server, making sure handlers are registered before initialization happens
+    /// Starts the context server, making sure handlers are registered before initialization happens,
+    /// so that the client capabilities reflect the requests it can answer.
     pub async fn start_with_handlers(
         &self,
         notification_handlers: Vec<(
             &'static str,
             Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)>,
         )>,
+        request_handlers: Vec<(&'static str, RequestHandler)>,
         cx: &AsyncApp,
     ) -> Result<()> {
         let client = self.new_client(cx)?;
         for (method, handler) in notification_handlers {
             client.on_notification(method, handler);
         }
+        for (method, handler) in request_handlers {
+            client.on_request(method, handler);
+        }
         self.initialize(client).await
     }
 
@@ -101,6 +119,12 @@ impl ContextServer {
                 working_directory,
                 cx.clone(),
             )?,
+            ContextServerTransport::Http(endpoint) => Client::http(
+                client::ContextServerId(self.id.0.clone()),
+                endpoint,
+                cx.update(|cx| cx.http_client())?,
+                cx.clone(),
+            )?,
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
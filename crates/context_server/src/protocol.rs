@@ -9,7 +9,7 @@ use std::time::Duration;
 
 use anyhow::Result;
 use futures::channel::oneshot;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use serde_json::Value;
 
 use crate::client::Client;
@@ -39,7 +39,14 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                sampling: self
+                    .inner
+                    .has_request_handler(types::requests::CreateMessage::METHOD)
+                    .then(|| serde_json::json!({})),
+                elicitation: self
+                    .inner
+                    .has_request_handler(types::requests::Elicit::METHOD)
+                    .then(|| serde_json::json!({})),
                 roots: None,
             },
             meta: None,
@@ -122,4 +129,12 @@ impl InitializedContextServerProtocol {
     ) {
         self.inner.on_notification(method, f);
     }
+
+    pub fn on_request(
+        &self,
+        method: &'static str,
+        f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) {
+        self.inner.on_request(method, f);
+    }
 }
@@ -1,8 +1,16 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
+use futures::{
+    FutureExt, Stream, StreamExt as _, channel::oneshot, future::BoxFuture, lock::Mutex,
+};
 use gpui::BackgroundExecutor;
-use std::{pin::Pin, sync::Arc};
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering::SeqCst},
+    },
+};
 
 use crate::{
     transport::Transport,
@@ -41,6 +49,8 @@ pub struct FakeTransport {
     >,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    client_responses: parking_lot::Mutex<HashMap<u64, oneshot::Sender<serde_json::Value>>>,
+    next_request_id: AtomicU64,
     executor: BackgroundExecutor,
 }
 
@@ -51,10 +61,34 @@ impl FakeTransport {
             request_handlers: Default::default(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
+            client_responses: Default::default(),
+            next_request_id: AtomicU64::new(0),
             executor,
         }
     }
 
+    /// Sends a request to the client, as the server would,
+    /// resolving to the response of the client.
+    pub fn request_client<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> impl Future<Output = anyhow::Result<serde_json::Value>> + use<T> {
+        let id = self.next_request_id.fetch_add(1, SeqCst);
+        let (response_tx, response_rx) = oneshot::channel();
+        self.client_responses.lock().insert(id, response_tx);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": T::METHOD,
+            "params": params,
+        });
+        let sent = self.tx.unbounded_send(request.to_string());
+        async move {
+            sent.context("sending a request")?;
+            response_rx.await.context("awaiting the response")
+        }
+    }
+
     pub fn on_request<T, Fut>(
         mut self,
         handler: impl 'static + Send + Sync + Fn(T::Params) -> Fut,
@@ -101,6 +135,8 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else if let Some(response_tx) = self.client_responses.lock().remove(&id) {
+                response_tx.send(msg).ok();
             }
         }
         Ok(())
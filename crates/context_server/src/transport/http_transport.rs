@@ -0,0 +1,690 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use futures::io::BufReader;
+use futures::{AsyncBufReadExt as _, AsyncReadExt as _, Stream, StreamExt as _};
+use gpui::{BackgroundExecutor, Task};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url};
+use parking_lot::Mutex;
+use serde_json::Value;
+use smol::channel;
+
+use crate::client::INTERNAL_ERROR;
+use crate::transport::Transport;
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// A remote context server endpoint, with the headers to send along every request.
+#[derive(Debug, Clone)]
+pub struct HttpEndpoint {
+    pub url: Url,
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpEndpoint {
+    pub fn new(endpoint: &settings::ContextServerEndpoint) -> Result<Self> {
+        let url = Url::parse(&endpoint.url)
+            .with_context(|| format!("invalid context server URL {:?}", endpoint.url))?;
+        let mut headers = endpoint
+            .headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        if let Some(token) = &endpoint.bearer_token {
+            headers.push(("Authorization".to_string(), format!("Bearer {token}")));
+        }
+        Ok(Self { url, headers })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Protocol {
+    /// No message was sent yet, so the protocol the server speaks is unknown.
+    Unknown,
+    /// Every message is POSTed to the endpoint, which answers in the response body,
+    /// either as JSON or as an SSE stream.
+    StreamableHttp,
+    /// The legacy HTTP with SSE protocol: a long lived SSE stream carries all server messages,
+    /// while client messages are POSTed to the URL announced in its `endpoint` event.
+    Sse { messages_url: Url },
+}
+
+struct HttpTransportState {
+    http_client: Arc<dyn HttpClient>,
+    endpoint: HttpEndpoint,
+    executor: BackgroundExecutor,
+    session_id: Mutex<Option<String>>,
+    incoming_tx: channel::Sender<String>,
+    error_tx: channel::Sender<String>,
+}
+
+/// A transport for remote context servers, speaking the Streamable HTTP protocol and
+/// falling back to the legacy HTTP with SSE one for servers which do not support it.
+pub struct HttpTransport {
+    state: Arc<HttpTransportState>,
+    protocol: Mutex<Protocol>,
+    incoming_rx: channel::Receiver<String>,
+    error_rx: channel::Receiver<String>,
+    outgoing_tx: channel::Sender<String>,
+    sse_stream: Mutex<Option<Task<()>>>,
+    _send_messages: Task<()>,
+}
+
+impl HttpTransport {
+    pub fn new(
+        http_client: Arc<dyn HttpClient>,
+        endpoint: HttpEndpoint,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        let (incoming_tx, incoming_rx) = channel::unbounded::<String>();
+        let (error_tx, error_rx) = channel::unbounded::<String>();
+        let (outgoing_tx, outgoing_rx) = channel::unbounded::<String>();
+        let state = Arc::new(HttpTransportState {
+            http_client,
+            endpoint,
+            executor: executor.clone(),
+            session_id: Mutex::new(None),
+            incoming_tx,
+            error_tx,
+        });
+        let send_messages = executor.spawn(state.clone().send_streamable_messages(outgoing_rx));
+        Self {
+            state,
+            protocol: Mutex::new(Protocol::Unknown),
+            incoming_rx,
+            error_rx,
+            outgoing_tx,
+            sse_stream: Mutex::new(None),
+            _send_messages: send_messages,
+        }
+    }
+
+    /// Sends the first message, which decides the protocol the server speaks.
+    async fn send_first_message(&self, message: String) -> Result<()> {
+        let response = self.state.post(&self.state.endpoint.url, &message).await?;
+        let status = response.status();
+        // Servers which only speak the legacy protocol reject the POST to their SSE endpoint.
+        if status.is_client_error()
+            && status != StatusCode::UNAUTHORIZED
+            && status != StatusCode::FORBIDDEN
+        {
+            log::debug!(
+                "context server at {} responded with {status}, falling back to HTTP with SSE",
+                self.state.endpoint.url
+            );
+            let (messages_url, sse_stream) = self.state.clone().connect_sse().await?;
+            *self.sse_stream.lock() = Some(sse_stream);
+            *self.protocol.lock() = Protocol::Sse {
+                messages_url: messages_url.clone(),
+            };
+            return self.state.post_sse_message(&messages_url, &message).await;
+        }
+
+        *self.protocol.lock() = Protocol::StreamableHttp;
+        self.state.handle_response(response).await
+    }
+}
+
+impl HttpTransportState {
+    fn request(&self, method: Method, url: &Url) -> http_client::http::request::Builder {
+        let mut request = Request::builder().method(method).uri(url.as_str());
+        for (name, value) in &self.endpoint.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(session_id) = self.session_id.lock().as_ref() {
+            request = request.header(SESSION_ID_HEADER, session_id.as_str());
+        }
+        request
+    }
+
+    async fn post(&self, url: &Url, message: &str) -> Result<Response<AsyncBody>> {
+        let request = self
+            .request(Method::POST, url)
+            .header("Content-Type", "application/json")
+            .header(
+                "Accept",
+                format!("application/json, {EVENT_STREAM_CONTENT_TYPE}"),
+            )
+            .body(AsyncBody::from(message.to_string()))?;
+        self.http_client
+            .send(request)
+            .await
+            .with_context(|| format!("sending a message to context server at {url}"))
+    }
+
+    /// POSTs the messages sent over Streamable HTTP one after the other, so that the server
+    /// receives them in order. Only the headers and JSON bodies of the responses are awaited,
+    /// the SSE streams answering long running requests are read on their own tasks.
+    async fn send_streamable_messages(self: Arc<Self>, outgoing_rx: channel::Receiver<String>) {
+        while let Ok(message) = outgoing_rx.recv().await {
+            if let Err(error) = self.post_streamable_message(&message).await {
+                self.error_tx.send(format!("{error:#}")).await.ok();
+                self.fail_request(&message, &error).await;
+            }
+        }
+    }
+
+    async fn post_streamable_message(&self, message: &str) -> Result<()> {
+        let response = self.post(&self.endpoint.url, message).await?;
+        self.handle_response(response).await
+    }
+
+    /// Forwards the messages in the response to a Streamable HTTP POST.
+    async fn handle_response(&self, response: Response<AsyncBody>) -> Result<()> {
+        let status = response.status();
+        if !status.is_success() {
+            return Err(error_for_response(response).await);
+        }
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|session_id| session_id.to_str().ok())
+        {
+            *self.session_id.lock() = Some(session_id.to_string());
+        }
+        if status == StatusCode::ACCEPTED {
+            return Ok(());
+        }
+
+        let is_event_stream = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with(EVENT_STREAM_CONTENT_TYPE));
+        if is_event_stream {
+            // The stream stays open until the server has sent the response to the request,
+            // and may carry requests and notifications of the server before that.
+            let incoming_tx = self.incoming_tx.clone();
+            let error_tx = self.error_tx.clone();
+            self.executor
+                .spawn(async move {
+                    let mut events = SseEvents::new(response.into_body());
+                    loop {
+                        match events.next().await {
+                            Ok(Some(event)) => {
+                                if event.is_message() && incoming_tx.send(event.data).await.is_err()
+                                {
+                                    break;
+                                }
+                            }
+                            Ok(None) => break,
+                            Err(error) => {
+                                error_tx.send(format!("{error:#}")).await.ok();
+                                break;
+                            }
+                        }
+                    }
+                })
+                .detach();
+        } else {
+            let mut body = String::new();
+            response.into_body().read_to_string(&mut body).await?;
+            self.forward_json(&body).await;
+        }
+        Ok(())
+    }
+
+    /// Forwards a message, or each message of a JSON-RPC batch.
+    async fn forward_json(&self, body: &str) {
+        match serde_json::from_str::<Value>(body) {
+            Ok(Value::Array(messages)) => {
+                for message in messages {
+                    self.incoming_tx.send(message.to_string()).await.ok();
+                }
+            }
+            Ok(_) => {
+                self.incoming_tx.send(body.to_string()).await.ok();
+            }
+            Err(_) if body.trim().is_empty() => {}
+            Err(error) => {
+                self.error_tx
+                    .send(format!("invalid JSON from context server: {error}"))
+                    .await
+                    .ok();
+            }
+        }
+    }
+
+    /// Answers a request which could not be delivered with an error,
+    /// so that it fails right away instead of timing out.
+    async fn fail_request(&self, message: &str, error: &anyhow::Error) {
+        let Some(id) = serde_json::from_str::<Value>(message)
+            .ok()
+            .filter(|message| message.get("method").is_some())
+            .and_then(|mut message| message.get_mut("id").map(Value::take))
+        else {
+            return;
+        };
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": INTERNAL_ERROR,
+                "message": format!("{error:#}"),
+            },
+        });
+        self.incoming_tx.send(response.to_string()).await.ok();
+    }
+
+    /// Opens the SSE stream of a legacy server, returning the URL to POST messages to.
+    async fn connect_sse(self: Arc<Self>) -> Result<(Url, Task<()>)> {
+        let request = self
+            .request(Method::GET, &self.endpoint.url)
+            .header("Accept", EVENT_STREAM_CONTENT_TYPE)
+            .body(AsyncBody::empty())?;
+        let response =
+            self.http_client.send(request).await.with_context(|| {
+                format!("connecting to context server at {}", self.endpoint.url)
+            })?;
+        if !response.status().is_success() {
+            return Err(error_for_response(response).await);
+        }
+
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let state = self.clone();
+        let sse_stream = self.executor.spawn(async move {
+            let mut events = SseEvents::new(response.into_body());
+            let mut endpoint_tx = Some(endpoint_tx);
+            loop {
+                match events.next().await {
+                    Ok(Some(event)) if event.event.as_deref() == Some("endpoint") => {
+                        if let Some(endpoint_tx) = endpoint_tx.take() {
+                            endpoint_tx.send(event.data).ok();
+                        }
+                    }
+                    Ok(Some(event)) => {
+                        if event.is_message() && state.incoming_tx.send(event.data).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => {
+                        state
+                            .error_tx
+                            .send("context server closed the SSE stream".to_string())
+                            .await
+                            .ok();
+                        break;
+                    }
+                    Err(error) => {
+                        state.error_tx.send(format!("{error:#}")).await.ok();
+                        break;
+                    }
+                }
+            }
+        });
+
+        let endpoint = endpoint_rx
+            .await
+            .context("context server closed the SSE stream before announcing its endpoint")?;
+        let messages_url = self
+            .endpoint
+            .url
+            .join(endpoint.trim())
+            .with_context(|| format!("invalid context server endpoint {endpoint:?}"))?;
+        Ok((messages_url, sse_stream))
+    }
+
+    async fn post_sse_message(&self, messages_url: &Url, message: &str) -> Result<()> {
+        let response = self.post(messages_url, message).await?;
+        if !response.status().is_success() {
+            return Err(error_for_response(response).await);
+        }
+        Ok(())
+    }
+}
+
+async fn error_for_response(response: Response<AsyncBody>) -> anyhow::Error {
+    let status = response.status();
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await.ok();
+    anyhow!("context server responded with {status}: {}", body.trim())
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        let protocol = self.protocol.lock().clone();
+        match protocol {
+            Protocol::Unknown => self.send_first_message(message).await,
+            Protocol::StreamableHttp => {
+                // Queued rather than awaited, for the responses to requests not to hold back
+                // the messages sent after them.
+                self.outgoing_tx
+                    .send(message)
+                    .await
+                    .context("context server transport was closed")
+            }
+            Protocol::Sse { messages_url } => {
+                self.state.post_sse_message(&messages_url, &message).await
+            }
+        }
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.incoming_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.error_rx.clone())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        // Let the server release the session instead of waiting for it to expire.
+        let Some(session_id) = self.state.session_id.lock().take() else {
+            return;
+        };
+        let Ok(request) = self
+            .state
+            .request(Method::DELETE, &self.state.endpoint.url)
+            .header(SESSION_ID_HEADER, session_id)
+            .body(AsyncBody::empty())
+        else {
+            return;
+        };
+        let http_client = self.state.http_client.clone();
+        self.state
+            .executor
+            .spawn(async move {
+                http_client.send(request).await.ok();
+            })
+            .detach();
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    event: Option<String>,
+    data: String,
+}
+
+impl SseEvent {
+    /// Whether the event carries a JSON-RPC message, events without a type default to `message`.
+    fn is_message(&self) -> bool {
+        self.event.as_deref().is_none_or(|event| event == "message") && !self.data.is_empty()
+    }
+}
+
+/// Parses the events of a `text/event-stream` body.
+struct SseEvents {
+    lines: futures::io::Lines<BufReader<AsyncBody>>,
+}
+
+impl SseEvents {
+    fn new(body: AsyncBody) -> Self {
+        Self {
+            lines: BufReader::new(body).lines(),
+        }
+    }
+
+    async fn next(&mut self) -> Result<Option<SseEvent>> {
+        let mut event = SseEvent::default();
+        let mut has_fields = false;
+        while let Some(line) = self.lines.next().await {
+            let line = line.context("reading context server SSE stream")?;
+            if line.is_empty() {
+                if has_fields {
+                    return Ok(Some(event));
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line.as_str(), ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => event.event = Some(value.to_string()),
+                "data" => {
+                    if !event.data.is_empty() {
+                        event.data.push('\n');
+                    }
+                    event.data.push_str(value);
+                }
+                _ => continue,
+            }
+            has_fields = true;
+        }
+        Ok(has_fields.then_some(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+
+    #[derive(Debug)]
+    struct RecordedRequest {
+        method: Method,
+        url: String,
+        session_id: Option<String>,
+        authorization: Option<String>,
+        body: String,
+    }
+
+    fn fake_server<F>(respond: F) -> (Arc<dyn HttpClient>, Arc<Mutex<Vec<RecordedRequest>>>)
+    where
+        F: Fn(&RecordedRequest) -> Response<AsyncBody> + Send + Sync + 'static,
+    {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let respond = Arc::new(respond);
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                let requests = requests.clone();
+                let respond = respond.clone();
+                async move {
+                    let header = |name: &str| {
+                        request
+                            .headers()
+                            .get(name)
+                            .and_then(|value| value.to_str().ok())
+                            .map(ToString::to_string)
+                    };
+                    let mut recorded = RecordedRequest {
+                        method: request.method().clone(),
+                        url: request.uri().to_string(),
+                        session_id: header(SESSION_ID_HEADER),
+                        authorization: header("Authorization"),
+                        body: String::new(),
+                    };
+                    request
+                        .into_body()
+                        .read_to_string(&mut recorded.body)
+                        .await?;
+                    let response = respond(&recorded);
+                    requests.lock().push(recorded);
+                    Ok(response)
+                }
+            }
+        });
+        (http_client, requests)
+    }
+
+    fn endpoint() -> HttpEndpoint {
+        HttpEndpoint::new(&settings::ContextServerEndpoint {
+            url: "http://mcp.example/mcp".to_string(),
+            headers: None,
+            bearer_token: Some("secret".to_string()),
+            timeout: None,
+        })
+        .unwrap()
+    }
+
+    #[gpui::test]
+    async fn test_streamable_http(cx: &mut TestAppContext) {
+        let (http_client, requests) = fake_server(|request| {
+            if request.body.contains("\"initialize\"") {
+                Response::builder()
+                    .status(200)
+                    .header("Content-Type", "application/json")
+                    .header(SESSION_ID_HEADER, "session-1")
+                    .body(r#"{"jsonrpc":"2.0","id":0,"result":{}}"#.into())
+                    .unwrap()
+            } else if request.body.contains("\"tools/list\"") {
+                Response::builder()
+                    .status(200)
+                    .header("Content-Type", "text/event-stream")
+                    .body(
+                        concat!(
+                            ": keep-alive\n\n",
+                            "event: message\n",
+                            "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n",
+                            "data: {\"jsonrpc\":\"2.0\",\"id\":1,\n",
+                            "data: \"result\":{\"tools\":[]}}\n\n",
+                        )
+                        .into(),
+                    )
+                    .unwrap()
+            } else if request.body.contains("\"failing\"") {
+                Response::builder()
+                    .status(500)
+                    .body("internal failure".into())
+                    .unwrap()
+            } else {
+                Response::builder().status(202).body("".into()).unwrap()
+            }
+        });
+        let transport = HttpTransport::new(http_client, endpoint(), cx.executor());
+        let mut incoming = transport.receive();
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}"#.to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            incoming.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":0,"result":{}}"#
+        );
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#.to_string())
+            .await
+            .unwrap();
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#.to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            incoming.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","method":"notifications/progress"}"#
+        );
+        assert_eq!(
+            incoming.next().await.unwrap(),
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\n\"result\":{\"tools\":[]}}"
+        );
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":2,"method":"failing"}"#.to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let response = serde_json::from_str::<Value>(&incoming.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], INTERNAL_ERROR);
+
+        {
+            let requests = requests.lock();
+            assert_eq!(requests.len(), 4);
+            assert!(requests.iter().all(|request| request.method == Method::POST
+                && request.url == "http://mcp.example/mcp"
+                && request.authorization.as_deref() == Some("Bearer secret")));
+            assert_eq!(requests[0].session_id, None);
+            let methods = requests
+                .iter()
+                .map(|request| {
+                    serde_json::from_str::<Value>(&request.body).unwrap()["method"].clone()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                methods,
+                [
+                    "initialize",
+                    "notifications/initialized",
+                    "tools/list",
+                    "failing"
+                ]
+            );
+            assert!(
+                requests[1..]
+                    .iter()
+                    .all(|request| request.session_id.as_deref() == Some("session-1"))
+            );
+        }
+
+        drop(transport);
+        cx.run_until_parked();
+        let requests = requests.lock();
+        let last_request = requests.last().unwrap();
+        assert_eq!(last_request.method, Method::DELETE);
+        assert_eq!(last_request.session_id.as_deref(), Some("session-1"));
+    }
+
+    #[gpui::test]
+    async fn test_sse_fallback(cx: &mut TestAppContext) {
+        let (http_client, requests) = fake_server(|request| match request.method {
+            Method::GET => Response::builder()
+                .status(200)
+                .header("Content-Type", "text/event-stream")
+                .body(
+                    concat!(
+                        "event: endpoint\n",
+                        "data: /messages?session_id=abc\n\n",
+                        "event: message\n",
+                        "data: {\"jsonrpc\":\"2.0\",\"id\":0,\"result\":{}}\n\n",
+                    )
+                    .into(),
+                )
+                .unwrap(),
+            _ if request.url.contains("/messages") => {
+                Response::builder().status(202).body("".into()).unwrap()
+            }
+            _ => Response::builder()
+                .status(405)
+                .body("Method Not Allowed".into())
+                .unwrap(),
+        });
+        let transport = HttpTransport::new(http_client, endpoint(), cx.executor());
+        let mut incoming = transport.receive();
+
+        let initialize = r#"{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}"#;
+        transport.send(initialize.to_string()).await.unwrap();
+        assert_eq!(
+            incoming.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":0,"result":{}}"#
+        );
+
+        let requests = requests.lock();
+        let requests = requests
+            .iter()
+            .map(|request| {
+                (
+                    request.method.clone(),
+                    request.url.as_str(),
+                    request.body.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            [
+                (Method::POST, "http://mcp.example/mcp", initialize),
+                (Method::GET, "http://mcp.example/mcp", ""),
+                (
+                    Method::POST,
+                    "http://mcp.example/messages?session_id=abc",
+                    initialize
+                ),
+            ]
+        );
+    }
+}
@@ -54,6 +54,10 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Steps into the next machine instruction.
+        StepIntoInstruction,
+        /// Steps over the next machine instruction.
+        StepOverInstruction,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
@@ -191,6 +195,22 @@ pub fn init(cx: &mut App) {
                             active_item.update(cx, |item, cx| item.step_over(cx)).ok();
                         }
                     })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepIntoInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_in_instruction(cx))
+                                .ok();
+                        }
+                    })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepOverInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_over_instruction(cx))
+                                .ok();
+                        }
+                    })
                     .on_action({
                         let active_item = active_item.clone();
                         move |_: &StepOut, _, cx| {
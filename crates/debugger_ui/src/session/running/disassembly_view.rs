@@ -0,0 +1,391 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::HashMap;
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{Buffer, Point};
+use project::debugger::session::Session;
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use workspace::Workspace;
+
+use crate::session::running::stack_frame_list::{StackFrameList, StackFrameListEvent};
+
+/// How many instructions are disassembled before and after the instruction pointer.
+const INSTRUCTIONS_AROUND_POINTER: u64 = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum DisassemblyRow {
+    /// The source line the following instructions were compiled from.
+    Source {
+        path: Arc<Path>,
+        line: u32,
+    },
+    Instruction(dap::DisassembledInstruction),
+}
+
+/// Inserts a row for each source line before the instructions it was compiled from.
+///
+/// Adapters only report the location of an instruction when it differs from the previous one,
+/// so the current location is carried over instructions that have none.
+pub(crate) fn interleave_source(
+    instructions: &[dap::DisassembledInstruction],
+) -> Vec<DisassemblyRow> {
+    let mut rows = Vec::with_capacity(instructions.len());
+    let mut current_path: Option<Arc<Path>> = None;
+    let mut current_line = None;
+    for instruction in instructions {
+        if let Some(location) = &instruction.location {
+            let path = location
+                .path
+                .as_deref()
+                .map(|path| Arc::<Path>::from(Path::new(path)));
+            if path != current_path {
+                current_path = path;
+                current_line = None;
+            }
+        }
+        if let Some(line) = instruction.line
+            && current_line != Some(line)
+        {
+            current_line = Some(line);
+            if let Some(path) = &current_path {
+                rows.push(DisassemblyRow::Source {
+                    path: path.clone(),
+                    line: line as u32,
+                });
+            }
+        }
+        rows.push(DisassemblyRow::Instruction(instruction.clone()));
+    }
+    rows
+}
+
+fn parse_address(reference: &str) -> Option<u64> {
+    let reference = reference.trim();
+    let hex = reference
+        .strip_prefix("0x")
+        .or_else(|| reference.strip_prefix("0X"))?;
+    u64::from_str_radix(hex, 16).ok()
+}
+
+/// Whether two memory references point at the same address, even when formatted differently.
+fn same_address(lhs: &str, rhs: &str) -> bool {
+    match (parse_address(lhs), parse_address(rhs)) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => lhs == rhs,
+    }
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
+    stack_frame_list: Entity<StackFrameList>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    instruction_pointer: Option<String>,
+    rows: Vec<DisassemblyRow>,
+    /// Buffers of the source files shown between instructions, or `None` while loading them.
+    source_buffers: HashMap<Arc<Path>, Option<Entity<Buffer>>>,
+    _fetch_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        workspace: WeakEntity<Workspace>,
+        stack_frame_list: Entity<StackFrameList>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&stack_frame_list, |this, _, event, cx| match event {
+                StackFrameListEvent::SelectedStackFrameChanged(stack_frame_id) => {
+                    this.disassemble_stack_frame(*stack_frame_id, cx);
+                }
+                StackFrameListEvent::BuiltEntries => {}
+            }),
+            cx.observe(&session, |_, _, cx| cx.notify()),
+        ];
+
+        Self {
+            session,
+            workspace,
+            stack_frame_list,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            instruction_pointer: None,
+            rows: Vec::new(),
+            source_buffers: HashMap::default(),
+            _fetch_task: Task::ready(()),
+            _subscriptions,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn rows(&self) -> &[DisassemblyRow] {
+        &self.rows
+    }
+
+    fn disassemble_stack_frame(&mut self, stack_frame_id: u64, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .update(cx, |list, cx| list.dap_stack_frames(cx))
+            .into_iter()
+            .find(|stack_frame| stack_frame.id == stack_frame_id)
+            .and_then(|stack_frame| stack_frame.instruction_pointer_reference);
+        self.instruction_pointer = instruction_pointer.clone();
+        let Some(instruction_pointer) = instruction_pointer else {
+            self.rows.clear();
+            cx.notify();
+            return;
+        };
+
+        let instructions = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                instruction_pointer.clone(),
+                -(INSTRUCTIONS_AROUND_POINTER as i64),
+                INSTRUCTIONS_AROUND_POINTER * 2,
+                cx,
+            )
+        });
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let Some(instructions) = instructions.await else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.rows = interleave_source(&instructions);
+                this.load_source_buffers(cx);
+                if let Some(ix) = this.rows.iter().position(|row| {
+                    matches!(row, DisassemblyRow::Instruction(instruction)
+                        if same_address(&instruction.address, &instruction_pointer))
+                }) {
+                    this.scroll_handle
+                        .scroll_to_item(ix, ScrollStrategy::Center);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn load_source_buffers(&mut self, cx: &mut Context<Self>) {
+        let Some(project) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+            .ok()
+        else {
+            return;
+        };
+        for row in &self.rows {
+            let DisassemblyRow::Source { path, .. } = row else {
+                continue;
+            };
+            if self.source_buffers.contains_key(path) {
+                continue;
+            }
+            self.source_buffers.insert(path.clone(), None);
+
+            let buffer = project.update(cx, |project, cx| project.open_local_buffer(path, cx));
+            let path = path.clone();
+            cx.spawn(async move |this, cx| {
+                let buffer = buffer.await?;
+                this.update(cx, |this, cx| {
+                    this.source_buffers.insert(path, Some(buffer));
+                    cx.notify();
+                })
+            })
+            .detach();
+        }
+    }
+
+    fn source_line_text(&self, path: &Arc<Path>, line: u32, cx: &App) -> Option<String> {
+        let buffer = self.source_buffers.get(path)?.as_ref()?.read(cx);
+        let row = line.checked_sub(1)?;
+        if row > buffer.max_point().row {
+            return None;
+        }
+        let text = buffer
+            .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+            .collect::<String>();
+        Some(text.trim().to_string())
+    }
+
+    fn render_source_row(
+        &self,
+        ix: usize,
+        path: &Arc<Path>,
+        line: u32,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+
+        h_flex()
+            .id(("disassembly-source", ix))
+            .w_full()
+            .gap_2()
+            .pl_6()
+            .pt_1()
+            .child(
+                Label::new(format!("{file_name}:{line}"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(self.source_line_text(path, line, cx), |this, text| {
+                this.child(Label::new(text).buffer_font(cx).size(LabelSize::Small))
+            })
+            .into_any()
+    }
+
+    fn render_instruction_row(
+        &self,
+        ix: usize,
+        instruction: &dap::DisassembledInstruction,
+        supports_breakpoints: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let is_current = self
+            .instruction_pointer
+            .as_deref()
+            .is_some_and(|pointer| same_address(&instruction.address, pointer));
+        let has_breakpoint = self
+            .session
+            .read(cx)
+            .has_instruction_breakpoint(&instruction.address);
+
+        let gutter = div()
+            .id(("disassembly-gutter", ix))
+            .w_4()
+            .flex_none()
+            .when(has_breakpoint, |this| {
+                this.child(
+                    Icon::new(IconName::DebugBreakpoint)
+                        .size(IconSize::XSmall)
+                        .color(Color::Debugger),
+                )
+            })
+            .when(supports_breakpoints, |this| {
+                let address = instruction.address.clone();
+                this.cursor_pointer()
+                    .tooltip(Tooltip::text(if has_breakpoint {
+                        "Remove Instruction Breakpoint"
+                    } else {
+                        "Set Instruction Breakpoint"
+                    }))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.session.update(cx, |session, cx| {
+                            session.toggle_instruction_breakpoint(address.clone(), cx);
+                        });
+                        cx.notify();
+                    }))
+            });
+
+        h_flex()
+            .id(("disassembly-instruction", ix))
+            .w_full()
+            .gap_2()
+            .when(is_current, |this| {
+                this.bg(cx.theme().colors().editor_debugger_active_line_background)
+            })
+            .child(gutter)
+            .child(div().w_4().flex_none().when(is_current, |this| {
+                this.child(
+                    Icon::new(IconName::ArrowRight)
+                        .size(IconSize::XSmall)
+                        .color(Color::Warning),
+                )
+            }))
+            .child(
+                Label::new(instruction.address.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                this.child(
+                    div().min_w_32().child(
+                        Label::new(bytes)
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
+            .child(
+                Label::new(instruction.instruction.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small),
+            )
+            .when_some(instruction.symbol.clone(), |this, symbol| {
+                this.child(
+                    Label::new(format!("<{symbol}>"))
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any()
+    }
+
+    fn render_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            "disassembly-view",
+            self.rows.len(),
+            cx.processor(|this, range: Range<usize>, _window, cx| {
+                let supports_breakpoints = this
+                    .session
+                    .read(cx)
+                    .capabilities()
+                    .supports_instruction_breakpoints
+                    .unwrap_or_default();
+                range
+                    .filter_map(|ix| {
+                        let row = this.rows.get(ix)?.clone();
+                        Some(match row {
+                            DisassemblyRow::Source { path, line } => {
+                                this.render_source_row(ix, &path, line, cx)
+                            }
+                            DisassemblyRow::Instruction(instruction) => this
+                                .render_instruction_row(ix, &instruction, supports_breakpoints, cx),
+                        })
+                    })
+                    .collect()
+            }),
+        )
+        .track_scroll(self.scroll_handle.clone())
+        .size_full()
+    }
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.instruction_pointer.is_none() && self.rows.is_empty() {
+            div()
+                .p_2()
+                .child(
+                    Label::new("No instruction pointer for the selected stack frame")
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            self.render_list(cx).into_any_element()
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .child(content)
+            .vertical_scrollbar_for(self.scroll_handle.clone(), window, cx)
+    }
+}
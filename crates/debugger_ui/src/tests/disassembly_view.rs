@@ -0,0 +1,219 @@
+use crate::{
+    debugger_panel::DebugPanel,
+    session::running::disassembly_view::DisassemblyRow,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, SteppingGranularity,
+    requests::{
+        Disassemble, Initialize, Scopes, SetInstructionBreakpoints, StackTrace, StepIn, Threads,
+    },
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use util::path;
+
+fn instruction(address: &str, text: &str, line: Option<u64>) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: text.into(),
+        symbol: None,
+        location: line.map(|_| dap::Source {
+            name: Some("main.c".into()),
+            path: Some(path!("/project/main.c").into()),
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }),
+        line: line.map(|line| line as _),
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": "int main() {\n    return 0;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: None,
+                line: 2,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1004".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    let instructions = vec![
+        instruction("0x0000000000001000", "push rbp", Some(1)),
+        instruction("0x0000000000001004", "xor eax, eax", Some(2)),
+        instruction("0x0000000000001006", "pop rbp", None),
+    ];
+    client.on_request::<Disassemble, _>({
+        let instructions = instructions.clone();
+        move |_, args| {
+            assert_eq!(args.memory_reference, "0x1004");
+            Ok(dap::DisassembleResponse {
+                instructions: instructions.clone(),
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.select_current_thread(
+            &running_state
+                .session()
+                .update(cx, |session, cx| session.threads(cx)),
+            window,
+            cx,
+        );
+    });
+
+    cx.run_until_parked();
+
+    let disassembly_view = running_state.update(cx, |state, _| state.disassembly_view().clone());
+    let main_path: Arc<Path> = Path::new(path!("/project/main.c")).into();
+    disassembly_view.update(cx, |view, _| {
+        assert_eq!(
+            view.rows(),
+            [
+                DisassemblyRow::Source {
+                    path: main_path.clone(),
+                    line: 1,
+                },
+                DisassemblyRow::Instruction(instructions[0].clone()),
+                DisassemblyRow::Source {
+                    path: main_path.clone(),
+                    line: 2,
+                },
+                DisassemblyRow::Instruction(instructions[1].clone()),
+                DisassemblyRow::Instruction(instructions[2].clone()),
+            ]
+        );
+    });
+
+    let sent_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let sent_breakpoints = sent_breakpoints.clone();
+        move |_, args| {
+            *sent_breakpoints.lock().unwrap() = args
+                .breakpoints
+                .iter()
+                .map(|breakpoint| breakpoint.instruction_reference.clone())
+                .collect::<Vec<_>>();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x0000000000001006".into(), cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(*sent_breakpoints.lock().unwrap(), ["0x0000000000001006"]);
+    session.read_with(cx, |session, _| {
+        assert!(session.has_instruction_breakpoint("0x0000000000001006"));
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x0000000000001006".into(), cx);
+    });
+    cx.run_until_parked();
+    assert!(sent_breakpoints.lock().unwrap().is_empty());
+
+    let stepped = Arc::new(Mutex::new(None));
+    client.on_request::<StepIn, _>({
+        let stepped = stepped.clone();
+        move |_, args| {
+            *stepped.lock().unwrap() = Some(args.granularity);
+            Ok(())
+        }
+    });
+
+    running_state.update(cx, |running_state, cx| {
+        running_state.step_in_instruction(cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        *stepped.lock().unwrap(),
+        Some(Some(SteppingGranularity::Instruction))
+    );
+}
@@ -318,6 +318,34 @@ pub struct GoToPreviousDiagnostic {
     pub severity: GoToDiagnosticSeverityFilter,
 }
 
+/// Moves back to an earlier state of the buffer, in the order the changes were made, even if that
+/// state is on another branch of the undo tree.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct UndoEarlier {
+    /// The number of changes to go back. Defaults to one.
+    #[serde(default)]
+    pub count: Option<usize>,
+    /// Go back to the state from this many seconds earlier instead.
+    #[serde(default)]
+    pub seconds: Option<u64>,
+}
+
+/// Moves forward to a later state of the buffer, in the order the changes were made, even if that
+/// state is on another branch of the undo tree.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct UndoLater {
+    /// The number of changes to go forward. Defaults to one.
+    #[serde(default)]
+    pub count: Option<usize>,
+    /// Go forward to the state from this many seconds later instead.
+    #[serde(default)]
+    pub seconds: Option<u64>,
+}
+
 actions!(
     debugger,
     [
@@ -712,12 +740,16 @@ actions!(
         SelectToStartOfParagraph,
         /// Extends selection up.
         SelectUp,
+        /// Shows the callers and callees of the symbol at cursor in the hierarchy panel.
+        ShowCallHierarchy,
         /// Shows the system character palette.
         ShowCharacterPalette,
         /// Shows edit prediction at cursor.
         ShowEditPrediction,
         /// Shows signature help for the current function.
         ShowSignatureHelp,
+        /// Shows the supertypes and subtypes of the type at cursor in the hierarchy panel.
+        ShowTypeHierarchy,
         /// Shows word completions.
         ShowWordCompletions,
         /// Randomly shuffles selected lines.
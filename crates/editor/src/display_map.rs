@@ -92,6 +92,7 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<HighlightKey, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type FoldingRanges = TreeMap<BufferId, Arc<[Range<text::Anchor>]>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -117,6 +118,8 @@ pub struct DisplayMap {
     inlay_highlights: InlayHighlights,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
+    /// Foldable ranges reported by language servers, which supersede indentation based fold range suggestions in their buffers.
+    folding_ranges: FoldingRanges,
     pub(crate) fold_placeholder: FoldPlaceholder,
     pub clip_at_line_ends: bool,
     pub(crate) masked: bool,
@@ -161,6 +164,7 @@ impl DisplayMap {
             diagnostics_max_severity,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            folding_ranges: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -182,6 +186,7 @@ impl DisplayMap {
             block_snapshot,
             diagnostics_max_severity: self.diagnostics_max_severity,
             crease_snapshot: self.crease_map.snapshot(),
+            folding_ranges: self.folding_ranges.clone(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
@@ -515,6 +520,19 @@ impl DisplayMap {
         self.text_highlights.values()
     }
 
+    /// Replaces the language server folding ranges of the buffer, which must be sorted by their start.
+    pub fn set_folding_ranges(&mut self, buffer_id: BufferId, ranges: Arc<[Range<text::Anchor>]>) {
+        if ranges.is_empty() {
+            self.folding_ranges.remove(&buffer_id);
+        } else {
+            self.folding_ranges.insert(buffer_id, ranges);
+        }
+    }
+
+    pub fn remove_text_highlights(&mut self, key: HighlightKey) -> bool {
+        self.text_highlights.remove(&key).is_some()
+    }
+
     pub fn clear_highlights(&mut self, type_id: TypeId) -> bool {
         let mut cleared = self
             .text_highlights
@@ -758,6 +776,7 @@ impl<'a> HighlightedChunk<'a> {
 #[derive(Clone)]
 pub struct DisplaySnapshot {
     pub crease_snapshot: CreaseSnapshot,
+    folding_ranges: FoldingRanges,
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
@@ -1290,6 +1309,44 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Whether a fold can start at the given row, according to the language servers' folding ranges
+    /// of the row's buffer if there are any, or the indentation otherwise.
+    pub fn starts_folding_range(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.lsp_folding_range_for_buffer_row(buffer_row) {
+            Some(folding_range) => folding_range.is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    /// Returns `None` when the language servers reported no folding ranges for the row's buffer,
+    /// otherwise the outermost folding range starting at the row, if any.
+    fn lsp_folding_range_for_buffer_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<Option<Range<Point>>> {
+        let multi_buffer = self.buffer_snapshot();
+        let (buffer, buffer_point, excerpt_id) =
+            multi_buffer.point_to_buffer_point(Point::new(buffer_row.0, 0))?;
+        let ranges = self.folding_ranges.get(&buffer.remote_id())?;
+
+        let start_row =
+            |range: &Range<text::Anchor>| buffer.summary_for_anchor::<Point>(&range.start).row;
+        let first_candidate = ranges.partition_point(|range| start_row(range) < buffer_point.row);
+        let end = ranges[first_candidate..]
+            .iter()
+            .take_while(|range| start_row(range) == buffer_point.row)
+            .filter_map(|range| {
+                multi_buffer
+                    .anchor_in_excerpt(excerpt_id, range.end)
+                    .map(|end| end.to_point(multi_buffer))
+            })
+            .max();
+        Some(
+            end.filter(|end| end.row > buffer_row.0)
+                .map(|end| Point::new(buffer_row.0, multi_buffer.line_len(buffer_row))..end),
+        )
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start =
             MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot().line_len(buffer_row));
@@ -1327,6 +1384,15 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(folding_range) = self.lsp_folding_range_for_buffer_row(buffer_row) {
+            let range = folding_range.filter(|_| !self.is_line_folded(buffer_row))?;
+            Some(Crease::Inline {
+                range,
+                placeholder: self.fold_placeholder.clone(),
+                render_toggle: None,
+                render_trailer: None,
+                metadata: None,
+            })
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
@@ -30,6 +30,7 @@ mod jsx_tag_auto_close;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
+mod lsp_folding_ranges;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -37,6 +38,8 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
+mod sticky_scroll;
 pub mod tasks;
 
 #[cfg(test)]
@@ -146,9 +149,9 @@ use parking_lot::Mutex;
 use persistence::DB;
 use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
-    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint,
-    Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, TaskSourceKind,
+    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, DocumentLink,
+    DocumentLinkTarget, InlayHint, Location, LocationLink, PrepareRenameResponse, Project,
+    ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -166,6 +169,7 @@ use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager};
 use selections_collection::{
     MutableSelectionsCollection, SelectionsCollection, resolve_selections,
 };
+use semantic_tokens::SemanticTokensState;
 use serde::{Deserialize, Serialize};
 use settings::{GitGutterSetting, Settings, SettingsLocation, SettingsStore, update_settings_file};
 use smallvec::{SmallVec, smallvec};
@@ -185,7 +189,7 @@ use std::{
     time::{Duration, Instant},
 };
 use task::{ResolvedTask, RunnableTag, TaskTemplate, TaskVariables};
-use text::{BufferId, FromAnchor, OffsetUtf16, Rope, ToOffset as _};
+use text::{BufferId, FromAnchor, OffsetUtf16, Rope, ToOffset as _, UndoTree};
 use theme::{
     ActiveTheme, PlayerColor, StatusColors, SyntaxTheme, Theme, ThemeSettings,
     observe_buffer_font_size_adjustment,
@@ -227,6 +231,8 @@ pub(crate) const CODE_ACTION_TIMEOUT: Duration = Duration::from_secs(5);
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(5);
 pub(crate) const SCROLL_CENTER_TOP_BOTTOM_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
 pub const FETCH_COLORS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+pub const FETCH_SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
+pub const FETCH_FOLDING_RANGES_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(150);
 
 pub(crate) const EDIT_PREDICTION_KEY_CONTEXT: &str = "edit_prediction";
 pub(crate) const EDIT_PREDICTION_CONFLICT_KEY_CONTEXT: &str = "edit_prediction_conflict";
@@ -1037,6 +1043,7 @@ pub struct Editor {
     autoclose_regions: Vec<AutocloseRegion>,
     snippet_stack: InvalidationStack<SnippetState>,
     select_syntax_node_history: SelectSyntaxNodeHistory,
+    select_larger_syntax_node_task: Task<()>,
     ime_transaction: Option<TransactionId>,
     pub diagnostics_max_severity: DiagnosticSeverity,
     active_diagnostics: ActiveDiagnostic,
@@ -1191,6 +1198,10 @@ pub struct Editor {
     selection_drag_state: SelectionDragState,
     colors: Option<LspColorData>,
     refresh_colors_task: Task<()>,
+    semantic_tokens: SemanticTokensState,
+    refresh_semantic_tokens_task: Task<()>,
+    folding_ranges_cache_versions: HashMap<BufferId, usize>,
+    refresh_folding_ranges_task: Task<()>,
     folding_newlines: Task<()>,
     pub lookup_key: Option<Box<dyn Any + Send + Sync>>,
 }
@@ -1882,6 +1893,9 @@ impl Editor {
                     project::Event::RefreshInlayHints => {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                     }
+                    project::Event::RefreshSemanticTokens => {
+                        editor.refresh_semantic_tokens(true, None, window, cx);
+                    }
                     project::Event::LanguageServerAdded(..)
                     | project::Event::LanguageServerRemoved(..) => {
                         if editor.tasks_update_task.is_none() {
@@ -2094,6 +2108,7 @@ impl Editor {
             autoclose_regions: Vec::new(),
             snippet_stack: InvalidationStack::default(),
             select_syntax_node_history: SelectSyntaxNodeHistory::default(),
+            select_larger_syntax_node_task: Task::ready(()),
             ime_transaction: None,
             active_diagnostics: ActiveDiagnostic::None,
             show_inline_diagnostics: ProjectSettings::get_global(cx).diagnostics.inline.enabled,
@@ -2247,6 +2262,10 @@ impl Editor {
             pull_diagnostics_task: Task::ready(()),
             colors: None,
             refresh_colors_task: Task::ready(()),
+            semantic_tokens: SemanticTokensState::default(),
+            refresh_semantic_tokens_task: Task::ready(()),
+            folding_ranges_cache_versions: HashMap::default(),
+            refresh_folding_ranges_task: Task::ready(()),
             next_color_inlay_id: 0,
             linked_edit_ranges: Default::default(),
             in_project_search: false,
@@ -12911,6 +12930,76 @@ impl Editor {
         }
     }
 
+    pub fn undo_earlier(
+        &mut self,
+        action: &UndoEarlier,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.travel_undo_tree(window, cx, |tree| match action.seconds {
+            Some(seconds) => tree.earlier_by(Duration::from_secs(seconds)),
+            None => tree.earlier(action.count.unwrap_or(1)),
+        });
+    }
+
+    pub fn undo_later(&mut self, action: &UndoLater, window: &mut Window, cx: &mut Context<Self>) {
+        self.travel_undo_tree(window, cx, |tree| match action.seconds {
+            Some(seconds) => tree.later_by(Duration::from_secs(seconds)),
+            None => tree.later(action.count.unwrap_or(1)),
+        });
+    }
+
+    fn travel_undo_tree(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        target: impl FnOnce(&UndoTree) -> Option<TransactionId>,
+    ) {
+        // Undo trees are per buffer, so time travel is only available in singleton editors.
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let undo_tree = buffer.read(cx).undo_tree();
+        self.travel_to_undo_state(target(&undo_tree), window, cx);
+    }
+
+    /// Moves the editor's buffer to the given state of its undo tree, where `None` is the
+    /// buffer's original text.
+    pub fn travel_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return;
+        };
+
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+
+        let previous = buffer.read(cx).undo_tree().current();
+        if !buffer.update(cx, |buffer, cx| buffer.travel_to_undo_state(target, cx)) {
+            return;
+        }
+        if let Some((_, Some(selections))) = target
+            .and_then(|target| self.selection_history.transaction(target))
+            .cloned()
+        {
+            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_anchors(selections.to_vec());
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(window, cx);
+        self.refresh_edit_prediction(true, false, window, cx);
+        if let Some(transaction_id) = target.or(previous) {
+            cx.emit(EditorEvent::Edited { transaction_id });
+        }
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut Context<Self>) {
         self.buffer
             .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
@@ -15220,9 +15309,6 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let Some(visible_row_count) = self.visible_row_count() else {
-            return;
-        };
         let old_selections: Box<[_]> = self.selections.all::<usize>(cx).into();
         if old_selections.is_empty() {
             return;
@@ -15230,15 +15316,141 @@ impl Editor {
 
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
 
+        let Some(selection_ranges) = self.lsp_selection_ranges(&old_selections, cx) else {
+            self.select_larger_ranges(old_selections, Vec::new(), window, cx);
+            return;
+        };
+        self.select_larger_syntax_node_task = cx.spawn_in(window, async move |editor, cx| {
+            let selection_ranges = selection_ranges.await;
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    // The ranges are stale if the selections were changed while they were queried.
+                    if *editor.selections.all::<usize>(cx) == *old_selections {
+                        editor.select_larger_ranges(old_selections, selection_ranges, window, cx);
+                    }
+                })
+                .ok();
+        });
+    }
+
+    /// Queries the language servers for the selection ranges around the start of each selection.
+    /// Returns `None` if none of the selected buffers has a language server supporting them.
+    fn lsp_selection_ranges(
+        &self,
+        selections: &[Selection<usize>],
+        cx: &mut App,
+    ) -> Option<Task<Vec<(usize, ExcerptId, Vec<Range<text::Anchor>>)>>> {
+        let provider = self.semantics_provider.clone()?;
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut positions_by_buffer =
+            HashMap::<BufferId, (Entity<Buffer>, Vec<(usize, ExcerptId, text::Anchor)>)>::default();
+        for (selection_ix, selection) in selections.iter().enumerate() {
+            let anchor = snapshot.anchor_after(selection.start);
+            let Some(buffer) = anchor
+                .buffer_id
+                .and_then(|buffer_id| multi_buffer.buffer(buffer_id))
+            else {
+                continue;
+            };
+            positions_by_buffer
+                .entry(buffer.read(cx).remote_id())
+                .or_insert_with(|| (buffer, Vec::new()))
+                .1
+                .push((selection_ix, anchor.excerpt_id, anchor.text_anchor));
+        }
+
+        let tasks = positions_by_buffer
+            .into_values()
+            .filter_map(|(buffer, positions)| {
+                let task = provider.selection_ranges(
+                    &buffer,
+                    positions.iter().map(|(_, _, position)| *position).collect(),
+                    cx,
+                )?;
+                Some(async move {
+                    let ranges = task.await.log_err().unwrap_or_default();
+                    positions
+                        .into_iter()
+                        .zip(ranges)
+                        .map(|((selection_ix, excerpt_id, _), ranges)| {
+                            (selection_ix, excerpt_id, ranges)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        if tasks.is_empty() {
+            return None;
+        }
+        Some(cx.background_spawn(async move {
+            future::join_all(tasks)
+                .await
+                .into_iter()
+                .flatten()
+                .collect()
+        }))
+    }
+
+    /// Expands each selection to the smallest language server selection range that contains it,
+    /// falling back to the enclosing syntax node for selections the servers reported no range for.
+    fn select_larger_ranges(
+        &mut self,
+        old_selections: Box<[Selection<usize>]>,
+        lsp_selection_ranges: Vec<(usize, ExcerptId, Vec<Range<text::Anchor>>)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(visible_row_count) = self.visible_row_count() else {
+            return;
+        };
+
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
+        let mut lsp_ranges_by_selection = HashMap::<usize, Vec<Range<usize>>>::default();
+        for (selection_ix, excerpt_id, ranges) in lsp_selection_ranges {
+            lsp_ranges_by_selection.insert(
+                selection_ix,
+                ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        let start = buffer.anchor_in_excerpt(excerpt_id, range.start)?;
+                        let end = buffer.anchor_in_excerpt(excerpt_id, range.end)?;
+                        Some(start.to_offset(&buffer)..end.to_offset(&buffer))
+                    })
+                    .collect(),
+            );
+        }
+
         let mut selected_larger_node = false;
         let mut new_selections = old_selections
             .iter()
-            .map(|selection| {
+            .enumerate()
+            .map(|(selection_ix, selection)| {
                 let old_range = selection.start..selection.end;
 
+                if let Some(lsp_range) = lsp_ranges_by_selection
+                    .get(&selection_ix)
+                    .into_iter()
+                    .flatten()
+                    .filter(|range| {
+                        range.start <= old_range.start
+                            && old_range.end <= range.end
+                            && **range != old_range
+                    })
+                    .min_by_key(|range| range.len())
+                {
+                    selected_larger_node = true;
+                    return Selection {
+                        id: selection.id,
+                        start: lsp_range.start,
+                        end: lsp_range.end,
+                        goal: SelectionGoal::None,
+                        reversed: selection.reversed,
+                    };
+                }
+
                 if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
                     // manually select word at selection
                     if ["string_content", "inline"].contains(&node.kind()) {
@@ -18001,6 +18213,16 @@ impl Editor {
         cx.notify();
     }
 
+    /// Whether motions extend the selection from the mark rather than moving the cursor.
+    pub fn selection_mark_mode(&self) -> bool {
+        self.selection_mark_mode
+    }
+
+    pub fn set_selection_mark_mode(&mut self, selection_mark_mode: bool, cx: &mut Context<Self>) {
+        self.selection_mark_mode = selection_mark_mode;
+        cx.notify();
+    }
+
     pub fn swap_selection_ends(
         &mut self,
         _: &actions::SwapSelectionEnds,
@@ -21134,6 +21356,7 @@ impl Editor {
             }
             self.refresh_colors(false, None, window, cx);
         }
+        self.refresh_semantic_tokens(false, None, window, cx);
 
         cx.notify();
     }
@@ -22051,6 +22274,8 @@ impl Editor {
     ) {
         self.pull_diagnostics(for_buffer, window, cx);
         self.refresh_colors(ignore_cache, for_buffer, window, cx);
+        self.refresh_semantic_tokens(ignore_cache, for_buffer, window, cx);
+        self.refresh_folding_ranges(ignore_cache, for_buffer, window, cx);
     }
 }
 
@@ -22798,6 +23023,27 @@ pub trait SemanticsProvider {
         new_name: String,
         cx: &mut App,
     ) -> Option<Task<Result<ProjectTransaction>>>;
+
+    /// Returns `None` if no language server of the buffer supports selection ranges.
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>>;
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: DocumentLink,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<DocumentLinkTarget>>>>;
 }
 
 pub trait CompletionProvider {
@@ -23328,6 +23574,39 @@ impl SemanticsProvider for Entity<Project> {
             project.perform_rename(buffer.clone(), position, new_name, cx)
         }))
     }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        self.update(cx, |project, cx| {
+            let supported = buffer.update(cx, |buffer, cx| {
+                project.any_language_server_supports_selection_ranges(buffer, cx)
+            });
+            supported.then(|| project.selection_ranges(buffer, positions, cx))
+        })
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: DocumentLink,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<DocumentLinkTarget>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(buffer, link, cx)
+        }))
+    }
 }
 
 fn inlay_hint_settings(
@@ -23662,7 +23941,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_folding_range(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
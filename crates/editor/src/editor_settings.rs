@@ -28,6 +28,7 @@ pub struct EditorSettings {
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
     pub minimap: Minimap,
+    pub sticky_scroll: StickyScroll,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f64,
@@ -115,6 +116,12 @@ impl Minimap {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_depth: usize,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Gutter {
     pub min_line_number_digits: usize,
@@ -180,6 +187,7 @@ impl Settings for EditorSettings {
         let editor = content.editor.clone();
         let scrollbar = editor.scrollbar.unwrap();
         let minimap = editor.minimap.unwrap();
+        let sticky_scroll = editor.sticky_scroll.unwrap();
         let gutter = editor.gutter.unwrap();
         let axes = scrollbar.axes.unwrap();
         let toolbar = editor.toolbar.unwrap();
@@ -222,6 +230,10 @@ impl Settings for EditorSettings {
                 current_line_highlight: minimap.current_line_highlight,
                 max_width_columns: minimap.max_width_columns.unwrap(),
             },
+            sticky_scroll: StickyScroll {
+                enabled: sticky_scroll.enabled.unwrap(),
+                max_depth: sticky_scroll.max_depth.unwrap() as usize,
+            },
             gutter: Gutter {
                 min_line_number_digits: gutter.min_line_number_digits.unwrap(),
                 line_numbers: gutter.line_numbers.unwrap(),
@@ -473,5 +485,15 @@ impl Settings for EditorSettings {
         if minimap != settings::MinimapContent::default() {
             current.editor.minimap = Some(minimap)
         }
+
+        let mut sticky_scroll = settings::StickyScrollContent::default();
+        vscode.bool_setting("editor.stickyScroll.enabled", &mut sticky_scroll.enabled);
+        vscode.u32_setting(
+            "editor.stickyScroll.maxLineCount",
+            &mut sticky_scroll.max_depth,
+        );
+        if sticky_scroll != settings::StickyScrollContent::default() {
+            current.editor.sticky_scroll = Some(sticky_scroll)
+        }
     }
 }
@@ -9301,6 +9301,92 @@ async fn test_select_larger_smaller_syntax_node_for_string(cx: &mut TestAppConte
     });
 }
 
+#[gpui::test]
+async fn test_select_larger_smaller_lsp_selection_range(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    // The server only knows the ranges on the second line: from the word straight to the
+    // whole statement. Every other selection falls back to the syntax nodes.
+    cx.lsp
+        .set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(|params, _| async move {
+            Ok(Some(
+                params
+                    .positions
+                    .into_iter()
+                    .map(|position| {
+                        if position.line != 1 {
+                            return lsp::SelectionRange {
+                                range: lsp::Range::new(position, position),
+                                parent: None,
+                            };
+                        }
+                        lsp::SelectionRange {
+                            range: lsp::Range::new(
+                                lsp::Position::new(1, 12),
+                                lsp::Position::new(1, 15),
+                            ),
+                            parent: Some(Box::new(lsp::SelectionRange {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(1, 4),
+                                    lsp::Position::new(1, 22),
+                                ),
+                                parent: None,
+                            })),
+                        }
+                    })
+                    .collect(),
+            ))
+        });
+
+    cx.set_state(indoc! {"
+        fn main() {
+            let a = foˇo(1, 2);
+            let b = baˇr(3);
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «fooˇ»(1, 2);
+            let b = «ˇbar»(3);
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            «let a = foo(1, 2);ˇ»
+            let b = «bar(3)ˇ»;
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            let a = «fooˇ»(1, 2);
+            let b = «ˇbar»(3);
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_unwrap_syntax_nodes(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -25686,6 +25772,103 @@ async fn test_add_selection_after_moving_with_multiple_cursors(cx: &mut TestAppC
     );
 }
 
+#[gpui::test]
+async fn test_lsp_folding_ranges(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": indoc! {"
+                fn main() {
+                    // #region setup
+                    let a = 1;
+                    let b = 2;
+                    // #endregion
+                }
+            "},
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let editor = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_abs_path(
+                PathBuf::from(path!("/a/main.rs")),
+                OpenOptions::default(),
+                window,
+                cx,
+            )
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let mut folding_range_requests = fake_server
+        .set_request_handler::<lsp::request::FoldingRangeRequest, _, _>(|_, _| async move {
+            Ok(Some(vec![lsp::FoldingRange {
+                start_line: 1,
+                start_character: None,
+                end_line: 4,
+                end_character: None,
+                kind: Some(lsp::FoldingRangeKind::Region),
+                collapsed_text: None,
+            }]))
+        });
+
+    editor.update_in(cx, |editor, window, cx| {
+        let snapshot = editor.snapshot(window, cx);
+        assert!(snapshot.starts_folding_range(MultiBufferRow(0)));
+        assert!(
+            !snapshot.starts_folding_range(MultiBufferRow(1)),
+            "Regions are not foldable by indentation"
+        );
+    });
+
+    cx.executor().advance_clock(FETCH_FOLDING_RANGES_DEBOUNCE_TIMEOUT);
+    folding_range_requests.next().await.unwrap();
+    cx.run_until_parked();
+
+    editor.update_in(cx, |editor, window, cx| {
+        let snapshot = editor.snapshot(window, cx);
+        assert!(
+            !snapshot.starts_folding_range(MultiBufferRow(0)),
+            "Server folding ranges should supersede the indentation based ones"
+        );
+        assert!(snapshot.starts_folding_range(MultiBufferRow(1)));
+
+        editor.fold_at(MultiBufferRow(1), window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            indoc! {"
+                fn main() {
+                    // #region setup⋯
+                }
+            "}
+        );
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_document_colors(cx: &mut TestAppContext) {
     let expected_color = Rgba {
@@ -26642,3 +26825,85 @@ async fn test_copy_line_without_trailing_newline(cx: &mut TestAppContext) {
 
     cx.assert_editor_state("line1\nline2\nˇ");
 }
+
+#[gpui::test]
+async fn test_sticky_scroll_items(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"
+                (impl_item "impl" @context type: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        ˇimpl Foo {
+            fn one() {
+                a();
+                b();
+            }
+
+            fn two() {
+                c();
+            }
+        }
+        fn three() {}
+    "});
+    cx.run_until_parked();
+
+    let mut sticky_scroll_items = |scroll_top: f64| {
+        cx.update_editor(|editor, window, cx| {
+            editor
+                .snapshot(window, cx)
+                .sticky_scroll_items(scroll_top, 5, None)
+                .into_iter()
+                .map(|item| (item.outline_item.text, item.offset))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Scopes are only pinned once their first line is scrolled out of view.
+    assert_eq!(sticky_scroll_items(0.), vec![]);
+    assert_eq!(
+        sticky_scroll_items(1.),
+        vec![("impl Foo".to_string(), 0.), ("fn one".to_string(), 1.)]
+    );
+    assert_eq!(
+        sticky_scroll_items(3.),
+        vec![("impl Foo".to_string(), 0.), ("fn one".to_string(), 1.)]
+    );
+
+    // The end of a scope pushes its pinned line up.
+    assert_eq!(
+        sticky_scroll_items(3.5),
+        vec![("impl Foo".to_string(), 0.), ("fn one".to_string(), 0.5)]
+    );
+    assert_eq!(sticky_scroll_items(5.), vec![("impl Foo".to_string(), 0.)]);
+    assert_eq!(
+        sticky_scroll_items(7.),
+        vec![("impl Foo".to_string(), 0.), ("fn two".to_string(), 1.)]
+    );
+    assert_eq!(
+        sticky_scroll_items(9.5),
+        vec![("impl Foo".to_string(), -0.5)]
+    );
+    assert_eq!(sticky_scroll_items(10.), vec![]);
+
+    // The depth is limited.
+    cx.update_editor(|editor, window, cx| {
+        let items = editor.snapshot(window, cx).sticky_scroll_items(3., 1, None);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].outline_item.text, "impl Foo");
+        assert_eq!(items[0].row, DisplayRow(0));
+    });
+}
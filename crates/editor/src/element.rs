@@ -8,8 +8,8 @@ use crate::{
     HandleInput, HoveredCursor, InlayHintRefreshReason, JumpData, LineDown, LineHighlight, LineUp,
     MAX_LINE_LEN, MINIMAP_FONT_SIZE, MULTI_BUFFER_EXCERPT_HEADER_HEIGHT, OpenExcerpts, PageDown,
     PageUp, PhantomBreakpointIndicator, Point, RowExt, RowRangeExt, SelectPhase,
-    SelectedTextHighlight, Selection, SelectionDragState, SoftWrap, StickyHeaderExcerpt, ToPoint,
-    ToggleFold, ToggleFoldAll,
+    SelectedTextHighlight, Selection, SelectionDragState, SelectionEffects, SoftWrap,
+    StickyHeaderExcerpt, ToPoint, ToggleFold, ToggleFoldAll,
     code_context_menus::{CodeActionsMenu, MENU_ASIDE_MAX_WIDTH, MENU_ASIDE_MIN_WIDTH, MENU_GAP},
     display_map::{
         Block, BlockContext, BlockStyle, ChunkRendererId, DisplaySnapshot, EditorMargins,
@@ -29,7 +29,7 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition},
     scroll::{
-        ActiveScrollbarState, ScrollOffset, ScrollPixelOffset, ScrollbarThumbState,
+        ActiveScrollbarState, Autoscroll, ScrollOffset, ScrollPixelOffset, ScrollbarThumbState,
         scroll_amount::ScrollAmount,
     },
 };
@@ -49,9 +49,9 @@ use gpui::{
     KeybindingKeystroke, Length, Modifiers, ModifiersChangedEvent, MouseButton, MouseClickEvent,
     MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta,
     ScrollHandle, ScrollWheelEvent, ShapedLine, SharedString, Size, StatefulInteractiveElement,
-    Style, Styled, TextRun, TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill,
-    linear_color_stop, linear_gradient, outline, point, px, quad, relative, size, solid_background,
-    transparent_black,
+    Style, Styled, StyledText, TextRun, TextStyleRefinement, WeakEntity, Window, anchored,
+    deferred, div, fill, linear_color_stop, linear_gradient, outline, point, px, quad, relative,
+    size, solid_background, transparent_black,
 };
 use itertools::Itertools;
 use language::{IndentGuideSettings, language_settings::ShowWhitespaceSetting};
@@ -280,6 +280,8 @@ impl EditorElement {
         register_action(editor, window, Editor::paste);
         register_action(editor, window, Editor::undo);
         register_action(editor, window, Editor::redo);
+        register_action(editor, window, Editor::undo_earlier);
+        register_action(editor, window, Editor::undo_later);
         register_action(editor, window, Editor::move_page_up);
         register_action(editor, window, Editor::move_page_down);
         register_action(editor, window, Editor::next_screen);
@@ -4453,6 +4455,127 @@ impl EditorElement {
         header
     }
 
+    fn layout_sticky_scroll(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<ScrollOffset>,
+        scroll_pixel_position: gpui::Point<ScrollPixelOffset>,
+        has_sticky_buffer_header: bool,
+        line_height: Pixels,
+        em_advance: Pixels,
+        right_margin: Pixels,
+        gutter_dimensions: &GutterDimensions,
+        hitbox: &Hitbox,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
+        let settings = EditorSettings::get_global(cx);
+        if !settings.sticky_scroll.enabled || !snapshot.mode.is_full() {
+            return None;
+        }
+        // In multibuffers, the scopes are pinned below the header of the buffer at the top.
+        let header_height = if has_sticky_buffer_header {
+            FILE_HEADER_HEIGHT
+        } else {
+            0
+        };
+        let items = snapshot.sticky_scroll_items(
+            scroll_position.y + header_height as f64,
+            settings.sticky_scroll.max_depth,
+            Some(cx.theme().syntax()),
+        );
+        let last_item = items.last()?;
+        let height = (last_item.offset + 1.).max(0.);
+
+        let include_line_numbers = snapshot
+            .show_line_numbers
+            .unwrap_or(settings.gutter.line_numbers);
+        let colors = cx.theme().colors();
+        let available_width = hitbox.bounds.size.width - right_margin;
+        let text_left = gutter_dimensions.width + gutter_dimensions.margin
+            - Pixels::from(scroll_pixel_position.x);
+
+        let mut sticky_scroll = div()
+            .id("sticky-scroll")
+            .relative()
+            .w(available_width)
+            .h(Pixels::from(height * ScrollPixelOffset::from(line_height)))
+            .overflow_hidden()
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .children(items.into_iter().enumerate().map(|(depth, item)| {
+                let line_number = include_line_numbers
+                    .then(|| snapshot.row_infos(item.row).next()?.buffer_row)
+                    .flatten()
+                    .map(|row| (row + 1).to_string());
+                let start = item.outline_item.range.start;
+                let column = start.to_display_point(snapshot).column();
+                let editor = self.editor.clone();
+
+                h_flex()
+                    .id(("sticky-scroll-item", depth))
+                    .absolute()
+                    .left_0()
+                    .top(Pixels::from(
+                        item.offset * ScrollPixelOffset::from(line_height),
+                    ))
+                    .w_full()
+                    .h(line_height)
+                    .bg(colors.editor_background)
+                    .cursor_pointer()
+                    .hover(|style| style.bg(colors.editor_active_line_background))
+                    .block_mouse_except_scroll()
+                    .child(
+                        h_flex()
+                            .w(gutter_dimensions.width)
+                            .h_full()
+                            .flex_none()
+                            .justify_end()
+                            .pr(gutter_dimensions.right_padding)
+                            .font_family(self.style.text.font().family)
+                            .text_color(colors.editor_line_number)
+                            .children(line_number),
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left(text_left + column as f32 * em_advance)
+                            .h_full()
+                            .child(
+                                StyledText::new(item.outline_item.text).with_default_highlights(
+                                    &self.style.text,
+                                    item.outline_item.highlight_ranges,
+                                ),
+                            ),
+                    )
+                    .on_mouse_down(MouseButton::Left, move |_, window, cx| {
+                        cx.stop_propagation();
+                        editor.update(cx, |editor, cx| {
+                            // Keep the line below the pinned lines of the scopes enclosing it.
+                            let autoscroll =
+                                Autoscroll::top_relative(depth + header_height as usize);
+                            editor.change_selections(
+                                SelectionEffects::scroll(autoscroll),
+                                window,
+                                cx,
+                                |s| s.select_anchor_ranges([start..start]),
+                            );
+                            window.focus(&editor.focus_handle(cx));
+                        });
+                    })
+            }))
+            .into_any_element();
+
+        let origin = hitbox.origin + point(Pixels::ZERO, header_height as f32 * line_height);
+        let size = size(
+            AvailableSpace::Definite(available_width),
+            AvailableSpace::MinContent,
+        );
+        sticky_scroll.prepaint_as_root(origin, size, window, cx);
+        Some(sticky_scroll)
+    }
+
     fn layout_cursor_popovers(
         &self,
         line_height: Pixels,
@@ -9113,6 +9236,21 @@ impl Element for EditorElement {
                         scroll_position.x * f64::from(em_advance),
                         scroll_position.y * f64::from(line_height),
                     );
+                    let sticky_scroll = window.with_element_namespace("sticky_scroll", |window| {
+                        self.layout_sticky_scroll(
+                            &snapshot,
+                            scroll_position,
+                            scroll_pixel_position,
+                            sticky_buffer_header.is_some(),
+                            line_height,
+                            em_advance,
+                            right_margin,
+                            &gutter_dimensions,
+                            &hitbox,
+                            window,
+                            cx,
+                        )
+                    });
                     let indent_guides = self.layout_indent_guides(
                         content_origin,
                         text_hitbox.origin,
@@ -9586,6 +9724,7 @@ impl Element for EditorElement {
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scroll,
                         expand_toggles,
                     }
                 })
@@ -9650,6 +9789,12 @@ impl Element for EditorElement {
                         });
                     }
 
+                    window.with_element_namespace("sticky_scroll", |window| {
+                        if let Some(mut sticky_scroll) = layout.sticky_scroll.take() {
+                            sticky_scroll.paint(window, cx)
+                        }
+                    });
+
                     window.with_element_namespace("blocks", |window| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(window, cx)
@@ -9764,6 +9909,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scroll: Option<AnyElement>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
@@ -1,7 +1,7 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToDefinitionSplit, GoToTypeDefinition, GoToTypeDefinitionSplit, GotoDefinitionKind, InlayId,
-    Navigated, PointForPosition, SelectPhase,
+    Navigated, PointForPosition, SelectPhase, SemanticsProvider,
     editor_settings::GoToDefinitionFallback,
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
@@ -11,11 +11,11 @@ use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink, Project,
-    ResolveState, ResolvedPath,
+    DocumentLinkTarget, HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip,
+    Location, LocationLink, Project, ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc};
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
@@ -549,6 +549,16 @@ pub fn show_link_definition(
                             (range, vec![HoverLink::Url(url)])
                         })
                         .ok()
+                    } else if let Some((link_range, link)) =
+                        find_document_link(&buffer, provider.clone(), buffer_position, cx).await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+
+                        Some((range, vec![link]))
                     } else if let Some((filename_range, filename)) =
                         find_file(&buffer, project.clone(), buffer_position, cx).await
                     {
@@ -778,6 +788,45 @@ pub(crate) fn find_url_from_range(
     None
 }
 
+/// Finds the language server document link at the position and resolves where it points to.
+async fn find_document_link(
+    buffer: &Entity<language::Buffer>,
+    provider: Option<Rc<dyn SemanticsProvider>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let provider = provider?;
+    let links = cx
+        .update(|_, cx| provider.document_links(buffer, cx))
+        .ok()??
+        .await
+        .log_err()?;
+    let link = buffer
+        .read_with(cx, |buffer, _| {
+            links.into_iter().find(|link| {
+                link.range.start.cmp(&position, buffer).is_le()
+                    && link.range.end.cmp(&position, buffer).is_ge()
+            })
+        })
+        .ok()??;
+    let target = cx
+        .update(|_, cx| provider.resolve_document_link(buffer, link.clone(), cx))
+        .ok()??
+        .await
+        .log_err()??;
+    let hover_link = match target {
+        DocumentLinkTarget::Url(url) => HoverLink::Url(url),
+        DocumentLinkTarget::Location(target) => HoverLink::Text(LocationLink {
+            origin: Some(Location {
+                buffer: buffer.clone(),
+                range: link.range.clone(),
+            }),
+            target,
+        }),
+    };
+    Some((link.range, hover_link))
+}
+
 pub(crate) async fn find_file(
     buffer: &Entity<language::Buffer>,
     project: Option<Entity<Project>>,
@@ -931,6 +980,7 @@ mod tests {
     use indoc::indoc;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
     use settings::InlayHintSettingsContent;
+    use std::str::FromStr as _;
     use util::{assert_set_eq, path};
     use workspace::item::Item;
 
@@ -1507,6 +1557,105 @@ mod tests {
         assert_eq!(cx.opened_url(), Some("https://zed.dev/releases".into()));
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            mod docs;
+            use serde;ˇ
+        "});
+        let docs_range = cx.lsp_range(indoc! {"
+            mod «docs»;
+            use serde;
+        "});
+        let serde_range = cx.lsp_range(indoc! {"
+            mod docs;
+            use «serde»;
+        "});
+
+        let mut link_requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |url, _, _| async move {
+                Ok(Some(vec![
+                    lsp::DocumentLink {
+                        range: docs_range,
+                        target: Some(
+                            lsp::Uri::from_str(&format!("{}#L2,5", url.as_str())).unwrap(),
+                        ),
+                        tooltip: None,
+                        data: None,
+                    },
+                    lsp::DocumentLink {
+                        range: serde_range,
+                        target: None,
+                        tooltip: None,
+                        data: Some(serde_json::json!("serde")),
+                    },
+                ]))
+            },
+        );
+        let mut resolve_requests = cx
+            .set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+                move |_, mut link, _| async move {
+                    assert_eq!(link.data, Some(serde_json::json!("serde")));
+                    link.target = Some(lsp::Uri::from_str("https://docs.rs/serde").unwrap());
+                    Ok(link)
+                },
+            );
+
+        // Links without a target get it resolved by the language server.
+        let screen_coord = cx.pixel_position(indoc! {"
+            mod docs;
+            use seˇrde;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        link_requests.next().await;
+        resolve_requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            mod docs;
+            use «serdeˇ»;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+
+        // File targets are opened at the position in their fragment.
+        // The links of the unchanged buffer are reused.
+        let screen_coord = cx.pixel_position(indoc! {"
+            mod doˇcs;
+            use serde;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            mod «docsˇ»;
+            use serde;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            mod docs;
+            use ˇserde;
+        "});
+        assert!(
+            link_requests.try_next().is_err(),
+            "Document links should not be refetched for the same buffer version"
+        );
+    }
+
     #[gpui::test]
     async fn test_surrounding_filename(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
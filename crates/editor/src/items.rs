@@ -1602,9 +1602,10 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let text = snapshot
+            .text_for_range(identifier.clone())
+            .collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
         } else {
@@ -1612,7 +1613,8 @@ impl SearchableItem for Editor {
             joined_chunks.into()
         };
 
-        if let Some(replacement) = query.replacement_for(&text) {
+        let language = snapshot.language_at(identifier.start);
+        if let Some(replacement) = query.replacement_for_language(&text, language) {
             self.transact(window, cx, |this, _, cx| {
                 this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
             });
@@ -1625,12 +1627,11 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
         let mut edits = vec![];
 
         for m in matches {
-            let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
+            let text = snapshot.text_for_range(m.clone()).collect::<Vec<_>>();
 
             let text: Cow<_> = if text.len() == 1 {
                 text.first().cloned().unwrap().into()
@@ -1639,7 +1640,8 @@ impl SearchableItem for Editor {
                 joined_chunks.into()
             };
 
-            if let Some(replacement) = query.replacement_for(&text) {
+            let language = snapshot.language_at(m.start);
+            if let Some(replacement) = query.replacement_for_language(&text, language) {
                 edits.push((m.clone(), Arc::from(&*replacement)));
             }
         }
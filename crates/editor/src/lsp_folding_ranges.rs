@@ -0,0 +1,95 @@
+use futures::future::join_all;
+use itertools::Itertools;
+use project::lsp_store::LspFetchStrategy;
+use text::BufferId;
+use ui::{Context, Window};
+
+use crate::{Editor, FETCH_FOLDING_RANGES_DEBOUNCE_TIMEOUT};
+
+impl Editor {
+    pub(super) fn refresh_folding_ranges(
+        &mut self,
+        ignore_cache: bool,
+        buffer_id: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode().is_full() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let visible_buffers = self
+            .visible_excerpts(None, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer.read(cx).remote_id())
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let all_ranges_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let fetch_strategy = if ignore_cache {
+                        LspFetchStrategy::IgnoreCache
+                    } else {
+                        LspFetchStrategy::UseCache {
+                            known_cache_version: self
+                                .folding_ranges_cache_versions
+                                .get(&buffer_id)
+                                .copied(),
+                        }
+                    };
+                    let ranges_task = lsp_store.folding_ranges(fetch_strategy, buffer, cx)?;
+                    Some(async move { (buffer_id, ranges_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        self.refresh_folding_ranges_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_FOLDING_RANGES_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let all_ranges = join_all(all_ranges_task).await;
+            if all_ranges.is_empty() {
+                return;
+            }
+            editor
+                .update(cx, |editor, cx| {
+                    let mut updated = false;
+                    for (buffer_id, ranges) in all_ranges {
+                        let ranges = match ranges {
+                            Ok(ranges) => ranges,
+                            Err(e) => {
+                                log::error!("Failed to retrieve folding ranges: {e}");
+                                continue;
+                            }
+                        };
+                        // The buffer got edited while the ranges were fetched, keep the old ranges
+                        // until the next refresh.
+                        let Some(cache_version) = ranges.cache_version else {
+                            continue;
+                        };
+                        editor
+                            .folding_ranges_cache_versions
+                            .insert(buffer_id, cache_version);
+                        editor.display_map.update(cx, |display_map, _| {
+                            display_map.set_folding_ranges(buffer_id, ranges.ranges)
+                        });
+                        updated = true;
+                    }
+                    if updated {
+                        cx.notify();
+                    }
+                })
+                .ok();
+        });
+    }
+}
@@ -5,7 +5,7 @@ use futures::{channel::mpsc, future::join_all};
 use gpui::{App, Entity, EventEmitter, Focusable, Render, Subscription, Task};
 use language::{Buffer, BufferEvent, Capability};
 use multi_buffer::{ExcerptRange, MultiBuffer};
-use project::Project;
+use project::{DocumentLink, DocumentLinkTarget, Project};
 use smol::stream::StreamExt;
 use std::{any::TypeId, ops::Range, rc::Rc, time::Duration};
 use text::ToOffset;
@@ -513,4 +513,31 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
     ) -> Option<Task<anyhow::Result<project::ProjectTransaction>>> {
         None
     }
+
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        let buffer = self.to_base(buffer, &positions, cx)?;
+        self.0.selection_ranges(&buffer, positions, cx)
+    }
+
+    fn document_links(
+        &self,
+        _: &Entity<Buffer>,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<DocumentLink>>>> {
+        None
+    }
+
+    fn resolve_document_link(
+        &self,
+        _: &Entity<Buffer>,
+        _: DocumentLink,
+        _: &mut App,
+    ) -> Option<Task<anyhow::Result<Option<DocumentLinkTarget>>>> {
+        None
+    }
 }
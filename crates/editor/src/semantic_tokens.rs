@@ -0,0 +1,348 @@
+use std::{any::TypeId, ops::Range, sync::Arc};
+
+use collections::{HashMap, HashSet};
+use futures::future::join_all;
+use itertools::Itertools;
+use language::{HighlightId, Unclipped, language_settings::language_settings};
+use multi_buffer::Anchor;
+use project::lsp_store::{LspFetchStrategy, semantic_tokens::SemanticToken};
+use text::{Bias, BufferId, OffsetRangeExt as _};
+use theme::{ActiveTheme as _, SyntaxTheme};
+use ui::{Context, Window};
+
+use crate::{Editor, FETCH_SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT, display_map::HighlightKey};
+
+/// Tag for the text highlights produced from the language servers' semantic tokens.
+/// Every theme syntax style used gets its own highlight key, so that the tokens are
+/// layered over the tree-sitter highlights the same way other text highlights are.
+enum SemanticTokenHighlight {}
+
+#[derive(Debug, Default)]
+pub(super) struct SemanticTokensState {
+    buffer_highlights: HashMap<BufferId, BufferSemanticHighlights>,
+    applied_highlight_ids: HashSet<u32>,
+    syntax_theme: Option<Arc<SyntaxTheme>>,
+}
+
+#[derive(Debug, Default)]
+struct BufferSemanticHighlights {
+    cache_version_used: Option<usize>,
+    highlights: HashMap<u32, Vec<Range<Anchor>>>,
+}
+
+/// Maps a semantic token onto a tree-sitter-like capture name, so that it resolves
+/// to a theme syntax style the same way the `highlights.scm` captures do.
+/// Modifiers are appended as extra components, letting themes style e.g. `variable.mutable`.
+fn semantic_token_capture_name(token: &SemanticToken) -> String {
+    let has_modifier = |modifier: &str| {
+        token
+            .token_modifiers
+            .iter()
+            .any(|token_modifier| token_modifier == modifier)
+    };
+    let base = match token.token_type.as_ref() {
+        "type" | "class" | "struct" | "interface" | "typeAlias" | "typeParameter" => "type",
+        "parameter" => "variable.parameter",
+        "variable" if has_modifier("readonly") => "constant",
+        "property" | "event" => "property",
+        "enumMember" => "variant",
+        "method" => "function.method",
+        "macro" => "function.special",
+        "modifier" => "keyword",
+        "comment" if has_modifier("documentation") => "comment.doc",
+        "regexp" => "string.regex",
+        "decorator" => "attribute",
+        token_type => token_type,
+    };
+    let mut capture_name = base.to_string();
+    for modifier in &token.token_modifiers {
+        capture_name.push('.');
+        capture_name.push_str(modifier);
+    }
+    capture_name
+}
+
+fn semantic_token_highlight_id(token: &SemanticToken, syntax_theme: &SyntaxTheme) -> Option<u32> {
+    let highlight_id =
+        HighlightId::for_capture_name(&semantic_token_capture_name(token), syntax_theme);
+    (!highlight_id.is_default()).then_some(highlight_id.0)
+}
+
+impl Editor {
+    pub(super) fn refresh_semantic_tokens(
+        &mut self,
+        ignore_cache: bool,
+        buffer_id: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.mode().is_full() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let syntax_theme = cx.theme().syntax().clone();
+        if self
+            .semantic_tokens
+            .syntax_theme
+            .as_ref()
+            .is_none_or(|used_theme| !Arc::ptr_eq(used_theme, &syntax_theme))
+        {
+            self.semantic_tokens.syntax_theme = Some(syntax_theme);
+            for buffer_highlights in self.semantic_tokens.buffer_highlights.values_mut() {
+                buffer_highlights.cache_version_used = None;
+            }
+        }
+
+        let visible_buffers = self
+            .visible_excerpts(None, cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer.read(cx).remote_id())
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let mut disabled_buffers = Vec::new();
+        let all_tokens_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let enabled = {
+                        let buffer = buffer.read(cx);
+                        language_settings(
+                            buffer.language().map(|language| language.name()),
+                            buffer.file(),
+                            cx,
+                        )
+                        .semantic_tokens
+                    };
+                    if !enabled {
+                        disabled_buffers.push(buffer_id);
+                        return None;
+                    }
+                    let fetch_strategy = if ignore_cache {
+                        LspFetchStrategy::IgnoreCache
+                    } else {
+                        LspFetchStrategy::UseCache {
+                            known_cache_version: self
+                                .semantic_tokens
+                                .buffer_highlights
+                                .get(&buffer_id)
+                                .and_then(|buffer_highlights| buffer_highlights.cache_version_used),
+                        }
+                    };
+                    let tokens_task = lsp_store.semantic_tokens(fetch_strategy, buffer, cx)?;
+                    Some(async move { (buffer_id, tokens_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut disabled_highlights = false;
+        for buffer_id in disabled_buffers {
+            disabled_highlights |= self
+                .semantic_tokens
+                .buffer_highlights
+                .remove(&buffer_id)
+                .is_some();
+        }
+        if disabled_highlights {
+            self.apply_semantic_token_highlights(cx);
+        }
+
+        self.refresh_semantic_tokens_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_SEMANTIC_TOKENS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let all_tokens = join_all(all_tokens_task).await;
+            if all_tokens.is_empty() {
+                return;
+            }
+            editor
+                .update(cx, |editor, cx| {
+                    let Some(syntax_theme) = editor.semantic_tokens.syntax_theme.clone() else {
+                        return;
+                    };
+                    let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let mut updated = false;
+                    for (buffer_id, tokens) in all_tokens {
+                        let tokens = match tokens {
+                            Ok(tokens) => tokens,
+                            Err(e) => {
+                                log::error!("Failed to retrieve semantic tokens: {e}");
+                                continue;
+                            }
+                        };
+                        // The buffer got edited while the tokens were fetched, keep the old highlights
+                        // until the next refresh to avoid flickering.
+                        if tokens.cache_version.is_none() {
+                            continue;
+                        }
+
+                        let mut highlights = HashMap::<u32, Vec<Range<Anchor>>>::default();
+                        for (excerpt_id, buffer_snapshot, excerpt_range) in multi_buffer_snapshot
+                            .excerpts()
+                            .filter(|(_, buffer_snapshot, _)| {
+                                buffer_snapshot.remote_id() == buffer_id
+                            })
+                        {
+                            let excerpt_range =
+                                excerpt_range.context.to_point_utf16(buffer_snapshot);
+                            for token in tokens.tokens.values().flat_map(|tokens| tokens.iter()) {
+                                if token.range.start < excerpt_range.start
+                                    || token.range.end > excerpt_range.end
+                                {
+                                    continue;
+                                }
+                                let Some(highlight_id) =
+                                    semantic_token_highlight_id(token, &syntax_theme)
+                                else {
+                                    continue;
+                                };
+                                let Some(start) = multi_buffer_snapshot.anchor_in_excerpt(
+                                    excerpt_id,
+                                    buffer_snapshot.anchor_after(buffer_snapshot.clip_point_utf16(
+                                        Unclipped(token.range.start),
+                                        Bias::Left,
+                                    )),
+                                ) else {
+                                    continue;
+                                };
+                                let Some(end) = multi_buffer_snapshot.anchor_in_excerpt(
+                                    excerpt_id,
+                                    buffer_snapshot.anchor_before(
+                                        buffer_snapshot.clip_point_utf16(
+                                            Unclipped(token.range.end),
+                                            Bias::Right,
+                                        ),
+                                    ),
+                                ) else {
+                                    continue;
+                                };
+                                highlights.entry(highlight_id).or_default().push(start..end);
+                            }
+                        }
+
+                        let buffer_highlights = editor
+                            .semantic_tokens
+                            .buffer_highlights
+                            .entry(buffer_id)
+                            .or_default();
+                        buffer_highlights.cache_version_used = tokens.cache_version;
+                        buffer_highlights.highlights = highlights;
+                        updated = true;
+                    }
+
+                    if updated {
+                        editor.apply_semantic_token_highlights(cx);
+                    }
+                })
+                .ok();
+        });
+    }
+
+    fn apply_semantic_token_highlights(&mut self, cx: &mut Context<Self>) {
+        let Some(syntax_theme) = self.semantic_tokens.syntax_theme.clone() else {
+            return;
+        };
+        let multi_buffer_snapshot = self.buffer().read(cx).snapshot(cx);
+        let mut all_highlights = HashMap::<u32, Vec<Range<Anchor>>>::default();
+        for buffer_highlights in self.semantic_tokens.buffer_highlights.values() {
+            for (highlight_id, ranges) in &buffer_highlights.highlights {
+                all_highlights
+                    .entry(*highlight_id)
+                    .or_default()
+                    .extend(ranges.iter().cloned());
+            }
+        }
+
+        let stale_highlight_ids = self
+            .semantic_tokens
+            .applied_highlight_ids
+            .iter()
+            .filter(|highlight_id| !all_highlights.contains_key(highlight_id))
+            .copied()
+            .collect::<Vec<_>>();
+        self.semantic_tokens.applied_highlight_ids = all_highlights.keys().copied().collect();
+        self.display_map.update(cx, |display_map, _| {
+            for highlight_id in stale_highlight_ids {
+                display_map.remove_text_highlights(HighlightKey::TypePlus(
+                    TypeId::of::<SemanticTokenHighlight>(),
+                    highlight_id as usize,
+                ));
+            }
+            for (highlight_id, mut ranges) in all_highlights {
+                let Some(style) = HighlightId(highlight_id).style(&syntax_theme) else {
+                    continue;
+                };
+                ranges.sort_by(|a, b| {
+                    a.start
+                        .cmp(&b.start, &multi_buffer_snapshot)
+                        .then_with(|| a.end.cmp(&b.end, &multi_buffer_snapshot))
+                });
+                display_map.highlight_text(
+                    HighlightKey::TypePlus(
+                        TypeId::of::<SemanticTokenHighlight>(),
+                        highlight_id as usize,
+                    ),
+                    ranges,
+                    style,
+                );
+            }
+        });
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(token_type: &'static str, token_modifiers: &[&'static str]) -> SemanticToken {
+        SemanticToken {
+            range: Default::default(),
+            token_type: token_type.into(),
+            token_modifiers: token_modifiers
+                .iter()
+                .map(|modifier| (*modifier).into())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_semantic_token_theme_mapping() {
+        let syntax_theme = SyntaxTheme::new_test([
+            ("function", gpui::red()),
+            ("function.method", gpui::green()),
+            ("variable", gpui::blue()),
+            ("variable.mutable", gpui::yellow()),
+            ("constant", gpui::black()),
+        ]);
+        let style_name = |token: SemanticToken| {
+            semantic_token_highlight_id(&token, &syntax_theme)
+                .and_then(|highlight_id| HighlightId(highlight_id).name(&syntax_theme))
+        };
+
+        assert_eq!(style_name(token("function", &[])), Some("function"));
+        assert_eq!(
+            style_name(token("method", &["declaration"])),
+            Some("function.method")
+        );
+        assert_eq!(style_name(token("variable", &[])), Some("variable"));
+        assert_eq!(
+            style_name(token("variable", &["mutable"])),
+            Some("variable.mutable")
+        );
+        assert_eq!(
+            style_name(token("variable", &["readonly"])),
+            Some("constant")
+        );
+        assert_eq!(style_name(token("parameter", &[])), Some("variable"));
+        assert_eq!(style_name(token("unresolvedReference", &[])), None);
+    }
+}
@@ -0,0 +1,78 @@
+use language::{OutlineItem, Point};
+use multi_buffer::{Anchor, MultiBufferRow, ToPoint as _};
+use theme::SyntaxTheme;
+
+use crate::{DisplayPoint, DisplayRow, EditorSnapshot, RowExt as _, display_map::ToDisplayPoint};
+
+/// An outline item enclosing the top of the editor, whose first line is pinned there while its
+/// body is scrolled through.
+#[derive(Clone, Debug)]
+pub(crate) struct StickyScrollItem {
+    pub outline_item: OutlineItem<Anchor>,
+    /// The display row the item starts on.
+    pub row: DisplayRow,
+    /// Where the item's line is drawn, in lines from the top of the sticky area. This is less
+    /// than the item's depth while the end of its scope pushes it up and out of the way.
+    pub offset: f64,
+}
+
+impl EditorSnapshot {
+    /// Returns the outline items whose first lines are pinned when the editor is scrolled to
+    /// `scroll_top`, outermost first.
+    ///
+    /// Each pinned line covers a line of the editor, and the scope enclosing that line is pinned
+    /// below it, if its first line is hidden. In multibuffers, only the scopes of the excerpt at
+    /// the top are pinned.
+    pub(crate) fn sticky_scroll_items(
+        &self,
+        scroll_top: f64,
+        max_depth: usize,
+        theme: Option<&SyntaxTheme>,
+    ) -> Vec<StickyScrollItem> {
+        let buffer = self.buffer_snapshot();
+        let max_row = self.max_point().row();
+        let mut items = Vec::<StickyScrollItem>::new();
+        for depth in 0..max_depth {
+            let covered_row = DisplayRow((scroll_top + depth as f64) as u32);
+            if covered_row > max_row {
+                break;
+            }
+            let row = DisplayPoint::new(covered_row, 0).to_point(self).row;
+            let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+            let Some((_, mut scopes)) = buffer.symbols_containing(line_end, theme) else {
+                break;
+            };
+            scopes.retain(|scope| {
+                scope.range.start.to_point(buffer).row <= row
+                    && scope.range.end.to_point(buffer).row >= row
+            });
+
+            // The line must be enclosed by the scopes that are already pinned, and one more.
+            if scopes.len() <= depth
+                || items
+                    .iter()
+                    .zip(&scopes)
+                    .any(|(item, scope)| item.outline_item.range != scope.range)
+            {
+                break;
+            }
+            let scope = scopes.swap_remove(depth);
+            let start_row = scope.range.start.to_display_point(self).row();
+            if start_row.as_f64() >= scroll_top + depth as f64 {
+                break;
+            }
+
+            let end_row = scope.range.end.to_display_point(self).row();
+            let mut offset = (depth as f64).min(end_row.as_f64() - scroll_top);
+            if let Some(parent) = items.last() {
+                offset = offset.min(parent.offset + 1.);
+            }
+            items.push(StickyScrollItem {
+                outline_item: scope,
+                row: start_row,
+                offset,
+            });
+        }
+        items
+    }
+}
@@ -0,0 +1,376 @@
+//! Emacs support for Zed.
+//!
+//! The `emacs` base keymap binds keys to these actions, which keep the state that Emacs commands
+//! share: the kill ring, the mark ring, the numeric argument typed before a command and the
+//! command that ran last.
+
+#[cfg(test)]
+mod test;
+
+mod isearch;
+mod kill_ring;
+mod mark;
+
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use editor::{Anchor, Editor};
+use gpui::{
+    Action, App, AppContext, Context, Entity, IntoElement, KeyContext, KeystrokeEvent, Render,
+    Subscription, WeakEntity, Window, actions,
+};
+use isearch::Isearch;
+use kill_ring::KillRing;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use settings::{BaseKeymap, Settings, SettingsStore};
+use vim_mode_setting::{HelixModeSetting, VimModeSetting};
+use workspace::Workspace;
+
+actions!(
+    emacs,
+    [
+        /// Begins a numeric argument for the next command, or multiplies it by four if one is
+        /// already being typed.
+        UniversalArgument,
+        /// Negates the numeric argument for the next command.
+        NegativeArgument,
+        /// Kills the rest of the line, or the newline if only whitespace follows the cursor.
+        KillLine,
+        /// Kills the text between the mark and the cursor.
+        KillRegion,
+        /// Adds the text between the mark and the cursor to the kill ring without deleting it.
+        KillRingSave,
+        /// Kills to the end of the next word.
+        KillWord,
+        /// Kills to the start of the previous word.
+        BackwardKillWord,
+        /// Inserts the most recently killed text.
+        Yank,
+        /// Replaces the text that was just yanked with an earlier kill.
+        YankPop,
+        /// Sets the mark at the cursor, or jumps to the previous mark with a numeric argument.
+        SetMark,
+        /// Moves the cursor to the mark and the mark to where the cursor was.
+        ExchangePointAndMark,
+        /// Searches forward as the query is typed, or moves to the next match.
+        IsearchForward,
+        /// Searches backward as the query is typed, or moves to the previous match.
+        IsearchBackward,
+        /// Ends the search, leaving the cursor at the match and the mark where the search began.
+        IsearchExit,
+        /// Ends the search, returning the cursor to where the search began.
+        IsearchAbort
+    ]
+);
+
+/// Adds a digit to the numeric argument for the next command.
+#[derive(Clone, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = emacs)]
+struct DigitArgument(u32);
+
+pub fn init(cx: &mut App) {
+    vim_mode_setting::init(cx);
+    BaseKeymap::register(cx);
+    cx.set_global(KillRing::default());
+
+    cx.observe_new(Emacs::register).detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| isearch::register(workspace))
+        .detach();
+}
+
+#[derive(Clone)]
+pub(crate) struct EmacsAddon {
+    pub(crate) entity: Entity<Emacs>,
+}
+
+impl editor::Addon for EmacsAddon {
+    fn extend_key_context(&self, key_context: &mut KeyContext, cx: &App) {
+        self.entity.read(cx).extend_key_context(key_context)
+    }
+
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// The kinds of commands that change how the next command behaves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Command {
+    Kill,
+    Yank,
+    SetMark,
+    PrefixArgument,
+}
+
+/// A numeric argument typed before a command with `C-u` or `M-<digit>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct PrefixArgument {
+    /// How many times `C-u` was typed before any digits.
+    universal: u32,
+    digits: Option<u32>,
+    negative: bool,
+    /// Whether typing a digit adds to the argument. Typing `C-u` after digits ends the argument,
+    /// so that digits typed next are inserted.
+    accepting_digits: bool,
+}
+
+impl PrefixArgument {
+    fn value(&self) -> i64 {
+        let magnitude = match self.digits {
+            Some(digits) => i64::from(digits),
+            None if self.negative => 1,
+            None => 4_i64.saturating_pow(self.universal),
+        };
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// Whether the argument was only typed with `C-u`, which some commands treat differently
+    /// from a number.
+    fn is_raw(&self) -> bool {
+        self.digits.is_none() && !self.negative
+    }
+}
+
+/// The state pertaining to Emacs mode in one editor.
+pub(crate) struct Emacs {
+    editor: WeakEntity<Editor>,
+    prefix_argument: Option<PrefixArgument>,
+    /// The command that handled the current keystroke, which becomes `last_command` once the
+    /// keystroke has been dispatched.
+    this_command: Option<Command>,
+    last_command: Option<Command>,
+    mark: Option<Anchor>,
+    /// Previous marks, the most recent first.
+    mark_ring: VecDeque<Anchor>,
+    /// Where each cursor inserted text in the last yank, which `M-y` replaces.
+    yanked_ranges: Vec<Range<Anchor>>,
+    isearch: Option<Isearch>,
+    _subscriptions: Vec<Subscription>,
+}
+
+// Like Vim, Emacs is an entity that is never rendered, so that it can observe keystrokes.
+impl Render for Emacs {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        gpui::Empty
+    }
+}
+
+impl Emacs {
+    pub fn new(_: &mut Window, cx: &mut Context<Editor>) -> Entity<Self> {
+        let editor = cx.entity().downgrade();
+        cx.new(|cx| Emacs {
+            editor,
+            prefix_argument: None,
+            this_command: None,
+            last_command: None,
+            mark: None,
+            mark_ring: VecDeque::new(),
+            yanked_ranges: Vec::new(),
+            isearch: None,
+            _subscriptions: vec![cx.observe_keystrokes(Self::observe_keystrokes)],
+        })
+    }
+
+    fn register(editor: &mut Editor, window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let Some(window) = window else {
+            return;
+        };
+
+        let mut was_enabled = Emacs::enabled(cx);
+        cx.observe_global_in::<SettingsStore>(window, move |editor, window, cx| {
+            let enabled = Emacs::enabled(cx);
+            if was_enabled == enabled {
+                return;
+            }
+            was_enabled = enabled;
+            if enabled {
+                Self::activate(editor, window, cx)
+            } else {
+                Self::deactivate(editor, cx)
+            }
+        })
+        .detach();
+        if was_enabled {
+            Self::activate(editor, window, cx)
+        }
+    }
+
+    fn activate(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+        let emacs = Emacs::new(window, cx);
+        editor.register_addon(EmacsAddon {
+            entity: emacs.clone(),
+        });
+
+        emacs.update(cx, |_, cx| {
+            Emacs::action(editor, cx, Emacs::universal_argument);
+            Emacs::action(editor, cx, Emacs::digit_argument);
+            Emacs::action(editor, cx, Emacs::negative_argument);
+            Emacs::action(editor, cx, Emacs::kill_line);
+            Emacs::action(editor, cx, Emacs::kill_region);
+            Emacs::action(editor, cx, Emacs::kill_ring_save);
+            Emacs::action(editor, cx, Emacs::kill_word);
+            Emacs::action(editor, cx, Emacs::backward_kill_word);
+            Emacs::action(editor, cx, Emacs::yank);
+            Emacs::action(editor, cx, Emacs::yank_pop);
+            Emacs::action(editor, cx, Emacs::set_mark);
+            Emacs::action(editor, cx, Emacs::exchange_point_and_mark);
+        });
+    }
+
+    fn deactivate(editor: &mut Editor, cx: &mut Context<Editor>) {
+        editor.set_selection_mark_mode(false, cx);
+        editor.unregister_addon::<EmacsAddon>();
+    }
+
+    /// Register an action on the editor.
+    pub fn action<A: Action>(
+        editor: &mut Editor,
+        cx: &mut Context<Emacs>,
+        f: impl Fn(&mut Emacs, &A, &mut Window, &mut Context<Emacs>) + 'static,
+    ) {
+        let subscription = editor.register_action(cx.listener(f));
+        cx.on_release(|_, _| drop(subscription)).detach();
+    }
+
+    pub fn enabled(cx: &App) -> bool {
+        *BaseKeymap::get_global(cx) == BaseKeymap::Emacs
+            && !VimModeSetting::get_global(cx).0
+            && !HelixModeSetting::get_global(cx).0
+    }
+
+    fn extend_key_context(&self, key_context: &mut KeyContext) {
+        key_context.add("emacs_mode");
+        if self
+            .prefix_argument
+            .is_some_and(|argument| argument.accepting_digits)
+        {
+            key_context.add("emacs_prefix_argument");
+        }
+    }
+
+    /// Called after every keystroke is dispatched, to remember which command ran and to repeat
+    /// commands that don't read the numeric argument themselves.
+    fn observe_keystrokes(
+        &mut self,
+        keystroke_event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if keystroke_event.action.is_none()
+            && (window.has_pending_keystrokes() || keystroke_event.keystroke.is_ime_in_progress())
+        {
+            return;
+        }
+        // Typing an argument doesn't change which command ran last, so `C-k C-u 2 C-k` appends.
+        if self.this_command == Some(Command::PrefixArgument) {
+            self.this_command = None;
+            return;
+        }
+        self.last_command = self.this_command.take();
+
+        let Some(argument) = self.prefix_argument.take() else {
+            return;
+        };
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        self.notify_editor(cx);
+        if !editor.read(cx).is_focused(window) {
+            return;
+        }
+        let repeat = argument.value().unsigned_abs().saturating_sub(1) as usize;
+        if repeat == 0 {
+            return;
+        }
+        if let Some(action) = keystroke_event.action.as_ref() {
+            if !action.name().starts_with("emacs::") {
+                for _ in 0..repeat {
+                    window.dispatch_action(action.boxed_clone(), cx);
+                }
+            }
+        } else if let Some(text) = keystroke_event.keystroke.key_char.as_ref() {
+            let text = text.repeat(repeat);
+            editor.update(cx, |editor, cx| editor.handle_input(&text, window, cx));
+        }
+    }
+
+    fn universal_argument(
+        &mut self,
+        _: &UniversalArgument,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match &mut self.prefix_argument {
+            Some(argument) if argument.is_raw() => argument.universal += 1,
+            Some(argument) => argument.accepting_digits = false,
+            None => {
+                self.prefix_argument = Some(PrefixArgument {
+                    universal: 1,
+                    accepting_digits: true,
+                    ..PrefixArgument::default()
+                })
+            }
+        }
+        self.this_command = Some(Command::PrefixArgument);
+        self.notify_editor(cx);
+    }
+
+    fn digit_argument(
+        &mut self,
+        &DigitArgument(digit): &DigitArgument,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let argument = self.prefix_argument.get_or_insert(PrefixArgument {
+            accepting_digits: true,
+            ..PrefixArgument::default()
+        });
+        argument.digits = Some(
+            argument
+                .digits
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit),
+        );
+        self.this_command = Some(Command::PrefixArgument);
+        self.notify_editor(cx);
+    }
+
+    fn negative_argument(&mut self, _: &NegativeArgument, _: &mut Window, cx: &mut Context<Self>) {
+        let argument = self.prefix_argument.get_or_insert(PrefixArgument {
+            accepting_digits: true,
+            ..PrefixArgument::default()
+        });
+        if argument.digits.is_none() {
+            argument.negative = !argument.negative;
+        }
+        self.this_command = Some(Command::PrefixArgument);
+        self.notify_editor(cx);
+    }
+
+    fn take_prefix_argument(&mut self) -> Option<PrefixArgument> {
+        self.prefix_argument.take()
+    }
+
+    /// Takes the numeric argument for a command that does something a number of times.
+    fn take_count(&mut self) -> i64 {
+        self.take_prefix_argument()
+            .map_or(1, |argument| argument.value())
+    }
+
+    /// Re-renders the editor, whose key context depends on the numeric argument.
+    fn notify_editor(&mut self, cx: &mut Context<Self>) {
+        self.update_editor(cx, |_, _, cx| cx.notify());
+    }
+
+    fn update_editor<S>(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut Self, &mut Editor, &mut Context<Editor>) -> S,
+    ) -> Option<S> {
+        let editor = self.editor.upgrade()?;
+        Some(editor.update(cx, |editor, cx| update(self, editor, cx)))
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use editor::{Anchor, Editor, EditorSettings, SelectionEffects};
+use gpui::{Context, Entity, Window};
+use language::{Selection, SelectionGoal};
+use search::{BufferSearchBar, SearchOptions, buffer_search::Dismiss};
+use settings::Settings;
+use workspace::{Pane, Workspace, searchable::Direction};
+
+use crate::{Emacs, EmacsAddon, IsearchAbort, IsearchBackward, IsearchExit, IsearchForward};
+
+/// An incremental search started with `C-s` or `C-r`, which types into the buffer search bar.
+pub(crate) struct Isearch {
+    direction: Direction,
+    /// The selections when the search started, restored if it is aborted.
+    origin: Arc<[Selection<Anchor>]>,
+    /// The query of the previous search, which is searched for again if the command is repeated
+    /// before anything is typed.
+    previous_query: String,
+}
+
+/// The search bar's query editor is focused during a search, so these actions are handled by the
+/// workspace on behalf of the editor in the focused pane.
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &IsearchForward, window, cx| {
+        with_emacs(workspace, window, cx, |emacs, pane, window, cx| {
+            emacs.isearch(Direction::Next, pane, window, cx)
+        });
+    });
+    workspace.register_action(|workspace, _: &IsearchBackward, window, cx| {
+        with_emacs(workspace, window, cx, |emacs, pane, window, cx| {
+            emacs.isearch(Direction::Prev, pane, window, cx)
+        });
+    });
+    workspace.register_action(|workspace, _: &IsearchExit, window, cx| {
+        with_emacs(workspace, window, cx, |emacs, pane, window, cx| {
+            emacs.isearch_exit(pane, window, cx)
+        });
+    });
+    workspace.register_action(|workspace, _: &IsearchAbort, window, cx| {
+        with_emacs(workspace, window, cx, |emacs, pane, window, cx| {
+            emacs.isearch_abort(pane, window, cx)
+        });
+    });
+}
+
+fn with_emacs(
+    workspace: &Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+    f: impl FnOnce(&mut Emacs, &Entity<Pane>, &mut Window, &mut Context<Emacs>),
+) {
+    let pane = workspace.focused_pane(window, cx);
+    let emacs = pane
+        .read(cx)
+        .active_item()
+        .and_then(|item| item.act_as::<Editor>(cx))
+        .and_then(|editor| editor.read(cx).addon::<EmacsAddon>().cloned());
+    let Some(emacs) = emacs else {
+        return;
+    };
+    emacs
+        .entity
+        .update(cx, |emacs, cx| f(emacs, &pane, window, cx));
+}
+
+fn search_bar(pane: &Entity<Pane>, cx: &Context<Emacs>) -> Option<Entity<BufferSearchBar>> {
+    pane.read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<BufferSearchBar>()
+}
+
+impl Emacs {
+    fn isearch(
+        &mut self,
+        direction: Direction,
+        pane: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(search_bar) = search_bar(pane, cx) else {
+            return;
+        };
+        let Some(origin) =
+            self.update_editor(cx, |_, editor, _| editor.selections.disjoint_anchors_arc())
+        else {
+            return;
+        };
+        search_bar.update(cx, |search_bar, cx| {
+            // Repeating the command during a search moves between matches, or searches for the
+            // previous query if nothing has been typed yet.
+            if !search_bar.is_dismissed() && search_bar.query_editor_focused() {
+                if !search_bar.query(cx).is_empty() {
+                    search_bar.select_match(direction, 1, window, cx);
+                } else if let Some(isearch) = self.isearch.as_ref() {
+                    drop(search_bar.search(&isearch.previous_query, None, false, window, cx));
+                }
+                return;
+            }
+
+            if !search_bar.show(window, cx) {
+                return;
+            }
+            let previous_query = search_bar.query(cx);
+            let mut options = SearchOptions::from_settings(&EditorSettings::get_global(cx).search);
+            if direction == Direction::Prev {
+                options |= SearchOptions::BACKWARDS;
+            }
+            search_bar.set_replacement(None, cx);
+            drop(search_bar.search("", Some(options), false, window, cx));
+            cx.focus_self(window);
+            self.isearch = Some(Isearch {
+                direction,
+                origin,
+                previous_query,
+            });
+        });
+    }
+
+    fn isearch_exit(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
+        let isearch = self.isearch.take();
+        if let Some(search_bar) = search_bar(pane, cx) {
+            search_bar.update(cx, |search_bar, cx| {
+                search_bar.dismiss(&Dismiss, window, cx)
+            });
+        }
+
+        // The cursor is left at the far end of the match, as if it had moved over it.
+        let backward = isearch
+            .as_ref()
+            .is_some_and(|isearch| isearch.direction == Direction::Prev);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                s.move_with(|_, selection| {
+                    let cursor = if backward {
+                        selection.start
+                    } else {
+                        selection.end
+                    };
+                    selection.collapse_to(cursor, SelectionGoal::None)
+                });
+            });
+        });
+        if let Some(origin) = isearch.and_then(|isearch| {
+            isearch
+                .origin
+                .iter()
+                .max_by_key(|selection| selection.id)
+                .cloned()
+        }) {
+            self.push_mark(origin.head());
+        }
+    }
+
+    fn isearch_abort(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
+        let isearch = self.isearch.take();
+        if let Some(search_bar) = search_bar(pane, cx) {
+            search_bar.update(cx, |search_bar, cx| {
+                search_bar.dismiss(&Dismiss, window, cx)
+            });
+        }
+        if let Some(isearch) = isearch {
+            self.update_editor(cx, |_, editor, cx| {
+                editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                    s.select_anchors(isearch.origin.to_vec())
+                });
+            });
+        }
+    }
+}
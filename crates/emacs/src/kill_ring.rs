@@ -0,0 +1,361 @@
+use std::{collections::VecDeque, ops::Range};
+
+use editor::{
+    Anchor, Editor, SelectionEffects, ToOffset, ToPoint,
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    movement,
+};
+use gpui::{App, BorrowAppContext, ClipboardItem, Context, Global, Window};
+use language::{Point, Selection, SelectionGoal};
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
+
+use crate::{
+    BackwardKillWord, Command, Emacs, KillLine, KillRegion, KillRingSave, KillWord, Yank, YankPop,
+};
+
+/// How many kills are remembered before the oldest is forgotten.
+const KILL_RING_MAX: usize = 120;
+
+/// Text that was killed in any editor, most recent first.
+#[derive(Default)]
+pub(crate) struct KillRing {
+    entries: VecDeque<String>,
+    /// The entry the last yank inserted, which the next yank starts from.
+    yank_index: usize,
+}
+
+impl Global for KillRing {}
+
+impl KillRing {
+    fn push(&mut self, text: String) {
+        self.entries.push_front(text);
+        self.entries.truncate(KILL_RING_MAX);
+        self.yank_index = 0;
+    }
+
+    /// Adds text to the most recent kill, in front of it if it was killed backwards.
+    fn append(&mut self, text: &str, backward: bool) {
+        match self.entries.front_mut() {
+            Some(entry) if backward => entry.insert_str(0, text),
+            Some(entry) => entry.push_str(text),
+            None => self.entries.push_front(text.to_string()),
+        }
+        self.yank_index = 0;
+    }
+
+    /// Moves the yank pointer `offset` entries towards older kills, wrapping around, and returns
+    /// the entry it points to.
+    fn rotate(&mut self, offset: i64) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let len = self.entries.len() as i64;
+        self.yank_index = (self.yank_index as i64 + offset).rem_euclid(len) as usize;
+        self.entries.get(self.yank_index).cloned()
+    }
+
+    /// Adds text copied in other applications, so that yanking inserts it.
+    fn add_clipboard_text(&mut self, cx: &App) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        if !text.is_empty() && self.entries.front() != Some(&text) {
+            self.push(text);
+        }
+    }
+}
+
+impl Emacs {
+    pub(crate) fn kill_line(&mut self, _: &KillLine, window: &mut Window, cx: &mut Context<Self>) {
+        let count = self.take_prefix_argument().map(|argument| argument.value());
+        let backward = count.is_some_and(|count| count <= 0);
+        self.kill(backward, window, cx, move |map, selection| {
+            let snapshot = map.buffer_snapshot();
+            let head = selection.head();
+            match count {
+                None => {
+                    let line_end =
+                        Point::new(head.row, snapshot.line_len(MultiBufferRow(head.row)));
+                    let rest_is_blank = snapshot
+                        .text_for_range(head..line_end)
+                        .all(|chunk| chunk.trim().is_empty());
+                    if rest_is_blank {
+                        head..line_start(snapshot, head.row.saturating_add(1))
+                    } else {
+                        head..line_end
+                    }
+                }
+                Some(count) if count > 0 => {
+                    let lines = u32::try_from(count).unwrap_or(u32::MAX);
+                    head..line_start(snapshot, head.row.saturating_add(lines))
+                }
+                Some(count) => {
+                    let lines = u32::try_from(count.unsigned_abs()).unwrap_or(u32::MAX);
+                    line_start(snapshot, head.row.saturating_sub(lines))..head
+                }
+            }
+        });
+    }
+
+    pub(crate) fn kill_word(&mut self, _: &KillWord, window: &mut Window, cx: &mut Context<Self>) {
+        let count = self.take_count();
+        self.kill_words(count, window, cx);
+    }
+
+    pub(crate) fn backward_kill_word(
+        &mut self,
+        _: &BackwardKillWord,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let count = self.take_count();
+        self.kill_words(-count, window, cx);
+    }
+
+    fn kill_words(&mut self, count: i64, window: &mut Window, cx: &mut Context<Self>) {
+        self.kill(count < 0, window, cx, move |map, selection| {
+            let head = selection.head();
+            let mut point = head.to_display_point(map);
+            for _ in 0..count.unsigned_abs() {
+                point = if count < 0 {
+                    movement::previous_word_start(map, point)
+                } else {
+                    movement::next_word_end(map, point)
+                };
+            }
+            let point = point.to_point(map);
+            if count < 0 { point..head } else { head..point }
+        });
+    }
+
+    pub(crate) fn kill_region(
+        &mut self,
+        _: &KillRegion,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mark = self.mark;
+        let backward = self
+            .update_editor(cx, |_, editor, cx| {
+                let selection = editor.selections.newest::<Point>(cx);
+                if selection.is_empty() {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    mark.is_some_and(|mark| mark.to_point(&snapshot) < selection.head())
+                } else {
+                    selection.reversed
+                }
+            })
+            .unwrap_or(false);
+        self.kill(backward, window, cx, move |map, selection| {
+            region_range(map.buffer_snapshot(), selection, mark)
+        });
+    }
+
+    pub(crate) fn kill_ring_save(
+        &mut self,
+        _: &KillRingSave,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mark = self.mark;
+        let Some(text) = self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let text = editor
+                .selections
+                .all::<Point>(cx)
+                .iter()
+                .map(|selection| {
+                    snapshot
+                        .text_for_range(region_range(&snapshot, selection, mark))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            editor.set_selection_mark_mode(false, cx);
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.move_with(|_, selection| {
+                    selection.collapse_to(selection.head(), SelectionGoal::None)
+                });
+            });
+            text
+        }) else {
+            return;
+        };
+        if !text.is_empty() {
+            cx.global_mut::<KillRing>().push(text.clone());
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    /// Deletes the range each selection maps to and adds the deleted text to the kill ring,
+    /// appending it to the previous kill when kills are typed in a row.
+    fn kill(
+        &mut self,
+        backward: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        range_for_selection: impl Fn(&DisplaySnapshot, &Selection<Point>) -> Range<Point>,
+    ) {
+        let Some(text) = self.update_editor(cx, |_, editor, cx| {
+            let map = editor.snapshot(window, cx).display_snapshot;
+            let ranges = editor
+                .selections
+                .all::<Point>(cx)
+                .iter()
+                .map(|selection| range_for_selection(&map, selection))
+                .collect::<Vec<_>>();
+            let text = ranges
+                .iter()
+                .map(|range| {
+                    map.buffer_snapshot()
+                        .text_for_range(range.clone())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            editor.set_selection_mark_mode(false, cx);
+            editor.transact(window, cx, |editor, window, cx| {
+                editor
+                    .change_selections(Default::default(), window, cx, |s| s.select_ranges(ranges));
+                editor.insert("", window, cx);
+            });
+            text
+        }) else {
+            return;
+        };
+
+        let appending = self.last_command == Some(Command::Kill);
+        self.this_command = Some(Command::Kill);
+        if text.is_empty() {
+            return;
+        }
+        let kill_ring = cx.global_mut::<KillRing>();
+        if appending {
+            kill_ring.append(&text, backward);
+        } else {
+            kill_ring.push(text);
+        }
+        if let Some(entry) = kill_ring.entries.front().cloned() {
+            cx.write_to_clipboard(ClipboardItem::new_string(entry));
+        }
+    }
+
+    pub(crate) fn yank(&mut self, _: &Yank, window: &mut Window, cx: &mut Context<Self>) {
+        let argument = self.take_prefix_argument();
+        let offset = match argument {
+            Some(argument) if !argument.is_raw() => argument.value() - 1,
+            _ => 0,
+        };
+        let text = cx.update_global(|kill_ring: &mut KillRing, cx| {
+            kill_ring.add_clipboard_text(cx);
+            kill_ring.rotate(offset)
+        });
+        let Some(text) = text else {
+            return;
+        };
+        self.this_command = Some(Command::Yank);
+
+        // A bare `C-u` leaves the cursor before the yanked text, and the mark after it.
+        let cursor_at_start = argument.is_some_and(|argument| argument.is_raw());
+        let Some(ranges) = self.update_editor(cx, |_, editor, cx| {
+            editor.set_selection_mark_mode(false, cx);
+            let ranges = editor
+                .selections
+                .disjoint_anchors()
+                .iter()
+                .map(|selection| selection.head()..selection.head())
+                .collect();
+            replace_ranges(editor, ranges, &text, cursor_at_start, window, cx)
+        }) else {
+            return;
+        };
+        if let Some(range) = ranges.last() {
+            self.push_mark(if cursor_at_start {
+                range.end
+            } else {
+                range.start
+            });
+        }
+        self.yanked_ranges = ranges;
+    }
+
+    pub(crate) fn yank_pop(&mut self, _: &YankPop, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.take_count();
+        if self.last_command != Some(Command::Yank) || self.yanked_ranges.is_empty() {
+            return;
+        }
+        let Some(text) = cx.global_mut::<KillRing>().rotate(offset) else {
+            return;
+        };
+        self.this_command = Some(Command::Yank);
+        let ranges = std::mem::take(&mut self.yanked_ranges);
+        self.yanked_ranges = self
+            .update_editor(cx, |_, editor, cx| {
+                replace_ranges(editor, ranges, &text, false, window, cx)
+            })
+            .unwrap_or_default();
+    }
+}
+
+/// The range a region command acts on: the selection, or the text between the cursor and the
+/// mark when nothing is selected.
+fn region_range(
+    snapshot: &MultiBufferSnapshot,
+    selection: &Selection<Point>,
+    mark: Option<Anchor>,
+) -> Range<Point> {
+    if !selection.is_empty() {
+        return selection.range();
+    }
+    let head = selection.head();
+    match mark.map(|mark| mark.to_point(snapshot)) {
+        Some(mark) if mark < head => mark..head,
+        Some(mark) => head..mark,
+        None => head..head,
+    }
+}
+
+fn line_start(snapshot: &MultiBufferSnapshot, row: u32) -> Point {
+    let max_point = snapshot.max_point();
+    if row > max_point.row {
+        max_point
+    } else {
+        Point::new(row, 0)
+    }
+}
+
+/// Replaces each range with the text without reindenting it, and puts a cursor at the end of
+/// each insertion, or at the start if `cursor_at_start` is set. Returns where the text now is.
+fn replace_ranges(
+    editor: &mut Editor,
+    ranges: Vec<Range<Anchor>>,
+    text: &str,
+    cursor_at_start: bool,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) -> Vec<Range<Anchor>> {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let (edits, inserted_ranges): (Vec<_>, Vec<_>) = ranges
+        .into_iter()
+        .map(|range| {
+            let range = range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+            let inserted_range =
+                snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+            ((range, text), inserted_range)
+        })
+        .unzip();
+    editor.transact(window, cx, |editor, window, cx| {
+        editor.edit(edits, cx);
+        editor.change_selections(Default::default(), window, cx, |s| {
+            s.select_anchor_ranges(inserted_ranges.iter().map(|range| {
+                let cursor = if cursor_at_start {
+                    range.start
+                } else {
+                    range.end
+                };
+                cursor..cursor
+            }))
+        });
+    });
+    inserted_ranges
+}
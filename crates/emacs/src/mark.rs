@@ -0,0 +1,82 @@
+use editor::{Anchor, SelectionEffects};
+use gpui::{Context, Window};
+use language::SelectionGoal;
+
+use crate::{Command, Emacs, ExchangePointAndMark, SetMark};
+
+/// How many previous marks are remembered.
+const MARK_RING_MAX: usize = 16;
+
+impl Emacs {
+    pub(crate) fn set_mark(&mut self, _: &SetMark, window: &mut Window, cx: &mut Context<Self>) {
+        if self.take_prefix_argument().is_some() {
+            self.pop_mark(window, cx);
+            return;
+        }
+
+        let repeated = self.last_command == Some(Command::SetMark);
+        self.this_command = Some(Command::SetMark);
+        self.update_editor(cx, |emacs, editor, cx| {
+            // `C-SPC C-SPC` sets the mark without activating the region.
+            if repeated && editor.selection_mark_mode() {
+                editor.set_selection_mark_mode(false, cx);
+                return;
+            }
+            emacs.push_mark(editor.selections.newest_anchor().head());
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.move_with(|_, selection| {
+                    selection.collapse_to(selection.head(), SelectionGoal::None)
+                });
+            });
+            editor.set_selection_mark_mode(true, cx);
+        });
+    }
+
+    pub(crate) fn exchange_point_and_mark(
+        &mut self,
+        _: &ExchangePointAndMark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(cx, |emacs, editor, cx| {
+            let selection = editor.selections.newest_anchor();
+            let point = selection.head();
+            let mark = if editor.selection_mark_mode() {
+                selection.tail()
+            } else if let Some(mark) = emacs.mark {
+                mark
+            } else {
+                return;
+            };
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges([point..mark])
+            });
+            editor.set_selection_mark_mode(true, cx);
+            emacs.mark = Some(point);
+        });
+    }
+
+    /// Sets the mark, remembering the previous one in the mark ring.
+    pub(crate) fn push_mark(&mut self, anchor: Anchor) {
+        if let Some(mark) = self.mark.replace(anchor) {
+            self.mark_ring.push_front(mark);
+            self.mark_ring.truncate(MARK_RING_MAX);
+        }
+    }
+
+    /// Moves the cursor to the mark, and replaces the mark with the most recent one in the mark
+    /// ring, which it rotates, so that popping repeatedly cycles through the previous marks.
+    fn pop_mark(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(mark) = self.mark.take() else {
+            return;
+        };
+        self.mark_ring.push_back(mark);
+        self.mark = self.mark_ring.pop_front();
+        self.update_editor(cx, |_, editor, cx| {
+            editor.set_selection_mark_mode(false, cx);
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges([mark..mark])
+            });
+        });
+    }
+}
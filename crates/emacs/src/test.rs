@@ -0,0 +1,162 @@
+mod emacs_test_context;
+
+pub use emacs_test_context::*;
+
+#[gpui::test]
+async fn test_kill_line_appends_successive_kills(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("ˇone\ntwo\nthree");
+    cx.simulate_keystrokes("ctrl-k");
+    cx.assert_editor_state("ˇ\ntwo\nthree");
+    cx.simulate_keystrokes("ctrl-k ctrl-k ctrl-k");
+    cx.assert_editor_state("ˇthree");
+    cx.simulate_keystrokes("ctrl-y");
+    cx.assert_editor_state("one\ntwo\nˇthree");
+
+    // Moving between kills starts a new entry.
+    cx.set_state("ˇone two");
+    cx.simulate_keystrokes("alt-d ctrl-e alt-backspace");
+    cx.assert_editor_state(" ˇ");
+    cx.simulate_keystrokes("ctrl-y");
+    cx.assert_editor_state(" twoˇ");
+
+    // Backward kills are prepended.
+    cx.set_state("one two threeˇ");
+    cx.simulate_keystrokes("alt-backspace alt-backspace ctrl-a ctrl-y");
+    cx.assert_editor_state("two threeˇone ");
+}
+
+#[gpui::test]
+async fn test_kill_line_with_prefix_argument(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("ˇone\ntwo\nthree\nfour");
+    cx.simulate_keystrokes("ctrl-u 2 ctrl-k");
+    cx.assert_editor_state("ˇthree\nfour");
+    cx.simulate_keystrokes("ctrl-e ctrl-u 0 ctrl-k");
+    cx.assert_editor_state("ˇ\nfour");
+    cx.simulate_keystrokes("ctrl-y");
+    cx.assert_editor_state("threeˇ\nfour");
+
+    cx.set_state("one\ntwo\nthˇree");
+    cx.simulate_keystrokes("alt-- ctrl-k");
+    cx.assert_editor_state("one\nˇree");
+}
+
+#[gpui::test]
+async fn test_yank_pop(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("ˇone two three");
+    cx.simulate_keystrokes("alt-d ctrl-f alt-d");
+    cx.assert_editor_state(" ˇ three");
+    cx.simulate_keystrokes("ctrl-y");
+    cx.assert_editor_state(" twoˇ three");
+    cx.simulate_keystrokes("alt-y");
+    cx.assert_editor_state(" oneˇ three");
+    cx.simulate_keystrokes("alt-y");
+    cx.assert_editor_state(" twoˇ three");
+    cx.simulate_keystrokes("alt-y");
+    cx.assert_editor_state(" oneˇ three");
+
+    // The next yank starts from the entry that was popped to.
+    cx.simulate_keystrokes("ctrl-y");
+    cx.assert_editor_state(" oneoneˇ three");
+
+    // Yank-pop only replaces text that was just yanked.
+    cx.simulate_keystrokes("ctrl-e alt-y");
+    cx.assert_editor_state(" oneone threeˇ");
+
+    // A numeric argument yanks a kill relative to the last one yanked, and a bare `C-u` leaves
+    // the cursor before the text.
+    cx.simulate_keystrokes("ctrl-u 2 ctrl-y");
+    cx.assert_editor_state(" oneone threetwoˇ");
+    cx.simulate_keystrokes("ctrl-u ctrl-y");
+    cx.assert_editor_state(" oneone threetwoˇtwo");
+}
+
+#[gpui::test]
+async fn test_universal_argument_repeats_commands(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("ˇ");
+    cx.simulate_keystrokes("ctrl-u a");
+    cx.assert_editor_state("aaaaˇ");
+    cx.simulate_keystrokes("ctrl-u 3 b");
+    cx.assert_editor_state("aaaabbbˇ");
+    cx.simulate_keystrokes("ctrl-u 5 ctrl-b");
+    cx.assert_editor_state("aaˇaabbb");
+    cx.simulate_keystrokes("alt-2 ctrl-f");
+    cx.assert_editor_state("aaaaˇbbb");
+
+    // `C-u` after digits ends the argument, so the next digit is inserted.
+    cx.simulate_keystrokes("ctrl-u 2 ctrl-u 7");
+    cx.assert_editor_state("aaaa77ˇbbb");
+}
+
+#[gpui::test]
+async fn test_mark_ring(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("ˇone two three");
+    cx.simulate_keystrokes("ctrl-space ctrl-space alt-f alt-f");
+    cx.assert_editor_state("one twoˇ three");
+    cx.simulate_keystrokes("ctrl-space ctrl-space ctrl-e");
+    cx.assert_editor_state("one two threeˇ");
+
+    cx.simulate_keystrokes("ctrl-u ctrl-space");
+    cx.assert_editor_state("one twoˇ three");
+    cx.simulate_keystrokes("ctrl-u ctrl-space");
+    cx.assert_editor_state("ˇone two three");
+    cx.simulate_keystrokes("ctrl-u ctrl-space");
+    cx.assert_editor_state("one twoˇ three");
+}
+
+#[gpui::test]
+async fn test_region(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("one ˇtwo three");
+    cx.simulate_keystrokes("ctrl-space alt-f");
+    cx.assert_editor_state("one «twoˇ» three");
+    cx.simulate_keystrokes("ctrl-x ctrl-x");
+    cx.assert_editor_state("one «ˇtwo» three");
+    cx.simulate_keystrokes("alt-w");
+    cx.assert_editor_state("one ˇtwo three");
+    cx.simulate_keystrokes("ctrl-e ctrl-y");
+    cx.assert_editor_state("one two threetwoˇ");
+
+    // The region is between the cursor and the mark even when it isn't active.
+    cx.set_state("ˇone two three");
+    cx.simulate_keystrokes("ctrl-space ctrl-space alt-f alt-f ctrl-w");
+    cx.assert_editor_state("ˇ three");
+    cx.simulate_keystrokes("ctrl-e ctrl-y");
+    cx.assert_editor_state(" threeone twoˇ");
+}
+
+#[gpui::test]
+async fn test_isearch(cx: &mut gpui::TestAppContext) {
+    let mut cx = EmacsTestContext::new(cx).await;
+
+    cx.set_state("ˇone two one two");
+    cx.simulate_keystrokes("ctrl-s t w o");
+    cx.assert_editor_state("one «twoˇ» one two");
+    cx.simulate_keystrokes("enter");
+    cx.assert_editor_state("one twoˇ one two");
+
+    // The mark is left where the search started.
+    cx.simulate_keystrokes("ctrl-u ctrl-space");
+    cx.assert_editor_state("ˇone two one two");
+
+    // Repeating the search before typing searches for the previous query.
+    cx.simulate_keystrokes("ctrl-s ctrl-s ctrl-s enter");
+    cx.assert_editor_state("one two one twoˇ");
+
+    cx.simulate_keystrokes("ctrl-r o n e");
+    cx.assert_editor_state("one two «oneˇ» two");
+    cx.simulate_keystrokes("ctrl-r");
+    cx.assert_editor_state("«oneˇ» two one two");
+    cx.simulate_keystrokes("ctrl-g");
+    cx.assert_editor_state("one two one twoˇ");
+}
@@ -0,0 +1,69 @@
+use std::ops::{Deref, DerefMut};
+
+use editor::test::editor_lsp_test_context::EditorLspTestContext;
+use gpui::{AppContext, UpdateGlobal};
+use search::BufferSearchBar;
+use settings::{BaseKeymap, BaseKeymapContent, KeymapFile, SettingsStore};
+
+pub struct EmacsTestContext {
+    cx: EditorLspTestContext,
+}
+
+impl EmacsTestContext {
+    pub fn init(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            crate::init(cx);
+            search::init(cx);
+            workspace::init_settings(cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            project::Project::init_settings(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    pub async fn new(cx: &mut gpui::TestAppContext) -> EmacsTestContext {
+        Self::init(cx);
+        let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |s| s.base_keymap = Some(BaseKeymapContent::Emacs));
+            });
+            // The Emacs keymap refers to actions of crates that aren't loaded here.
+            for asset_path in [
+                settings::DEFAULT_KEYMAP_PATH,
+                BaseKeymap::Emacs.asset_path().unwrap(),
+            ] {
+                cx.bind_keys(KeymapFile::load_asset_allow_partial_failure(asset_path, cx).unwrap());
+            }
+        });
+
+        cx.update_workspace(|workspace, window, cx| {
+            workspace.active_pane().update(cx, |pane, cx| {
+                pane.toolbar().update(cx, |toolbar, cx| {
+                    let buffer_search_bar = cx.new(|cx| BufferSearchBar::new(None, window, cx));
+                    toolbar.add_item(buffer_search_bar, window, cx);
+                })
+            });
+        });
+
+        Self { cx }
+    }
+}
+
+impl Deref for EmacsTestContext {
+    type Target = EditorLspTestContext;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cx
+    }
+}
+
+impl DerefMut for EmacsTestContext {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.cx
+    }
+}
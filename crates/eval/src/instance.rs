@@ -590,6 +590,7 @@ impl ExampleInstance {
                 tools: Vec::new(),
                 tool_choice: None,
                 stop: Vec::new(),
+                max_output_tokens: None,
                 thinking_allowed: true,
             };
 
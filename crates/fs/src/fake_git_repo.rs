@@ -5,9 +5,12 @@ use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid,
     blame::Blame,
+    rebase::{RebaseCommand, RebaseProgress, RebaseTodoEntry},
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        AskPassDelegate, BisectMark, BisectStatus, Branch, CommitDetails, CommitLogEntry,
+        CommitOptions, FetchOptions, GitRepository, GitRepositoryCheckpoint, GitTag, GitWorktree,
+        LogOptions, PushOptions, Remote, RepoPath, ResetMode, SequencerOperation, SequencerOutcome,
+        StoppedSequencer,
     },
     status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
@@ -40,6 +43,11 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// The commits listed by `log`, most recent first.
+    pub log: Vec<CommitLogEntry>,
+    pub worktrees: Vec<GitWorktree>,
+    /// The tags listed by `tags`, most recently created first.
+    pub tags: Vec<GitTag>,
 }
 
 impl FakeGitRepositoryState {
@@ -54,6 +62,9 @@ impl FakeGitRepositoryState {
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            log: Default::default(),
+            worktrees: Default::default(),
+            tags: Default::default(),
         }
     }
 }
@@ -379,6 +390,210 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitLogEntry>>> {
+        self.with_state_async(false, move |state| {
+            let contains = |text: &str, query: &Option<String>| {
+                query
+                    .as_ref()
+                    .is_none_or(|query| text.to_lowercase().contains(&query.to_lowercase()))
+            };
+            let limit = if options.limit == 0 {
+                usize::MAX
+            } else {
+                options.limit
+            };
+            Ok(state
+                .log
+                .iter()
+                .filter(|commit| {
+                    contains(&commit.author_name, &options.author)
+                        && contains(&commit.subject, &options.message)
+                })
+                .skip(options.skip)
+                .take(limit)
+                .cloned()
+                .collect())
+        })
+    }
+
+    fn rebase_todo(&self, _onto: Option<String>) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        unimplemented!()
+    }
+
+    fn start_rebase(
+        &self,
+        _onto: Option<String>,
+        _todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        unimplemented!()
+    }
+
+    fn rebase(
+        &self,
+        _command: RebaseCommand,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        unimplemented!()
+    }
+
+    fn rebase_progress(&self) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        async { Ok(None) }.boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<GitWorktree>>> {
+        self.with_state_async(false, |state| Ok(state.worktrees.clone()))
+    }
+
+    fn add_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch_from: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ref_name = format!("refs/heads/{branch}");
+            if state.worktrees.iter().any(|worktree| {
+                worktree.path == path || worktree.ref_name.as_deref() == Some(&ref_name)
+            }) {
+                bail!("worktree already exists");
+            }
+            if create_branch_from.is_some() {
+                state.branches.insert(branch);
+            } else if !state.branches.contains(&branch) {
+                bail!("no such branch: {branch}");
+            }
+            state.worktrees.push(GitWorktree {
+                path,
+                sha: None,
+                ref_name: Some(ref_name.into()),
+                is_main: false,
+                is_locked: false,
+                is_prunable: false,
+            });
+            Ok(())
+        })
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        _force: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let ix = state
+                .worktrees
+                .iter()
+                .position(|worktree| worktree.path == path && !worktree.is_main)
+                .with_context(|| format!("no linked worktree at {path:?}"))?;
+            state.worktrees.remove(ix);
+            Ok(())
+        })
+    }
+
+    fn prune_worktrees(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            state.worktrees.retain(|worktree| !worktree.is_prunable);
+            Ok(())
+        })
+    }
+
+    fn cherry_pick(
+        &self,
+        _commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        unimplemented!()
+    }
+
+    fn revert(
+        &self,
+        _commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        unimplemented!()
+    }
+
+    fn resume_sequencer(
+        &self,
+        _operation: SequencerOperation,
+        _command: RebaseCommand,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        unimplemented!()
+    }
+
+    fn stopped_sequencer(&self) -> BoxFuture<'_, Result<Option<StoppedSequencer>>> {
+        async { Ok(None) }.boxed()
+    }
+
+    fn bisect_start(
+        &self,
+        _bad: Option<String>,
+        _good: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_mark(
+        &self,
+        _mark: BisectMark,
+        _commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn bisect_status(&self) -> BoxFuture<'_, Result<Option<BisectStatus>>> {
+        async { Ok(None) }.boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<GitTag>>> {
+        self.with_state_async(false, |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: String,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name.as_ref() == name) {
+                bail!("tag '{name}' already exists");
+            }
+            let sha = state.refs.get(&target).cloned().unwrap_or(target);
+            state.tags.insert(
+                0,
+                GitTag {
+                    name: name.into(),
+                    sha: sha.into(),
+                    message: message.map(Into::into),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn push_tags(
+        &self,
+        _remote_name: String,
+        _tag_name: Option<String>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -1,6 +1,7 @@
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod rebase;
 mod remote;
 pub mod repository;
 pub mod stash;
@@ -96,6 +97,43 @@ actions!(
         Clone,
         /// Adds a file to .gitignore.
         AddToGitignore,
+        /// Opens the commit history of the current branch.
+        ViewHistory,
+        /// Opens the commit history of the current file.
+        FileHistory,
+        /// Opens the commit history of the selected lines of the current file.
+        LineHistory,
+        /// Opens an interactive rebase of the current branch onto its upstream, or the progress of
+        /// the rebase in progress.
+        InteractiveRebase,
+        /// Opens a picker of the worktrees of the repository, to open one or create one for a branch.
+        Worktrees,
+        /// Prunes the worktrees whose directory no longer exists.
+        PruneWorktrees,
+        /// Opens a picker of the tags of the repository.
+        Tags,
+        /// Creates a tag on the current commit.
+        CreateTag,
+        /// Pushes all tags to a remote.
+        PushTags,
+        /// Continues the cherry-pick or revert that stopped on conflicts.
+        ContinueCherryPickOrRevert,
+        /// Skips the commit the cherry-pick or revert stopped on.
+        SkipCherryPickOrRevert,
+        /// Aborts the cherry-pick or revert that stopped on conflicts.
+        AbortCherryPickOrRevert,
+        /// Starts a bisect with the current commit marked bad.
+        StartBisect,
+        /// Marks the current commit good in the bisect in progress.
+        BisectGood,
+        /// Marks the current commit bad in the bisect in progress.
+        BisectBad,
+        /// Skips the current commit in the bisect in progress.
+        BisectSkip,
+        /// Runs a task on the commits of the bisect in progress to find the first bad one.
+        BisectRun,
+        /// Ends the bisect in progress and checks out the commit it started from.
+        BisectReset,
     ]
 );
 
@@ -0,0 +1,292 @@
+//! Interactive rebases whose todo list is edited in Zed, rather than in an editor spawned by git.
+//!
+//! Git still runs the rebase: the todo list is handed over to it by a sequence editor command
+//! copying the file Zed wrote, and new commit messages by an editor command copying the message
+//! written for the commit being reworded.
+
+use anyhow::Result;
+use gpui::SharedString;
+use std::path::Path;
+
+/// The directory of the git directory where the todo list and commit messages of a rebase started
+/// by Zed are written.
+pub(crate) const REBASE_STATE_DIR: &str = "zed-rebase";
+
+/// What an interactive rebase does with a commit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RebaseAction {
+    /// Applies the commit as is.
+    #[default]
+    Pick,
+    /// Applies the commit with a new message.
+    Reword,
+    /// Melds the commit into the previous one, combining their messages.
+    Squash,
+    /// Melds the commit into the previous one, keeping the previous one's message.
+    Fixup,
+    /// Leaves the commit out.
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [Self; 5] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    /// The command of the action in a todo list.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "p" | "pick" => Some(Self::Pick),
+            "r" | "reword" => Some(Self::Reword),
+            "s" | "squash" => Some(Self::Squash),
+            "f" | "fixup" => Some(Self::Fixup),
+            "d" | "drop" => Some(Self::Drop),
+            _ => None,
+        }
+    }
+
+    /// Whether the action melds the commit into the one applied before it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+/// A commit in the todo list of an interactive rebase, with what to do with it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of the commit, when it is reworded.
+    pub message: Option<SharedString>,
+}
+
+impl RebaseTodoEntry {
+    pub fn short_sha(&self) -> SharedString {
+        self.sha[..crate::SHORT_SHA_LENGTH.min(self.sha.len())]
+            .to_string()
+            .into()
+    }
+}
+
+/// The state of an interactive rebase that stopped before applying all of its todo list, usually
+/// because of conflicts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RebaseProgress {
+    /// The steps applied so far, the last one being the one the rebase stopped at.
+    pub done: Vec<RebaseTodoEntry>,
+    /// The steps left to apply.
+    pub remaining: Vec<RebaseTodoEntry>,
+    /// The branch being rebased, if the rebase didn't start on a detached `HEAD`.
+    pub branch: Option<SharedString>,
+}
+
+/// What to do with a rebase that stopped. Cherry-picks and reverts that stopped are resumed the
+/// same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseCommand {
+    /// Goes on applying the remaining commits, once the conflicts are resolved and staged.
+    Continue,
+    /// Leaves out the commit the rebase stopped at, and goes on.
+    Skip,
+    /// Stops the operation, and restores the branch to what it was before it.
+    Abort,
+}
+
+impl RebaseCommand {
+    pub fn arg(&self) -> &'static str {
+        match self {
+            Self::Continue => "--continue",
+            Self::Skip => "--skip",
+            Self::Abort => "--abort",
+        }
+    }
+}
+
+/// Checks that git can apply a todo list, which can't start by melding a commit into a previous one.
+pub fn validate_todo(entries: &[RebaseTodoEntry]) -> Result<()> {
+    let first_applied = entries
+        .iter()
+        .find(|entry| entry.action != RebaseAction::Drop);
+    if let Some(entry) = first_applied
+        && entry.action.melds_into_previous()
+    {
+        anyhow::bail!(
+            "Cannot {} {} into a previous commit, as it is the first one applied",
+            entry.action.name(),
+            entry.short_sha()
+        );
+    }
+    Ok(())
+}
+
+/// Writes a todo list in the format git reads it in.
+pub fn format_todo(entries: &[RebaseTodoEntry]) -> String {
+    let mut todo = String::new();
+    for entry in entries {
+        todo.push_str(entry.action.name());
+        todo.push(' ');
+        todo.push_str(&entry.sha);
+        todo.push(' ');
+        todo.push_str(&entry.subject);
+        todo.push('\n');
+    }
+    todo
+}
+
+/// Reads the commits of a todo list written by git, leaving out comments and the commands that
+/// aren't about a single commit, such as `exec` or `break`.
+pub fn parse_todo(todo: &str) -> Vec<RebaseTodoEntry> {
+    todo.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let action = RebaseAction::from_command(parts.next()?)?;
+            let sha = parts.next()?;
+            let subject = parts.next().unwrap_or_default();
+            Some(RebaseTodoEntry {
+                action,
+                sha: sha.to_string().into(),
+                subject: subject.to_string().into(),
+                message: None,
+            })
+        })
+        .collect()
+}
+
+/// Reads the state of the interactive rebase in progress in a git directory, if any.
+pub(crate) async fn read_progress(git_dir: &Path) -> Result<Option<RebaseProgress>> {
+    let rebase_dir = git_dir.join("rebase-merge");
+    if !smol::fs::metadata(&rebase_dir)
+        .await
+        .is_ok_and(|metadata| metadata.is_dir())
+    {
+        return Ok(None);
+    }
+
+    let done = smol::fs::read_to_string(rebase_dir.join("done"))
+        .await
+        .unwrap_or_default();
+    let remaining = smol::fs::read_to_string(rebase_dir.join("git-rebase-todo"))
+        .await
+        .unwrap_or_default();
+    let head_name = smol::fs::read_to_string(rebase_dir.join("head-name"))
+        .await
+        .unwrap_or_default();
+    Ok(Some(RebaseProgress {
+        done: parse_todo(&done),
+        remaining: parse_todo(&remaining),
+        branch: head_name
+            .trim()
+            .strip_prefix("refs/heads/")
+            .map(|branch| branch.to_string().into()),
+    }))
+}
+
+/// The `GIT_SEQUENCE_EDITOR` replacing the todo list git asks to edit with the one at `todo_path`.
+pub(crate) fn sequence_editor_command(todo_path: &Path) -> String {
+    format!("cp {}", shell_quote(todo_path))
+}
+
+/// The `GIT_EDITOR` replacing the message of the commit being reworded with the one written for
+/// it in `messages_dir`, if any. Other messages, such as the combined message of squashed
+/// commits, are left as git wrote them.
+pub(crate) fn message_editor_command(git_dir: &Path, messages_dir: &Path) -> String {
+    let done_path = git_dir.join("rebase-merge").join("done");
+    // The todo list may refer to commits by abbreviated SHAs, while messages are written for full ones.
+    format!(
+        "sha=$(tail -n 1 {} | cut -d ' ' -f 2); \
+         for message in {}/\"$sha\"*; do if [ -f \"$message\" ]; then cp \"$message\" \"$1\"; fi; done; :",
+        shell_quote(&done_path),
+        shell_quote(messages_dir),
+    )
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: RebaseAction, sha: &str, subject: &str) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action,
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_todo_round_trip() {
+        let entries = vec![
+            entry(RebaseAction::Pick, "1a2b3c4", "Add the parser"),
+            entry(RebaseAction::Fixup, "5d6e7f8", "fixup! Add the parser"),
+            entry(RebaseAction::Reword, "9a8b7c6", "Fix typo"),
+            entry(RebaseAction::Drop, "5f4e3d2", "WIP"),
+        ];
+        let todo = format_todo(&entries);
+        assert_eq!(
+            todo,
+            "pick 1a2b3c4 Add the parser\n\
+             fixup 5d6e7f8 fixup! Add the parser\n\
+             reword 9a8b7c6 Fix typo\n\
+             drop 5f4e3d2 WIP\n"
+        );
+        assert_eq!(parse_todo(&todo), entries);
+    }
+
+    #[test]
+    fn test_parsing_todo_written_by_git() {
+        let todo = "p 1a2b3c4 Add the parser\n\
+                    exec cargo test\n\
+                    s 5d6e7f8 Tests\n\
+                    \n\
+                    # Rebase 0f0f0f0..5d6e7f8 onto 0f0f0f0 (2 commands)\n";
+        assert_eq!(
+            parse_todo(todo),
+            vec![
+                entry(RebaseAction::Pick, "1a2b3c4", "Add the parser"),
+                entry(RebaseAction::Squash, "5d6e7f8", "Tests"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validating_todo() {
+        assert!(validate_todo(&[entry(RebaseAction::Pick, "1a2b3c4", "One")]).is_ok());
+        assert!(
+            validate_todo(&[
+                entry(RebaseAction::Drop, "1a2b3c4", "One"),
+                entry(RebaseAction::Squash, "5d6e7f8", "Two"),
+            ])
+            .is_err()
+        );
+        assert!(
+            validate_todo(&[
+                entry(RebaseAction::Reword, "1a2b3c4", "One"),
+                entry(RebaseAction::Fixup, "5d6e7f8", "Two"),
+            ])
+            .is_ok()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::rebase::{self, RebaseCommand, RebaseProgress, RebaseTodoEntry};
 use crate::stash::GitStash;
 use crate::status::{GitStatus, StatusCode};
 use crate::{Oid, SHORT_SHA_LENGTH};
@@ -20,6 +21,7 @@ use std::process::{ExitStatus, Stdio};
 use std::{
     cmp::Ordering,
     future,
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -182,6 +184,176 @@ impl CommitDetails {
     }
 }
 
+/// A commit in the history of a repository, as listed by [`GitRepository::log`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CommitLogEntry {
+    pub sha: SharedString,
+    /// The commits this one was made on top of, first parent first. When the history is
+    /// filtered, these are the closest ancestors that are part of the filtered history.
+    pub parents: Vec<SharedString>,
+    pub subject: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// The branches and tags pointing at this commit.
+    pub refs: Vec<SharedString>,
+}
+
+impl CommitLogEntry {
+    pub fn short_sha(&self) -> SharedString {
+        self.sha[..SHORT_SHA_LENGTH.min(self.sha.len())]
+            .to_string()
+            .into()
+    }
+
+    pub fn to_summary(&self) -> CommitSummary {
+        CommitSummary {
+            sha: self.sha.clone(),
+            subject: self.subject.clone(),
+            commit_timestamp: self.commit_timestamp,
+            author_name: self.author_name.clone(),
+            has_parent: !self.parents.is_empty(),
+        }
+    }
+}
+
+/// Which commits [`GitRepository::log`] lists, most recent first.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct LogOptions {
+    /// The revision whose history is listed, `HEAD` if none.
+    pub revision: Option<String>,
+    /// Only list the commits changing any of these paths.
+    pub paths: Vec<RepoPath>,
+    /// Only list the commits changing these lines of a file.
+    pub line_range: Option<LogLineRange>,
+    /// Only list the commits whose author contains this text, ignoring case.
+    pub author: Option<String>,
+    /// Only list the commits whose message contains this text, ignoring case.
+    pub message: Option<String>,
+    /// How many of the matching commits to skip, to list the history a page at a time.
+    pub skip: usize,
+    /// How many commits to list at most, all of them if zero.
+    pub limit: usize,
+}
+
+/// Lines of a file whose history is listed, as they are in the listed revision.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogLineRange {
+    pub path: RepoPath,
+    /// The zero-based rows of the lines, the end being exclusive.
+    pub rows: Range<u32>,
+}
+
+/// A working tree of a repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    /// The commit checked out in the worktree, if any.
+    pub sha: Option<SharedString>,
+    /// The ref name of the branch checked out in the worktree, if its `HEAD` isn't detached.
+    pub ref_name: Option<SharedString>,
+    /// Whether this is the main worktree of the repository, rather than a linked one.
+    pub is_main: bool,
+    /// Whether the worktree is locked, preventing it from being pruned or removed.
+    pub is_locked: bool,
+    /// Whether the directory of the worktree is gone, so that it can be pruned.
+    pub is_prunable: bool,
+}
+
+impl GitWorktree {
+    /// The name of the branch checked out in the worktree, if any.
+    pub fn branch_name(&self) -> Option<&str> {
+        self.ref_name
+            .as_ref()
+            .map(|ref_name| ref_name.strip_prefix("refs/heads/").unwrap_or(ref_name))
+    }
+}
+
+/// A tag of a repository, as listed by `git for-each-ref refs/tags`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GitTag {
+    pub name: SharedString,
+    /// The commit the tag points to.
+    pub sha: SharedString,
+    /// The subject of the tag's message, if it is an annotated tag.
+    pub message: Option<SharedString>,
+}
+
+/// A git command applying commits one at a time, which stops on conflicts until it is resumed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SequencerOperation {
+    CherryPick,
+    Revert,
+}
+
+impl SequencerOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+        }
+    }
+
+    /// The file of the git directory naming the commit the operation stopped at.
+    fn head_file(&self) -> &'static str {
+        match self {
+            Self::CherryPick => "CHERRY_PICK_HEAD",
+            Self::Revert => "REVERT_HEAD",
+        }
+    }
+}
+
+/// A cherry-pick or revert that stopped at a commit, waiting to be resumed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StoppedSequencer {
+    pub operation: SequencerOperation,
+    /// The commit the operation stopped at.
+    pub sha: SharedString,
+}
+
+/// How a cherry-pick or a revert ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SequencerOutcome {
+    /// All the commits were applied.
+    Completed,
+    /// The operation stopped at a commit, usually because of conflicts, and is waiting to be
+    /// continued, skipped or aborted.
+    Stopped,
+}
+
+/// How a commit is marked during a bisect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BisectMark {
+    /// The commit doesn't have the regression.
+    Good,
+    /// The commit has the regression.
+    Bad,
+    /// The commit can't be tested, so that another one is tested instead.
+    Skip,
+}
+
+impl BisectMark {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+/// A bisect in progress, as recorded in its log.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BisectStatus {
+    /// The most recent commit marked bad.
+    pub bad: Option<SharedString>,
+    pub good: Vec<SharedString>,
+    pub skipped: Vec<SharedString>,
+    /// The first bad commit, once the bisect narrowed the regression down to it.
+    pub culprit: Option<SharedString>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Remote {
     pub name: SharedString,
@@ -407,6 +579,131 @@ pub trait GitRepository: Send + Sync {
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
+    /// Lists the commits in the history of a revision, most recent first.
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitLogEntry>>>;
+
+    /// Lists the commits an interactive rebase onto `onto` would apply, oldest first, picking
+    /// each of them. Without `onto`, lists all the commits of `HEAD`.
+    fn rebase_todo(&self, onto: Option<String>) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Starts an interactive rebase applying `todo` onto `onto`, or from the root commit.
+    ///
+    /// Returns the progress of the rebase if it stopped before applying the whole todo list.
+    fn start_rebase(
+        &self,
+        onto: Option<String>,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseProgress>>>;
+
+    /// Continues, skips the current commit of, or aborts the rebase in progress.
+    ///
+    /// Returns the progress of the rebase if it stopped again.
+    fn rebase(
+        &self,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseProgress>>>;
+
+    /// Returns the progress of the interactive rebase in progress, if any.
+    fn rebase_progress(&self) -> BoxFuture<'_, Result<Option<RebaseProgress>>>;
+
+    /// Lists the worktrees of the repository, the main one first.
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<GitWorktree>>>;
+
+    /// Creates a worktree at `path`, checking out `branch`. If `create_branch_from` is set, the
+    /// branch is first created from that revision.
+    fn add_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch_from: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Removes the linked worktree at `path`, even if it has uncommitted changes when `force` is set.
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Removes the information about worktrees whose directories were deleted.
+    fn prune_worktrees(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the changes of `commits`, in order, as new commits on top of `HEAD`.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>>;
+
+    /// Commits the reverse of the changes of `commits`, in order, on top of `HEAD`.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>>;
+
+    /// Continues, skips the commit it stopped at, or aborts a cherry-pick or revert that stopped.
+    fn resume_sequencer(
+        &self,
+        operation: SequencerOperation,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>>;
+
+    /// Returns the cherry-pick or revert that stopped, if any.
+    fn stopped_sequencer(&self) -> BoxFuture<'_, Result<Option<StoppedSequencer>>>;
+
+    /// Starts a bisect, marking `bad` and `good` right away when given. Good commits can only be
+    /// given along with a bad one.
+    fn bisect_start(
+        &self,
+        bad: Option<String>,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Marks `commit`, or `HEAD` if none is given, and checks out the next commit to test.
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Ends the bisect, checking out what was checked out when it started.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the bisect in progress, if any.
+    fn bisect_status(&self) -> BoxFuture<'_, Result<Option<BisectStatus>>>;
+
+    /// Lists the tags of the repository, the most recently created first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<GitTag>>>;
+
+    /// Tags `target`, with an annotated tag if a message is given and a lightweight one otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        target: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Pushes a tag to a remote, or all of them if no tag is given.
+    fn push_tags(
+        &self,
+        remote_name: String,
+        tag_name: Option<String>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
     /// worktree's gitdir within the main repository (typically `.git/worktrees/<name>`).
     fn path(&self) -> PathBuf;
@@ -575,6 +872,75 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    fn run_sequencer(
+        &self,
+        operation: SequencerOperation,
+        args: Vec<String>,
+        resuming: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let head_path = git_dir.join(operation.head_file());
+                // Git refuses to start a cherry-pick or revert while another one is stopped, which
+                // stays stopped.
+                let already_stopped = !resuming && smol::fs::metadata(&head_path).await.is_ok();
+                let output = new_smol_command(&git_binary_path)
+                    .envs(env.iter())
+                    // Keep the messages git prepared when continuing, rather than waiting on an editor.
+                    .env("GIT_EDITOR", "true")
+                    .current_dir(working_directory?)
+                    .arg(operation.name())
+                    .args(args)
+                    .output()
+                    .await?;
+                if output.status.success() {
+                    return Ok(SequencerOutcome::Completed);
+                }
+                anyhow::ensure!(
+                    !already_stopped && smol::fs::metadata(&head_path).await.is_ok(),
+                    "Failed to {}:\n{}",
+                    operation.name(),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(SequencerOutcome::Stopped)
+            })
+            .boxed()
+    }
+
+    fn run_bisect(
+        &self,
+        args: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                if let Some(arg) = args.iter().skip(1).find(|arg| arg.starts_with('-')) {
+                    anyhow::bail!("Invalid revision: {arg}");
+                }
+                let output = new_smol_command(&git_binary_path)
+                    .envs(env.iter())
+                    .current_dir(working_directory?)
+                    .arg("bisect")
+                    .args(&args)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to run git bisect {}:\n{}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -1202,38 +1568,483 @@ impl GitRepository for RealGitRepository {
     fn rename_branch(&self, branch: String, new_name: String) -> BoxFuture<'_, Result<()>> {
         let git_binary_path = self.any_git_binary_path.clone();
         let working_directory = self.working_directory();
-        let executor = self.executor.clone();
-
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["branch", "-m", &branch, &new_name])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+
+        let remote_url = self
+            .remote_url("upstream")
+            .or_else(|| self.remote_url("origin"));
+
+        self.executor
+            .spawn(async move {
+                crate::blame::Blame::for_path(
+                    &git_binary_path,
+                    &working_directory?,
+                    &path,
+                    &content,
+                    remote_url,
+                )
+                .await
+            })
+            .boxed()
+    }
+
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitLogEntry>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let args = log_args(&options)?;
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .args(args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    if stderr.contains("does not have any commits yet") {
+                        return Ok(Vec::new());
+                    }
+                    anyhow::bail!("git log failed: {stderr}");
+                }
+                parse_log_output(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn rebase_todo(&self, onto: Option<String>) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let range = match onto {
+                    Some(onto) => format!("{onto}..HEAD"),
+                    None => "HEAD".to_string(),
+                };
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .args([
+                        "--no-optional-locks",
+                        "log",
+                        "--reverse",
+                        "--no-merges",
+                        "--format=%H%x00%s",
+                        &range,
+                        "--",
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list the commits to rebase:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(|line| {
+                        let (sha, subject) = line.split_once('\0')?;
+                        Some(RebaseTodoEntry {
+                            action: Default::default(),
+                            sha: sha.to_string().into(),
+                            subject: subject.to_string().into(),
+                            message: None,
+                        })
+                    })
+                    .collect())
+            })
+            .boxed()
+    }
+
+    fn start_rebase(
+        &self,
+        onto: Option<String>,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                rebase::validate_todo(&todo)?;
+                let state_dir = git_dir.join(rebase::REBASE_STATE_DIR);
+                let messages_dir = state_dir.join("messages");
+                let todo_path = state_dir.join("git-rebase-todo");
+                smol::fs::remove_dir_all(&state_dir).await.ok();
+                smol::fs::create_dir_all(&messages_dir).await?;
+                smol::fs::write(&todo_path, rebase::format_todo(&todo)).await?;
+                for entry in &todo {
+                    if entry.action == rebase::RebaseAction::Reword
+                        && let Some(message) = &entry.message
+                    {
+                        smol::fs::write(messages_dir.join(&*entry.sha), message.as_bytes()).await?;
+                    }
+                }
+
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .envs(env.iter())
+                    .env(
+                        "GIT_SEQUENCE_EDITOR",
+                        rebase::sequence_editor_command(&todo_path),
+                    )
+                    .env(
+                        "GIT_EDITOR",
+                        rebase::message_editor_command(&git_dir, &messages_dir),
+                    )
+                    .current_dir(working_directory?)
+                    .args(["rebase", "--interactive"]);
+                match &onto {
+                    Some(onto) => command.arg(onto),
+                    None => command.arg("--root"),
+                };
+                let output = command.output().await?;
+                rebase_outcome(&git_dir, output).await
+            })
+            .boxed()
+    }
+
+    fn rebase(
+        &self,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let messages_dir = git_dir.join(rebase::REBASE_STATE_DIR).join("messages");
+                let output = new_smol_command(&git_binary_path)
+                    .envs(env.iter())
+                    .env(
+                        "GIT_EDITOR",
+                        rebase::message_editor_command(&git_dir, &messages_dir),
+                    )
+                    .current_dir(working_directory?)
+                    .args(["rebase", command.arg()])
+                    .output()
+                    .await?;
+                rebase_outcome(&git_dir, output).await
+            })
+            .boxed()
+    }
+
+    fn rebase_progress(&self) -> BoxFuture<'_, Result<Option<RebaseProgress>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { rebase::read_progress(&git_dir).await })
+            .boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<'_, Result<Vec<GitWorktree>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .args(["--no-optional-locks", "worktree", "list", "--porcelain"])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list worktrees:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(parse_worktree_list(&String::from_utf8_lossy(
+                    &output.stdout,
+                )))
+            })
+            .boxed()
+    }
+
+    fn add_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch_from: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .envs(env.iter())
+                    .current_dir(working_directory?)
+                    .args(["worktree", "add"]);
+                match create_branch_from {
+                    Some(start_point) => command
+                        .arg("-b")
+                        .arg(&branch)
+                        .arg("--")
+                        .arg(&path)
+                        .arg(start_point),
+                    None => command.arg("--").arg(&path).arg(&branch),
+                };
+                let output = command.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to create worktree:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .envs(env.iter())
+                    .current_dir(working_directory?)
+                    .args(["worktree", "remove"]);
+                if force {
+                    command.arg("--force");
+                }
+                let output = command.arg("--").arg(&path).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to remove worktree:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn prune_worktrees(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .envs(env.iter())
+                    .current_dir(working_directory?)
+                    .args(["worktree", "prune"])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to prune worktrees:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        self.run_sequencer(SequencerOperation::CherryPick, commits, false, env)
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        let mut args = vec!["--no-edit".to_string()];
+        args.extend(commits);
+        self.run_sequencer(SequencerOperation::Revert, args, false, env)
+    }
+
+    fn resume_sequencer(
+        &self,
+        operation: SequencerOperation,
+        command: RebaseCommand,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<SequencerOutcome>> {
+        self.run_sequencer(operation, vec![command.arg().to_string()], true, env)
+    }
+
+    fn stopped_sequencer(&self) -> BoxFuture<'_, Result<Option<StoppedSequencer>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                for operation in [SequencerOperation::CherryPick, SequencerOperation::Revert] {
+                    if let Ok(sha) =
+                        smol::fs::read_to_string(git_dir.join(operation.head_file())).await
+                    {
+                        return Ok(Some(StoppedSequencer {
+                            operation,
+                            sha: sha.trim().to_string().into(),
+                        }));
+                    }
+                }
+                Ok(None)
+            })
+            .boxed()
+    }
+
+    fn bisect_start(
+        &self,
+        bad: Option<String>,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        if bad.is_none() && !good.is_empty() {
+            return future::ready(Err(anyhow!(
+                "Good commits can only be marked along with a bad one when starting a bisect"
+            )))
+            .boxed();
+        }
+        let mut args = vec!["start".to_string()];
+        args.extend(bad);
+        args.extend(good);
+        self.run_bisect(args, env)
+    }
+
+    fn bisect_mark(
+        &self,
+        mark: BisectMark,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let mut args = vec![mark.name().to_string()];
+        args.extend(commit);
+        self.run_bisect(args, env)
+    }
+
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.run_bisect(vec!["reset".to_string()], env)
+    }
+
+    fn bisect_status(&self) -> BoxFuture<'_, Result<Option<BisectStatus>>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                if smol::fs::metadata(git_dir.join("BISECT_START"))
+                    .await
+                    .is_err()
+                {
+                    return Ok(None);
+                }
+                let log = smol::fs::read_to_string(git_dir.join("BISECT_LOG"))
+                    .await
+                    .unwrap_or_default();
+                Ok(Some(parse_bisect_log(&log)))
+            })
+            .boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<GitTag>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .args([
+                        "--no-optional-locks",
+                        "for-each-ref",
+                        "--sort=-creatordate",
+                        "--format=%(refname:short)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(contents:subject)",
+                        "refs/tags",
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list tags:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(parse_tag_list(&String::from_utf8_lossy(&output.stdout)))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
         self.executor
             .spawn(async move {
-                GitBinary::new(git_binary_path, working_directory?, executor)
-                    .run(&["branch", "-m", &branch, &new_name])
-                    .await?;
-                anyhow::Ok(())
+                // `git tag` doesn't accept `--` before the tag name.
+                anyhow::ensure!(!name.starts_with('-'), "Invalid tag name: {name}");
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .envs(env.iter())
+                    .current_dir(working_directory?)
+                    .arg("tag");
+                if let Some(message) = message {
+                    command.arg("-a").arg("-m").arg(message);
+                }
+                let output = command.arg(&name).arg(&target).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to create tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
             })
             .boxed()
     }
 
-    fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
+    fn push_tags(
+        &self,
+        remote_name: String,
+        tag_name: Option<String>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
         let working_directory = self.working_directory();
-        let git_binary_path = self.any_git_binary_path.clone();
-
-        let remote_url = self
-            .remote_url("upstream")
-            .or_else(|| self.remote_url("origin"));
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't push tags")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .arg("push")
+                .arg(remote_name);
+            match tag_name {
+                Some(tag_name) => command.arg(format!("refs/tags/{tag_name}")),
+                None => command.arg("--tags"),
+            };
+            command
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
 
-        self.executor
-            .spawn(async move {
-                crate::blame::Blame::for_path(
-                    &git_binary_path,
-                    &working_directory?,
-                    &path,
-                    &content,
-                    remote_url,
-                )
-                .await
-            })
-            .boxed()
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
     }
 
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>> {
@@ -2179,6 +2990,177 @@ impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
     }
 }
 
+/// Each commit starts with a record separator, followed by its fields separated by null bytes.
+/// When listing the history of lines, git prints a patch after the fields, which is ignored.
+const LOG_FORMAT: &str = "--format=%x1e%H%x00%P%x00%at%x00%an%x00%ae%x00%D%x00%s%x00";
+
+fn log_args(options: &LogOptions) -> Result<Vec<OsString>> {
+    let mut args: Vec<OsString> = vec![
+        "--no-optional-locks".into(),
+        "log".into(),
+        LOG_FORMAT.into(),
+        // Rewrite the parents of the commits to the ones listed when the history is filtered.
+        "--parents".into(),
+    ];
+    if options.skip > 0 {
+        args.push(format!("--skip={}", options.skip).into());
+    }
+    if options.limit > 0 {
+        args.push(format!("--max-count={}", options.limit).into());
+    }
+    if options.author.is_some() || options.message.is_some() {
+        args.extend(["--regexp-ignore-case".into(), "--fixed-strings".into()]);
+        args.push("--all-match".into());
+    }
+    if let Some(author) = &options.author {
+        args.push(format!("--author={author}").into());
+    }
+    if let Some(message) = &options.message {
+        args.push(format!("--grep={message}").into());
+    }
+    if let Some(line_range) = &options.line_range {
+        anyhow::ensure!(
+            !line_range.rows.is_empty(),
+            "cannot list the history of no lines"
+        );
+        args.push(
+            format!(
+                "-L{},{}:{}",
+                line_range.rows.start + 1,
+                line_range.rows.end,
+                line_range.path.as_unix_str()
+            )
+            .into(),
+        );
+    }
+    let revision = options.revision.as_deref().unwrap_or("HEAD");
+    anyhow::ensure!(!revision.starts_with('-'), "invalid revision {revision:?}");
+    args.push(revision.into());
+    args.push("--".into());
+    args.extend(options.paths.iter().map(|path| path.as_unix_str().into()));
+    Ok(args)
+}
+
+fn parse_log_output(output: &str) -> Result<Vec<CommitLogEntry>> {
+    output
+        .split('\x1e')
+        .skip(1)
+        .map(|record| {
+            let fields = record.splitn(8, '\0').collect::<Vec<_>>();
+            if fields.len() != 8 {
+                bail!("unexpected git-log output: {record:?}")
+            }
+            let refs = fields[5]
+                .split(", ")
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    let name = name.strip_prefix("HEAD -> ").unwrap_or(name);
+                    let name = name.strip_prefix("tag: ").unwrap_or(name);
+                    SharedString::from(name.to_string())
+                })
+                .collect();
+            Ok(CommitLogEntry {
+                sha: fields[0].to_string().into(),
+                parents: fields[1]
+                    .split(' ')
+                    .filter(|sha| !sha.is_empty())
+                    .map(|sha| sha.to_string().into())
+                    .collect(),
+                commit_timestamp: fields[2].parse()?,
+                author_name: fields[3].to_string().into(),
+                author_email: fields[4].to_string().into(),
+                refs,
+                subject: fields[6].to_string().into(),
+            })
+        })
+        .collect()
+}
+
+/// Parses the log git keeps of a bisect, where each step is recorded with a comment such as
+/// `# good: [<sha>] <subject>`.
+fn parse_bisect_log(log: &str) -> BisectStatus {
+    let mut status = BisectStatus::default();
+    for line in log.lines() {
+        let Some((kind, rest)) = line
+            .strip_prefix("# ")
+            .and_then(|comment| comment.split_once(": ["))
+        else {
+            continue;
+        };
+        let Some((sha, _subject)) = rest.split_once(']') else {
+            continue;
+        };
+        let sha = SharedString::from(sha.to_string());
+        match kind {
+            "bad" => status.bad = Some(sha),
+            "good" => status.good.push(sha),
+            "skip" => status.skipped.push(sha),
+            "first bad commit" => status.culprit = Some(sha),
+            _ => {}
+        }
+    }
+    status
+}
+
+/// Parses the output of `git for-each-ref refs/tags`, in the format used by `tags`.
+fn parse_tag_list(output: &str) -> Vec<GitTag> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let name = fields.next()?;
+            let object_type = fields.next()?;
+            let object_sha = fields.next()?;
+            let peeled_sha = fields.next()?;
+            let subject = fields.next().unwrap_or_default();
+            // Annotated tags point to a tag object, which in turn points to the commit.
+            let is_annotated = object_type == "tag";
+            Some(GitTag {
+                name: name.to_string().into(),
+                sha: if is_annotated { peeled_sha } else { object_sha }
+                    .to_string()
+                    .into(),
+                message: is_annotated.then(|| subject.to_string().into()),
+            })
+        })
+        .collect()
+}
+
+/// Parses the output of `git worktree list --porcelain`, made of a paragraph per worktree.
+fn parse_worktree_list(output: &str) -> Vec<GitWorktree> {
+    output
+        .split("\n\n")
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let path = lines.next()?.strip_prefix("worktree ")?;
+            let mut worktree = GitWorktree {
+                path: PathBuf::from(path),
+                sha: None,
+                ref_name: None,
+                is_main: false,
+                is_locked: false,
+                is_prunable: false,
+            };
+            for line in lines {
+                let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+                match key {
+                    "HEAD" => worktree.sha = Some(value.to_string().into()),
+                    "branch" => worktree.ref_name = Some(value.to_string().into()),
+                    "locked" => worktree.is_locked = true,
+                    "prunable" => worktree.is_prunable = true,
+                    _ => {}
+                }
+            }
+            Some(worktree)
+        })
+        .enumerate()
+        .map(|(ix, mut worktree)| {
+            worktree.is_main = ix == 0;
+            worktree
+        })
+        .collect()
+}
+
 fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     let mut branches = Vec::new();
     for line in input.split('\n') {
@@ -2253,6 +3235,26 @@ fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     }))
 }
 
+/// Reads the state a rebase command left the repository in. A failed command that left a rebase
+/// in progress stopped on conflicts, which the user resolves before continuing.
+async fn rebase_outcome(
+    git_dir: &Path,
+    output: std::process::Output,
+) -> Result<Option<RebaseProgress>> {
+    let progress = rebase::read_progress(git_dir).await?;
+    if progress.is_none() {
+        smol::fs::remove_dir_all(git_dir.join(rebase::REBASE_STATE_DIR))
+            .await
+            .ok();
+        anyhow::ensure!(
+            output.status.success(),
+            "Failed to rebase:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(progress)
+}
+
 fn checkpoint_author_envs() -> HashMap<String, String> {
     HashMap::from_iter([
         ("GIT_AUTHOR_NAME".to_string(), "Zed".to_string()),
@@ -2496,6 +3498,100 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_interactive_rebase(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        for (file, content, message) in [
+            ("a", "a", "Initial commit"),
+            ("b", "b", "Add b"),
+            ("b", "b\nb", "Fix b"),
+            ("c", "c", "Add c"),
+        ] {
+            smol::fs::write(repo_dir.path().join(file), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(message.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+        }
+        let subjects = async || {
+            repo.log(LogOptions::default())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.subject.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let mut todo = repo.rebase_todo(Some("HEAD~3".into())).await.unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add b", "Fix b", "Add c"]
+        );
+        assert!(repo.rebase_progress().await.unwrap().is_none());
+
+        // Applying a change to a file before the commit adding it stops on a conflict.
+        todo.swap(0, 1);
+        let progress = repo
+            .start_rebase(Some("HEAD~3".into()), todo.clone(), env.clone())
+            .await
+            .unwrap()
+            .expect("rebase should stop on a conflict");
+        assert_eq!(progress.done.len(), 1);
+        assert_eq!(progress.remaining.len(), 2);
+        assert!(progress.branch.is_some());
+        assert_eq!(repo.rebase_progress().await.unwrap(), Some(progress));
+
+        assert!(
+            repo.rebase(RebaseCommand::Abort, env.clone())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            subjects().await,
+            ["Add c", "Fix b", "Add b", "Initial commit"]
+        );
+
+        todo.swap(0, 1);
+        todo.rotate_right(1);
+        todo[0].action = rebase::RebaseAction::Reword;
+        todo[0].message = Some("Add the c file\n".into());
+        todo[2].action = rebase::RebaseAction::Fixup;
+        assert!(
+            repo.start_rebase(Some("HEAD~3".into()), todo, env.clone())
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            subjects().await,
+            ["Add b", "Add the c file", "Initial commit"]
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("b"))
+                .await
+                .unwrap(),
+            "b\nb"
+        );
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
@@ -2524,6 +3620,315 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_log_parsing() {
+        let output = "\x1e1b0f4fe9e1dc1fc3d4d9bfa04fd3bd1a1a3ad3b4\0\01733187470\0John Doe\0john@example.com\0HEAD -> main, tag: v1.0, origin/main\0Initial commit\0\n\
+            \x1e9c2b7c4d52a05bca9c3f8a1e5f7d3c4b2a1e0f9d\0aaaa bbbb\01733187480\0Jane Doe\0jane@example.com\0\0Merge branch 'feature'\0\n\
+            diff --git a/file b/file\n";
+        assert_eq!(
+            parse_log_output(output).unwrap(),
+            vec![
+                CommitLogEntry {
+                    sha: "1b0f4fe9e1dc1fc3d4d9bfa04fd3bd1a1a3ad3b4".into(),
+                    parents: Vec::new(),
+                    subject: "Initial commit".into(),
+                    commit_timestamp: 1733187470,
+                    author_name: "John Doe".into(),
+                    author_email: "john@example.com".into(),
+                    refs: vec!["main".into(), "v1.0".into(), "origin/main".into()],
+                },
+                CommitLogEntry {
+                    sha: "9c2b7c4d52a05bca9c3f8a1e5f7d3c4b2a1e0f9d".into(),
+                    parents: vec!["aaaa".into(), "bbbb".into()],
+                    subject: "Merge branch 'feature'".into(),
+                    commit_timestamp: 1733187480,
+                    author_name: "Jane Doe".into(),
+                    author_email: "jane@example.com".into(),
+                    refs: Vec::new(),
+                },
+            ]
+        );
+        assert_eq!(parse_log_output("").unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_worktree_list_parsing() {
+        let output = "worktree /home/me/zed\n\
+            HEAD 1b0f4fe9e1dc1fc3d4d9bfa04fd3bd1a1a3ad3b4\n\
+            branch refs/heads/main\n\
+            \n\
+            worktree /home/me/zed-review\n\
+            HEAD 9c2b7c4d52a05bca9c3f8a1e5f7d3c4b2a1e0f9d\n\
+            detached\n\
+            locked\n\
+            \n\
+            worktree /home/me/zed-feature\n\
+            HEAD 9c2b7c4d52a05bca9c3f8a1e5f7d3c4b2a1e0f9d\n\
+            branch refs/heads/feature/parser\n\
+            prunable gitdir file points to non-existent location\n\
+            \n";
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(
+            worktrees,
+            vec![
+                GitWorktree {
+                    path: PathBuf::from("/home/me/zed"),
+                    sha: Some("1b0f4fe9e1dc1fc3d4d9bfa04fd3bd1a1a3ad3b4".into()),
+                    ref_name: Some("refs/heads/main".into()),
+                    is_main: true,
+                    is_locked: false,
+                    is_prunable: false,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/home/me/zed-review"),
+                    sha: Some("9c2b7c4d52a05bca9c3f8a1e5f7d3c4b2a1e0f9d".into()),
+                    ref_name: None,
+                    is_main: false,
+                    is_locked: true,
+                    is_prunable: false,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/home/me/zed-feature"),
+                    sha: Some("9c2b7c4d52a05bca9c3f8a1e5f7d3c4b2a1e0f9d".into()),
+                    ref_name: Some("refs/heads/feature/parser".into()),
+                    is_main: false,
+                    is_locked: false,
+                    is_prunable: true,
+                },
+            ]
+        );
+        assert_eq!(worktrees[2].branch_name(), Some("feature/parser"));
+        assert_eq!(parse_worktree_list(""), Vec::new());
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_revert_and_tags(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let file_path = repo_dir.path().join("a");
+        for (content, message) in [("one", "Initial commit"), ("two", "Change a")] {
+            smol::fs::write(&file_path, content).await.unwrap();
+            repo.stage_paths(vec![repo_path("a")], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(message.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+        }
+        let change_sha = repo
+            .revparse_batch(vec!["HEAD".into()])
+            .await
+            .unwrap()
+            .remove(0)
+            .unwrap();
+
+        assert_eq!(
+            repo.revert(vec![change_sha.clone()], env.clone())
+                .await
+                .unwrap(),
+            SequencerOutcome::Completed
+        );
+        assert_eq!(smol::fs::read_to_string(&file_path).await.unwrap(), "one");
+        assert_eq!(
+            repo.cherry_pick(vec![change_sha.clone()], env.clone())
+                .await
+                .unwrap(),
+            SequencerOutcome::Completed
+        );
+        assert_eq!(smol::fs::read_to_string(&file_path).await.unwrap(), "two");
+
+        // Reverting the change conflicts with the later commit changing the same line.
+        smol::fs::write(&file_path, "three").await.unwrap();
+        repo.stage_paths(vec![repo_path("a")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit("Three".into(), None, CommitOptions::default(), env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.revert(vec![change_sha.clone()], env.clone())
+                .await
+                .unwrap(),
+            SequencerOutcome::Stopped
+        );
+        assert_eq!(
+            repo.stopped_sequencer().await.unwrap(),
+            Some(StoppedSequencer {
+                operation: SequencerOperation::Revert,
+                sha: change_sha.clone().into(),
+            })
+        );
+        assert!(
+            repo.cherry_pick(vec![change_sha.clone()], env.clone())
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            repo.resume_sequencer(
+                SequencerOperation::Revert,
+                RebaseCommand::Abort,
+                env.clone()
+            )
+            .await
+            .unwrap(),
+            SequencerOutcome::Completed
+        );
+        assert_eq!(smol::fs::read_to_string(&file_path).await.unwrap(), "three");
+        assert_eq!(repo.stopped_sequencer().await.unwrap(), None);
+
+        repo.create_tag("v1".into(), "HEAD".into(), None, env.clone())
+            .await
+            .unwrap();
+        repo.create_tag(
+            "v0".into(),
+            change_sha.clone(),
+            Some("First release".into()),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            repo.create_tag("--force".into(), "HEAD".into(), None, env.clone())
+                .await
+                .is_err()
+        );
+        let mut tags = repo.tags().await.unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].name.as_ref(), "v0");
+        assert_eq!(tags[0].sha.as_ref(), change_sha);
+        assert_eq!(tags[0].message.as_deref(), Some("First release"));
+        assert_eq!(tags[1].name.as_ref(), "v1");
+        assert_eq!(tags[1].message, None);
+    }
+
+    #[gpui::test]
+    async fn test_bisect(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let file_path = repo_dir.path().join("a");
+        let mut shas = Vec::new();
+        for content in [
+            "fine",
+            "still fine",
+            "broken",
+            "still broken",
+            "very broken",
+        ] {
+            smol::fs::write(&file_path, content).await.unwrap();
+            repo.stage_paths(vec![repo_path("a")], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(content.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+            shas.push(
+                repo.revparse_batch(vec!["HEAD".into()])
+                    .await
+                    .unwrap()
+                    .remove(0)
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(repo.bisect_status().await.unwrap(), None);
+        assert!(
+            repo.bisect_start(None, vec![shas[0].clone()], env.clone())
+                .await
+                .is_err()
+        );
+        repo.bisect_start(Some("HEAD".into()), vec![shas[0].clone()], env.clone())
+            .await
+            .unwrap();
+        let mut status = repo.bisect_status().await.unwrap().unwrap();
+        assert_eq!(status.bad.as_deref(), Some(shas[4].as_str()));
+        assert_eq!(status.good, vec![SharedString::from(shas[0].clone())]);
+
+        while status.culprit.is_none() {
+            let content = smol::fs::read_to_string(&file_path).await.unwrap();
+            let mark = if content.contains("broken") {
+                BisectMark::Bad
+            } else {
+                BisectMark::Good
+            };
+            repo.bisect_mark(mark, None, env.clone()).await.unwrap();
+            status = repo.bisect_status().await.unwrap().unwrap();
+        }
+        assert_eq!(status.culprit.as_deref(), Some(shas[2].as_str()));
+
+        repo.bisect_reset(env.clone()).await.unwrap();
+        assert_eq!(repo.bisect_status().await.unwrap(), None);
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "very broken"
+        );
+    }
+
+    #[test]
+    fn test_bisect_log_parsing() {
+        let log = "git bisect start\n\
+                   # status: waiting for both good and bad commits\n\
+                   # bad: [87aa9163] Break the parser\n\
+                   git bisect bad 87aa9163\n\
+                   # good: [be401869] Initial commit\n\
+                   git bisect good be401869\n\
+                   # skip: [a867e56d] Refactor the parser\n\
+                   git bisect skip a867e56d\n\
+                   # bad: [423a3da3] Rename the parser\n\
+                   git bisect bad 423a3da3\n\
+                   # first bad commit: [423a3da3] Rename the parser\n";
+        assert_eq!(
+            parse_bisect_log(log),
+            BisectStatus {
+                bad: Some("423a3da3".into()),
+                good: vec!["be401869".into()],
+                skipped: vec!["a867e56d".into()],
+                culprit: Some("423a3da3".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tag_list_parsing() {
+        let output = "v1.1\0tag\01a2b3c4d\09f8e7d6c\0Release 1.1\n\
+                      v1.0\0commit\05e6f7a8b\0\0Fix the parser\n";
+        assert_eq!(
+            parse_tag_list(output),
+            vec![
+                GitTag {
+                    name: "v1.1".into(),
+                    sha: "9f8e7d6c".into(),
+                    message: Some("Release 1.1".into()),
+                },
+                GitTag {
+                    name: "v1.0".into(),
+                    sha: "5e6f7a8b".into(),
+                    message: None,
+                },
+            ]
+        );
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<'_, Result<()>> {
@@ -0,0 +1,522 @@
+use std::iter;
+
+use anyhow::Context as _;
+use git::repository::{BisectMark, BisectStatus, CommitSummary};
+use gpui::{
+    Action as _, AsyncWindowContext, Corner, Entity, EventEmitter, Subscription, Task, WeakEntity,
+};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use project::git_store::{GitStoreEvent, Repository};
+use task::{ResolvedTask, ShellBuilder, SpawnInTerminal, TaskId};
+use ui::{ButtonLike, ContextMenu, PopoverMenu, Tooltip, prelude::*};
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{StatusItemView, ToolbarItemEvent, Workspace, item::ItemHandle};
+
+use crate::{commit_view::CommitView, picker_prompt};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::StartBisect, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        start(repository, None, workspace.weak_handle(), window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::BisectGood, window, cx| {
+        mark_head(workspace, BisectMark::Good, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::BisectBad, window, cx| {
+        mark_head(workspace, BisectMark::Bad, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::BisectSkip, window, cx| {
+        mark_head(workspace, BisectMark::Skip, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::BisectRun, window, cx| {
+        run(workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::BisectReset, window, cx| {
+        reset(workspace, window, cx);
+    });
+}
+
+/// Starts a bisect with `HEAD` marked bad, and `good` marked good when given.
+pub(crate) fn start(
+    repository: Entity<Repository>,
+    good: Option<SharedString>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let start = repository.update(cx, |repository, _| {
+        repository.bisect_start(
+            Some("HEAD".to_string()),
+            good.into_iter().map(|sha| sha.to_string()).collect(),
+        )
+    });
+    window
+        .spawn(cx, async move |cx| {
+            start.await??;
+            finish_step(repository, workspace, cx).await
+        })
+        .detach_and_prompt_err("Failed to start bisect", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+}
+
+/// Marks `commit`, or `HEAD` when there is none, in the bisect in progress.
+pub(crate) fn mark(
+    repository: Entity<Repository>,
+    mark: BisectMark,
+    commit: Option<SharedString>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let task = repository.update(cx, |repository, _| {
+        repository.bisect_mark(mark, commit.map(|sha| sha.to_string()))
+    });
+    window
+        .spawn(cx, async move |cx| {
+            task.await??;
+            finish_step(repository, workspace, cx).await
+        })
+        .detach_and_prompt_err("Failed to mark commit", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+}
+
+fn mark_head(
+    workspace: &mut Workspace,
+    bisect_mark: BisectMark,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    mark(
+        repository,
+        bisect_mark,
+        None,
+        workspace.weak_handle(),
+        window,
+        cx,
+    );
+}
+
+fn reset(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let reset = repository.update(cx, |repository, _| repository.bisect_reset());
+    cx.spawn(async move |workspace, cx| {
+        reset.await??;
+        workspace.update(cx, |workspace, cx| refresh_status_item(workspace, cx))
+    })
+    .detach_and_prompt_err("Failed to reset bisect", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
+/// Lets git mark the commits of the bisect in progress by running a task on each of them, in a
+/// terminal: the commits the task succeeds on are good, and the ones it fails on are bad.
+fn run(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let is_via_collab = workspace.project().read(cx).is_via_collab();
+    let task_contexts = tasks_ui::task_contexts(workspace, window, cx);
+    let status = repository.update(cx, |repository, _| repository.bisect_status());
+    cx.spawn_in(window, async move |workspace, cx| {
+        anyhow::ensure!(
+            !is_via_collab,
+            "Tasks can't be run in projects shared with you"
+        );
+        let status = status.await??.context("No bisect in progress")?;
+        anyhow::ensure!(
+            status.bad.is_some() && !status.good.is_empty(),
+            "Mark a good and a bad commit before running a task on the commits between them"
+        );
+
+        let task_contexts = task_contexts.await;
+        let tasks = workspace
+            .update(cx, |workspace, cx| {
+                let Some(task_inventory) = workspace
+                    .project()
+                    .read(cx)
+                    .task_store()
+                    .read(cx)
+                    .task_inventory()
+                    .cloned()
+                else {
+                    return Task::ready(Vec::new());
+                };
+                let (file, language) = task_contexts
+                    .location()
+                    .map(|location| {
+                        let buffer = location.buffer.read(cx);
+                        (
+                            buffer.file().cloned(),
+                            buffer.language_at(location.range.start),
+                        )
+                    })
+                    .unwrap_or_default();
+                task_inventory
+                    .read(cx)
+                    .list_tasks(file, language, task_contexts.worktree(), cx)
+            })?
+            .await;
+        anyhow::ensure!(!tasks.is_empty(), "There are no tasks to run");
+
+        let labels = tasks
+            .iter()
+            .map(|(_, template)| SharedString::from(template.label.clone()))
+            .collect();
+        let selection = workspace
+            .update_in(cx, |workspace, window, cx| {
+                picker_prompt::prompt(
+                    "Pick the task telling good commits from bad ones",
+                    labels,
+                    workspace.weak_handle(),
+                    window,
+                    cx,
+                )
+            })?
+            .await;
+        let Some(selection) = selection else {
+            return Ok(());
+        };
+        let (task_source_kind, template) = &tasks[selection];
+        let task_context = task_contexts.active_context().cloned().unwrap_or_default();
+        let resolved_task = template
+            .resolve_task(&task_source_kind.to_id_base(), &task_context)
+            .context("Failed to resolve the task")?;
+
+        let exit_status = workspace
+            .update_in(cx, |workspace, window, cx| {
+                let is_windows = workspace.project().read(cx).path_style(cx).is_windows();
+                let bisect_task = bisect_run(resolved_task, is_windows)?;
+                anyhow::Ok(workspace.spawn_in_terminal(bisect_task, window, cx))
+            })??
+            .await;
+        match exit_status {
+            Some(Ok(_)) => finish_step(repository, workspace, cx).await,
+            Some(Err(error)) => Err(error),
+            None => Ok(()),
+        }
+    })
+    .detach_and_prompt_err("Failed to run bisect", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
+/// Wraps the command of a task in `git bisect run`. Git runs each of its arguments as a single
+/// word, so the command is wrapped in the task's shell first, and each word is quoted for that
+/// shell, which the terminal then runs `git bisect run` in.
+fn bisect_run(resolved_task: ResolvedTask, is_windows: bool) -> anyhow::Result<SpawnInTerminal> {
+    anyhow::ensure!(
+        !resolved_task.is_compound(),
+        "Task {:?} has no command of its own to test the commits with",
+        resolved_task.display_label()
+    );
+    let mut spawn_in_terminal = resolved_task.resolved;
+    let shell_kind = spawn_in_terminal.shell.shell_kind(is_windows);
+    let (shell, shell_args) = ShellBuilder::new(&spawn_in_terminal.shell, is_windows)
+        .non_interactive()
+        .build(spawn_in_terminal.command.clone(), &spawn_in_terminal.args);
+    let command = iter::once(shell)
+        .chain(shell_args)
+        .map(|word| {
+            shell_kind
+                .try_quote(&word)
+                .map(|quoted| quoted.into_owned())
+                .with_context(|| format!("Failed to quote {word:?} for {shell_kind:?}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    spawn_in_terminal.id = TaskId(format!("git_bisect_run_{}", spawn_in_terminal.id.0));
+    spawn_in_terminal.label = format!("git bisect run {}", spawn_in_terminal.label);
+    spawn_in_terminal.full_label = format!("git bisect run {}", spawn_in_terminal.full_label);
+    spawn_in_terminal.command_label = format!("git bisect run {}", spawn_in_terminal.command_label);
+    spawn_in_terminal.command = Some("git".to_string());
+    spawn_in_terminal.args = ["bisect", "run"]
+        .into_iter()
+        .map(ToString::to_string)
+        .chain(command)
+        .collect();
+    spawn_in_terminal.show_rerun = false;
+    Ok(spawn_in_terminal)
+}
+
+/// Refreshes the status bar after a bisect step, and opens the first bad commit once the
+/// bisect found it.
+async fn finish_step(
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> anyhow::Result<()> {
+    let status = repository
+        .update(cx, |repository, _| repository.bisect_status())?
+        .await??;
+    workspace.update_in(cx, |workspace, window, cx| {
+        refresh_status_item(workspace, cx);
+        let Some(culprit) = status.and_then(|status| status.culprit) else {
+            return;
+        };
+        let toast = StatusToast::new(
+            format!(
+                "Found the first bad commit, {}",
+                culprit.get(..git::SHORT_SHA_LENGTH).unwrap_or(&culprit)
+            ),
+            cx,
+            |this, _| {
+                this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+                    .action("Reset Bisect", |window, cx| {
+                        window.dispatch_action(git::BisectReset.boxed_clone(), cx);
+                    })
+            },
+        );
+        workspace.toggle_status_toast(toast, cx);
+        open_commit(culprit, &repository, workspace.weak_handle(), window, cx);
+    })
+}
+
+fn open_commit(
+    sha: SharedString,
+    repository: &Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    CommitView::open(
+        CommitSummary {
+            sha,
+            subject: SharedString::default(),
+            commit_timestamp: 0,
+            author_name: SharedString::default(),
+            has_parent: true,
+        },
+        repository.downgrade(),
+        workspace,
+        window,
+        cx,
+    );
+}
+
+/// Whether the status bar of `workspace` shows a bisect in progress.
+pub(crate) fn is_bisecting(workspace: &Workspace, cx: &App) -> bool {
+    workspace
+        .status_bar()
+        .read(cx)
+        .item_of_type::<BisectStatusItem>()
+        .is_some_and(|item| item.read(cx).status.is_some())
+}
+
+fn refresh_status_item(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    if let Some(item) = workspace
+        .status_bar()
+        .read(cx)
+        .item_of_type::<BisectStatusItem>()
+    {
+        item.update(cx, |item, cx| item.refresh(cx));
+    }
+}
+
+/// The status bar item showing the bisect in progress in the active repository, with the
+/// actions marking the commit being tested.
+pub struct BisectStatusItem {
+    workspace: WeakEntity<Workspace>,
+    repository: Option<Entity<Repository>>,
+    status: Option<BisectStatus>,
+    refresh_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl BisectStatusItem {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let project = workspace.project().read(cx);
+        let git_store = project.git_store().clone();
+        let repository = project.active_repository(cx);
+        let _subscription = cx.subscribe(&git_store, |this, git_store, event, cx| match event {
+            GitStoreEvent::ActiveRepositoryChanged(_) => {
+                this.repository = git_store.read(cx).active_repository();
+                this.refresh(cx);
+            }
+            GitStoreEvent::RepositoryUpdated(_, _, true) => this.refresh(cx),
+            _ => {}
+        });
+        let mut this = Self {
+            workspace: workspace.weak_handle(),
+            repository,
+            status: None,
+            refresh_task: Task::ready(()),
+            _subscription,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(repository) = self.repository.clone() else {
+            self.status = None;
+            cx.notify();
+            return;
+        };
+        let status = repository.update(cx, |repository, _| repository.bisect_status());
+        self.refresh_task = cx.spawn(async move |this, cx| {
+            let Ok(Ok(status)) = status.await else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.status = status;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn render_menu(culprit_found: bool) -> impl IntoElement {
+        PopoverMenu::new("bisect-menu")
+            .trigger(
+                IconButton::new("bisect-menu-trigger", IconName::ChevronDown)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted),
+            )
+            .menu(move |window, cx| {
+                Some(ContextMenu::build(window, cx, |context_menu, _, _| {
+                    context_menu
+                        .when(!culprit_found, |context_menu| {
+                            context_menu
+                                .action("Mark Good", git::BisectGood.boxed_clone())
+                                .action("Mark Bad", git::BisectBad.boxed_clone())
+                                .action("Skip", git::BisectSkip.boxed_clone())
+                                .action("Run Task…", git::BisectRun.boxed_clone())
+                                .separator()
+                        })
+                        .action("Reset Bisect", git::BisectReset.boxed_clone())
+                }))
+            })
+            .anchor(Corner::BottomLeft)
+    }
+}
+
+impl Render for BisectStatusItem {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (Some(status), Some(repository)) = (&self.status, &self.repository) else {
+            return div().into_any_element();
+        };
+        let short_sha = |sha: &SharedString| {
+            SharedString::from(sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(sha).to_string())
+        };
+
+        let (label, tooltip, commit) = if let Some(culprit) = &status.culprit {
+            (
+                format!("Bisect: {} is the first bad commit", short_sha(culprit)),
+                "Open the first bad commit".to_string(),
+                Some(culprit.clone()),
+            )
+        } else if status.bad.is_none() || status.good.is_empty() {
+            (
+                "Bisect: waiting for a good and a bad commit".to_string(),
+                "Mark commits from the commit history, or mark the current one from this menu"
+                    .to_string(),
+                None,
+            )
+        } else {
+            let head = repository
+                .read(cx)
+                .head_commit
+                .as_ref()
+                .map(|commit| commit.sha.clone());
+            let label = match &head {
+                Some(head) => format!("Bisect: testing {}", short_sha(head)),
+                None => "Bisect: testing".to_string(),
+            };
+            let tooltip = format!(
+                "{} good, {} skipped. Mark the current commit from this menu",
+                status.good.len(),
+                status.skipped.len()
+            );
+            (label, tooltip, head)
+        };
+
+        h_flex()
+            .gap_0p5()
+            .child(
+                ButtonLike::new("bisect-status")
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Icon::new(IconName::GitBranchAlt)
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(Label::new(label).size(LabelSize::Small)),
+                    )
+                    .tooltip(Tooltip::text(tooltip))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        let (Some(commit), Some(repository)) = (commit.clone(), &this.repository)
+                        else {
+                            return;
+                        };
+                        open_commit(commit, repository, this.workspace.clone(), window, cx);
+                    })),
+            )
+            .child(Self::render_menu(status.culprit.is_some()))
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for BisectStatusItem {}
+
+impl StatusItemView for BisectStatusItem {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use task::{Shell, TaskContext, TaskTemplate};
+
+    use super::*;
+
+    #[test]
+    fn test_bisect_run_wraps_the_task_in_its_shell() {
+        let task = TaskTemplate {
+            label: "test".to_string(),
+            command: "cargo".to_string(),
+            args: vec!["test".to_string(), "--workspace".to_string()],
+            shell: Shell::Program("bash".to_string()),
+            ..TaskTemplate::default()
+        }
+        .resolve_task("test", &TaskContext::default())
+        .unwrap();
+
+        let bisect_task = bisect_run(task, false).unwrap();
+        assert_eq!(bisect_task.command.as_deref(), Some("git"));
+        assert_eq!(
+            bisect_task.args,
+            ["bisect", "run", "bash", "-c", "'cargo test --workspace'"]
+        );
+        assert_eq!(bisect_task.shell, Shell::Program("bash".to_string()));
+    }
+
+    #[test]
+    fn test_bisect_run_rejects_compound_tasks() {
+        let task = TaskTemplate {
+            label: "all".to_string(),
+            depends_on: vec!["test".to_string()],
+            ..TaskTemplate::default()
+        }
+        .resolve_task("test", &TaskContext::default())
+        .unwrap();
+
+        assert!(bisect_run(task, false).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use git::rebase::RebaseCommand;
+use git::repository::{SequencerOperation, SequencerOutcome};
+use gpui::{Action as _, App, Context, Entity, SharedString, WeakEntity, Window};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use project::git_store::Repository;
+use ui::prelude::*;
+use workspace::Workspace;
+use workspace::notifications::DetachAndPromptErr;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(
+        |workspace, _: &git::ContinueCherryPickOrRevert, window, cx| {
+            resume(workspace, RebaseCommand::Continue, window, cx);
+        },
+    );
+    workspace.register_action(|workspace, _: &git::SkipCherryPickOrRevert, window, cx| {
+        resume(workspace, RebaseCommand::Skip, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::AbortCherryPickOrRevert, window, cx| {
+        resume(workspace, RebaseCommand::Abort, window, cx);
+    });
+}
+
+/// Applies the changes of a commit on top of `HEAD`.
+pub(crate) fn cherry_pick(
+    repository: Entity<Repository>,
+    sha: SharedString,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    apply(
+        SequencerOperation::CherryPick,
+        repository,
+        sha,
+        workspace,
+        window,
+        cx,
+    );
+}
+
+/// Commits the reverse of the changes of a commit on top of `HEAD`.
+pub(crate) fn revert(
+    repository: Entity<Repository>,
+    sha: SharedString,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    apply(
+        SequencerOperation::Revert,
+        repository,
+        sha,
+        workspace,
+        window,
+        cx,
+    );
+}
+
+fn apply(
+    operation: SequencerOperation,
+    repository: Entity<Repository>,
+    sha: SharedString,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let short_sha: SharedString = sha
+        .get(..git::SHORT_SHA_LENGTH)
+        .unwrap_or(&sha)
+        .to_string()
+        .into();
+    let task = repository.update(cx, |repository, _| match operation {
+        SequencerOperation::CherryPick => repository.cherry_pick(vec![sha.to_string()]),
+        SequencerOperation::Revert => repository.revert(vec![sha.to_string()]),
+    });
+    window
+        .spawn(cx, async move |cx| {
+            let outcome = task.await??;
+            workspace.update(cx, |workspace, cx| {
+                show_outcome(workspace, operation, outcome, Some(short_sha), cx)
+            })
+        })
+        .detach_and_prompt_err(
+            &format!("Failed to {} commit", operation.name()),
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+}
+
+/// Continues, skips or aborts the cherry-pick or revert of the active repository that stopped.
+fn resume(
+    workspace: &mut Workspace,
+    command: RebaseCommand,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let stopped = repository.update(cx, |repository, _| repository.stopped_sequencer());
+    cx.spawn(async move |workspace, cx| {
+        let stopped = stopped
+            .await??
+            .ok_or_else(|| anyhow::anyhow!("No cherry-pick or revert in progress"))?;
+        let outcome = repository
+            .update(cx, |repository, _| {
+                repository.resume_sequencer(stopped.operation, command)
+            })?
+            .await??;
+        if command == RebaseCommand::Abort {
+            return anyhow::Ok(());
+        }
+        workspace.update(cx, |workspace, cx| {
+            show_outcome(workspace, stopped.operation, outcome, None, cx)
+        })
+    })
+    .detach_and_prompt_err(
+        "Failed to resume cherry-pick or revert",
+        window,
+        cx,
+        |e, _, _| Some(e.to_string()),
+    );
+}
+
+fn show_outcome(
+    workspace: &mut Workspace,
+    operation: SequencerOperation,
+    outcome: SequencerOutcome,
+    short_sha: Option<SharedString>,
+    cx: &mut Context<Workspace>,
+) {
+    let toast = match outcome {
+        SequencerOutcome::Completed => {
+            let message = match (operation, short_sha) {
+                (SequencerOperation::CherryPick, Some(sha)) => format!("Cherry-picked {sha}"),
+                (SequencerOperation::Revert, Some(sha)) => format!("Reverted {sha}"),
+                (SequencerOperation::CherryPick, None) => "Cherry-pick completed".to_string(),
+                (SequencerOperation::Revert, None) => "Revert completed".to_string(),
+            };
+            StatusToast::new(message, cx, |this, _| {
+                this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+            })
+        }
+        SequencerOutcome::Stopped => {
+            let message = match operation {
+                SequencerOperation::CherryPick => "Cherry-pick stopped on conflicts",
+                SequencerOperation::Revert => "Revert stopped on conflicts",
+            };
+            StatusToast::new(message, cx, |this, _| {
+                this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+                    .action("Abort", |window, cx| {
+                        window.dispatch_action(git::AbortCherryPickOrRevert.boxed_clone(), cx);
+                    })
+            })
+        }
+    };
+    workspace.toggle_status_toast(toast, cx);
+}
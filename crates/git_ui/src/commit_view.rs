@@ -18,7 +18,7 @@ use std::{
     path::PathBuf,
     sync::Arc,
 };
-use ui::{Color, Icon, IconName, Label, LabelCommon as _, SharedString};
+use ui::{Tooltip, prelude::*};
 use util::{ResultExt, paths::PathStyle, rel_path::RelPath, truncate_and_trailoff};
 use workspace::{
     Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
@@ -26,10 +26,14 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
+use crate::{commit_operations, tag_picker};
+
 pub struct CommitView {
     commit: CommitDetails,
     editor: Entity<Editor>,
     multibuffer: Entity<MultiBuffer>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
 }
 
 struct GitBlob {
@@ -71,12 +75,14 @@ impl CommitView {
                 workspace
                     .update_in(cx, |workspace, window, cx| {
                         let project = workspace.project();
+                        let workspace_handle = workspace.weak_handle();
                         let commit_view = cx.new(|cx| {
                             CommitView::new(
                                 commit_details,
                                 commit_diff,
                                 repo,
                                 project.clone(),
+                                workspace_handle,
                                 window,
                                 cx,
                             )
@@ -106,6 +112,7 @@ impl CommitView {
         commit_diff: CommitDiff,
         repository: Entity<Repository>,
         project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -160,12 +167,13 @@ impl CommitView {
             });
         }
 
+        let repo = repository.clone();
         cx.spawn(async move |this, cx| {
             for file in commit_diff.files {
                 let is_deleted = file.new_text.is_none();
                 let new_text = file.new_text.unwrap_or_default();
                 let old_text = file.old_text;
-                let worktree_id = repository
+                let worktree_id = repo
                     .update(cx, |repository, cx| {
                         repository
                             .repo_path_to_project_path(&file.path, cx)
@@ -211,8 +219,83 @@ impl CommitView {
             commit,
             editor,
             multibuffer,
+            repository,
+            workspace,
         }
     }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let workspace = self.workspace.upgrade()?;
+        if workspace.read(cx).project().read(cx).is_read_only(cx) {
+            return None;
+        }
+        let sha: SharedString = self.commit.sha.clone();
+        Some(
+            h_flex()
+                .px_2()
+                .py_1()
+                .gap_1()
+                .justify_end()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("cherry-pick-commit", "Cherry-Pick")
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text(
+                            "Apply the changes of this commit on top of HEAD",
+                        ))
+                        .on_click(cx.listener({
+                            let sha = sha.clone();
+                            move |this, _, window, cx| {
+                                commit_operations::cherry_pick(
+                                    this.repository.clone(),
+                                    sha.clone(),
+                                    this.workspace.clone(),
+                                    window,
+                                    cx,
+                                );
+                            }
+                        })),
+                )
+                .child(
+                    Button::new("revert-commit", "Revert")
+                        .label_size(LabelSize::Small)
+                        .tooltip(Tooltip::text(
+                            "Commit the reverse of the changes of this commit",
+                        ))
+                        .on_click(cx.listener({
+                            let sha = sha.clone();
+                            move |this, _, window, cx| {
+                                commit_operations::revert(
+                                    this.repository.clone(),
+                                    sha.clone(),
+                                    this.workspace.clone(),
+                                    window,
+                                    cx,
+                                );
+                            }
+                        })),
+                )
+                .child(
+                    Button::new("tag-commit", "Create Tag…")
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            let repository = this.repository.clone();
+                            this.workspace
+                                .update(cx, |workspace, cx| {
+                                    tag_picker::create_tag(
+                                        workspace,
+                                        repository,
+                                        Some(sha.clone()),
+                                        window,
+                                        cx,
+                                    );
+                                })
+                                .ok();
+                        })),
+                ),
+        )
+    }
 }
 
 impl language::File for GitBlob {
@@ -538,13 +621,18 @@ impl Item for CommitView {
                 editor,
                 multibuffer,
                 commit: self.commit.clone(),
+                repository: self.repository.clone(),
+                workspace: self.workspace.clone(),
             }
         }))
     }
 }
 
 impl Render for CommitView {
-    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
-        self.editor.clone()
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .children(self.render_header(cx))
+            .child(self.editor.clone())
     }
 }
@@ -394,7 +394,7 @@ fn render_conflict_buttons(
         .gap_1()
         .bg(cx.theme().colors().editor_background)
         .child(
-            Button::new("head", "Use HEAD")
+            Button::new("head", use_side_label(&conflict.ours_label, "HEAD"))
                 .label_size(LabelSize::Small)
                 .on_click({
                     let editor = editor.clone();
@@ -414,7 +414,7 @@ fn render_conflict_buttons(
                 }),
         )
         .child(
-            Button::new("origin", "Use Origin")
+            Button::new("origin", use_side_label(&conflict.theirs_label, "Origin"))
                 .label_size(LabelSize::Small)
                 .on_click({
                     let editor = editor.clone();
@@ -456,6 +456,20 @@ fn render_conflict_buttons(
         .into_any()
 }
 
+/// Names a conflict resolution button after what git named the side in the conflict markers,
+/// such as `HEAD`, a branch or a commit being cherry-picked or reverted.
+fn use_side_label(marker_label: &str, fallback: &str) -> String {
+    const MAX_LABEL_LENGTH: usize = 40;
+    if marker_label.is_empty() {
+        format!("Use {fallback}")
+    } else {
+        format!(
+            "Use {}",
+            util::truncate_and_trailoff(marker_label, MAX_LABEL_LENGTH)
+        )
+    }
+}
+
 pub(crate) fn resolve_conflict(
     editor: WeakEntity<Editor>,
     excerpt_id: ExcerptId,
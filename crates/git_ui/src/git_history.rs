@@ -0,0 +1,944 @@
+use std::{ops::Range, time::Duration};
+
+use editor::{Editor, EditorEvent};
+use git::repository::{BisectMark, CommitLogEntry, LogLineRange, LogOptions, RepoPath};
+use gpui::{
+    App, Bounds, Context, Corner, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    MouseButton, MouseDownEvent, PathBuilder, Pixels, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, anchored, canvas, deferred, point, size,
+    uniform_list,
+};
+use language::Point;
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::git_store::Repository;
+use time::OffsetDateTime;
+use ui::{Chip, ContextMenu, Tooltip, prelude::*};
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+};
+
+use crate::{
+    bisect, commit_operations,
+    commit_view::CommitView,
+    rebase_editor::{RebaseBase, RebaseEditor},
+    tag_picker,
+};
+
+const PAGE_SIZE: usize = 200;
+const QUERY_DEBOUNCE: Duration = Duration::from_millis(200);
+const LANE_WIDTH: Pixels = px(14.);
+const COMMIT_DOT_SIZE: Pixels = px(8.);
+
+/// Which commits are listed in a [`GitHistoryView`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HistoryScope {
+    /// All the commits of the current branch.
+    Repository,
+    /// The commits changing a file.
+    File(RepoPath),
+    /// The commits changing some lines of a file.
+    Lines(LogLineRange),
+}
+
+/// Lists the commits in the history of a repository, a file or some lines of a file, along with
+/// a graph of their branches and merges.
+pub struct GitHistoryView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    scope: HistoryScope,
+    query_editor: Entity<Editor>,
+    commits: Vec<CommitLogEntry>,
+    graph: Vec<GraphRow>,
+    graph_builder: GraphBuilder,
+    selected_index: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    has_more: bool,
+    error: Option<SharedString>,
+    load_task: Option<Task<()>>,
+    context_menu: Option<(Entity<ContextMenu>, gpui::Point<Pixels>, Subscription)>,
+    _query_subscription: Subscription,
+}
+
+impl GitHistoryView {
+    pub fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &git::ViewHistory, window, cx| {
+            let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+                return;
+            };
+            Self::open(repository, HistoryScope::Repository, workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::FileHistory, window, cx| {
+            let Some((repository, path, _)) = active_file_and_rows(workspace, cx) else {
+                return;
+            };
+            Self::open(repository, HistoryScope::File(path), workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &git::LineHistory, window, cx| {
+            let Some((repository, path, rows)) = active_file_and_rows(workspace, cx) else {
+                return;
+            };
+            let scope = HistoryScope::Lines(LogLineRange { path, rows });
+            Self::open(repository, scope, workspace, window, cx);
+        });
+    }
+
+    pub fn open(
+        repository: Entity<Repository>,
+        scope: HistoryScope,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.items_of_type::<Self>(cx).find(|view| {
+            let view = view.read(cx);
+            view.scope == scope && view.repository == repository
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = cx.weak_entity();
+        let view = cx.new(|cx| Self::new(repository, scope, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        scope: HistoryScope,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search commit messages, or author:name", window, cx);
+            editor
+        });
+        let query_subscription = cx.subscribe(&query_editor, |this, _, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.fetch(true, QUERY_DEBOUNCE, cx);
+            }
+        });
+
+        let mut this = Self {
+            repository,
+            workspace,
+            scope,
+            query_editor,
+            commits: Vec::new(),
+            graph: Vec::new(),
+            graph_builder: GraphBuilder::default(),
+            selected_index: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            has_more: true,
+            error: None,
+            load_task: None,
+            context_menu: None,
+            _query_subscription: query_subscription,
+        };
+        this.fetch(true, Duration::ZERO, cx);
+        this
+    }
+
+    fn title(&self, cx: &App) -> SharedString {
+        match &self.scope {
+            HistoryScope::Repository => {
+                format!("History of {}", self.repository.read(cx).display_name()).into()
+            }
+            HistoryScope::File(path) => {
+                format!("History of {}", path.file_name().unwrap_or_default()).into()
+            }
+            HistoryScope::Lines(line_range) => format!(
+                "History of {}:{}-{}",
+                line_range.path.file_name().unwrap_or_default(),
+                line_range.rows.start + 1,
+                line_range.rows.end
+            )
+            .into(),
+        }
+    }
+
+    fn log_options(&self, skip: usize, cx: &App) -> LogOptions {
+        let query = self.query_editor.read(cx).text(cx);
+        let query = query.trim();
+        let (author, message) = match query.strip_prefix("author:") {
+            Some(author) => (author.trim(), ""),
+            None => ("", query),
+        };
+        let mut options = LogOptions {
+            author: (!author.is_empty()).then(|| author.to_string()),
+            message: (!message.is_empty()).then(|| message.to_string()),
+            skip,
+            limit: PAGE_SIZE,
+            ..LogOptions::default()
+        };
+        match &self.scope {
+            HistoryScope::Repository => {}
+            HistoryScope::File(path) => options.paths = vec![path.clone()],
+            HistoryScope::Lines(line_range) => options.line_range = Some(line_range.clone()),
+        }
+        options
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.load_task.is_none() && self.has_more {
+            self.fetch(false, Duration::ZERO, cx);
+        }
+    }
+
+    /// Loads the next page of commits, or the first one again if `reset` is set, replacing the
+    /// commits listed when it is loaded.
+    fn fetch(&mut self, reset: bool, delay: Duration, cx: &mut Context<Self>) {
+        let skip = if reset { 0 } else { self.commits.len() };
+        let options = self.log_options(skip, cx);
+        let repository = self.repository.clone();
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+            let result = async {
+                repository
+                    .update(cx, |repository, _| repository.log(options))?
+                    .await?
+            }
+            .await;
+
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                match result {
+                    Ok(commits) => {
+                        if reset {
+                            this.commits.clear();
+                            this.graph.clear();
+                            this.graph_builder = GraphBuilder::default();
+                            this.selected_index = None;
+                            this.scroll_handle.scroll_to_item(0, ScrollStrategy::Top);
+                        }
+                        this.has_more = commits.len() == PAGE_SIZE;
+                        this.error = None;
+                        for commit in commits {
+                            this.graph.push(this.graph_builder.push(&commit));
+                            this.commits.push(commit);
+                        }
+                    }
+                    Err(error) => {
+                        this.has_more = false;
+                        this.error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix < self.commits.len() {
+            self.selected_index = Some(ix);
+            self.scroll_handle.scroll_to_item(ix, ScrollStrategy::Top);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index.map_or(0, |ix| ix + 1);
+        self.select_index(ix.min(self.commits.len().saturating_sub(1)), cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_index(ix, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.commits.len().saturating_sub(1), cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_commit(ix, window, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        CommitView::open(
+            commit.to_summary(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    /// Opens an interactive rebase of the commits from the one at `ix` to `HEAD`.
+    fn rebase_from(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        let base = match commit.parents.first() {
+            Some(parent) => RebaseBase::Revision(parent.clone()),
+            None => RebaseBase::Root,
+        };
+        let repository = self.repository.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                RebaseEditor::open(repository, Some(base), workspace, window, cx);
+            })
+            .ok();
+    }
+
+    fn cherry_pick_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        commit_operations::cherry_pick(
+            self.repository.clone(),
+            commit.sha.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn revert_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        commit_operations::revert(
+            self.repository.clone(),
+            commit.sha.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn tag_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        let sha = commit.sha.clone();
+        let repository = self.repository.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                tag_picker::create_tag(workspace, repository, Some(sha), window, cx);
+            })
+            .ok();
+    }
+
+    fn bisect_from(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        bisect::start(
+            self.repository.clone(),
+            Some(commit.sha.clone()),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn mark_for_bisect(
+        &mut self,
+        ix: usize,
+        mark: BisectMark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        bisect::mark(
+            self.repository.clone(),
+            mark,
+            Some(commit.sha.clone()),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn deploy_commit_context_menu(
+        &mut self,
+        position: gpui::Point<Pixels>,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if ix >= self.commits.len() {
+            return;
+        }
+        self.selected_index = Some(ix);
+        // Merge commits can't be cherry-picked or reverted without choosing a parent.
+        let is_merge = self.commits[ix].parents.len() > 1;
+        let is_bisecting = self
+            .workspace
+            .read_with(cx, |workspace, cx| bisect::is_bisecting(workspace, cx))
+            .unwrap_or(false);
+        let this = cx.weak_entity();
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .entry("Open Commit", Some(Box::new(Confirm)), {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.open_commit(ix, window, cx))
+                            .ok();
+                    }
+                })
+                .entry("Interactive Rebase from Here", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.rebase_from(ix, window, cx))
+                            .ok();
+                    }
+                })
+                .separator()
+                .when(!is_merge, |context_menu| {
+                    context_menu
+                        .entry("Cherry-Pick Commit", None, {
+                            let this = this.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| this.cherry_pick_commit(ix, window, cx))
+                                    .ok();
+                            }
+                        })
+                        .entry("Revert Commit", None, {
+                            let this = this.clone();
+                            move |window, cx| {
+                                this.update(cx, |this, cx| this.revert_commit(ix, window, cx))
+                                    .ok();
+                            }
+                        })
+                })
+                .entry("Create Tag…", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.tag_commit(ix, window, cx))
+                            .ok();
+                    }
+                })
+                .separator()
+                .map(|context_menu| {
+                    if is_bisecting {
+                        context_menu
+                            .entry("Mark Good for Bisect", None, {
+                                let this = this.clone();
+                                move |window, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.mark_for_bisect(ix, BisectMark::Good, window, cx)
+                                    })
+                                    .ok();
+                                }
+                            })
+                            .entry("Mark Bad for Bisect", None, move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.mark_for_bisect(ix, BisectMark::Bad, window, cx)
+                                })
+                                .ok();
+                            })
+                    } else {
+                        context_menu.entry("Start Bisect from Here", None, move |window, cx| {
+                            this.update(cx, |this, cx| this.bisect_from(ix, window, cx))
+                                .ok();
+                        })
+                    }
+                })
+        });
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn render_commit(&self, ix: usize, graph_width: Pixels, cx: &Context<Self>) -> AnyElement {
+        let commit = &self.commits[ix];
+        let graph_row = self.graph[ix].clone();
+        let is_merge = commit.parents.len() > 1;
+        let colors = cx.theme().colors();
+        let date = time_format::format_local_timestamp(
+            OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+                .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+            OffsetDateTime::now_utc(),
+            time_format::TimestampFormat::Relative,
+        );
+        let tooltip = format!(
+            "{}\n{} <{}>",
+            commit.subject, commit.author_name, commit.author_email
+        );
+
+        h_flex()
+            .id(ix)
+            .w_full()
+            .h(rems(1.75))
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(self.selected_index == Some(ix), |row| {
+                row.bg(colors.element_selected)
+            })
+            .hover(|row| row.bg(colors.element_hover))
+            .tooltip(Tooltip::text(tooltip))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.selected_index = Some(ix);
+                this.open_commit(ix, window, cx);
+                cx.notify();
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    this.deploy_commit_context_menu(event.position, ix, window, cx);
+                }),
+            )
+            .child(
+                canvas(
+                    |_, _, _| {},
+                    move |bounds, _, window, cx| {
+                        paint_graph_row(&graph_row, is_merge, bounds, window, cx)
+                    },
+                )
+                .flex_none()
+                .w(graph_width)
+                .h_full(),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_1()
+                    .children(commit.refs.iter().map(|name| {
+                        Chip::new(name.clone())
+                            .label_color(Color::Accent)
+                            .label_size(LabelSize::XSmall)
+                    }))
+                    .child(Label::new(commit.subject.clone()).single_line().truncate()),
+            )
+            .child(
+                Label::new(commit.author_name.clone())
+                    .color(Color::Muted)
+                    .size(LabelSize::Small)
+                    .single_line(),
+            )
+            .child(
+                Label::new(date)
+                    .color(Color::Muted)
+                    .size(LabelSize::Small)
+                    .single_line(),
+            )
+            .child(
+                Label::new(commit.short_sha())
+                    .color(Color::Muted)
+                    .size(LabelSize::Small)
+                    .buffer_font(cx),
+            )
+            .into_any_element()
+    }
+}
+
+/// Returns the repository and path of the file in the active editor, along with the rows of its
+/// newest selection.
+fn active_file_and_rows(
+    workspace: &Workspace,
+    cx: &mut App,
+) -> Option<(Entity<Repository>, RepoPath, Range<u32>)> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+    let selection = editor.update(cx, |editor, cx| editor.selections.newest::<Point>(cx));
+    let buffer_id = buffer.read(cx).remote_id();
+    let (repository, path) = workspace
+        .project()
+        .read(cx)
+        .git_store()
+        .read(cx)
+        .repository_and_path_for_buffer_id(buffer_id, cx)?;
+
+    let mut rows = selection.start.row..selection.end.row + 1;
+    // A selection ending at the start of a line doesn't include that line.
+    if selection.end.column == 0 && selection.end.row > selection.start.row {
+        rows.end -= 1;
+    }
+    Some((repository, path, rows))
+}
+
+/// The part of the commit graph drawn next to a commit, made of lines between lanes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct GraphRow {
+    /// The lane the commit is drawn in.
+    lane: usize,
+    /// Lines from the top of the row to its middle, as the lanes they go from and to.
+    upper_edges: Vec<(usize, usize)>,
+    /// Lines from the middle of the row to its bottom, as the lanes they go from and to.
+    lower_edges: Vec<(usize, usize)>,
+}
+
+impl GraphRow {
+    fn lane_count(&self) -> usize {
+        self.upper_edges
+            .iter()
+            .chain(&self.lower_edges)
+            .map(|(from, to)| from.max(to) + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.lane + 1)
+    }
+}
+
+/// Lays the commit graph out a row at a time, as commits are listed from the most recent one.
+#[derive(Debug, Default)]
+struct GraphBuilder {
+    /// The commit each lane leads to, for the lanes in use.
+    lanes: Vec<Option<SharedString>>,
+}
+
+impl GraphBuilder {
+    fn push(&mut self, commit: &CommitLogEntry) -> GraphRow {
+        let lane = self
+            .lanes
+            .iter()
+            .position(|sha| sha.as_ref() == Some(&commit.sha))
+            .or_else(|| self.lanes.iter().position(Option::is_none))
+            .unwrap_or(self.lanes.len());
+        let mut row = GraphRow {
+            lane,
+            ..GraphRow::default()
+        };
+
+        for (ix, sha) in self.lanes.iter_mut().enumerate() {
+            let Some(expected_sha) = sha else {
+                continue;
+            };
+            if *expected_sha == commit.sha {
+                row.upper_edges.push((ix, lane));
+                *sha = None;
+            } else {
+                row.upper_edges.push((ix, ix));
+                row.lower_edges.push((ix, ix));
+            }
+        }
+        if lane == self.lanes.len() {
+            self.lanes.push(None);
+        }
+
+        for parent in &commit.parents {
+            let parent_lane = match self
+                .lanes
+                .iter()
+                .position(|sha| sha.as_ref() == Some(parent))
+            {
+                Some(parent_lane) => parent_lane,
+                None => {
+                    let parent_lane = if self.lanes[lane].is_none() {
+                        lane
+                    } else {
+                        self.lanes
+                            .iter()
+                            .position(Option::is_none)
+                            .unwrap_or(self.lanes.len())
+                    };
+                    if parent_lane == self.lanes.len() {
+                        self.lanes.push(None);
+                    }
+                    self.lanes[parent_lane] = Some(parent.clone());
+                    parent_lane
+                }
+            };
+            row.lower_edges.push((lane, parent_lane));
+        }
+
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+        row
+    }
+}
+
+fn paint_graph_row(
+    row: &GraphRow,
+    is_merge: bool,
+    bounds: Bounds<Pixels>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let accents = cx.theme().accents();
+    let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * (lane as f32 + 0.5);
+    let middle = bounds.center().y;
+
+    let upper_edges = row
+        .upper_edges
+        .iter()
+        .map(|&(from, to)| ((from, bounds.top()), (to, middle), from));
+    let lower_edges = row
+        .lower_edges
+        .iter()
+        .map(|&(from, to)| ((from, middle), (to, bounds.bottom()), to));
+    for ((from_lane, from_y), (to_lane, to_y), color_lane) in upper_edges.chain(lower_edges) {
+        let mut builder = PathBuilder::stroke(px(1.5));
+        builder.move_to(point(lane_x(from_lane), from_y));
+        builder.line_to(point(lane_x(to_lane), to_y));
+        if let Ok(path) = builder.build() {
+            window.paint_path(path, accents.color_for_index(color_lane as u32));
+        }
+    }
+
+    let color = accents.color_for_index(row.lane as u32);
+    let dot_bounds = Bounds::centered_at(
+        point(lane_x(row.lane), middle),
+        size(COMMIT_DOT_SIZE, COMMIT_DOT_SIZE),
+    );
+    let background = if is_merge {
+        cx.theme().colors().editor_background
+    } else {
+        color
+    };
+    window.paint_quad(
+        gpui::fill(dot_bounds, background)
+            .corner_radii(COMMIT_DOT_SIZE / 2.)
+            .border_widths(px(1.5))
+            .border_color(color),
+    );
+}
+
+impl EventEmitter<ItemEvent> for GitHistoryView {}
+
+impl Focusable for GitHistoryView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for GitHistoryView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &ItemEvent, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        self.title(cx)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git History Opened")
+    }
+}
+
+impl Render for GitHistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let content = if self.commits.is_empty() {
+            let message = if let Some(error) = &self.error {
+                error.clone()
+            } else if self.load_task.is_some() {
+                "Loading commits…".into()
+            } else {
+                "No commits found".into()
+            };
+            div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            let graph_width = LANE_WIDTH
+                * self
+                    .graph
+                    .iter()
+                    .map(GraphRow::lane_count)
+                    .max()
+                    .unwrap_or(1) as f32;
+            uniform_list(
+                "commits",
+                self.commits.len(),
+                cx.processor(move |this, range: Range<usize>, _window, cx| {
+                    if range.end + PAGE_SIZE / 2 >= this.commits.len() {
+                        this.load_more(cx);
+                    }
+                    range
+                        .map(|ix| this.render_commit(ix, graph_width, cx))
+                        .collect()
+                }),
+            )
+            .size_full()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .key_context("GitHistory")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(colors.editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(
+                        Icon::new(IconName::MagnifyingGlass)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(div().flex_1().child(self.query_editor.clone()))
+                    .child(
+                        Label::new(self.title(cx))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    ),
+            )
+            .child(div().flex_1().min_h_0().child(content))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| {
+                    if !this.query_editor.focus_handle(cx).is_focused(window) {
+                        window.focus(&this.focus_handle);
+                    }
+                }),
+            )
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(sha: &str, parents: &[&str]) -> CommitLogEntry {
+        CommitLogEntry {
+            sha: sha.to_string().into(),
+            parents: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            subject: SharedString::default(),
+            commit_timestamp: 0,
+            author_name: SharedString::default(),
+            author_email: SharedString::default(),
+            refs: Vec::new(),
+        }
+    }
+
+    fn layout(commits: &[CommitLogEntry]) -> Vec<GraphRow> {
+        let mut builder = GraphBuilder::default();
+        commits.iter().map(|commit| builder.push(commit)).collect()
+    }
+
+    #[test]
+    fn test_linear_history_graph() {
+        let rows = layout(&[commit("c", &["b"]), commit("b", &["a"]), commit("a", &[])]);
+        assert_eq!(
+            rows,
+            vec![
+                GraphRow {
+                    lane: 0,
+                    upper_edges: vec![],
+                    lower_edges: vec![(0, 0)],
+                },
+                GraphRow {
+                    lane: 0,
+                    upper_edges: vec![(0, 0)],
+                    lower_edges: vec![(0, 0)],
+                },
+                GraphRow {
+                    lane: 0,
+                    upper_edges: vec![(0, 0)],
+                    lower_edges: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_graph() {
+        // m merges c into b, both made on top of a.
+        let rows = layout(&[
+            commit("m", &["b", "c"]),
+            commit("b", &["a"]),
+            commit("c", &["a"]),
+            commit("a", &[]),
+        ]);
+        assert_eq!(
+            rows,
+            vec![
+                GraphRow {
+                    lane: 0,
+                    upper_edges: vec![],
+                    lower_edges: vec![(0, 0), (0, 1)],
+                },
+                GraphRow {
+                    lane: 0,
+                    upper_edges: vec![(0, 0), (1, 1)],
+                    lower_edges: vec![(1, 1), (0, 0)],
+                },
+                GraphRow {
+                    lane: 1,
+                    upper_edges: vec![(0, 0), (1, 1)],
+                    lower_edges: vec![(0, 0), (1, 0)],
+                },
+                GraphRow {
+                    lane: 0,
+                    upper_edges: vec![(0, 0)],
+                    lower_edges: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            rows.iter().map(GraphRow::lane_count).collect::<Vec<_>>(),
+            vec![2, 2, 2, 1]
+        );
+    }
+}
@@ -31,21 +31,27 @@ use zed_actions;
 use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
+pub mod bisect;
 pub mod branch_picker;
 mod commit_modal;
+mod commit_operations;
 pub mod commit_tooltip;
 mod commit_view;
 mod conflict_view;
 pub mod file_diff_view;
+pub mod git_history;
 pub mod git_panel;
 mod git_panel_settings;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod rebase_editor;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
+pub mod tag_picker;
 pub mod text_diff_view;
+pub mod worktree_picker;
 
 actions!(
     git,
@@ -72,6 +78,12 @@ pub fn init(cx: &mut App) {
         repository_selector::register(workspace);
         branch_picker::register(workspace);
         stash_picker::register(workspace);
+        git_history::GitHistoryView::register(workspace);
+        rebase_editor::RebaseEditor::register(workspace);
+        worktree_picker::register(workspace);
+        tag_picker::register(workspace);
+        commit_operations::register(workspace);
+        bisect::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
@@ -118,6 +130,14 @@ pub fn init(cx: &mut App) {
                     panel.push(true, false, window, cx);
                 });
             });
+            workspace.register_action(|workspace, _: &git::PushTags, window, cx| {
+                let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.push_tags(None, window, cx);
+                });
+            });
             workspace.register_action(|workspace, _: &git::Pull, window, cx| {
                 let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                     return;
@@ -0,0 +1,1004 @@
+use anyhow::Result;
+use editor::Editor;
+use futures::channel::oneshot;
+use git::{
+    rebase::{self, RebaseAction, RebaseCommand, RebaseProgress, RebaseTodoEntry},
+    repository::RepoPath,
+};
+use gpui::{
+    Action, AnyElement, App, Context, Corner, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyContext, Subscription, Task, WeakEntity, Window, actions,
+};
+use menu::{SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use project::git_store::{Repository, RepositoryEvent};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::{ContextMenu, KeyBinding, PopoverMenu, Tooltip, prelude::*};
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+};
+
+actions!(
+    rebase_editor,
+    [
+        /// Moves the selected commit up, applying it earlier.
+        MoveEntryUp,
+        /// Moves the selected commit down, applying it later.
+        MoveEntryDown,
+        /// Applies the selected commit as is.
+        Pick,
+        /// Applies the selected commit with a new message.
+        Reword,
+        /// Melds the selected commit into the one above it, combining their messages.
+        Squash,
+        /// Melds the selected commit into the one above it, keeping only that one's message.
+        Fixup,
+        /// Leaves the selected commit out of the rebase.
+        DropCommit,
+        /// Starts the rebase with the edited list of commits.
+        StartRebase,
+        /// Continues the rebase in progress, once its conflicts are resolved and staged.
+        ContinueRebase,
+        /// Leaves out the commit the rebase in progress stopped at, and continues.
+        SkipRebase,
+        /// Aborts the rebase in progress, restoring the branch to what it was before it.
+        AbortRebase,
+    ]
+);
+
+/// What the commits of an interactive rebase are applied onto.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RebaseBase {
+    /// A revision, rebasing the commits between it and `HEAD`.
+    Revision(SharedString),
+    /// Nothing, rebasing the whole history of `HEAD`.
+    Root,
+}
+
+impl RebaseBase {
+    fn onto(&self) -> Option<String> {
+        match self {
+            Self::Revision(revision) => Some(revision.to_string()),
+            Self::Root => None,
+        }
+    }
+}
+
+struct TodoItem {
+    entry: RebaseTodoEntry,
+    /// The new message of the commit, created when it is first reworded.
+    message_editor: Option<Entity<Editor>>,
+}
+
+/// Edits the list of commits of an interactive rebase, in the order they are applied, and
+/// follows the rebase through its conflicts once it is started.
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    base: Option<RebaseBase>,
+    todo: Vec<TodoItem>,
+    progress: Option<RebaseProgress>,
+    selected_index: usize,
+    focus_handle: FocusHandle,
+    error: Option<SharedString>,
+    pending_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RebaseEditor {
+    pub fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &git::InteractiveRebase, window, cx| {
+            let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+                return;
+            };
+            let base = repository
+                .read(cx)
+                .branch
+                .as_ref()
+                .and_then(|branch| branch.upstream.as_ref())
+                .map(|upstream| RebaseBase::Revision(upstream.ref_name.clone()));
+            Self::open(repository, base, workspace, window, cx);
+        });
+    }
+
+    /// Opens the rebase editor of a repository, listing the commits to rebase onto `base`, or
+    /// the progress of the rebase in progress if there is one.
+    pub fn open(
+        repository: Entity<Repository>,
+        base: Option<RebaseBase>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .find(|editor| editor.read(cx).repository == repository);
+        if let Some(existing) = existing {
+            existing.update(cx, |editor, cx| {
+                if base.is_some() && base != editor.base {
+                    editor.base = base;
+                    editor.reload(cx);
+                }
+            });
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = cx.weak_entity();
+        let editor = cx.new(|cx| Self::new(repository, base, workspace_handle, cx));
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        base: Option<RebaseBase>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscriptions = vec![
+            cx.observe(&repository, |_, _, cx| cx.notify()),
+            cx.subscribe(&repository, |this, _, event: &RepositoryEvent, cx| {
+                // The rebase may also be continued or aborted outside of Zed.
+                if let RepositoryEvent::MergeHeadsChanged = event
+                    && this.progress.is_some()
+                    && this.pending_task.is_none()
+                {
+                    this.reload(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            repository,
+            workspace,
+            base,
+            todo: Vec::new(),
+            progress: None,
+            selected_index: 0,
+            focus_handle: cx.focus_handle(),
+            error: None,
+            pending_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.reload(cx);
+        this
+    }
+
+    /// Loads the progress of the rebase in progress, or else the commits to rebase onto the base.
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let repository = self.repository.clone();
+        let base = self.base.clone();
+        self.error = None;
+        self.pending_task = Some(cx.spawn(async move |this, cx| {
+            let result = async {
+                let progress = repository
+                    .update(cx, |repository, _| repository.rebase_progress())?
+                    .await??;
+                let todo = match (&progress, base) {
+                    (None, Some(base)) => {
+                        repository
+                            .update(cx, |repository, _| repository.rebase_todo(base.onto()))?
+                            .await??
+                    }
+                    _ => Vec::new(),
+                };
+                anyhow::Ok((progress, todo))
+            }
+            .await;
+
+            this.update(cx, |this, cx| {
+                this.pending_task = None;
+                match result {
+                    Ok((progress, todo)) => {
+                        this.progress = progress;
+                        this.todo = todo
+                            .into_iter()
+                            .map(|entry| TodoItem {
+                                entry,
+                                message_editor: None,
+                            })
+                            .collect();
+                        this.selected_index = 0;
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn is_editing_todo(&self) -> bool {
+        self.progress.is_none() && self.pending_task.is_none()
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.is_editing_todo() {
+            return;
+        }
+        let Some(item) = self.todo.get_mut(ix) else {
+            return;
+        };
+        item.entry.action = action;
+        if action == RebaseAction::Reword && item.message_editor.is_none() {
+            let message_editor = cx.new(|cx| {
+                let mut editor = Editor::auto_height(1, 12, window, cx);
+                editor.set_placeholder_text("New commit message", window, cx);
+                editor
+            });
+            item.message_editor = Some(message_editor.clone());
+
+            let sha = item.entry.sha.to_string();
+            let details = self
+                .repository
+                .update(cx, |repository, _| repository.show(sha));
+            cx.spawn_in(window, async move |_, cx| {
+                let details = details.await??;
+                message_editor.update_in(cx, |editor, window, cx| {
+                    if editor.text(cx).is_empty() {
+                        editor.set_text(details.message.trim_end(), window, cx);
+                    }
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        self.error = None;
+        cx.notify();
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_action(self.selected_index, action, window, cx);
+        if action == RebaseAction::Reword
+            && let Some(message_editor) = self
+                .todo
+                .get(self.selected_index)
+                .and_then(|item| item.message_editor.as_ref())
+        {
+            window.focus(&message_editor.focus_handle(cx));
+        }
+    }
+
+    fn pick(&mut self, _: &Pick, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Pick, window, cx);
+    }
+
+    fn reword(&mut self, _: &Reword, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Reword, window, cx);
+    }
+
+    fn squash(&mut self, _: &Squash, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Squash, window, cx);
+    }
+
+    fn fixup(&mut self, _: &Fixup, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Fixup, window, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_selected_action(RebaseAction::Drop, window, cx);
+    }
+
+    fn move_entry(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        if !self.is_editing_todo() || from >= self.todo.len() || to >= self.todo.len() {
+            return;
+        }
+        let item = self.todo.remove(from);
+        self.todo.insert(to, item);
+        self.selected_index = to;
+        self.error = None;
+        cx.notify();
+    }
+
+    fn move_entry_up(&mut self, _: &MoveEntryUp, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(to) = self.selected_index.checked_sub(1) {
+            self.move_entry(self.selected_index, to, cx);
+        }
+    }
+
+    fn move_entry_down(&mut self, _: &MoveEntryDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_entry(self.selected_index, self.selected_index + 1, cx);
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix < self.todo.len() {
+            self.selected_index = ix;
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.selected_index + 1, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.selected_index.saturating_sub(1), cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.todo.len().saturating_sub(1), cx);
+    }
+
+    fn start_rebase(&mut self, _: &StartRebase, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.is_editing_todo() || self.todo.is_empty() {
+            return;
+        }
+        let Some(base) = self.base.clone() else {
+            return;
+        };
+        let todo = self
+            .todo
+            .iter()
+            .map(|item| {
+                let mut entry = item.entry.clone();
+                if entry.action == RebaseAction::Reword {
+                    entry.message = item
+                        .message_editor
+                        .as_ref()
+                        .map(|editor| editor.read(cx).text(cx))
+                        .filter(|message| !message.trim().is_empty())
+                        .map(Into::into);
+                }
+                entry
+            })
+            .collect::<Vec<_>>();
+        if let Err(error) = rebase::validate_todo(&todo) {
+            self.error = Some(error.to_string().into());
+            cx.notify();
+            return;
+        }
+
+        let task = self.repository.update(cx, |repository, _| {
+            repository.start_rebase(base.onto(), todo)
+        });
+        self.run(task, "Rebase completed", cx);
+    }
+
+    fn run_command(&mut self, command: RebaseCommand, cx: &mut Context<Self>) {
+        if self.progress.is_none() || self.pending_task.is_some() {
+            return;
+        }
+        let completed_message = match command {
+            RebaseCommand::Continue | RebaseCommand::Skip => "Rebase completed",
+            RebaseCommand::Abort => "Rebase aborted",
+        };
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.rebase(command));
+        self.run(task, completed_message, cx);
+    }
+
+    fn continue_rebase(&mut self, _: &ContinueRebase, _: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(RebaseCommand::Continue, cx);
+    }
+
+    fn skip_rebase(&mut self, _: &SkipRebase, _: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(RebaseCommand::Skip, cx);
+    }
+
+    fn abort_rebase(&mut self, _: &AbortRebase, _: &mut Window, cx: &mut Context<Self>) {
+        self.run_command(RebaseCommand::Abort, cx);
+    }
+
+    /// Waits for a rebase command, following the rebase if it stopped, or closing the editor
+    /// once it is over.
+    fn run(
+        &mut self,
+        task: oneshot::Receiver<Result<Option<RebaseProgress>>>,
+        completed_message: &'static str,
+        cx: &mut Context<Self>,
+    ) {
+        self.error = None;
+        self.pending_task = Some(cx.spawn(async move |this, cx| {
+            let result = async { task.await? }.await;
+            this.update(cx, |this, cx| {
+                this.pending_task = None;
+                match result {
+                    Ok(Some(progress)) => this.progress = Some(progress),
+                    Ok(None) => {
+                        this.progress = None;
+                        this.todo.clear();
+                        this.show_toast(completed_message, cx);
+                        cx.emit(ItemEvent::CloseItem);
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn show_toast(&self, message: &'static str, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let toast = StatusToast::new(message, cx, |this, _| {
+                this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+            });
+            workspace.toggle_status_toast(toast, cx);
+        });
+    }
+
+    fn open_conflict(&self, path: &RepoPath, window: &mut Window, cx: &mut App) {
+        let Some(project_path) = self.repository.read(cx).repo_path_to_project_path(path, cx)
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .ok();
+    }
+
+    fn dispatch_context(&self, window: &Window) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("RebaseEditor");
+        if self.focus_handle.is_focused(window) {
+            dispatch_context.add("not_editing");
+        }
+        dispatch_context
+    }
+
+    fn title(&self, cx: &App) -> SharedString {
+        format!("Rebase {}", self.repository.read(cx).display_name()).into()
+    }
+
+    fn render_action_menu(
+        &self,
+        ix: usize,
+        action: RebaseAction,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let this = cx.weak_entity();
+        let focus_handle = self.focus_handle.clone();
+        PopoverMenu::new(("rebase-action-menu", ix))
+            .trigger(
+                Button::new(("rebase-action", ix), action.name())
+                    .label_size(LabelSize::Small)
+                    .icon(IconName::ChevronDown)
+                    .icon_position(IconPosition::End)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted)
+                    .disabled(!self.is_editing_todo()),
+            )
+            .menu(move |window, cx| {
+                let this = this.clone();
+                Some(ContextMenu::build(window, cx, |mut menu, _, _| {
+                    menu = menu.context(focus_handle.clone());
+                    for option in RebaseAction::ALL {
+                        let this = this.clone();
+                        menu = menu.toggleable_entry(
+                            option.name(),
+                            option == action,
+                            IconPosition::Start,
+                            Some(action_for(option)),
+                            move |window, cx| {
+                                this.update(cx, |this, cx| {
+                                    this.selected_index = ix;
+                                    this.set_action(ix, option, window, cx);
+                                })
+                                .ok();
+                            },
+                        );
+                    }
+                    menu
+                }))
+            })
+            .anchor(Corner::TopLeft)
+            .into_any_element()
+    }
+
+    fn render_todo_item(&self, ix: usize, item: &TodoItem, cx: &Context<Self>) -> AnyElement {
+        let entry = &item.entry;
+        let colors = cx.theme().colors();
+        let is_dropped = entry.action == RebaseAction::Drop;
+        let message_editor = item
+            .message_editor
+            .clone()
+            .filter(|_| entry.action == RebaseAction::Reword);
+
+        v_flex()
+            .id(("rebase-entry", ix))
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .when(ix == self.selected_index, |row| {
+                row.bg(colors.element_selected)
+            })
+            .drag_over::<DraggedRebaseEntry>(|style, _, _, cx| {
+                style.bg(cx.theme().colors().drop_target_background)
+            })
+            .on_drop(
+                cx.listener(move |this, dragged: &DraggedRebaseEntry, _, cx| {
+                    this.move_entry(dragged.ix, ix, cx);
+                }),
+            )
+            .child(
+                h_flex()
+                    .id(("rebase-entry-header", ix))
+                    .w_full()
+                    .gap_2()
+                    .cursor_grab()
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.select_index(ix, cx);
+                        window.focus(&this.focus_handle);
+                    }))
+                    .when(self.is_editing_todo(), |row| {
+                        row.on_drag(
+                            DraggedRebaseEntry {
+                                ix,
+                                action: entry.action,
+                                subject: entry.subject.clone(),
+                            },
+                            |dragged, _, _, cx| cx.new(|_| dragged.clone()),
+                        )
+                    })
+                    .child(
+                        Icon::new(IconName::EllipsisVertical)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        div()
+                            .w(rems(6.))
+                            .child(self.render_action_menu(ix, entry.action, cx)),
+                    )
+                    .child(
+                        Label::new(entry.short_sha())
+                            .color(Color::Muted)
+                            .size(LabelSize::Small)
+                            .buffer_font(cx),
+                    )
+                    .child(
+                        div().flex_1().min_w_0().child(
+                            Label::new(entry.subject.clone())
+                                .single_line()
+                                .truncate()
+                                .when(is_dropped, |label| {
+                                    label.strikethrough().color(Color::Disabled)
+                                }),
+                        ),
+                    ),
+            )
+            .when_some(message_editor, |row, message_editor| {
+                row.child(
+                    div()
+                        .ml_8()
+                        .p_1()
+                        .border_1()
+                        .border_color(colors.border)
+                        .rounded_sm()
+                        .child(message_editor),
+                )
+            })
+            .into_any_element()
+    }
+
+    fn render_step(
+        &self,
+        id: impl Into<ElementId>,
+        entry: &RebaseTodoEntry,
+        icon: IconName,
+        color: Color,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        h_flex()
+            .id(id.into())
+            .px_2()
+            .py_1()
+            .gap_2()
+            .child(Icon::new(icon).size(IconSize::Small).color(color))
+            .child(
+                Label::new(entry.action.name())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(entry.short_sha())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .child(Label::new(entry.subject.clone()).single_line().truncate()),
+            )
+            .into_any_element()
+    }
+
+    fn render_progress(&self, progress: &RebaseProgress, cx: &Context<Self>) -> AnyElement {
+        let repository = self.repository.read(cx);
+        let conflicts = repository
+            .merge
+            .conflicted_paths
+            .iter()
+            .map(|path| {
+                let is_resolved = !repository
+                    .status_for_path(path)
+                    .is_some_and(|entry| entry.status.is_conflicted());
+                (path.clone(), is_resolved)
+            })
+            .collect::<Vec<_>>();
+        let (current, done) = match progress.done.split_last() {
+            Some((current, done)) => (Some(current), done),
+            None => (None, &[][..]),
+        };
+
+        v_flex()
+            .w_full()
+            .when(!conflicts.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .p_2()
+                        .gap_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Label::new("Resolve and stage these conflicts, then continue.")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .children(conflicts.into_iter().enumerate().map(
+                            |(ix, (path, is_resolved))| {
+                                h_flex()
+                                    .id(("rebase-conflict", ix))
+                                    .gap_2()
+                                    .cursor_pointer()
+                                    .child(
+                                        Icon::new(if is_resolved {
+                                            IconName::Check
+                                        } else {
+                                            IconName::Warning
+                                        })
+                                        .size(IconSize::Small)
+                                        .color(
+                                            if is_resolved {
+                                                Color::Success
+                                            } else {
+                                                Color::Warning
+                                            },
+                                        ),
+                                    )
+                                    .child(Label::new(
+                                        path.display(repository.path_style).to_string(),
+                                    ))
+                                    .tooltip(Tooltip::text("Open File"))
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.open_conflict(&path, window, cx);
+                                    }))
+                            },
+                        )),
+                )
+            })
+            .children(done.iter().enumerate().map(|(ix, entry)| {
+                self.render_step(
+                    ("rebase-done", ix),
+                    entry,
+                    IconName::Check,
+                    Color::Success,
+                    cx,
+                )
+            }))
+            .children(current.map(|entry| {
+                self.render_step(
+                    "rebase-current",
+                    entry,
+                    IconName::Warning,
+                    Color::Warning,
+                    cx,
+                )
+            }))
+            .children(progress.remaining.iter().enumerate().map(|(ix, entry)| {
+                self.render_step(
+                    ("rebase-remaining", ix),
+                    entry,
+                    IconName::Circle,
+                    Color::Muted,
+                    cx,
+                )
+            }))
+            .into_any_element()
+    }
+
+    fn render_placeholder(&self, cx: &Context<Self>) -> AnyElement {
+        let content = if let Some(error) = &self.error {
+            Label::new(error.clone())
+                .color(Color::Error)
+                .into_any_element()
+        } else if self.pending_task.is_some() {
+            Label::new("Loading commits…")
+                .color(Color::Muted)
+                .into_any_element()
+        } else if self.base.is_none() {
+            v_flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    Label::new(
+                        "The current branch has no upstream to rebase onto. \
+                         Choose the commit to rebase from in the commit history.",
+                    )
+                    .color(Color::Muted),
+                )
+                .child(
+                    Button::new("view-history", "View History").on_click(cx.listener(
+                        |_, _, window, cx| {
+                            window.dispatch_action(git::ViewHistory.boxed_clone(), cx);
+                        },
+                    )),
+                )
+                .into_any_element()
+        } else {
+            Label::new("No commits to rebase")
+                .color(Color::Muted)
+                .into_any_element()
+        };
+        div()
+            .size_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .child(content)
+            .into_any_element()
+    }
+
+    fn render_footer(&self, window: &Window, cx: &Context<Self>) -> AnyElement {
+        let focus_handle = self.focus_handle.clone();
+        let is_pending = self.pending_task.is_some();
+        let buttons = if self.progress.is_some() {
+            let has_unresolved_conflicts = {
+                let repository = self.repository.read(cx);
+                repository.merge.conflicted_paths.iter().any(|path| {
+                    repository
+                        .status_for_path(path)
+                        .is_some_and(|entry| entry.status.is_conflicted())
+                })
+            };
+            h_flex()
+                .gap_1()
+                .child(
+                    Button::new("abort-rebase", "Abort")
+                        .disabled(is_pending)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.abort_rebase(&AbortRebase, window, cx)
+                        })),
+                )
+                .child(
+                    Button::new("skip-rebase", "Skip")
+                        .disabled(is_pending)
+                        .tooltip(Tooltip::text("Leave out the commit the rebase stopped at"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.skip_rebase(&SkipRebase, window, cx)
+                        })),
+                )
+                .child(
+                    Button::new("continue-rebase", "Continue")
+                        .style(ButtonStyle::Filled)
+                        .disabled(is_pending || has_unresolved_conflicts)
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.continue_rebase(&ContinueRebase, window, cx)
+                        })),
+                )
+        } else {
+            h_flex().child(
+                Button::new("start-rebase", "Start Rebase")
+                    .style(ButtonStyle::Filled)
+                    .disabled(is_pending || self.todo.is_empty())
+                    .key_binding(KeyBinding::for_action_in(
+                        &StartRebase,
+                        &focus_handle,
+                        window,
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.start_rebase(&StartRebase, window, cx)
+                    })),
+            )
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .justify_between()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                div().flex_1().min_w_0().children(
+                    self.error
+                        .clone()
+                        .filter(|_| !self.todo.is_empty() || self.progress.is_some())
+                        .map(|error| Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                ),
+            )
+            .child(buttons)
+            .into_any_element()
+    }
+}
+
+/// Abbreviates full commit SHAs, leaving other revisions, such as branch names, as they are.
+fn display_revision(revision: &str) -> &str {
+    if revision.len() == 40 && revision.chars().all(|c| c.is_ascii_hexdigit()) {
+        &revision[..git::SHORT_SHA_LENGTH]
+    } else {
+        revision
+    }
+}
+
+fn action_for(action: RebaseAction) -> Box<dyn Action> {
+    match action {
+        RebaseAction::Pick => Pick.boxed_clone(),
+        RebaseAction::Reword => Reword.boxed_clone(),
+        RebaseAction::Squash => Squash.boxed_clone(),
+        RebaseAction::Fixup => Fixup.boxed_clone(),
+        RebaseAction::Drop => DropCommit.boxed_clone(),
+    }
+}
+
+#[derive(Clone)]
+struct DraggedRebaseEntry {
+    ix: usize,
+    action: RebaseAction,
+    subject: SharedString,
+}
+
+impl Render for DraggedRebaseEntry {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+        h_flex()
+            .font_family(ui_font)
+            .bg(cx.theme().colors().background)
+            .px_2()
+            .py_1()
+            .gap_2()
+            .child(
+                Label::new(self.action.name())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(self.subject.clone()))
+    }
+}
+
+impl EventEmitter<ItemEvent> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseEditor {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &ItemEvent, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        self.title(cx)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Rebase Editor Opened")
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let header = match (&self.progress, &self.base) {
+            (Some(progress), _) => match &progress.branch {
+                Some(branch) => format!("Rebasing {branch}"),
+                None => "Rebasing".to_string(),
+            },
+            (None, Some(RebaseBase::Revision(revision))) => {
+                format!("Rebase onto {}", display_revision(revision))
+            }
+            (None, Some(RebaseBase::Root)) => "Rebase from the root commit".to_string(),
+            (None, None) => "Rebase".to_string(),
+        };
+        let hint = if self.progress.is_some() {
+            "The rebase stopped at the highlighted commit"
+        } else {
+            "Commits are applied from top to bottom. Drag them or press alt-up and alt-down to reorder them."
+        };
+
+        let content = if let Some(progress) = &self.progress {
+            self.render_progress(progress, cx)
+        } else if self.todo.is_empty() {
+            self.render_placeholder(cx)
+        } else {
+            v_flex()
+                .w_full()
+                .children(
+                    self.todo
+                        .iter()
+                        .enumerate()
+                        .map(|(ix, item)| self.render_todo_item(ix, item, cx)),
+                )
+                .into_any_element()
+        };
+
+        v_flex()
+            .key_context(self.dispatch_context(window))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::move_entry_up))
+            .on_action(cx.listener(Self::move_entry_down))
+            .on_action(cx.listener(Self::pick))
+            .on_action(cx.listener(Self::reword))
+            .on_action(cx.listener(Self::squash))
+            .on_action(cx.listener(Self::fixup))
+            .on_action(cx.listener(Self::drop_commit))
+            .on_action(cx.listener(Self::start_rebase))
+            .on_action(cx.listener(Self::continue_rebase))
+            .on_action(cx.listener(Self::skip_rebase))
+            .on_action(cx.listener(Self::abort_rebase))
+            .size_full()
+            .bg(colors.editor_background)
+            .child(
+                v_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(colors.border_variant)
+                    .child(Label::new(header))
+                    .child(Label::new(hint).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .id("rebase-entries")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(content),
+            )
+            .child(self.render_footer(window, cx))
+    }
+}
@@ -9,6 +9,7 @@ pub enum RemoteAction {
     Fetch(Option<Remote>),
     Pull(Remote),
     Push(SharedString, Remote),
+    PushTags(Option<SharedString>, Remote),
 }
 
 impl RemoteAction {
@@ -16,7 +17,7 @@ impl RemoteAction {
         match self {
             RemoteAction::Fetch(_) => "fetch",
             RemoteAction::Pull(_) => "pull",
-            RemoteAction::Push(_, _) => "push",
+            RemoteAction::Push(_, _) | RemoteAction::PushTags(_, _) => "push",
         }
     }
 }
@@ -156,6 +157,23 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 style: style.unwrap_or(SuccessStyle::ToastWithLog { output }),
             }
         }
+        RemoteAction::PushTags(tag_name, remote_ref) => {
+            if output.stderr.ends_with("Everything up-to-date\n") {
+                SuccessMessage {
+                    message: "Push: Everything is up-to-date".into(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                let message = match tag_name {
+                    Some(tag_name) => format!("Pushed tag {} to {}", tag_name, remote_ref.name),
+                    None => format!("Pushed tags to {}", remote_ref.name),
+                };
+                SuccessMessage {
+                    message,
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
     }
 }
 
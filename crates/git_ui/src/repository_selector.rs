@@ -1,7 +1,11 @@
+use collections::HashMap;
 use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task, WeakEntity};
 use itertools::Itertools;
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
-use project::{Project, git_store::Repository};
+use project::{
+    Project,
+    git_store::{Repository, RepositoryId},
+};
 use std::sync::Arc;
 use ui::{ListItem, ListItemSpacing, prelude::*};
 use workspace::{ModalView, Workspace};
@@ -55,15 +59,53 @@ impl RepositorySelector {
             repository_selector: cx.entity().downgrade(),
             repository_entries,
             filtered_repositories,
+            linked_worktrees: HashMap::default(),
             selected_index: 0,
         };
 
+        let worktree_requests = delegate
+            .repository_entries
+            .iter()
+            .map(|repository| {
+                let id = repository.read(cx).id;
+                let path = repository.read(cx).work_directory_abs_path.clone();
+                let request = repository.update(cx, |repository, _| repository.worktrees());
+                (id, path, request)
+            })
+            .collect::<Vec<_>>();
+
         let picker = cx.new(|cx| {
             Picker::uniform_list(delegate, window, cx)
                 .widest_item(widest_item_ix)
                 .max_height(Some(rems(20.).into()))
         });
 
+        // Repositories checked out in a linked worktree are marked with the main worktree they
+        // belong to, to tell apart the worktrees of a same repository.
+        cx.spawn_in(window, {
+            let picker = picker.downgrade();
+            async move |_, cx| {
+                let mut linked_worktrees = HashMap::default();
+                for (id, path, request) in worktree_requests {
+                    let Some(worktrees) = request.await.ok().and_then(Result::ok) else {
+                        continue;
+                    };
+                    let main_worktree = worktrees.iter().find(|worktree| worktree.is_main);
+                    if let Some(main_worktree) = main_worktree
+                        && main_worktree.path.as_path() != &*path
+                        && let Some(name) = main_worktree.path.file_name()
+                    {
+                        linked_worktrees.insert(id, name.to_string_lossy().into_owned().into());
+                    }
+                }
+                picker.update(cx, |picker, cx| {
+                    picker.delegate.linked_worktrees = linked_worktrees;
+                    cx.notify();
+                })
+            }
+        })
+        .detach_and_log_err(cx);
+
         RepositorySelector { picker, width }
     }
 }
@@ -122,6 +164,8 @@ pub struct RepositorySelectorDelegate {
     repository_selector: WeakEntity<RepositorySelector>,
     repository_entries: Vec<Entity<Repository>>,
     filtered_repositories: Vec<Entity<Repository>>,
+    /// The name of the main worktree of the repositories checked out in a linked worktree.
+    linked_worktrees: HashMap<RepositoryId, SharedString>,
     selected_index: usize,
 }
 
@@ -227,12 +271,22 @@ impl PickerDelegate for RepositorySelectorDelegate {
     ) -> Option<Self::ListItem> {
         let repo_info = self.filtered_repositories.get(ix)?;
         let display_name = repo_info.read(cx).display_name();
+        let main_worktree = self.linked_worktrees.get(&repo_info.read(cx).id);
         Some(
             ListItem::new(ix)
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .toggle_state(selected)
-                .child(Label::new(display_name)),
+                .child(h_flex().gap_2().child(Label::new(display_name)).when_some(
+                    main_worktree,
+                    |this, main_worktree| {
+                        this.child(
+                            Label::new(format!("worktree of {main_worktree}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    },
+                )),
         )
     }
 }
@@ -0,0 +1,521 @@
+use editor::Editor;
+use fuzzy::StringMatchCandidate;
+use git::repository::{CommitSummary, GitTag};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity,
+    Window, rems,
+};
+use menu::{Cancel, Confirm};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+use crate::git_panel::GitPanel;
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(|workspace, _: &git::CreateTag, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        create_tag(workspace, repository, None, window, cx);
+    });
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::Tags,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        TagList::new(repository, workspace_handle, rems(34.), window, cx)
+    })
+}
+
+/// Opens a modal creating a tag on `target`, or on `HEAD` when there is none.
+pub(crate) fn create_tag(
+    workspace: &mut Workspace,
+    repository: Entity<Repository>,
+    target: Option<SharedString>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        CreateTagModal::new(repository, target, workspace_handle, window, cx)
+    });
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..git::SHORT_SHA_LENGTH).unwrap_or(sha)
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl TagList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TagListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for TagList {}
+impl EventEmitter<DismissEvent> for TagList {}
+
+impl Focusable for TagList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GitTagSelector")
+            .w(self.width)
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TagEntryKind {
+    /// An existing tag, whose commit is shown when confirmed.
+    Tag(GitTag),
+    /// A lightweight tag to create on `HEAD`.
+    NewTag(SharedString),
+}
+
+#[derive(Debug, Clone)]
+struct TagEntry {
+    kind: TagEntryKind,
+    positions: Vec<usize>,
+}
+
+pub struct TagListDelegate {
+    matches: Vec<TagEntry>,
+    all_tags: Option<Vec<GitTag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl TagListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_tags: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let tags_request = repo.update(cx, |repo, _| repo.tags());
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let tags = tags_request.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_tags = Some(tags);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn create_lightweight_tag(
+        &self,
+        name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| {
+                repo.create_tag(name.to_string(), "HEAD".to_string(), None)
+            })?
+            .await??;
+            workspace.update(cx, |workspace, cx| {
+                show_created_toast(workspace, &name, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn push_tag(&self, name: SharedString, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let Some(panel) = workspace.panel::<GitPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.push_tags(Some(name), window, cx);
+                });
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn can_push(&self, cx: &App) -> bool {
+        self.workspace.upgrade().is_some_and(|workspace| {
+            let project = workspace.read(cx).project().read(cx);
+            !project.is_via_collab() && !project.is_read_only(cx)
+        })
+    }
+}
+
+fn show_created_toast(workspace: &mut Workspace, name: &str, cx: &mut Context<Workspace>) {
+    let toast = StatusToast::new(format!("Created tag {name}"), cx, |this, _| {
+        this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+    });
+    workspace.toggle_status_toast(toast, cx);
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select tag…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<TagEntry> = if query.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| TagEntry {
+                        kind: TagEntryKind::Tag(tag),
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| TagEntry {
+                    kind: TagEntryKind::Tag(all_tags[candidate.candidate_id].clone()),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let query = query.trim();
+                    let is_exact_match = matches.first().is_some_and(|entry| match &entry.kind {
+                        TagEntryKind::Tag(tag) => tag.name.as_ref() == query,
+                        TagEntryKind::NewTag(_) => false,
+                    });
+                    if !query.is_empty() && !is_exact_match {
+                        matches.push(TagEntry {
+                            kind: TagEntryKind::NewTag(query.replace(' ', "-").into()),
+                            positions: Vec::new(),
+                        })
+                    }
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        match &entry.kind {
+            TagEntryKind::Tag(tag) => {
+                let Some(repo) = self.repo.as_ref() else {
+                    return;
+                };
+                CommitView::open(
+                    CommitSummary {
+                        sha: tag.sha.clone(),
+                        subject: tag.message.clone().unwrap_or_default(),
+                        commit_timestamp: 0,
+                        author_name: SharedString::default(),
+                        has_parent: true,
+                    },
+                    repo.downgrade(),
+                    self.workspace.clone(),
+                    window,
+                    cx,
+                );
+                cx.emit(DismissEvent);
+            }
+            TagEntryKind::NewTag(name) => {
+                self.create_lightweight_tag(name.clone(), window, cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+
+        let (icon, label, description, push_tag) = match &entry.kind {
+            TagEntryKind::Tag(tag) => {
+                let label = HighlightedLabel::new(tag.name.clone(), entry.positions.clone())
+                    .truncate()
+                    .into_any_element();
+                let description = match &tag.message {
+                    Some(message) => format!("{} · {}", short_sha(&tag.sha), message),
+                    None => short_sha(&tag.sha).to_string(),
+                };
+                let push_tag = self.can_push(cx).then(|| tag.name.clone());
+                (IconName::Hash, label, description, push_tag)
+            }
+            TagEntryKind::NewTag(name) => {
+                let label = Label::new(format!("Create tag \"{name}\"…"))
+                    .single_line()
+                    .truncate()
+                    .into_any_element();
+                (IconName::Plus, label, "on HEAD".to_string(), None)
+            }
+        };
+
+        let push_button = push_tag.map(|name| {
+            IconButton::new(("push-tag", ix), IconName::ArrowUp)
+                .icon_size(IconSize::Small)
+                .tooltip(Tooltip::text("Push Tag"))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.delegate.push_tag(name.clone(), window, cx);
+                }))
+        });
+
+        Some(
+            ListItem::new(SharedString::from(format!("tag-menu-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                .child(
+                    v_flex().w_full().overflow_hidden().child(label).child(
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .truncate()
+                            .color(Color::Muted),
+                    ),
+                )
+                .end_slot::<IconButton>(push_button),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        None
+    }
+}
+
+/// Creates an annotated tag when a message is given, and a lightweight one otherwise.
+struct CreateTagModal {
+    name_editor: Entity<Editor>,
+    message_editor: Entity<Editor>,
+    repo: Entity<Repository>,
+    target: Option<SharedString>,
+    workspace: WeakEntity<Workspace>,
+}
+
+impl CreateTagModal {
+    fn new(
+        repo: Entity<Repository>,
+        target: Option<SharedString>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Tag name", window, cx);
+            editor
+        });
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Message (leave empty for a lightweight tag)", window, cx);
+            editor
+        });
+        Self {
+            name_editor,
+            message_editor,
+            repo,
+            target,
+            workspace,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let message = self.message_editor.read(cx).text(cx).trim().to_string();
+        let message = (!message.is_empty()).then_some(message);
+        let target = self
+            .target
+            .as_ref()
+            .map_or("HEAD".to_string(), |target| target.to_string());
+
+        let repo = self.repo.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.create_tag(name.clone(), target, message))?
+                .await??;
+            workspace.update(cx, |workspace, cx| {
+                show_created_toast(workspace, &name, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateTagModal {}
+impl ModalView for CreateTagModal {}
+impl Focusable for CreateTagModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateTagModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match &self.target {
+            Some(target) => format!("Create Tag on {}", short_sha(target)),
+            None => "Create Tag on HEAD".to_string(),
+        };
+        v_flex()
+            .key_context("CreateTagModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::Hash).size(IconSize::XSmall))
+                    .child(Headline::new(title).size(HeadlineSize::XSmall)),
+            )
+            .child(
+                v_flex()
+                    .px_3()
+                    .pb_3()
+                    .w_full()
+                    .gap_2()
+                    .child(self.name_editor.clone())
+                    .child(self.message_editor.clone()),
+            )
+    }
+}
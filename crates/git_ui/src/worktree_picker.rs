@@ -0,0 +1,607 @@
+use collections::HashSet;
+use fuzzy::StringMatchCandidate;
+use git::repository::{Branch, GitWorktree};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity,
+    Window, rems,
+};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(prune);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::Worktrees,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let workspace_handle = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        WorktreeList::new(repository, workspace_handle, rems(34.), window, cx)
+    })
+}
+
+fn prune(
+    workspace: &mut Workspace,
+    _: &git::PruneWorktrees,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let prune = repository.update(cx, |repository, _| repository.prune_worktrees());
+    cx.spawn(async move |workspace, cx| {
+        prune.await??;
+        workspace.update(cx, |workspace, cx| {
+            let toast = StatusToast::new("Pruned stale worktrees", cx, |this, _| {
+                this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+            });
+            workspace.toggle_status_toast(toast, cx);
+        })
+    })
+    .detach_and_prompt_err("Failed to prune worktrees", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
+pub struct WorktreeList {
+    width: Rems,
+    pub picker: Entity<Picker<WorktreeListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl WorktreeList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = WorktreeListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            width,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for WorktreeList {}
+impl EventEmitter<DismissEvent> for WorktreeList {}
+
+impl Focusable for WorktreeList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreeList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("GitWorktreeSelector")
+            .w(self.width)
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WorktreeEntryKind {
+    /// A worktree of the repository, opened when confirmed.
+    Worktree(GitWorktree),
+    /// A branch that isn't checked out in any worktree, for which a worktree is created.
+    Branch(Branch),
+    /// A branch to create from `HEAD`, along with its worktree.
+    NewBranch(SharedString),
+}
+
+#[derive(Debug, Clone)]
+struct WorktreeEntry {
+    kind: WorktreeEntryKind,
+    label: SharedString,
+    positions: Vec<usize>,
+}
+
+pub struct WorktreeListDelegate {
+    matches: Vec<WorktreeEntry>,
+    all_entries: Option<Vec<WorktreeEntry>>,
+    main_worktree_path: Option<PathBuf>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl WorktreeListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_entries: None,
+            main_worktree_path: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let (worktrees_request, branches_request) =
+            repo.update(cx, |repo, _| (repo.worktrees(), repo.branches()));
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let worktrees = worktrees_request.await??;
+            let branches = branches_request.await??;
+            let main_worktree_path = worktrees
+                .iter()
+                .find(|worktree| worktree.is_main)
+                .map(|worktree| worktree.path.clone());
+            let entries = worktree_entries(worktrees, branches);
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.main_worktree_path = main_worktree_path;
+                picker.delegate.all_entries = Some(entries);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Where the worktree of a branch is created: next to the main worktree, in a directory
+    /// named after both, so that `~/src/zed` gets `~/src/zed-my-feature` for `my-feature`.
+    fn worktree_path_for_branch(&self, branch: &str) -> Option<PathBuf> {
+        let main_worktree_path = self.main_worktree_path.as_ref()?;
+        let repository_name = main_worktree_path.file_name()?.to_string_lossy();
+        let directory_name = format!("{repository_name}-{}", branch.replace('/', "-"));
+        Some(main_worktree_path.parent()?.join(directory_name))
+    }
+
+    fn create_worktree(
+        &self,
+        branch: String,
+        create_branch_from: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let Some(path) = self.worktree_path_for_branch(&branch) else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| {
+                repo.add_worktree(path.clone(), branch, create_branch_from)
+            })?
+            .await??;
+            open_worktree(workspace, path, true, cx).await
+        })
+        .detach_and_prompt_err("Failed to create worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn remove_worktree(&self, path: PathBuf, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| repo.remove_worktree(path, false))?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload(window, cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn is_current_worktree(&self, path: &Path, cx: &App) -> bool {
+        self.repo
+            .as_ref()
+            .is_some_and(|repo| repo.read(cx).work_directory_abs_path.as_ref() == path)
+    }
+}
+
+/// Lists the worktrees first, then the branches a worktree can be created for: local branches
+/// that aren't checked out yet, and remote branches that no local branch tracks.
+fn worktree_entries(worktrees: Vec<GitWorktree>, branches: Vec<Branch>) -> Vec<WorktreeEntry> {
+    let checked_out: HashSet<SharedString> = worktrees
+        .iter()
+        .filter_map(|worktree| worktree.ref_name.clone())
+        .collect();
+    let tracked: HashSet<SharedString> = branches
+        .iter()
+        .filter_map(|branch| branch.upstream.as_ref())
+        .map(|upstream| upstream.ref_name.clone())
+        .collect();
+
+    let mut branches = branches
+        .into_iter()
+        .filter(|branch| {
+            !checked_out.contains(&branch.ref_name) && !tracked.contains(&branch.ref_name)
+        })
+        .collect::<Vec<_>>();
+    branches.sort_by_key(|branch| {
+        (
+            branch.is_remote(),
+            branch
+                .most_recent_commit
+                .as_ref()
+                .map(|commit| 0 - commit.commit_timestamp),
+        )
+    });
+
+    worktrees
+        .into_iter()
+        .map(|worktree| WorktreeEntry {
+            label: worktree
+                .branch_name()
+                .map(SharedString::new)
+                .or_else(|| {
+                    worktree.sha.as_ref().map(|sha| {
+                        sha[..git::SHORT_SHA_LENGTH.min(sha.len())]
+                            .to_string()
+                            .into()
+                    })
+                })
+                .unwrap_or_else(|| "(bare)".into()),
+            kind: WorktreeEntryKind::Worktree(worktree),
+            positions: Vec::new(),
+        })
+        .chain(branches.into_iter().map(|branch| WorktreeEntry {
+            label: branch.name().to_string().into(),
+            kind: WorktreeEntryKind::Branch(branch),
+            positions: Vec::new(),
+        }))
+        .collect()
+}
+
+/// Opens a worktree in a window of its own, or activates the window it is already open in.
+/// Worktrees of remote projects can't be opened from here, as they live on another machine.
+async fn open_worktree(
+    workspace: WeakEntity<Workspace>,
+    path: PathBuf,
+    created: bool,
+    cx: &mut gpui::AsyncApp,
+) -> anyhow::Result<()> {
+    let open_task = workspace.update(cx, |workspace, cx| {
+        if workspace.project().read(cx).is_local() {
+            let app_state = workspace.app_state().clone();
+            return Ok(Some(workspace::open_paths(
+                &[path.clone()],
+                app_state,
+                OpenOptions::default(),
+                cx,
+            )));
+        }
+        anyhow::ensure!(
+            created,
+            "Worktrees of remote projects can't be opened in a new window yet"
+        );
+        let toast = StatusToast::new(
+            format!("Created worktree at {}", path.display()),
+            cx,
+            |this, _| this.icon(ToastIcon::new(IconName::Check).color(Color::Success)),
+        );
+        workspace.toggle_status_toast(toast, cx);
+        Ok(None)
+    })??;
+    if let Some(open_task) = open_task {
+        open_task.await?;
+    }
+    Ok(())
+}
+
+impl PickerDelegate for WorktreeListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select worktree or branch…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_entries) = self.all_entries.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<WorktreeEntry> = if query.is_empty() {
+                all_entries
+            } else {
+                let candidates = all_entries
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.label))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| WorktreeEntry {
+                    positions: candidate.positions,
+                    ..all_entries[candidate.candidate_id].clone()
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    if !query.is_empty()
+                        && !matches
+                            .first()
+                            .is_some_and(|entry| entry.label.as_ref() == query)
+                    {
+                        let name: SharedString = query.trim().replace(' ', "-").into();
+                        matches.push(WorktreeEntry {
+                            kind: WorktreeEntryKind::NewBranch(name.clone()),
+                            label: name,
+                            positions: Vec::new(),
+                        })
+                    }
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        match &entry.kind {
+            WorktreeEntryKind::Worktree(worktree) => {
+                let workspace = self.workspace.clone();
+                let path = worktree.path.clone();
+                let is_prunable = worktree.is_prunable;
+                cx.spawn(async move |_, cx| {
+                    anyhow::ensure!(
+                        !is_prunable,
+                        "The directory of the worktree at {} no longer exists",
+                        path.display()
+                    );
+                    open_worktree(workspace, path, false, cx).await
+                })
+                .detach_and_prompt_err(
+                    "Failed to open worktree",
+                    window,
+                    cx,
+                    |e, _, _| Some(e.to_string()),
+                );
+                cx.emit(DismissEvent);
+            }
+            WorktreeEntryKind::Branch(branch) => {
+                if branch.is_remote() {
+                    // Worktrees of remote branches get a local branch of the same name tracking them.
+                    let Some((_, local_name)) = branch.name().split_once('/') else {
+                        return;
+                    };
+                    self.create_worktree(
+                        local_name.to_string(),
+                        Some(branch.name().to_string()),
+                        window,
+                        cx,
+                    );
+                } else {
+                    self.create_worktree(branch.name().to_string(), None, window, cx);
+                }
+            }
+            WorktreeEntryKind::NewBranch(name) => {
+                self.create_worktree(name.to_string(), Some("HEAD".to_string()), window, cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+
+        let (icon, label, description, remove_path) = match &entry.kind {
+            WorktreeEntryKind::Worktree(worktree) => {
+                let is_current = self.is_current_worktree(&worktree.path, cx);
+                let description = if worktree.is_prunable {
+                    format!("{} (missing)", worktree.path.display())
+                } else if is_current {
+                    format!("{} (current)", worktree.path.display())
+                } else {
+                    worktree.path.display().to_string()
+                };
+                let remove_path = (!worktree.is_main && !worktree.is_locked && !is_current)
+                    .then(|| worktree.path.clone());
+                let label = HighlightedLabel::new(entry.label.clone(), entry.positions.clone())
+                    .truncate()
+                    .into_any_element();
+                (IconName::Folder, label, description, remove_path)
+            }
+            WorktreeEntryKind::Branch(branch) => {
+                let label = HighlightedLabel::new(entry.label.clone(), entry.positions.clone())
+                    .truncate()
+                    .into_any_element();
+                let local_name = if branch.is_remote() {
+                    branch
+                        .name()
+                        .split_once('/')
+                        .map_or(branch.name(), |(_, name)| name)
+                } else {
+                    branch.name()
+                };
+                let description = self
+                    .worktree_path_for_branch(local_name)
+                    .map(|path| format!("create worktree at {}", path.display()))
+                    .unwrap_or_default();
+                (IconName::GitBranchAlt, label, description, None)
+            }
+            WorktreeEntryKind::NewBranch(name) => {
+                let label = Label::new(format!("Create branch \"{name}\" in a new worktree…"))
+                    .single_line()
+                    .truncate()
+                    .into_any_element();
+                let description = self
+                    .worktree_path_for_branch(name)
+                    .map(|path| format!("based off HEAD, at {}", path.display()))
+                    .unwrap_or_default();
+                (IconName::Plus, label, description, None)
+            }
+        };
+
+        let remove_button = remove_path.map(|path| {
+            IconButton::new(("remove-worktree", ix), IconName::Trash)
+                .icon_size(IconSize::Small)
+                .tooltip(Tooltip::text("Remove Worktree"))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.delegate.remove_worktree(path.clone(), window, cx);
+                }))
+        });
+
+        Some(
+            ListItem::new(SharedString::from(format!("worktree-menu-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                .child(
+                    v_flex().w_full().overflow_hidden().child(label).child(
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .truncate()
+                            .color(Color::Muted),
+                    ),
+                )
+                .end_slot::<IconButton>(remove_button),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::Upstream;
+
+    fn branch(ref_name: &'static str, upstream: Option<&'static str>) -> Branch {
+        Branch {
+            is_head: false,
+            ref_name: ref_name.into(),
+            upstream: upstream.map(|ref_name| Upstream {
+                ref_name: ref_name.into(),
+                tracking: git::repository::UpstreamTracking::Gone,
+            }),
+            most_recent_commit: None,
+        }
+    }
+
+    fn worktree(path: &str, ref_name: Option<&'static str>, is_main: bool) -> GitWorktree {
+        GitWorktree {
+            path: PathBuf::from(path),
+            sha: Some("0123456789abcdef0123456789abcdef01234567".into()),
+            ref_name: ref_name.map(Into::into),
+            is_main,
+            is_locked: false,
+            is_prunable: false,
+        }
+    }
+
+    #[test]
+    fn test_worktree_entries() {
+        let entries = worktree_entries(
+            vec![
+                worktree("/src/zed", Some("refs/heads/main"), true),
+                worktree("/src/zed-review", None, false),
+            ],
+            vec![
+                branch("refs/heads/main", Some("refs/remotes/origin/main")),
+                branch("refs/heads/feature", None),
+                branch("refs/remotes/origin/main", None),
+                branch("refs/remotes/origin/pr-42", None),
+            ],
+        );
+        let labels = entries
+            .iter()
+            .map(|entry| entry.label.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["main", "0123456", "feature", "origin/pr-42"]);
+    }
+}
@@ -0,0 +1,1460 @@
+mod hierarchy_panel_settings;
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Context as _;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use file_icons::FileIcons;
+use gpui::{
+    Action, App, AsyncWindowContext, ClickEvent, Context, ElementId, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement, IntoElement, KeyContext, ParentElement, Pixels,
+    Render, ScrollStrategy, SharedString, Styled, Task, UniformListScrollHandle, WeakEntity,
+    Window, actions, px, uniform_list,
+};
+use hierarchy_panel_settings::{DockSide, HierarchyPanelSettings};
+use language::{Buffer, OffsetRangeExt as _, Point, ToPoint as _};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{CallHierarchyItem, Fs, Location, Project, TypeHierarchyItem};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use ui::{
+    ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Color, DynamicSpacing,
+    FluentBuilder, Icon, IconName, IconSize, Label, LabelCommon, LabelSize, ListItem, TintColor,
+    Toggleable, h_flex, v_flex,
+};
+use util::ResultExt;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    hierarchy_panel,
+    [
+        /// Collapses the currently selected entry.
+        CollapseSelectedEntry,
+        /// Expands the currently selected entry, fetching its calls if needed.
+        ExpandSelectedEntry,
+        /// Opens the selected entry in the editor.
+        OpenSelectedEntry,
+        /// Switches between the incoming and the outgoing calls, or between the supertypes
+        /// and the subtypes, depending on the shown hierarchy.
+        ToggleDirection,
+        /// Toggles focus on the hierarchy panel.
+        ToggleFocus,
+    ]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+/// Which hierarchy is shown, and which related items are shown as the children of each item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HierarchyMode {
+    /// The callers of each function.
+    #[default]
+    IncomingCalls,
+    /// The callees of each function.
+    OutgoingCalls,
+    /// The types each type derives from.
+    Supertypes,
+    /// The types deriving from each type.
+    Subtypes,
+}
+
+impl HierarchyMode {
+    fn is_type_hierarchy(self) -> bool {
+        matches!(self, Self::Supertypes | Self::Subtypes)
+    }
+
+    fn toggled(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&String> {
+        match self {
+            Self::Call(item) => item.detail.as_ref(),
+            Self::Type(item) => item.detail.as_ref(),
+        }
+    }
+
+    fn location(&self) -> &Location {
+        match self {
+            Self::Call(item) => &item.location,
+            Self::Type(item) => &item.location,
+        }
+    }
+
+    fn selection_range(&self) -> &Range<language::Anchor> {
+        match self {
+            Self::Call(item) => &item.selection_range,
+            Self::Type(item) => &item.selection_range,
+        }
+    }
+}
+
+pub struct HierarchyPanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    active: bool,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    mode: HierarchyMode,
+    root_items: Vec<HierarchyItem>,
+    nodes: Vec<HierarchyNode>,
+    root_nodes: Vec<usize>,
+    visible_entries: Vec<VisibleEntry>,
+    selected_entry: Option<usize>,
+    /// The hierarchy being fetched for the symbol under the cursor, if any.
+    loading: Option<HierarchyMode>,
+    /// Whether a hierarchy was requested since the panel was created.
+    requested: bool,
+    prepare_task: Task<()>,
+    fetch_tasks: Vec<Task<()>>,
+}
+
+#[derive(Debug)]
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// The call expressions, located in the caller: this item for the incoming calls,
+    /// and the parent item for the outgoing ones. Empty in the type hierarchy.
+    call_sites: Vec<Location>,
+    depth: usize,
+    expanded: bool,
+    /// `None` until the calls of the item are fetched.
+    children: Option<Vec<usize>>,
+}
+
+/// A row of the panel: either an item of the hierarchy, or one of the call sites of an item with
+/// several of them, listed before the children of the expanded item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VisibleEntry {
+    Node(usize),
+    CallSite { node_ix: usize, call_site_ix: usize },
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut App) {
+    HierarchyPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut App) {
+    init_settings(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+        });
+        workspace.register_action(
+            |workspace, _: &editor::actions::ShowCallHierarchy, window, cx| {
+                let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+                    return;
+                };
+                let Some(panel) = workspace.panel::<HierarchyPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.show_call_hierarchy(&editor, window, cx)
+                });
+                workspace.focus_panel::<HierarchyPanel>(window, cx);
+            },
+        );
+        workspace.register_action(
+            |workspace, _: &editor::actions::ShowTypeHierarchy, window, cx| {
+                let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+                    return;
+                };
+                let Some(panel) = workspace.panel::<HierarchyPanel>(cx) else {
+                    return;
+                };
+                panel.update(cx, |panel, cx| {
+                    panel.show_type_hierarchy(&editor, window, cx)
+                });
+                workspace.focus_panel::<HierarchyPanel>(window, cx);
+            },
+        );
+    })
+    .detach();
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = match workspace
+            .read_with(&cx, |workspace, _| {
+                HierarchyPanel::serialization_key(workspace)
+            })
+            .ok()
+            .flatten()
+        {
+            Some(serialization_key) => cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(&serialization_key) })
+                .await
+                .context("loading hierarchy panel")
+                .log_err()
+                .flatten()
+                .map(|panel| serde_json::from_str::<SerializedHierarchyPanel>(&panel))
+                .transpose()
+                .log_err()
+                .flatten(),
+            None => None,
+        };
+
+        workspace.update_in(&mut cx, |workspace, _, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &mut Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let workspace = cx.entity().downgrade();
+        cx.new(|cx| Self {
+            fs,
+            width: None,
+            project,
+            workspace,
+            active: false,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            pending_serialization: Task::ready(None),
+            mode: HierarchyMode::default(),
+            root_items: Vec::new(),
+            nodes: Vec::new(),
+            root_nodes: Vec::new(),
+            visible_entries: Vec::new(),
+            selected_entry: None,
+            loading: None,
+            requested: false,
+            prepare_task: Task::ready(()),
+            fetch_tasks: Vec::new(),
+        })
+    }
+
+    fn serialization_key(workspace: &Workspace) -> Option<String> {
+        workspace
+            .database_id()
+            .map(|id| i64::from(id).to_string())
+            .or(workspace.session_id())
+            .map(|id| format!("{}-{:?}", HIERARCHY_PANEL_KEY, id))
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(serialization_key) = self
+            .workspace
+            .read_with(cx, |workspace, _| {
+                HierarchyPanel::serialization_key(workspace)
+            })
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        serialization_key,
+                        serde_json::to_string(&SerializedHierarchyPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    pub fn mode(&self) -> HierarchyMode {
+        self.mode
+    }
+
+    /// Replaces the panel contents with the call hierarchy of the symbol under the newest
+    /// selection of the given editor.
+    pub fn show_call_hierarchy(
+        &mut self,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((buffer, position)) = newest_cursor_position(editor, cx) else {
+            return;
+        };
+        let prepare = self.project.update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, position, cx)
+        });
+        let mode = if self.mode.is_type_hierarchy() {
+            HierarchyMode::IncomingCalls
+        } else {
+            self.mode
+        };
+        let items = cx.background_spawn(async move {
+            let items = prepare.await?.unwrap_or_default();
+            anyhow::Ok(items.into_iter().map(HierarchyItem::Call).collect())
+        });
+        self.show_hierarchy(mode, items, window, cx);
+    }
+
+    /// Replaces the panel contents with the type hierarchy of the type under the newest
+    /// selection of the given editor.
+    pub fn show_type_hierarchy(
+        &mut self,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((buffer, position)) = newest_cursor_position(editor, cx) else {
+            return;
+        };
+        let prepare = self.project.update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, position, cx)
+        });
+        let mode = if self.mode.is_type_hierarchy() {
+            self.mode
+        } else {
+            HierarchyMode::Supertypes
+        };
+        let items = cx.background_spawn(async move {
+            let items = prepare.await?.unwrap_or_default();
+            anyhow::Ok(items.into_iter().map(HierarchyItem::Type).collect())
+        });
+        self.show_hierarchy(mode, items, window, cx);
+    }
+
+    fn show_hierarchy(
+        &mut self,
+        mode: HierarchyMode,
+        items: Task<anyhow::Result<Vec<HierarchyItem>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.loading = Some(mode);
+        self.requested = true;
+        cx.notify();
+        self.prepare_task = cx.spawn_in(window, async move |panel, cx| {
+            let items = items.await.log_err().unwrap_or_default();
+            panel
+                .update(cx, |panel, cx| {
+                    panel.loading = None;
+                    panel.mode = mode;
+                    panel.root_items = items;
+                    panel.rebuild_tree(cx);
+                })
+                .ok();
+        });
+    }
+
+    fn rebuild_tree(&mut self, cx: &mut Context<Self>) {
+        self.nodes.clear();
+        self.fetch_tasks.clear();
+        self.root_nodes = self
+            .root_items
+            .iter()
+            .cloned()
+            .map(|item| {
+                self.nodes.push(HierarchyNode {
+                    item,
+                    call_sites: Vec::new(),
+                    depth: 0,
+                    expanded: false,
+                    children: None,
+                });
+                self.nodes.len() - 1
+            })
+            .collect();
+        self.selected_entry = (!self.root_nodes.is_empty()).then_some(0);
+        if let Some(&first_root) = self.root_nodes.first() {
+            self.expand_node(first_root, cx);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn expand_node(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        node.expanded = true;
+        if node.children.is_some() {
+            return;
+        }
+
+        let item = node.item.clone();
+        let mode = self.mode;
+        let children = self
+            .project
+            .update(cx, |project, cx| fetch_children(project, mode, item, cx));
+        let fetch_task = cx.spawn(async move |panel, cx| {
+            let children = children.await.log_err().unwrap_or_default();
+            panel
+                .update(cx, |panel, cx| {
+                    panel.insert_children(node_ix, children);
+                    panel.update_visible_entries(cx);
+                })
+                .ok();
+        });
+        self.fetch_tasks.push(fetch_task);
+    }
+
+    fn insert_children(&mut self, parent_ix: usize, children: Vec<(HierarchyItem, Vec<Location>)>) {
+        let Some(depth) = self.nodes.get(parent_ix).map(|parent| parent.depth + 1) else {
+            return;
+        };
+        let children = children
+            .into_iter()
+            .map(|(item, call_sites)| {
+                self.nodes.push(HierarchyNode {
+                    item,
+                    call_sites,
+                    depth,
+                    expanded: false,
+                    children: None,
+                });
+                self.nodes.len() - 1
+            })
+            .collect();
+        self.nodes[parent_ix].children = Some(children);
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        let selected_entry = self.selected_visible_entry();
+        self.visible_entries.clear();
+        let mut stack = self.root_nodes.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(node_ix) = stack.pop() {
+            self.visible_entries.push(VisibleEntry::Node(node_ix));
+            let node = &self.nodes[node_ix];
+            if !node.expanded {
+                continue;
+            }
+            if node.call_sites.len() > 1 {
+                self.visible_entries
+                    .extend((0..node.call_sites.len()).map(|call_site_ix| {
+                        VisibleEntry::CallSite {
+                            node_ix,
+                            call_site_ix,
+                        }
+                    }));
+            }
+            if let Some(children) = &node.children {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+        self.selected_entry = selected_entry
+            .and_then(|selected_entry| {
+                self.visible_entries
+                    .iter()
+                    .position(|&entry| entry == selected_entry)
+            })
+            .or_else(|| (!self.visible_entries.is_empty()).then_some(0));
+        cx.notify();
+    }
+
+    fn selected_visible_entry(&self) -> Option<VisibleEntry> {
+        self.visible_entries.get(self.selected_entry?).copied()
+    }
+
+    /// The selected item, or the item of the selected call site.
+    fn selected_node(&self) -> Option<usize> {
+        match self.selected_visible_entry()? {
+            VisibleEntry::Node(node_ix) | VisibleEntry::CallSite { node_ix, .. } => Some(node_ix),
+        }
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        if self.nodes[node_ix].expanded {
+            self.nodes[node_ix].expanded = false;
+        } else {
+            self.expand_node(node_ix, cx);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(node_ix) = self.selected_node() {
+            self.expand_node(node_ix, cx);
+            self.update_visible_entries(cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(selected_entry) = self.selected_visible_entry() else {
+            return;
+        };
+        let collapsed_node = match selected_entry {
+            VisibleEntry::Node(node_ix) if self.nodes[node_ix].expanded => Some(node_ix),
+            VisibleEntry::Node(node_ix) => self.nodes.iter().position(|node| {
+                node.children
+                    .as_ref()
+                    .is_some_and(|children| children.contains(&node_ix))
+            }),
+            VisibleEntry::CallSite { node_ix, .. } => Some(node_ix),
+        };
+        if let Some(node_ix) = collapsed_node {
+            self.nodes[node_ix].expanded = false;
+            self.selected_entry = self
+                .visible_entries
+                .iter()
+                .position(|&entry| entry == VisibleEntry::Node(node_ix));
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_mode(self.mode.toggled(), cx);
+    }
+
+    fn set_mode(&mut self, mode: HierarchyMode, cx: &mut Context<Self>) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.rebuild_tree(cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let entry_ix = self
+            .selected_entry
+            .map_or(0, |selected_entry| selected_entry + 1);
+        if entry_ix < self.visible_entries.len() {
+            self.select_entry(entry_ix, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_entry) = self.selected_entry {
+            self.select_entry(selected_entry.saturating_sub(1), cx);
+        } else if !self.visible_entries.is_empty() {
+            self.select_entry(self.visible_entries.len() - 1, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.visible_entries.is_empty() {
+            self.select_entry(0, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.visible_entries.is_empty() {
+            self.select_entry(self.visible_entries.len() - 1, cx);
+        }
+    }
+
+    fn select_entry(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        self.selected_entry = Some(entry_ix);
+        self.scroll_handle
+            .scroll_to_item(entry_ix, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry) = self.selected_visible_entry() {
+            self.open_entry(entry, true, window, cx);
+        }
+    }
+
+    fn open_selected_entry(
+        &mut self,
+        _: &OpenSelectedEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(entry) = self.selected_visible_entry() {
+            self.open_entry(entry, false, window, cx);
+        }
+    }
+
+    /// Reveals the given call site, or the first call site of an item: in the caller for the
+    /// incoming calls, and in the parent item for the outgoing ones. The roots and the types,
+    /// which have no call sites, reveal the symbol itself.
+    fn open_entry(
+        &mut self,
+        entry: VisibleEntry,
+        focus: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (node, call_site) = match entry {
+            VisibleEntry::Node(node_ix) => {
+                let node = &self.nodes[node_ix];
+                (node, node.call_sites.first())
+            }
+            VisibleEntry::CallSite {
+                node_ix,
+                call_site_ix,
+            } => {
+                let node = &self.nodes[node_ix];
+                (node, node.call_sites.get(call_site_ix))
+            }
+        };
+        let (buffer, range) = match call_site {
+            Some(call_site) => (call_site.buffer.clone(), call_site.range.clone()),
+            None => (
+                node.item.location().buffer.clone(),
+                node.item.selection_range().clone(),
+            ),
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_range(workspace, buffer, range, focus, window, cx)
+            })
+            .ok();
+    }
+
+    fn render_entry(
+        &self,
+        entry_ix: usize,
+        entry: VisibleEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ListItem {
+        match entry {
+            VisibleEntry::Node(node_ix) => self.render_node(entry_ix, node_ix, window, cx),
+            VisibleEntry::CallSite {
+                node_ix,
+                call_site_ix,
+            } => self.render_call_site(entry_ix, node_ix, call_site_ix, window, cx),
+        }
+    }
+
+    fn render_node(
+        &self,
+        entry_ix: usize,
+        node_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ListItem {
+        let settings = HierarchyPanelSettings::get_global(cx);
+        let node = &self.nodes[node_ix];
+        let is_selected = self.selected_entry == Some(entry_ix);
+        let can_expand = node.call_sites.len() > 1
+            || node
+                .children
+                .as_ref()
+                .is_none_or(|children| !children.is_empty());
+        let file = node.item.location().buffer.read(cx).file().cloned();
+        let file_icon = file
+            .as_ref()
+            .and_then(|file| FileIcons::get_icon(file.path().as_std_path(), cx))
+            .map(|icon_path| Icon::from_path(icon_path).color(Color::Muted))
+            .unwrap_or_else(|| Icon::new(IconName::File).color(Color::Muted))
+            .size(IconSize::Small);
+        let file_name = file.map(|file| SharedString::from(file.file_name(cx).to_string()));
+        let call_count = node.call_sites.len();
+
+        ListItem::new(ElementId::Integer(node_ix as u64))
+            .indent_level(node.depth)
+            .indent_step_size(px(settings.indent_size))
+            .toggle_state(is_selected)
+            .focused(is_selected && self.focus_handle.contains_focused(window, cx))
+            .toggle(can_expand.then_some(node.expanded))
+            .on_toggle(cx.listener(move |panel, _: &ClickEvent, _, cx| {
+                panel.toggle_expanded(node_ix, cx);
+            }))
+            .on_click(cx.listener(move |panel, event: &ClickEvent, window, cx| {
+                panel.selected_entry = Some(entry_ix);
+                panel.open_entry(
+                    VisibleEntry::Node(node_ix),
+                    event.click_count() > 1,
+                    window,
+                    cx,
+                );
+                cx.notify();
+            }))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(file_icon)
+                    .child(Label::new(node.item.name().to_string()).single_line())
+                    .when(call_count > 1, |this| {
+                        this.child(
+                            Label::new(format!("({call_count})"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when_some(node.item.detail().cloned(), |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line()
+                                .truncate(),
+                        )
+                    })
+                    .when_some(file_name, |this, file_name| {
+                        this.child(
+                            Label::new(file_name)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+    }
+
+    fn render_call_site(
+        &self,
+        entry_ix: usize,
+        node_ix: usize,
+        call_site_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ListItem {
+        let settings = HierarchyPanelSettings::get_global(cx);
+        let node = &self.nodes[node_ix];
+        let is_selected = self.selected_entry == Some(entry_ix);
+        let entry = VisibleEntry::CallSite {
+            node_ix,
+            call_site_ix,
+        };
+
+        ListItem::new(ElementId::NamedInteger(
+            format!("call-site-{node_ix}").into(),
+            call_site_ix as u64,
+        ))
+        .indent_level(node.depth + 1)
+        .indent_step_size(px(settings.indent_size))
+        .toggle_state(is_selected)
+        .focused(is_selected && self.focus_handle.contains_focused(window, cx))
+        .on_click(cx.listener(move |panel, event: &ClickEvent, window, cx| {
+            panel.selected_entry = Some(entry_ix);
+            panel.open_entry(entry, event.click_count() > 1, window, cx);
+            cx.notify();
+        }))
+        .child(
+            Label::new(call_site_label(&node.call_sites[call_site_ix], cx))
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .single_line()
+                .truncate(),
+        )
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mode_button = |id: &'static str, label: &'static str, mode: HierarchyMode| {
+            Button::new(id, label)
+                .style(ButtonStyle::Subtle)
+                .selected_style(ButtonStyle::Tinted(TintColor::Accent))
+                .toggle_state(self.mode == mode)
+                .on_click(cx.listener(move |panel, _, _, cx| {
+                    panel.set_mode(mode, cx);
+                }))
+        };
+        let (first_button, second_button) = if self.mode.is_type_hierarchy() {
+            (
+                mode_button("supertypes", "Supertypes", HierarchyMode::Supertypes),
+                mode_button("subtypes", "Subtypes", HierarchyMode::Subtypes),
+            )
+        } else {
+            (
+                mode_button(
+                    "incoming-calls",
+                    "Incoming Calls",
+                    HierarchyMode::IncomingCalls,
+                ),
+                mode_button(
+                    "outgoing-calls",
+                    "Outgoing Calls",
+                    HierarchyMode::OutgoingCalls,
+                ),
+            )
+        };
+
+        h_flex()
+            .px_2()
+            .h(DynamicSpacing::Base32.px(cx))
+            .flex_shrink_0()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(first_button)
+            .child(second_button)
+    }
+}
+
+/// The line number and the text of the line of a call site.
+fn call_site_label(call_site: &Location, cx: &App) -> String {
+    let snapshot = call_site.buffer.read(cx).snapshot();
+    let row = call_site.range.start.to_point(&snapshot).row;
+    let line = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    format!("{}: {}", row + 1, line.trim())
+}
+
+fn newest_cursor_position(
+    editor: &Entity<Editor>,
+    cx: &App,
+) -> Option<(Entity<Buffer>, language::Anchor)> {
+    let editor = editor.read(cx);
+    editor
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(editor.selections.newest_anchor().head(), cx)
+}
+
+/// Fetches the items related to `item` in the hierarchy shown with `mode`, along with their
+/// call sites.
+fn fetch_children(
+    project: &mut Project,
+    mode: HierarchyMode,
+    item: HierarchyItem,
+    cx: &mut Context<Project>,
+) -> Task<anyhow::Result<Vec<(HierarchyItem, Vec<Location>)>>> {
+    let calls = match (mode, item) {
+        (HierarchyMode::IncomingCalls, HierarchyItem::Call(item)) => {
+            project.incoming_calls(item, cx)
+        }
+        (HierarchyMode::OutgoingCalls, HierarchyItem::Call(item)) => {
+            project.outgoing_calls(item, cx)
+        }
+        (HierarchyMode::Supertypes, HierarchyItem::Type(item)) => {
+            let supertypes = project.supertypes(item, cx);
+            return cx.background_spawn(async move { types_to_children(supertypes.await?) });
+        }
+        (HierarchyMode::Subtypes, HierarchyItem::Type(item)) => {
+            let subtypes = project.subtypes(item, cx);
+            return cx.background_spawn(async move { types_to_children(subtypes.await?) });
+        }
+        _ => return Task::ready(Ok(Vec::new())),
+    };
+    cx.background_spawn(async move {
+        Ok(calls
+            .await?
+            .into_iter()
+            .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+            .collect())
+    })
+}
+
+fn types_to_children(
+    items: Vec<TypeHierarchyItem>,
+) -> anyhow::Result<Vec<(HierarchyItem, Vec<Location>)>> {
+    Ok(items
+        .into_iter()
+        .map(|item| (HierarchyItem::Type(item), Vec::new()))
+        .collect())
+}
+
+fn open_range(
+    workspace: &mut Workspace,
+    buffer: Entity<Buffer>,
+    range: Range<language::Anchor>,
+    focus: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let range = range.to_point(&buffer.read(cx).snapshot());
+    let pane = workspace.active_pane().clone();
+    let editor = workspace.open_project_item::<Editor>(pane, buffer, true, focus, window, cx);
+    editor.update(cx, |editor, cx| {
+        editor.go_to_singleton_buffer_range(range, window, cx);
+    });
+}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        match HierarchyPanelSettings::get_global(cx).dock {
+            DockSide::Left => DockPosition::Left,
+            DockSide::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            let dock = match position {
+                DockPosition::Left | DockPosition::Bottom => DockSide::Left,
+                DockPosition::Right => DockSide::Right,
+            };
+            settings.hierarchy_panel.get_or_insert_default().dock = Some(dock);
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+        cx.defer_in(window, |this, _, cx| {
+            this.serialize(cx);
+        });
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::ArrowRightLeft)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _window: &Window, _: &App) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.active != active {
+            self.active = active;
+            self.serialize(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let contents = if self.visible_entries.is_empty() {
+            let message = match self.loading {
+                Some(mode) if mode.is_type_hierarchy() => "Loading type hierarchy…",
+                Some(_) => "Loading call hierarchy…",
+                None if !self.requested => {
+                    "Use \"Show Call Hierarchy\" or \"Show Type Hierarchy\" on a symbol"
+                }
+                None if self.mode.is_type_hierarchy() => "No type found under the cursor",
+                None => "No function found under the cursor",
+            };
+            v_flex()
+                .id("empty-hierarchy-state")
+                .flex_1()
+                .size_full()
+                .justify_center()
+                .child(
+                    h_flex()
+                        .justify_center()
+                        .child(Label::new(message).color(Color::Muted)),
+                )
+                .into_any_element()
+        } else {
+            uniform_list(
+                "hierarchy-entries",
+                self.visible_entries.len(),
+                cx.processor(|panel, range: Range<usize>, window, cx| {
+                    range
+                        .filter_map(|entry_ix| {
+                            let entry = *panel.visible_entries.get(entry_ix)?;
+                            Some(panel.render_entry(entry_ix, entry, window, cx))
+                        })
+                        .collect()
+                }),
+            )
+            .size_full()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .overflow_hidden()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::open_selected_entry))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .child(self.render_header(cx))
+            .child(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, VisualTestContext, WindowHandle};
+    use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher};
+    use lsp::FakeLanguageServer;
+    use pretty_assertions::assert_eq;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::OpenOptions;
+
+    use super::*;
+
+    const SELECTED_MARKER: &str = "  <==== selected";
+
+    #[gpui::test]
+    async fn test_call_hierarchy_tree(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (workspace, editor, fake_server) = open_file_with_fake_server(
+            "fn main() {\n    run();\n}\n\nfn run() {\n    helper();\n    helper();\n}\n\nfn helper() {}\n",
+            lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            cx,
+        )
+        .await;
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = hierarchy_panel(&workspace, cx);
+
+        let uri = lsp::Uri::from_file_path(path!("/project/main.rs")).unwrap();
+        let item = |name: &str, line: u32| lsp::CallHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: uri.clone(),
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line + 2, 1)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(line, 3),
+                lsp::Position::new(line, 3 + name.len() as u32),
+            ),
+            data: None,
+        };
+        let call_site = |line: u32, name: &str| {
+            lsp::Range::new(
+                lsp::Position::new(line, 4),
+                lsp::Position::new(line, 4 + name.len() as u32),
+            )
+        };
+
+        let (main, run, helper) = (item("main", 0), item("run", 4), item("helper", 9));
+        fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+            let (run, helper) = (run.clone(), helper.clone());
+            move |params, _| {
+                let item =
+                    if params.text_document_position_params.position.line == run.range.start.line {
+                        run.clone()
+                    } else {
+                        helper.clone()
+                    };
+                async move { Ok(Some(vec![item])) }
+            }
+        });
+        fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+            let (main, run) = (main.clone(), run.clone());
+            move |params, _| {
+                let calls = match params.item.name.as_str() {
+                    "helper" => vec![lsp::CallHierarchyIncomingCall {
+                        from: run.clone(),
+                        from_ranges: vec![call_site(5, "helper"), call_site(6, "helper")],
+                    }],
+                    "run" => vec![lsp::CallHierarchyIncomingCall {
+                        from: main.clone(),
+                        from_ranges: vec![call_site(1, "run")],
+                    }],
+                    _ => Vec::new(),
+                };
+                async move { Ok(Some(calls)) }
+            }
+        });
+        fake_server.set_request_handler::<lsp::request::CallHierarchyOutgoingCalls, _, _>({
+            let helper = helper.clone();
+            move |params, _| {
+                let calls = match params.item.name.as_str() {
+                    "run" => vec![lsp::CallHierarchyOutgoingCall {
+                        to: helper.clone(),
+                        from_ranges: vec![call_site(5, "helper"), call_site(6, "helper")],
+                    }],
+                    _ => Vec::new(),
+                };
+                async move { Ok(Some(calls)) }
+            }
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.show_call_hierarchy(&editor, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("helper{SELECTED_MARKER}\n  run\n"),
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_next(&SelectNext, window, cx);
+            panel.expand_selected_entry(&ExpandSelectedEntry, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(
+                display_entries(panel, cx),
+                format!(
+                    "helper\n  run{SELECTED_MARKER}\n    6: helper();\n    7: helper();\n    main\n"
+                ),
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.collapse_selected_entry(&CollapseSelectedEntry, window, cx);
+        });
+        panel.update(cx, |panel, cx| {
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("helper\n  run{SELECTED_MARKER}\n"),
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.toggle_direction(&ToggleDirection, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.mode(), HierarchyMode::OutgoingCalls);
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("helper{SELECTED_MARKER}\n")
+            );
+        });
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(Default::default(), window, cx, |selections| {
+                selections.select_ranges([Point::new(4, 4)..Point::new(4, 4)])
+            });
+        });
+        panel.update_in(cx, |panel, window, cx| {
+            panel.show_call_hierarchy(&editor, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.mode(), HierarchyMode::OutgoingCalls);
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("run{SELECTED_MARKER}\n  helper\n"),
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_next(&SelectNext, window, cx);
+            panel.expand_selected_entry(&ExpandSelectedEntry, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update_in(cx, |panel, window, cx| {
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("run\n  helper{SELECTED_MARKER}\n    6: helper();\n    7: helper();\n"),
+            );
+            panel.open_selected_entry(&OpenSelectedEntry, window, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.selections.newest::<Point>(cx).range(),
+                Point::new(5, 4)..Point::new(5, 10),
+                "the callee should open at its first call site in the caller"
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_last(&SelectLast, window, cx);
+            panel.open_selected_entry(&OpenSelectedEntry, window, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.selections.newest::<Point>(cx).range(),
+                Point::new(6, 4)..Point::new(6, 10),
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.collapse_selected_entry(&CollapseSelectedEntry, window, cx);
+        });
+        panel.update(cx, |panel, cx| {
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("run\n  helper{SELECTED_MARKER}\n"),
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_type_hierarchy_tree(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (workspace, editor, fake_server) = open_file_with_fake_server(
+            "trait Shape {}\n\nstruct Square;\n\nimpl Shape for Square {}\n",
+            lsp::ServerCapabilities {
+                type_hierarchy_provider: Some(lsp::TypeHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            cx,
+        )
+        .await;
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = hierarchy_panel(&workspace, cx);
+
+        let uri = lsp::Uri::from_file_path(path!("/project/main.rs")).unwrap();
+        let item = |name: &str, line: u32| lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::STRUCT,
+            tags: None,
+            detail: None,
+            uri: uri.clone(),
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 14)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(line, 7),
+                lsp::Position::new(line, 7 + name.len() as u32),
+            ),
+            data: None,
+        };
+        let (shape, square) = (item("Shape", 0), item("Square", 2));
+        fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>({
+            let square = square.clone();
+            move |_, _| {
+                let square = square.clone();
+                async move { Ok(Some(vec![square])) }
+            }
+        });
+        fake_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>({
+            let shape = shape.clone();
+            move |params, _| {
+                let supertypes = if params.item.name == "Square" {
+                    vec![shape.clone()]
+                } else {
+                    Vec::new()
+                };
+                async move { Ok(Some(supertypes)) }
+            }
+        });
+        fake_server.set_request_handler::<lsp::request::TypeHierarchySubtypes, _, _>({
+            let square = square.clone();
+            move |params, _| {
+                let subtypes = if params.item.name == "Shape" {
+                    vec![square.clone()]
+                } else {
+                    Vec::new()
+                };
+                async move { Ok(Some(subtypes)) }
+            }
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.show_type_hierarchy(&editor, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.mode(), HierarchyMode::Supertypes);
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("Square{SELECTED_MARKER}\n  Shape\n"),
+            );
+        });
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.toggle_direction(&ToggleDirection, window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, cx| {
+            assert_eq!(panel.mode(), HierarchyMode::Subtypes);
+            assert_eq!(
+                display_entries(panel, cx),
+                format!("Square{SELECTED_MARKER}\n")
+            );
+        });
+    }
+
+    async fn open_file_with_fake_server(
+        text: &str,
+        capabilities: lsp::ServerCapabilities,
+        cx: &mut TestAppContext,
+    ) -> (WindowHandle<Workspace>, Entity<Editor>, FakeLanguageServer) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "main.rs": text }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..LanguageMatcher::default()
+                },
+                ..LanguageConfig::default()
+            },
+            None,
+        )));
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities,
+                ..FakeLspAdapter::default()
+            },
+        );
+
+        let workspace = add_hierarchy_panel(&project, cx).await;
+        let editor = workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.open_abs_path(
+                    PathBuf::from(path!("/project/main.rs")),
+                    OpenOptions::default(),
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        let fake_server = fake_servers.next().await.unwrap();
+        (workspace, editor, fake_server)
+    }
+
+    fn display_entries(panel: &HierarchyPanel, cx: &App) -> String {
+        let mut display_string = String::new();
+        for (entry_ix, &entry) in panel.visible_entries.iter().enumerate() {
+            match entry {
+                VisibleEntry::Node(node_ix) => {
+                    let node = &panel.nodes[node_ix];
+                    display_string.push_str(&"  ".repeat(node.depth));
+                    display_string.push_str(node.item.name());
+                }
+                VisibleEntry::CallSite {
+                    node_ix,
+                    call_site_ix,
+                } => {
+                    let node = &panel.nodes[node_ix];
+                    display_string.push_str(&"  ".repeat(node.depth + 1));
+                    display_string.push_str(&call_site_label(&node.call_sites[call_site_ix], cx));
+                }
+            }
+            if panel.selected_entry == Some(entry_ix) {
+                display_string.push_str(SELECTED_MARKER);
+            }
+            display_string.push('\n');
+        }
+        display_string
+    }
+
+    async fn add_hierarchy_panel(
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> WindowHandle<Workspace> {
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let panel = window
+            .update(cx, |_, window, cx| {
+                cx.spawn_in(window, async |this, cx| {
+                    HierarchyPanel::load(this, cx.clone()).await
+                })
+            })
+            .unwrap()
+            .await
+            .expect("Failed to load hierarchy panel");
+        window
+            .update(cx, |workspace, window, cx| {
+                workspace.add_panel(panel, window, cx);
+            })
+            .unwrap();
+        window
+    }
+
+    fn hierarchy_panel(
+        workspace: &WindowHandle<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<HierarchyPanel> {
+        workspace
+            .update(cx, |workspace, _, cx| {
+                workspace
+                    .panel::<HierarchyPanel>(cx)
+                    .expect("no hierarchy panel")
+            })
+            .unwrap()
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+
+            theme::init(theme::LoadThemes::JustBase, cx);
+
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            super::init(cx);
+        });
+    }
+}
@@ -59,7 +59,7 @@ pub use text::{
     Anchor, Bias, Buffer as TextBuffer, BufferId, BufferSnapshot as TextBufferSnapshot, Edit,
     LineIndent, OffsetRangeExt, OffsetUtf16, Patch, Point, PointUtf16, Rope, Selection,
     SelectionGoal, Subscription, TextDimension, TextSummary, ToOffset, ToOffsetUtf16, ToPoint,
-    ToPointUtf16, Transaction, TransactionId, Unclipped,
+    ToPointUtf16, Transaction, TransactionId, Unclipped, UndoTree, UndoTreeNode,
 };
 use theme::{ActiveTheme as _, SyntaxTheme};
 #[cfg(any(test, feature = "test-support"))]
@@ -2824,6 +2824,27 @@ impl Buffer {
         redone
     }
 
+    /// Undoes and redoes transactions until the buffer is in the given state of its undo tree,
+    /// switching branches if necessary. `None` is the buffer's original state.
+    pub fn travel_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let operations = self.text.travel_to_undo_state(target);
+        let traveled = !operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), true, cx);
+        }
+        if traveled {
+            self.did_edit(&old_version, was_dirty, cx)
+        }
+        traveled
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(
         &mut self,
@@ -526,6 +526,35 @@ async fn test_apply_diff(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_undo_tree_survives_reload(cx: &mut TestAppContext) {
+    let buffer = cx.new(|cx| Buffer::local("one two three", cx));
+    let undone_transaction = buffer.update(cx, |buffer, cx| {
+        buffer.edit([(4..7, "TWO")], None, cx);
+        let transaction_id = buffer.undo(cx).unwrap();
+        assert_eq!(buffer.text(), "one two three");
+        transaction_id
+    });
+
+    // Reloading applies the new contents as another transaction, keeping the undone one as a
+    // separate branch of the undo tree.
+    let diff = buffer
+        .update(cx, |buffer, cx| buffer.diff("one two four".into(), cx))
+        .await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx).unwrap();
+        assert_eq!(buffer.text(), "one two four");
+
+        assert!(buffer.travel_to_undo_state(Some(undone_transaction), cx));
+        assert_eq!(buffer.text(), "one TWO three");
+        let earlier = buffer.undo_tree().earlier(1);
+        assert!(buffer.travel_to_undo_state(earlier, cx));
+        assert_eq!(buffer.text(), "one two three");
+        assert!(buffer.redo(cx).is_some());
+        assert_eq!(buffer.text(), "one two four");
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_normalize_whitespace(cx: &mut gpui::TestAppContext) {
     let text = [
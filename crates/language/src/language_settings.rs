@@ -144,6 +144,8 @@ pub struct LanguageSettings {
     pub always_treat_brackets_as_autoclosed: bool,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to highlight the code with semantic tokens from the language server.
+    pub semantic_tokens: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskSettings,
     /// Whether to pop the completions menu while typing in an editor without
@@ -577,6 +579,7 @@ impl settings::Settings for AllLanguageSettings {
                     .always_treat_brackets_as_autoclosed
                     .unwrap(),
                 linked_edits: settings.linked_edits.unwrap(),
+                semantic_tokens: settings.semantic_tokens.unwrap(),
                 tasks: LanguageTaskSettings {
                     variables: tasks.variables.unwrap_or_default(),
                     enabled: tasks.enabled.unwrap(),
@@ -746,6 +749,7 @@ impl settings::Settings for AllLanguageSettings {
         );
         vscode.bool_setting("editor.formatOnType", &mut d.use_on_type_format);
         vscode.bool_setting("editor.linkedEditing", &mut d.linked_edits);
+        vscode.bool_setting("editor.semanticHighlighting.enabled", &mut d.semantic_tokens);
         vscode.bool_setting("editor.formatOnPaste", &mut d.auto_indent_on_paste);
         vscode.bool_setting(
             "editor.suggestOnTriggerCharacters",
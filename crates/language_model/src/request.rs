@@ -399,9 +399,21 @@ pub struct LanguageModelRequest {
     pub tool_choice: Option<LanguageModelToolChoice>,
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
+    /// The maximum number of tokens to generate, below the model's own limit.
+    pub max_output_tokens: Option<u64>,
     pub thinking_allowed: bool,
 }
 
+impl LanguageModelRequest {
+    /// Returns the lowest of the request's and the model's limits on the tokens to generate.
+    pub fn output_token_limit(&self, model_limit: Option<u64>) -> Option<u64> {
+        match (self.max_output_tokens, model_limit) {
+            (Some(limit), Some(model_limit)) => Some(limit.min(model_limit)),
+            (limit, model_limit) => limit.or(model_limit),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct LanguageModelResponseMessage {
     pub role: Option<Role>,
@@ -441,6 +441,9 @@ pub fn into_anthropic(
     max_output_tokens: u64,
     mode: AnthropicModelMode,
 ) -> anthropic::Request {
+    let max_output_tokens = request
+        .max_output_tokens
+        .map_or(max_output_tokens, |limit| limit.min(max_output_tokens));
     let mut new_messages: Vec<anthropic::Message> = Vec::new();
     let mut system_message = String::new();
 
@@ -1011,6 +1014,7 @@ mod tests {
             temperature: None,
             tools: vec![],
             tool_choice: None,
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
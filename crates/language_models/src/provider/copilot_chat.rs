@@ -624,6 +624,7 @@ fn into_copilot_chat(
         n: 1,
         stream: model.uses_streaming(),
         temperature: 0.1,
+        max_tokens: request.max_output_tokens,
         model: model.id().to_string(),
         messages,
         tools,
@@ -524,7 +524,7 @@ pub fn into_google(
         generation_config: Some(google_ai::GenerationConfig {
             candidate_count: Some(1),
             stop_sequences: Some(request.stop),
-            max_output_tokens: None,
+            max_output_tokens: request.max_output_tokens.map(|limit| limit as usize),
             temperature: request.temperature.map(|t| t as f64).or(Some(1.0)),
             thinking_config: match (request.thinking_allowed, mode) {
                 (true, GoogleModelMode::Thinking { budget_tokens }) => {
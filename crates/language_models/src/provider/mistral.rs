@@ -399,6 +399,7 @@ pub fn into_mistral(
     model: mistral::Model,
     max_output_tokens: Option<u64>,
 ) -> mistral::Request {
+    let max_output_tokens = request.output_token_limit(max_output_tokens);
     let stream = true;
 
     let mut messages = Vec::new();
@@ -1080,6 +1081,7 @@ mod tests {
             intent: None,
             mode: None,
             stop: vec![],
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
@@ -1113,6 +1115,7 @@ mod tests {
             intent: None,
             mode: None,
             stop: vec![],
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
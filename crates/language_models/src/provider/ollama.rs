@@ -412,6 +412,9 @@ impl OllamaLanguageModel {
             stream: true,
             options: Some(ChatOptions {
                 num_ctx: Some(self.model.max_tokens),
+                num_predict: request
+                    .max_output_tokens
+                    .map(|limit| limit.try_into().unwrap_or(isize::MAX)),
                 stop: Some(request.stop),
                 temperature: request.temperature.or(Some(1.0)),
                 ..Default::default()
@@ -349,6 +349,7 @@ pub fn into_open_ai(
     max_output_tokens: Option<u64>,
     reasoning_effort: Option<ReasoningEffort>,
 ) -> open_ai::Request {
+    let max_output_tokens = request.output_token_limit(max_output_tokens);
     let stream = !model_id.starts_with("o1-");
 
     let mut messages = Vec::new();
@@ -897,6 +898,7 @@ mod tests {
             tool_choice: None,
             stop: vec![],
             temperature: None,
+            max_output_tokens: None,
             thinking_allowed: true,
         };
 
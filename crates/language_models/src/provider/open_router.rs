@@ -390,6 +390,7 @@ pub fn into_open_router(
     model: &Model,
     max_output_tokens: Option<u64>,
 ) -> open_router::Request {
+    let max_output_tokens = request.output_token_limit(max_output_tokens);
     let mut messages = Vec::new();
     for message in request.messages {
         for content in message.content {
@@ -1,17 +1,20 @@
 pub mod extension;
 pub mod registry;
+mod requests;
 
 use std::sync::Arc;
 
 use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::{ContextServer, ContextServerCommand, ContextServerEndpoint, ContextServerId};
 use futures::{FutureExt as _, future::join_all};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use registry::ContextServerDescriptorRegistry;
 use settings::{Settings as _, SettingsStore};
 use util::{ResultExt as _, rel_path::RelPath};
 
+pub use requests::{ContextServerRequest, ContextServerRequestId, ContextServerRequestKind};
+
 use crate::{
     Project,
     project_settings::{ContextServerSettings, ProjectSettings},
@@ -99,13 +102,18 @@ pub enum ContextServerConfiguration {
         command: ContextServerCommand,
         settings: serde_json::Value,
     },
+    Http {
+        endpoint: ContextServerEndpoint,
+    },
 }
 
 impl ContextServerConfiguration {
-    pub fn command(&self) -> &ContextServerCommand {
+    /// The command starting the server, `None` for remote servers.
+    pub fn command(&self) -> Option<&ContextServerCommand> {
         match self {
-            ContextServerConfiguration::Custom { command } => command,
-            ContextServerConfiguration::Extension { command, .. } => command,
+            ContextServerConfiguration::Custom { command } => Some(command),
+            ContextServerConfiguration::Extension { command, .. } => Some(command),
+            ContextServerConfiguration::Http { .. } => None,
         }
     }
 
@@ -121,6 +129,10 @@ impl ContextServerConfiguration {
                 enabled: _,
                 command,
             } => Some(ContextServerConfiguration::Custom { command }),
+            ContextServerSettings::Http {
+                enabled: _,
+                endpoint,
+            } => Some(ContextServerConfiguration::Http { endpoint }),
             ContextServerSettings::Extension {
                 enabled: _,
                 settings,
@@ -150,6 +162,8 @@ pub struct ContextServerStore {
     update_servers_task: Option<Task<Result<()>>>,
     context_server_factory: Option<ContextServerFactory>,
     needs_server_update: bool,
+    pending_requests: Vec<requests::PendingContextServerRequest>,
+    next_request_id: usize,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -252,6 +266,8 @@ impl ContextServerStore {
             servers: HashMap::default(),
             update_servers_task: None,
             context_server_factory,
+            pending_requests: Vec::new(),
+            next_request_id: 0,
         };
         if maintain_server_loop {
             this.available_context_servers_changed(cx);
@@ -364,6 +380,7 @@ impl ContextServerStore {
             result = server.stop();
         }
         drop(state);
+        self.remove_pending_requests(id, cx);
 
         self.update_server_state(
             id.clone(),
@@ -402,12 +419,17 @@ impl ContextServerStore {
             self.stop_server(&id, cx).log_err();
         }
 
+        let request_handlers = Self::request_handlers(id.clone(), cx);
         let task = cx.spawn({
             let id = server.id();
             let server = server.clone();
             let configuration = configuration.clone();
             async move |this, cx| {
-                match server.clone().start(cx).await {
+                match server
+                    .clone()
+                    .start_with_handlers(Vec::new(), request_handlers, cx)
+                    .await
+                {
                     Ok(_) => {
                         debug_assert!(server.client().is_some());
 
@@ -459,6 +481,7 @@ impl ContextServerStore {
             .remove(id)
             .context("Context server not found")?;
         drop(state);
+        self.remove_pending_requests(id, cx);
         cx.emit(Event::ServerStatusChanged {
             server_id: id.clone(),
             status: ContextServerStatus::Stopped,
@@ -491,13 +514,16 @@ impl ContextServerStore {
         };
 
         if let Some(factory) = self.context_server_factory.as_ref() {
-            factory(id, configuration)
-        } else {
-            Arc::new(ContextServer::stdio(
-                id,
-                configuration.command().clone(),
-                root_path,
-            ))
+            return factory(id, configuration);
+        }
+        match configuration.as_ref() {
+            ContextServerConfiguration::Custom { command }
+            | ContextServerConfiguration::Extension { command, .. } => {
+                Arc::new(ContextServer::stdio(id, command.clone(), root_path))
+            }
+            ContextServerConfiguration::Http { endpoint } => {
+                Arc::new(ContextServer::http(id, endpoint.clone()))
+            }
         }
     }
 
@@ -644,7 +670,13 @@ mod tests {
         FakeFs, Project, context_server_store::registry::ContextServerDescriptor,
         project_settings::ProjectSettings,
     };
-    use context_server::test::create_fake_transport;
+    use context_server::{
+        test::create_fake_transport,
+        types::{
+            ElicitAction, ElicitResult,
+            requests::{CreateMessage, Elicit},
+        },
+    };
     use gpui::{AppContext, TestAppContext, UpdateGlobal as _};
     use serde_json::json;
     use std::{cell::RefCell, path::PathBuf, rc::Rc};
@@ -853,6 +885,105 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_context_server_requests(cx: &mut TestAppContext) {
+        const SERVER_1_ID: &str = "mcp-1";
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_1_ID.into(), dummy_server_settings())],
+        )
+        .await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let server_id = ContextServerId(SERVER_1_ID.into());
+        let transport = Arc::new(create_fake_transport(SERVER_1_ID, cx.executor()));
+        let server = Arc::new(ContextServer::new(server_id.clone(), transport.clone()));
+        store.update(cx, |store, cx| store.start_server(server, cx));
+        cx.run_until_parked();
+
+        let elicitation = cx.background_spawn(
+            transport.request_client::<Elicit>(
+                serde_json::from_value(json!({
+                    "message": "Who are you?",
+                    "requestedSchema": {
+                        "type": "object",
+                        "properties": {"name": {"type": "string", "title": "Name"}},
+                        "required": ["name"],
+                    },
+                }))
+                .unwrap(),
+            ),
+        );
+        cx.run_until_parked();
+
+        let request_id = store.read_with(cx, |store, _| {
+            let requests = store.pending_requests().collect::<Vec<_>>();
+            assert_eq!(requests.len(), 1);
+            assert_eq!(requests[0].server_id, server_id);
+            let ContextServerRequestKind::Elicitation(params) = requests[0].kind.as_ref() else {
+                panic!("expected an elicitation request");
+            };
+            assert_eq!(params.message, "Who are you?");
+            assert_eq!(
+                params.requested_schema.properties["name"].title(),
+                Some("Name")
+            );
+            requests[0].id
+        });
+        store.update(cx, |store, cx| {
+            store.respond_to_elicitation(
+                request_id,
+                ElicitResult {
+                    action: ElicitAction::Accept,
+                    content: Some(json!({"name": "Zed"}).as_object().unwrap().clone()),
+                },
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            elicitation.await.unwrap()["result"],
+            json!({"action": "accept", "content": {"name": "Zed"}})
+        );
+        store.read_with(cx, |store, _| {
+            assert_eq!(store.pending_requests().count(), 0)
+        });
+
+        // Stopping the server drops its pending requests.
+        let sampling = cx.background_spawn(
+            transport.request_client::<CreateMessage>(
+                serde_json::from_value(json!({
+                    "messages": [{"role": "user", "content": {"type": "text", "text": "Hi"}}],
+                    "maxTokens": 100,
+                }))
+                .unwrap(),
+            ),
+        );
+        cx.run_until_parked();
+        store.read_with(cx, |store, _| {
+            assert_eq!(store.pending_requests().count(), 1)
+        });
+        store
+            .update(cx, |store, cx| store.stop_server(&server_id, cx))
+            .unwrap();
+        cx.run_until_parked();
+        store.read_with(cx, |store, _| {
+            assert_eq!(store.pending_requests().count(), 0)
+        });
+        drop(sampling);
+    }
+
     #[gpui::test]
     async fn test_context_server_maintain_servers_loop(cx: &mut TestAppContext) {
         const SERVER_1_ID: &str = "mcp-1";
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use context_server::{ContextServerId, RequestHandler, types};
+use futures::channel::oneshot;
+use gpui::{Context, WeakEntity};
+use util::post_inc;
+
+use crate::context_server_store::ContextServerStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContextServerRequestId(usize);
+
+/// A request a context server sent to Zed, which waits for the user to answer it.
+pub enum ContextServerRequestKind {
+    /// The server asks for a completion of the language model.
+    Sampling(types::CreateMessageRequest),
+    /// The server asks the user to fill in a form.
+    Elicitation(types::ElicitParams),
+}
+
+#[derive(Clone)]
+pub struct ContextServerRequest {
+    pub id: ContextServerRequestId,
+    pub server_id: ContextServerId,
+    pub kind: Arc<ContextServerRequestKind>,
+}
+
+pub(super) struct PendingContextServerRequest {
+    request: ContextServerRequest,
+    response_tx: oneshot::Sender<Result<serde_json::Value>>,
+}
+
+impl ContextServerStore {
+    /// The requests of context servers waiting for the user, oldest first.
+    pub fn pending_requests(&self) -> impl Iterator<Item = &ContextServerRequest> {
+        self.pending_requests.iter().map(|pending| &pending.request)
+    }
+
+    /// Answers a sampling request with the completion of the language model,
+    /// or the reason it was not obtained.
+    pub fn respond_to_sampling(
+        &mut self,
+        id: ContextServerRequestId,
+        result: Result<types::CreateMessageResult>,
+        cx: &mut Context<Self>,
+    ) {
+        let response = result.and_then(|result| Ok(serde_json::to_value(result)?));
+        self.respond(id, response, cx);
+    }
+
+    pub fn respond_to_elicitation(
+        &mut self,
+        id: ContextServerRequestId,
+        result: types::ElicitResult,
+        cx: &mut Context<Self>,
+    ) {
+        let response = serde_json::to_value(result).map_err(anyhow::Error::from);
+        self.respond(id, response, cx);
+    }
+
+    fn respond(
+        &mut self,
+        id: ContextServerRequestId,
+        response: Result<serde_json::Value>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self
+            .pending_requests
+            .iter()
+            .position(|pending| pending.request.id == id)
+        else {
+            return;
+        };
+        let pending = self.pending_requests.remove(ix);
+        pending.response_tx.send(response).ok();
+        cx.notify();
+    }
+
+    /// Drops the requests of a server which stopped, as their answers cannot be delivered anymore.
+    pub(super) fn remove_pending_requests(
+        &mut self,
+        server_id: &ContextServerId,
+        cx: &mut Context<Self>,
+    ) {
+        let len = self.pending_requests.len();
+        self.pending_requests
+            .retain(|pending| &pending.request.server_id != server_id);
+        if self.pending_requests.len() != len {
+            cx.notify();
+        }
+    }
+
+    fn push_request(
+        &mut self,
+        server_id: ContextServerId,
+        kind: ContextServerRequestKind,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<serde_json::Value>> {
+        let (response_tx, response_rx) = oneshot::channel();
+        let id = ContextServerRequestId(post_inc(&mut self.next_request_id));
+        self.pending_requests.push(PendingContextServerRequest {
+            request: ContextServerRequest {
+                id,
+                server_id,
+                kind: Arc::new(kind),
+            },
+            response_tx,
+        });
+        cx.notify();
+        response_rx
+    }
+
+    /// The handlers queueing the requests of the server, to be answered by the user.
+    pub(super) fn request_handlers(
+        server_id: ContextServerId,
+        cx: &mut Context<Self>,
+    ) -> Vec<(&'static str, RequestHandler)> {
+        let this = cx.weak_entity();
+        vec![
+            request_handler::<types::requests::CreateMessage>(
+                server_id.clone(),
+                this.clone(),
+                ContextServerRequestKind::Sampling,
+            ),
+            request_handler::<types::requests::Elicit>(
+                server_id,
+                this,
+                ContextServerRequestKind::Elicitation,
+            ),
+        ]
+    }
+}
+
+fn request_handler<R: types::Request>(
+    server_id: ContextServerId,
+    this: WeakEntity<ContextServerStore>,
+    kind: fn(R::Params) -> ContextServerRequestKind,
+) -> (&'static str, RequestHandler) {
+    (
+        R::METHOD,
+        Box::new(move |params, mut cx| {
+            let response_rx = serde_json::from_value::<R::Params>(params)
+                .with_context(|| format!("invalid {} request", R::METHOD))
+                .and_then(|params| {
+                    this.update(&mut cx, |this, cx| {
+                        this.push_request(server_id.clone(), kind(params), cx)
+                    })
+                });
+            cx.background_spawn(async move {
+                response_rx?
+                    .await
+                    .context("the request was dismissed before being answered")?
+            })
+        }),
+    )
+}
@@ -266,6 +266,8 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
         };
 
         let scenario = locator
@@ -293,6 +295,8 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
         };
 
         let scenario = locator
@@ -431,6 +435,8 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
         };
 
         let scenario = locator
@@ -1,5 +1,5 @@
 use crate::debugger::breakpoint_store::BreakpointSessionState;
-use crate::debugger::dap_command::{DataBreakpointContext, ReadMemory};
+use crate::debugger::dap_command::{DataBreakpointContext, DisassembleCommand, ReadMemory};
 use crate::debugger::memory::{self, Memory, MemoryIterator, MemoryPageBuilder, PageAddress};
 
 use super::breakpoint_store::{
@@ -9,9 +9,10 @@ use super::dap_command::{
     self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
     EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
     ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints,
+    SetInstructionBreakpointsCommand, SetVariableValueCommand, StackTraceCommand, StepBackCommand,
+    StepCommand, StepInCommand, StepOutCommand, TerminateCommand, TerminateThreadsCommand,
+    ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use anyhow::{Context as _, Result, anyhow};
@@ -699,6 +700,7 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, dap::InstructionBreakpoint>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: TaskContext,
@@ -876,6 +878,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -2019,6 +2022,46 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn has_instruction_breakpoint(&self, instruction_reference: &str) -> bool {
+        self.instruction_breakpoints
+            .contains_key(instruction_reference)
+    }
+
+    /// Sets or removes a breakpoint on the instruction at the given memory reference.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                dap::InstructionBreakpoint {
+                    instruction_reference,
+                    offset: None,
+                    condition: None,
+                    hit_condition: None,
+                    mode: None,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+        cx.notify();
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let command = SetInstructionBreakpointsCommand {
+                breakpoints: self.instruction_breakpoints.values().cloned().collect(),
+            };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2591,6 +2634,24 @@ impl Session {
         self.request(command, |_, response, _| response.ok(), cx)
     }
 
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions
+    /// away from the given memory reference.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Arc<[dap::DisassembledInstruction]>>> {
+        let command = DisassembleCommand {
+            memory_reference,
+            instruction_offset,
+            instruction_count,
+        };
+
+        self.request(command, |_, response, _| response.log_err(), cx)
+    }
+
     pub fn set_variable_value(
         &mut self,
         stack_frame_id: u64,
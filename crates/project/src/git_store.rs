@@ -23,10 +23,12 @@ use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid,
     blame::Blame,
     parse_git_remote_url,
+    rebase::{RebaseAction, RebaseCommand, RebaseProgress, RebaseTodoEntry},
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus,
+        BisectMark, BisectStatus, Branch, CommitDetails, CommitDiff, CommitFile, CommitLogEntry,
+        CommitOptions, DiffType, FetchOptions, GitRepository, GitRepositoryCheckpoint, GitTag,
+        GitWorktree, LogLineRange, LogOptions, PushOptions, Remote, RemoteCommandOutput, RepoPath,
+        ResetMode, SequencerOperation, SequencerOutcome, StoppedSequencer, UpstreamTrackingStatus,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -45,7 +47,10 @@ use parking_lot::Mutex;
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{
+        self, git_bisect_mark, git_rebase, git_rebase_todo_entry, git_reset, git_resume_sequencer,
+        split_repository_update,
+    },
 };
 use serde::Deserialize;
 use std::{
@@ -422,6 +427,26 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
+        client.add_entity_request_handler(Self::handle_git_log);
+        client.add_entity_request_handler(Self::handle_git_rebase_todo);
+        client.add_entity_request_handler(Self::handle_git_start_rebase);
+        client.add_entity_request_handler(Self::handle_git_rebase);
+        client.add_entity_request_handler(Self::handle_git_get_rebase_progress);
+        client.add_entity_request_handler(Self::handle_git_list_worktrees);
+        client.add_entity_request_handler(Self::handle_git_add_worktree);
+        client.add_entity_request_handler(Self::handle_git_remove_worktree);
+        client.add_entity_request_handler(Self::handle_git_prune_worktrees);
+        client.add_entity_request_handler(Self::handle_git_cherry_pick);
+        client.add_entity_request_handler(Self::handle_git_revert);
+        client.add_entity_request_handler(Self::handle_git_resume_sequencer);
+        client.add_entity_request_handler(Self::handle_git_get_stopped_sequencer);
+        client.add_entity_request_handler(Self::handle_git_list_tags);
+        client.add_entity_request_handler(Self::handle_git_create_tag);
+        client.add_entity_request_handler(Self::handle_git_push_tags);
+        client.add_entity_request_handler(Self::handle_git_bisect_start);
+        client.add_entity_request_handler(Self::handle_git_bisect_mark);
+        client.add_entity_request_handler(Self::handle_git_bisect_reset);
+        client.add_entity_request_handler(Self::handle_git_get_bisect_status);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
@@ -2016,6 +2041,394 @@ impl GitStore {
         })
     }
 
+    async fn handle_git_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = log_options_from_proto(envelope.payload)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(options)
+            })?
+            .await??;
+        Ok(proto::GitLogResponse {
+            entries: entries.iter().map(log_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_git_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.onto)
+            })?
+            .await??;
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_git_start_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStartRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseProgressResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let todo = envelope
+            .payload
+            .todo
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+
+        let progress = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.start_rebase(envelope.payload.onto, todo)
+            })?
+            .await??;
+        Ok(proto::GitRebaseProgressResponse {
+            progress: progress.as_ref().map(rebase_progress_to_proto),
+        })
+    }
+
+    async fn handle_git_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseProgressResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let command = match envelope.payload.command() {
+            git_rebase::Command::Continue => RebaseCommand::Continue,
+            git_rebase::Command::Skip => RebaseCommand::Skip,
+            git_rebase::Command::Abort => RebaseCommand::Abort,
+        };
+
+        let progress = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase(command)
+            })?
+            .await??;
+        Ok(proto::GitRebaseProgressResponse {
+            progress: progress.as_ref().map(rebase_progress_to_proto),
+        })
+    }
+
+    async fn handle_git_get_rebase_progress(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetRebaseProgress>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseProgressResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let progress = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_progress()
+            })?
+            .await??;
+        Ok(proto::GitRebaseProgressResponse {
+            progress: progress.as_ref().map(rebase_progress_to_proto),
+        })
+    }
+
+    async fn handle_git_list_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitListWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitListWorktreesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let worktrees = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktrees()
+            })?
+            .await??;
+        Ok(proto::GitListWorktreesResponse {
+            worktrees: worktrees.iter().map(worktree_to_proto).collect(),
+        })
+    }
+
+    async fn handle_git_add_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitAddWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.add_worktree(
+                    PathBuf::from(envelope.payload.path),
+                    envelope.payload.branch,
+                    envelope.payload.create_branch_from,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_remove_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle
+                    .remove_worktree(PathBuf::from(envelope.payload.path), envelope.payload.force)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_prune_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPruneWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.prune_worktrees()
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSequencerResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(envelope.payload.commits)
+            })?
+            .await??;
+        Ok(sequencer_outcome_to_proto(outcome))
+    }
+
+    async fn handle_git_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSequencerResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(envelope.payload.commits)
+            })?
+            .await??;
+        Ok(sequencer_outcome_to_proto(outcome))
+    }
+
+    async fn handle_git_resume_sequencer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitResumeSequencer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSequencerResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let operation = proto_to_sequencer_operation(envelope.payload.operation());
+        let command = match envelope.payload.command() {
+            git_rebase::Command::Continue => RebaseCommand::Continue,
+            git_rebase::Command::Skip => RebaseCommand::Skip,
+            git_rebase::Command::Abort => RebaseCommand::Abort,
+        };
+        let outcome = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.resume_sequencer(operation, command)
+            })?
+            .await??;
+        Ok(sequencer_outcome_to_proto(outcome))
+    }
+
+    async fn handle_git_get_stopped_sequencer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetStoppedSequencer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitStoppedSequencerResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let stopped = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stopped_sequencer()
+            })?
+            .await??;
+        Ok(match stopped {
+            Some(stopped) => proto::GitStoppedSequencerResponse {
+                operation: Some(sequencer_operation_to_proto(stopped.operation).into()),
+                sha: stopped.sha.to_string(),
+            },
+            None => proto::GitStoppedSequencerResponse::default(),
+        })
+    }
+
+    async fn handle_git_list_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitListTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitListTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())?
+            .await??;
+        Ok(proto::GitListTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_git_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.target,
+                    envelope.payload.message,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_push_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPushTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.push_tags(
+                    envelope.payload.remote_name.into(),
+                    envelope.payload.tag_name.map(Into::into),
+                    askpass,
+                )
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_git_bisect_start(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectStart>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_start(envelope.payload.bad, envelope.payload.good)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_bisect_mark(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let mark = match envelope.payload.mark() {
+            git_bisect_mark::Mark::Good => BisectMark::Good,
+            git_bisect_mark::Mark::Bad => BisectMark::Bad,
+            git_bisect_mark::Mark::Skip => BisectMark::Skip,
+        };
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_mark(mark, envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_bisect_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_reset()
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_get_bisect_status(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetBisectStatus>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBisectStatusResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let status = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_status()
+            })?
+            .await??;
+        Ok(proto::GitBisectStatusResponse {
+            status: status.as_ref().map(bisect_status_to_proto),
+        })
+    }
+
     async fn handle_reset(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitReset>,
@@ -3585,8 +3998,593 @@ impl Repository {
         })
     }
 
-    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
-        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
+    /// Lists a page of the commits in the history of a revision, most recent first.
+    pub fn log(&mut self, options: LogOptions) -> oneshot::Receiver<Result<Vec<CommitLogEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.log(options).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(log_options_to_proto(project_id.0, id.to_proto(), options))
+                        .await?;
+                    Ok(response.entries.iter().map(proto_to_log_entry).collect())
+                }
+            }
+        })
+    }
+
+    /// Lists the commits an interactive rebase onto `onto` would apply, oldest first.
+    pub fn rebase_todo(
+        &mut self,
+        onto: Option<String>,
+    ) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.rebase_todo(onto).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            onto,
+                        })
+                        .await?;
+                    Ok(response
+                        .entries
+                        .iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect())
+                }
+            }
+        })
+    }
+
+    /// Starts an interactive rebase applying `todo` onto `onto`, or from the root commit, and
+    /// returns its progress if it stopped on conflicts.
+    pub fn start_rebase(
+        &mut self,
+        onto: Option<String>,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<Option<RebaseProgress>>> {
+        let id = self.id;
+        self.send_job(Some("git rebase".into()), move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.start_rebase(onto, todo, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitStartRebase {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            onto,
+                            todo: todo.iter().map(rebase_todo_entry_to_proto).collect(),
+                        })
+                        .await?;
+                    Ok(response.progress.as_ref().map(proto_to_rebase_progress))
+                }
+            }
+        })
+    }
+
+    /// Continues, skips the current commit of, or aborts the rebase in progress, and returns its
+    /// progress if it stopped again.
+    pub fn rebase(
+        &mut self,
+        command: RebaseCommand,
+    ) -> oneshot::Receiver<Result<Option<RebaseProgress>>> {
+        let id = self.id;
+        self.send_job(Some("git rebase".into()), move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.rebase(command, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitRebase {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            command: match command {
+                                RebaseCommand::Continue => git_rebase::Command::Continue.into(),
+                                RebaseCommand::Skip => git_rebase::Command::Skip.into(),
+                                RebaseCommand::Abort => git_rebase::Command::Abort.into(),
+                            },
+                        })
+                        .await?;
+                    Ok(response.progress.as_ref().map(proto_to_rebase_progress))
+                }
+            }
+        })
+    }
+
+    pub fn rebase_progress(&mut self) -> oneshot::Receiver<Result<Option<RebaseProgress>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.rebase_progress().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetRebaseProgress {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.progress.as_ref().map(proto_to_rebase_progress))
+                }
+            }
+        })
+    }
+
+    /// Lists the worktrees of the repository, the main one first.
+    pub fn worktrees(&mut self) -> oneshot::Receiver<Result<Vec<GitWorktree>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.worktrees().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitListWorktrees {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.worktrees.iter().map(proto_to_worktree).collect())
+                }
+            }
+        })
+    }
+
+    /// Creates a worktree at `path` checking out `branch`, first creating the branch from
+    /// `create_branch_from` if it is set.
+    pub fn add_worktree(
+        &mut self,
+        path: PathBuf,
+        branch: String,
+        create_branch_from: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git worktree add".into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .add_worktree(path, branch, create_branch_from, environment)
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitAddWorktree {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.to_string_lossy().into_owned(),
+                                branch,
+                                create_branch_from,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn remove_worktree(&mut self, path: PathBuf, force: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git worktree remove".into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.remove_worktree(path, force, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitRemoveWorktree {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.to_string_lossy().into_owned(),
+                                force,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn prune_worktrees(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git worktree prune".into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.prune_worktrees(environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitPruneWorktrees {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Applies the changes of `commits`, in order, as new commits on top of `HEAD`. When the
+    /// cherry-pick stops on conflicts, they are resolved like those of a merge, before resuming
+    /// it with [`Self::resume_sequencer`].
+    pub fn cherry_pick(
+        &mut self,
+        commits: Vec<String>,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        self.apply_commits(SequencerOperation::CherryPick, commits)
+    }
+
+    /// Commits the reverse of the changes of `commits`, in order, on top of `HEAD`.
+    pub fn revert(&mut self, commits: Vec<String>) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        self.apply_commits(SequencerOperation::Revert, commits)
+    }
+
+    fn apply_commits(
+        &mut self,
+        operation: SequencerOperation,
+        commits: Vec<String>,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git {}", operation.name()).into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => match operation {
+                        SequencerOperation::CherryPick => {
+                            backend.cherry_pick(commits, environment).await
+                        }
+                        SequencerOperation::Revert => backend.revert(commits, environment).await,
+                    },
+                    RepositoryState::Remote { project_id, client } => {
+                        let response = match operation {
+                            SequencerOperation::CherryPick => {
+                                client
+                                    .request(proto::GitCherryPick {
+                                        project_id: project_id.0,
+                                        repository_id: id.to_proto(),
+                                        commits,
+                                    })
+                                    .await?
+                            }
+                            SequencerOperation::Revert => {
+                                client
+                                    .request(proto::GitRevert {
+                                        project_id: project_id.0,
+                                        repository_id: id.to_proto(),
+                                        commits,
+                                    })
+                                    .await?
+                            }
+                        };
+                        Ok(proto_to_sequencer_outcome(&response))
+                    }
+                }
+            },
+        )
+    }
+
+    /// Continues, skips the commit it stopped at, or aborts a cherry-pick or revert that stopped.
+    pub fn resume_sequencer(
+        &mut self,
+        operation: SequencerOperation,
+        command: RebaseCommand,
+    ) -> oneshot::Receiver<Result<SequencerOutcome>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git {} {}", operation.name(), command.arg()).into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .resume_sequencer(operation, command, environment)
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        let response = client
+                            .request(proto::GitResumeSequencer {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                operation: sequencer_operation_to_proto(operation).into(),
+                                command: match command {
+                                    RebaseCommand::Continue => git_rebase::Command::Continue.into(),
+                                    RebaseCommand::Skip => git_rebase::Command::Skip.into(),
+                                    RebaseCommand::Abort => git_rebase::Command::Abort.into(),
+                                },
+                            })
+                            .await?;
+                        Ok(proto_to_sequencer_outcome(&response))
+                    }
+                }
+            },
+        )
+    }
+
+    /// The cherry-pick or revert that stopped, if any.
+    pub fn stopped_sequencer(&mut self) -> oneshot::Receiver<Result<Option<StoppedSequencer>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.stopped_sequencer().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetStoppedSequencer {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.operation.is_some().then(|| StoppedSequencer {
+                        operation: proto_to_sequencer_operation(response.operation()),
+                        sha: response.sha.into(),
+                    }))
+                }
+            }
+        })
+    }
+
+    /// Starts a bisect, marking `bad` and `good` right away when given.
+    pub fn bisect_start(
+        &mut self,
+        bad: Option<String>,
+        good: Vec<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git bisect start".into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.bisect_start(bad, good, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitBisectStart {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                bad,
+                                good,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Marks `commit`, or `HEAD` if none is given, and checks out the next commit to test.
+    pub fn bisect_mark(
+        &mut self,
+        mark: BisectMark,
+        commit: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git bisect {}", mark.name()).into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.bisect_mark(mark, commit, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        let mark = match mark {
+                            BisectMark::Good => git_bisect_mark::Mark::Good,
+                            BisectMark::Bad => git_bisect_mark::Mark::Bad,
+                            BisectMark::Skip => git_bisect_mark::Mark::Skip,
+                        };
+                        client
+                            .request(proto::GitBisectMark {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                mark: mark.into(),
+                                commit,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Ends the bisect in progress.
+    pub fn bisect_reset(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some("git bisect reset".into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.bisect_reset(environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitBisectReset {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// The bisect in progress, if any.
+    pub fn bisect_status(&mut self) -> oneshot::Receiver<Result<Option<BisectStatus>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.bisect_status().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetBisectStatus {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.status.as_ref().map(proto_to_bisect_status))
+                }
+            }
+        })
+    }
+
+    /// Lists the tags of the repository, the most recently created first.
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<GitTag>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.tags().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitListTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    /// Tags `target`, with an annotated tag if a message is given and a lightweight one otherwise.
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        target: String,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.create_tag(name, target, message, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                target,
+                                message,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Pushes a tag to a remote, or all of them if no tag is given.
+    pub fn push_tags(
+        &mut self,
+        remote: SharedString,
+        tag: Option<SharedString>,
+        askpass: AskPassDelegate,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        let refspec = tag
+            .as_ref()
+            .map_or("--tags".to_string(), |tag| format!("refs/tags/{tag}"));
+        self.send_job(
+            Some(format!("git push {remote} {refspec}").into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .push_tags(
+                                remote.to_string(),
+                                tag.map(|tag| tag.to_string()),
+                                askpass,
+                                environment.clone(),
+                                cx,
+                            )
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitPushTags {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                remote_name: remote.to_string(),
+                                tag_name: tag.map(|tag| tag.to_string()),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending push tags request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
+        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
     }
 
     pub fn stage_entries(
@@ -5088,6 +6086,216 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn log_options_to_proto(project_id: u64, repository_id: u64, options: LogOptions) -> proto::GitLog {
+    proto::GitLog {
+        project_id,
+        repository_id,
+        revision: options.revision,
+        paths: options.paths.iter().map(|path| path.to_proto()).collect(),
+        line_range: options.line_range.map(|line_range| proto::GitLogLineRange {
+            path: line_range.path.to_proto(),
+            start_row: line_range.rows.start,
+            end_row: line_range.rows.end,
+        }),
+        author: options.author,
+        message: options.message,
+        skip: options.skip as u64,
+        limit: options.limit as u64,
+    }
+}
+
+fn log_options_from_proto(proto: proto::GitLog) -> Result<LogOptions> {
+    Ok(LogOptions {
+        revision: proto.revision,
+        paths: proto
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_proto(path))
+            .collect::<Result<_>>()?,
+        line_range: proto
+            .line_range
+            .map(|line_range| {
+                anyhow::Ok(LogLineRange {
+                    path: RepoPath::from_proto(&line_range.path)?,
+                    rows: line_range.start_row..line_range.end_row,
+                })
+            })
+            .transpose()?,
+        author: proto.author,
+        message: proto.message,
+        skip: proto.skip as usize,
+        limit: proto.limit as usize,
+    })
+}
+
+fn log_entry_to_proto(entry: &CommitLogEntry) -> proto::GitLogEntry {
+    proto::GitLogEntry {
+        sha: entry.sha.to_string(),
+        parents: entry.parents.iter().map(ToString::to_string).collect(),
+        subject: entry.subject.to_string(),
+        commit_timestamp: entry.commit_timestamp,
+        author_name: entry.author_name.to_string(),
+        author_email: entry.author_email.to_string(),
+        refs: entry.refs.iter().map(ToString::to_string).collect(),
+    }
+}
+
+fn proto_to_log_entry(proto: &proto::GitLogEntry) -> CommitLogEntry {
+    CommitLogEntry {
+        sha: proto.sha.clone().into(),
+        parents: proto.parents.iter().cloned().map(Into::into).collect(),
+        subject: proto.subject.clone().into(),
+        commit_timestamp: proto.commit_timestamp,
+        author_name: proto.author_name.clone().into(),
+        author_email: proto.author_email.clone().into(),
+        refs: proto.refs.iter().cloned().map(Into::into).collect(),
+    }
+}
+
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::GitRebaseTodoEntry {
+    let action = match entry.action {
+        RebaseAction::Pick => git_rebase_todo_entry::RebaseAction::Pick,
+        RebaseAction::Reword => git_rebase_todo_entry::RebaseAction::Reword,
+        RebaseAction::Squash => git_rebase_todo_entry::RebaseAction::Squash,
+        RebaseAction::Fixup => git_rebase_todo_entry::RebaseAction::Fixup,
+        RebaseAction::Drop => git_rebase_todo_entry::RebaseAction::Drop,
+    };
+    proto::GitRebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        message: entry.message.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_rebase_todo_entry(proto: &proto::GitRebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match proto.action() {
+        git_rebase_todo_entry::RebaseAction::Pick => RebaseAction::Pick,
+        git_rebase_todo_entry::RebaseAction::Reword => RebaseAction::Reword,
+        git_rebase_todo_entry::RebaseAction::Squash => RebaseAction::Squash,
+        git_rebase_todo_entry::RebaseAction::Fixup => RebaseAction::Fixup,
+        git_rebase_todo_entry::RebaseAction::Drop => RebaseAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: proto.sha.clone().into(),
+        subject: proto.subject.clone().into(),
+        message: proto.message.clone().map(Into::into),
+    }
+}
+
+fn rebase_progress_to_proto(progress: &RebaseProgress) -> proto::GitRebaseProgress {
+    proto::GitRebaseProgress {
+        done: progress
+            .done
+            .iter()
+            .map(rebase_todo_entry_to_proto)
+            .collect(),
+        remaining: progress
+            .remaining
+            .iter()
+            .map(rebase_todo_entry_to_proto)
+            .collect(),
+        branch: progress.branch.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_rebase_progress(proto: &proto::GitRebaseProgress) -> RebaseProgress {
+    RebaseProgress {
+        done: proto.done.iter().map(proto_to_rebase_todo_entry).collect(),
+        remaining: proto
+            .remaining
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect(),
+        branch: proto.branch.clone().map(Into::into),
+    }
+}
+
+fn worktree_to_proto(worktree: &GitWorktree) -> proto::GitWorktree {
+    proto::GitWorktree {
+        path: worktree.path.to_string_lossy().into_owned(),
+        sha: worktree.sha.as_ref().map(ToString::to_string),
+        ref_name: worktree.ref_name.as_ref().map(ToString::to_string),
+        is_main: worktree.is_main,
+        is_locked: worktree.is_locked,
+        is_prunable: worktree.is_prunable,
+    }
+}
+
+fn proto_to_worktree(proto: &proto::GitWorktree) -> GitWorktree {
+    GitWorktree {
+        path: PathBuf::from(&proto.path),
+        sha: proto.sha.clone().map(Into::into),
+        ref_name: proto.ref_name.clone().map(Into::into),
+        is_main: proto.is_main,
+        is_locked: proto.is_locked,
+        is_prunable: proto.is_prunable,
+    }
+}
+
+fn sequencer_operation_to_proto(operation: SequencerOperation) -> git_resume_sequencer::Operation {
+    match operation {
+        SequencerOperation::CherryPick => git_resume_sequencer::Operation::CherryPick,
+        SequencerOperation::Revert => git_resume_sequencer::Operation::Revert,
+    }
+}
+
+fn proto_to_sequencer_operation(operation: git_resume_sequencer::Operation) -> SequencerOperation {
+    match operation {
+        git_resume_sequencer::Operation::CherryPick => SequencerOperation::CherryPick,
+        git_resume_sequencer::Operation::Revert => SequencerOperation::Revert,
+    }
+}
+
+fn sequencer_outcome_to_proto(outcome: SequencerOutcome) -> proto::GitSequencerResponse {
+    proto::GitSequencerResponse {
+        stopped: outcome == SequencerOutcome::Stopped,
+    }
+}
+
+fn proto_to_sequencer_outcome(proto: &proto::GitSequencerResponse) -> SequencerOutcome {
+    if proto.stopped {
+        SequencerOutcome::Stopped
+    } else {
+        SequencerOutcome::Completed
+    }
+}
+
+fn tag_to_proto(tag: &GitTag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_tag(proto: &proto::GitTag) -> GitTag {
+    GitTag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().map(Into::into),
+    }
+}
+
+fn bisect_status_to_proto(status: &BisectStatus) -> proto::GitBisectStatus {
+    proto::GitBisectStatus {
+        bad: status.bad.as_ref().map(ToString::to_string),
+        good: status.good.iter().map(ToString::to_string).collect(),
+        skipped: status.skipped.iter().map(ToString::to_string).collect(),
+        culprit: status.culprit.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_bisect_status(proto: &proto::GitBisectStatus) -> BisectStatus {
+    BisectStatus {
+        bad: proto.bad.clone().map(Into::into),
+        good: proto.good.iter().cloned().map(Into::into).collect(),
+        skipped: proto.skipped.iter().cloned().map(Into::into).collect(),
+        culprit: proto.culprit.clone().map(Into::into),
+    }
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
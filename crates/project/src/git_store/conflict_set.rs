@@ -1,4 +1,4 @@
-use gpui::{App, Context, Entity, EventEmitter};
+use gpui::{App, Context, Entity, EventEmitter, SharedString};
 use std::{cmp::Ordering, ops::Range, sync::Arc};
 use text::{Anchor, BufferId, OffsetRangeExt as _};
 
@@ -94,6 +94,11 @@ pub struct ConflictRegion {
     pub ours: Range<Anchor>,
     pub theirs: Range<Anchor>,
     pub base: Option<Range<Anchor>>,
+    /// What git named our side in the conflict markers, such as `HEAD`.
+    pub ours_label: SharedString,
+    /// What git named their side in the conflict markers, such as the branch being merged, or
+    /// the commit being cherry-picked or reverted.
+    pub theirs_label: SharedString,
 }
 
 impl ConflictRegion {
@@ -180,15 +185,17 @@ impl ConflictSet {
         let mut base_start: Option<usize> = None;
         let mut base_end: Option<usize> = None;
         let mut theirs_start: Option<usize> = None;
+        let mut ours_label = SharedString::default();
 
         while let Some(line) = lines.next() {
             let line_end = line_pos + line.len();
 
-            if line.starts_with("<<<<<<< ") {
+            if let Some(label) = line.strip_prefix("<<<<<<< ") {
                 // If we see a new conflict marker while already parsing one,
                 // abandon the previous one and start a new one
                 conflict_start = Some(line_pos);
                 ours_start = Some(line_end + 1);
+                ours_label = label.trim().to_string().into();
             } else if line.starts_with("||||||| ")
                 && conflict_start.is_some()
                 && ours_start.is_some()
@@ -206,7 +213,7 @@ impl ConflictSet {
                     base_end = Some(line_pos);
                 }
                 theirs_start = Some(line_end + 1);
-            } else if line.starts_with(">>>>>>> ")
+            } else if let Some(theirs_label) = line.strip_prefix(">>>>>>> ")
                 && conflict_start.is_some()
                 && ours_start.is_some()
                 && ours_end.is_some()
@@ -231,6 +238,8 @@ impl ConflictSet {
                     ours,
                     theirs,
                     base,
+                    ours_label: std::mem::take(&mut ours_label),
+                    theirs_label: theirs_label.trim().to_string().into(),
                 });
 
                 conflict_start = None;
@@ -313,6 +322,8 @@ mod tests {
             .collect::<String>();
         assert_eq!(our_text, "This is our version\n");
         assert_eq!(their_text, "This is their version\n");
+        assert_eq!(first.ours_label.as_ref(), "HEAD");
+        assert_eq!(first.theirs_label.as_ref(), "branch-name");
 
         let second = &conflict_snapshot.conflicts[1];
         assert!(second.base.is_some());
@@ -1,12 +1,16 @@
 mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
-    ProjectTransaction, PulledDiagnostics, ResolveState,
-    lsp_store::{LocalLspStore, LspStore},
+    CallHierarchyCall, CallHierarchyItem, CodeAction, CompletionSource, CoreCompletion,
+    CoreCompletionResponse, DocumentColor, DocumentHighlight, DocumentLink, DocumentSymbol, Hover,
+    HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
+    InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink, LspAction,
+    LspPullDiagnostics, MarkupContent, PrepareRenameResponse, ProjectTransaction,
+    PulledDiagnostics, ResolveState, TypeHierarchyItem,
+    lsp_store::{
+        LocalLspStore, LspStore,
+        semantic_tokens::{RawSemanticTokens, semantic_tokens_options},
+    },
 };
 use anyhow::{Context as _, Result};
 use async_trait::async_trait;
@@ -17,7 +21,7 @@ use futures::future;
 use gpui::{App, AsyncApp, Entity, Task};
 use language::{
     Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, CharScopeContext,
-    OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
+    OffsetRangeExt, Point, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
     language_settings::{InlayHintKind, LanguageSettings, language_settings},
     point_from_lsp, point_to_lsp,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
@@ -27,7 +31,7 @@ use lsp::{
     AdapterServerCapabilities, CodeActionKind, CodeActionOptions, CodeDescription,
     CompletionContext, CompletionListItemDefaultsEditRange, CompletionTriggerKind,
     DocumentHighlightKind, LanguageServer, LanguageServerId, LinkedEditingRangeServerCapabilities,
-    OneOf, RenameOptions, ServerCapabilities,
+    OneOf, RenameOptions, SelectionRangeProviderCapability, ServerCapabilities,
 };
 use serde_json::Value;
 use signature_help::{lsp_to_proto_signature, proto_to_lsp_signature};
@@ -152,6 +156,12 @@ pub trait LspCommand: 'static + Sized + Send + std::fmt::Debug {
     ) -> Result<Self::Response>;
 
     fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId>;
+
+    /// The language server the request is bound to, if any, e.g. the one that produced the item the request continues from.
+    /// When handling the request for a remote peer, the first capable server is queried otherwise.
+    fn language_server_id(&self) -> Option<LanguageServerId> {
+        None
+    }
 }
 
 pub enum LspParamsOrResponse<P, R> {
@@ -196,6 +206,36 @@ pub(crate) struct GetReferences {
     pub position: PointUtf16,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub item: CallHierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: CallHierarchyItem,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSupertypes {
+    pub item: TypeHierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSubtypes {
+    pub item: TypeHierarchyItem,
+}
+
 #[derive(Debug)]
 pub(crate) struct GetDocumentHighlights {
     pub position: PointUtf16,
@@ -245,6 +285,15 @@ pub(crate) struct GetCodeLens;
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentColor;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetSemanticTokens;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetFoldingRanges;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
 impl GetCodeLens {
     pub(crate) fn can_resolve_lens(capabilities: &ServerCapabilities) -> bool {
         capabilities
@@ -260,6 +309,11 @@ pub(crate) struct LinkedEditingRange {
     pub position: Anchor,
 }
 
+#[derive(Debug)]
+pub(crate) struct GetSelectionRanges {
+    pub positions: Vec<Anchor>,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetDocumentDiagnostics {
     pub previous_result_id: Option<String>,
@@ -3726,6 +3780,183 @@ impl LspCommand for LinkedEditingRange {
     }
 }
 
+impl GetSelectionRanges {
+    pub fn check_server_capabilities(capabilities: ServerCapabilities) -> bool {
+        match capabilities.selection_range_provider {
+            Some(SelectionRangeProviderCapability::Simple(false)) | None => false,
+            Some(_) => true,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSelectionRanges {
+    /// For every requested position, the ranges around it, from the innermost to the outermost.
+    type Response = Vec<Vec<Range<Anchor>>>;
+    type LspRequest = lsp::request::SelectionRangeRequest;
+    type ProtoRequest = proto::GetSelectionRanges;
+
+    fn display_name(&self) -> &str {
+        "Get selection ranges"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        Self::check_server_capabilities(capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SelectionRangeParams> {
+        let snapshot = buffer.snapshot();
+        Ok(lsp::SelectionRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            positions: self
+                .positions
+                .iter()
+                .map(|position| point_to_lsp(position.to_point_utf16(&snapshot)))
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::SelectionRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        let Some(selection_ranges) = message else {
+            return Ok(Vec::new());
+        };
+        buffer.read_with(&cx, |buffer, _| {
+            selection_ranges
+                .into_iter()
+                .map(|selection_range| {
+                    let mut ranges = Vec::new();
+                    let mut next = Some(selection_range);
+                    while let Some(lsp::SelectionRange { range, parent }) = next {
+                        let start =
+                            buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                        ranges.push(buffer.anchor_before(start)..buffer.anchor_after(end));
+                        next = parent.map(|parent| *parent);
+                    }
+                    ranges
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSelectionRanges {
+        proto::GetSelectionRanges {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            positions: self.positions.iter().map(serialize_anchor).collect(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSelectionRanges,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let positions = message
+            .positions
+            .into_iter()
+            .map(|position| deserialize_anchor(position).context("invalid position"))
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(positions.clone())
+            })?
+            .await?;
+        Ok(Self { positions })
+    }
+
+    fn response_to_proto(
+        response: Vec<Vec<Range<Anchor>>>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSelectionRangesResponse {
+        proto::GetSelectionRangesResponse {
+            chains: response
+                .into_iter()
+                .map(|ranges| proto::SelectionRangeChain {
+                    ranges: ranges
+                        .into_iter()
+                        .map(|range| proto::AnchorRange {
+                            start: Some(serialize_anchor(&range.start)),
+                            end: Some(serialize_anchor(&range.end)),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSelectionRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let chains: Vec<Vec<Range<Anchor>>> = message
+            .chains
+            .into_iter()
+            .map(|chain| {
+                chain
+                    .ranges
+                    .into_iter()
+                    .filter_map(|range| {
+                        let start = deserialize_anchor(range.start?)?;
+                        let end = deserialize_anchor(range.end?)?;
+                        Some(start..end)
+                    })
+                    .collect()
+            })
+            .collect();
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    chains
+                        .iter()
+                        .flatten()
+                        .flat_map(|range| [range.start, range.end])
+                        .collect::<Vec<_>>(),
+                )
+            })?
+            .await?;
+        Ok(chains)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSelectionRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 impl GetDocumentDiagnostics {
     pub fn diagnostics_from_proto(
         response: proto::GetDocumentDiagnosticsResponse,
@@ -4345,6 +4576,1379 @@ impl LspCommand for GetDocumentColor {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetSemanticTokens {
+    type Response = RawSemanticTokens;
+    type LspRequest = lsp::request::SemanticTokensFullRequest;
+    type ProtoRequest = proto::GetSemanticTokens;
+
+    fn display_name(&self) -> &str {
+        "Semantic tokens"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        semantic_tokens_options(&server_capabilities.server_capabilities).is_some_and(|options| {
+            !matches!(
+                options.full,
+                None | Some(lsp::SemanticTokensFullOptions::Bool(false))
+            )
+        })
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SemanticTokensParams> {
+        Ok(lsp::SemanticTokensParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SemanticTokensResult>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(match message {
+            Some(lsp::SemanticTokensResult::Tokens(tokens)) => RawSemanticTokens::from(tokens),
+            Some(lsp::SemanticTokensResult::Partial(partial)) => RawSemanticTokens {
+                result_id: None,
+                data: RawSemanticTokens::flatten(partial.data),
+            },
+            None => RawSemanticTokens::default(),
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSemanticTokens {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSemanticTokensResponse {
+        proto::GetSemanticTokensResponse {
+            result_id: response.result_id,
+            data: response.data,
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSemanticTokensResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(RawSemanticTokens {
+            result_id: message.result_id,
+            data: message.data,
+        })
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    /// Line-wise ranges, each spanning from the end of its first line to the end of its last one.
+    type Response = Vec<Range<Anchor>>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn display_name(&self) -> &str {
+        "Folding ranges"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        match server_capabilities
+            .server_capabilities
+            .folding_range_provider
+        {
+            Some(lsp::FoldingRangeProviderCapability::Simple(supported)) => supported,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::FoldingRangeParams> {
+        Ok(lsp::FoldingRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::FoldingRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer.read_with(&cx, |buffer, _| {
+            let max_row = buffer.max_point().row;
+            let mut ranges = message
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|folding_range| {
+                    let start_row = folding_range.start_line;
+                    let end_row = folding_range.end_line.min(max_row);
+                    (start_row < end_row).then(|| {
+                        Point::new(start_row, buffer.line_len(start_row))
+                            ..Point::new(end_row, buffer.line_len(end_row))
+                    })
+                })
+                .collect::<Vec<_>>();
+            ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+            ranges.dedup();
+            ranges
+                .into_iter()
+                .map(|range| buffer.anchor_after(range.start)..buffer.anchor_after(range.end))
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetFoldingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: response
+                .into_iter()
+                .map(|range| proto::AnchorRange {
+                    start: Some(serialize_anchor(&range.start)),
+                    end: Some(serialize_anchor(&range.end)),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetFoldingRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let ranges = message
+            .ranges
+            .into_iter()
+            .filter_map(|range| {
+                let start = deserialize_anchor(range.start?)?;
+                let end = deserialize_anchor(range.end?)?;
+                Some(start..end)
+            })
+            .collect::<Vec<_>>();
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    ranges
+                        .iter()
+                        .flat_map(|range| [range.start, range.end])
+                        .collect::<Vec<_>>(),
+                )
+            })?
+            .await?;
+        Ok(ranges)
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn display_name(&self) -> &str {
+        "Document links"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        server_capabilities
+            .server_capabilities
+            .document_link_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer.read_with(&cx, |buffer, _| {
+            message
+                .unwrap_or_default()
+                .into_iter()
+                .map(|lsp_link| DocumentLink {
+                    range: anchor_range_from_lsp(buffer, lsp_link.range),
+                    language_server_id: server_id,
+                    lsp_link,
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response.iter().map(document_link_to_proto).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let links = message
+            .links
+            .into_iter()
+            .map(document_link_from_proto)
+            .collect::<Result<Vec<_>>>()?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_anchors(
+                    links
+                        .iter()
+                        .flat_map(|link| [link.range.start, link.range.end])
+                        .collect::<Vec<_>>(),
+                )
+            })?
+            .await?;
+        Ok(links)
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+pub(crate) fn document_link_to_proto(link: &DocumentLink) -> proto::DocumentLink {
+    proto::DocumentLink {
+        start: Some(serialize_anchor(&link.range.start)),
+        end: Some(serialize_anchor(&link.range.end)),
+        language_server_id: link.language_server_id.to_proto(),
+        lsp_link: serde_json::to_vec(&link.lsp_link).unwrap(),
+    }
+}
+
+pub(crate) fn document_link_from_proto(link: proto::DocumentLink) -> Result<DocumentLink> {
+    let start = link
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing document link start")?;
+    let end = link
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing document link end")?;
+    Ok(DocumentLink {
+        range: start..end,
+        language_server_id: LanguageServerId::from_proto(link.language_server_id),
+        lsp_link: serde_json::from_slice(&link.lsp_link).context("deserializing document link")?,
+    })
+}
+
+fn call_hierarchy_supported(capabilities: &ServerCapabilities) -> bool {
+    match &capabilities.call_hierarchy_provider {
+        Some(lsp::CallHierarchyServerCapability::Simple(supported)) => *supported,
+        Some(lsp::CallHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+async fn call_hierarchy_item_from_lsp(
+    lsp_item: lsp::CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyItem> {
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(lsp_item.uri.clone(), server_id, cx)
+        })?
+        .await?;
+    buffer.read_with(cx, |buffer_snapshot, _| CallHierarchyItem {
+        name: lsp_item.name.clone(),
+        kind: lsp_item.kind,
+        detail: lsp_item.detail.clone(),
+        location: Location {
+            buffer: buffer.clone(),
+            range: anchor_range_from_lsp(buffer_snapshot, lsp_item.range),
+        },
+        selection_range: anchor_range_from_lsp(buffer_snapshot, lsp_item.selection_range),
+        language_server_id: server_id,
+        lsp_item,
+    })
+}
+
+/// Converts the calls reported for `item`, where the call sites are either in the reported items (incoming calls),
+/// or in `item` itself (outgoing calls).
+async fn call_hierarchy_calls_from_lsp(
+    calls: Vec<(lsp::CallHierarchyItem, Vec<lsp::Range>)>,
+    call_sites_in_item: bool,
+    item: &CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<CallHierarchyCall>> {
+    let mut result = Vec::with_capacity(calls.len());
+    for (lsp_item, from_ranges) in calls {
+        let call_item =
+            call_hierarchy_item_from_lsp(lsp_item, lsp_store, item.language_server_id, cx).await?;
+        let call_sites_buffer = if call_sites_in_item {
+            call_item.location.buffer.clone()
+        } else {
+            item.location.buffer.clone()
+        };
+        let call_sites = call_sites_buffer.read_with(cx, |buffer, _| {
+            from_ranges
+                .into_iter()
+                .map(|range| Location {
+                    buffer: call_sites_buffer.clone(),
+                    range: anchor_range_from_lsp(buffer, range),
+                })
+                .collect()
+        })?;
+        result.push(CallHierarchyCall {
+            item: call_item,
+            call_sites,
+        });
+    }
+    Ok(result)
+}
+
+pub(crate) fn location_to_proto(
+    location: &Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::Location {
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+        buffer_id: location.buffer.read(cx).remote_id().into(),
+    }
+}
+
+pub(crate) async fn location_from_proto(
+    location: proto::Location,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing location start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing location end")?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+fn serialize_call_hierarchy_item(
+    item: &CallHierarchyItem,
+    buffer_id: BufferId,
+) -> proto::CallHierarchyItem {
+    proto::CallHierarchyItem {
+        location: Some(proto::Location {
+            start: Some(serialize_anchor(&item.location.range.start)),
+            end: Some(serialize_anchor(&item.location.range.end)),
+            buffer_id: buffer_id.into(),
+        }),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        language_server_id: item.language_server_id.to_proto(),
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap(),
+    }
+}
+
+fn call_hierarchy_item_to_proto(
+    item: &CallHierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::CallHierarchyItem {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&item.location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    serialize_call_hierarchy_item(item, item.location.buffer.read(cx).remote_id())
+}
+
+/// Deserializes the full and the selection ranges of a call or type hierarchy item.
+fn hierarchy_item_ranges_from_proto(
+    location: Option<proto::Location>,
+    selection_start: Option<proto::Anchor>,
+    selection_end: Option<proto::Anchor>,
+) -> Result<(Range<Anchor>, Range<Anchor>)> {
+    let location = location.context("missing hierarchy item location")?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item end")?;
+    let selection_start = selection_start
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item selection start")?;
+    let selection_end = selection_end
+        .and_then(deserialize_anchor)
+        .context("missing hierarchy item selection end")?;
+    Ok((start..end, selection_start..selection_end))
+}
+
+async fn wait_for_hierarchy_item_buffer(
+    location: Option<&proto::Location>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Entity<Buffer>> {
+    let buffer_id = BufferId::new(
+        location
+            .context("missing hierarchy item location")?
+            .buffer_id,
+    )?;
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await
+}
+
+async fn wait_for_hierarchy_item_anchors(
+    buffer: &Entity<Buffer>,
+    range: &Range<Anchor>,
+    selection_range: &Range<Anchor>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_anchors([
+                range.start,
+                range.end,
+                selection_range.start,
+                selection_range.end,
+            ])
+        })?
+        .await
+}
+
+/// Deserializes an item located in the given `buffer`.
+fn call_hierarchy_item_from_proto(
+    item: proto::CallHierarchyItem,
+    buffer: Entity<Buffer>,
+) -> Result<CallHierarchyItem> {
+    let (range, selection_range) =
+        hierarchy_item_ranges_from_proto(item.location, item.selection_start, item.selection_end)?;
+    let lsp_item = serde_json::from_slice::<lsp::CallHierarchyItem>(&item.lsp_item)
+        .context("deserializing call hierarchy item")?;
+    Ok(CallHierarchyItem {
+        name: lsp_item.name.clone(),
+        kind: lsp_item.kind,
+        detail: lsp_item.detail.clone(),
+        location: Location { buffer, range },
+        selection_range,
+        language_server_id: LanguageServerId::from_proto(item.language_server_id),
+        lsp_item,
+    })
+}
+
+async fn call_hierarchy_item_from_remote_proto(
+    item: proto::CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyItem> {
+    let buffer = wait_for_hierarchy_item_buffer(item.location.as_ref(), lsp_store, cx).await?;
+    let item = call_hierarchy_item_from_proto(item, buffer.clone())?;
+    wait_for_hierarchy_item_anchors(&buffer, &item.location.range, &item.selection_range, cx)
+        .await?;
+    Ok(item)
+}
+
+fn call_hierarchy_calls_to_proto(
+    calls: Vec<CallHierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::CallHierarchyCall> {
+    calls
+        .into_iter()
+        .map(|call| proto::CallHierarchyCall {
+            item: Some(call_hierarchy_item_to_proto(
+                &call.item, lsp_store, peer_id, cx,
+            )),
+            call_sites: call
+                .call_sites
+                .iter()
+                .map(|call_site| location_to_proto(call_site, lsp_store, peer_id, cx))
+                .collect(),
+        })
+        .collect()
+}
+
+async fn call_hierarchy_calls_from_proto(
+    calls: Vec<proto::CallHierarchyCall>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<CallHierarchyCall>> {
+    let mut result = Vec::with_capacity(calls.len());
+    for call in calls {
+        let item = call_hierarchy_item_from_remote_proto(
+            call.item.context("missing call hierarchy call item")?,
+            lsp_store,
+            cx,
+        )
+        .await?;
+        let mut call_sites = Vec::with_capacity(call.call_sites.len());
+        for call_site in call.call_sites {
+            call_sites.push(location_from_proto(call_site, lsp_store, cx).await?);
+        }
+        result.push(CallHierarchyCall { item, call_sites });
+    }
+    Ok(result)
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        call_hierarchy_supported(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut items = Vec::new();
+        for lsp_item in message.unwrap_or_default() {
+            items.push(
+                call_hierarchy_item_from_lsp(lsp_item, &lsp_store, server_id, &mut cx).await?,
+            );
+        }
+        Ok(items)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response
+                .iter()
+                .map(|item| call_hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut items = Vec::with_capacity(message.items.len());
+        for item in message.items {
+            items.push(call_hierarchy_item_from_remote_proto(item, &lsp_store, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        call_hierarchy_supported(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let calls = message
+            .unwrap_or_default()
+            .into_iter()
+            .map(|call| (call.from, call.from_ranges))
+            .collect();
+        call_hierarchy_calls_from_lsp(calls, true, &self.item, &lsp_store, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(serialize_call_hierarchy_item(
+                &self.item,
+                buffer.remote_id(),
+            )),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing call hierarchy item")?;
+        Ok(Self {
+            item: call_hierarchy_item_from_proto(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: call_hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message.calls, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+
+    fn language_server_id(&self) -> Option<LanguageServerId> {
+        Some(self.item.language_server_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        call_hierarchy_supported(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let calls = message
+            .unwrap_or_default()
+            .into_iter()
+            .map(|call| (call.to, call.from_ranges))
+            .collect();
+        call_hierarchy_calls_from_lsp(calls, false, &self.item, &lsp_store, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(serialize_call_hierarchy_item(
+                &self.item,
+                buffer.remote_id(),
+            )),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing call hierarchy item")?;
+        Ok(Self {
+            item: call_hierarchy_item_from_proto(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: call_hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message.calls, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+
+    fn language_server_id(&self) -> Option<LanguageServerId> {
+        Some(self.item.language_server_id)
+    }
+}
+
+fn type_hierarchy_supported(capabilities: &ServerCapabilities) -> bool {
+    match &capabilities.type_hierarchy_provider {
+        Some(lsp::TypeHierarchyServerCapability::Simple(supported)) => *supported,
+        Some(lsp::TypeHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
+
+async fn type_hierarchy_items_from_lsp(
+    lsp_items: Vec<lsp::TypeHierarchyItem>,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let mut items = Vec::with_capacity(lsp_items.len());
+    for lsp_item in lsp_items {
+        let buffer = lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.open_local_buffer_via_lsp(lsp_item.uri.clone(), server_id, cx)
+            })?
+            .await?;
+        items.push(
+            buffer.read_with(cx, |buffer_snapshot, _| TypeHierarchyItem {
+                name: lsp_item.name.clone(),
+                kind: lsp_item.kind,
+                detail: lsp_item.detail.clone(),
+                location: Location {
+                    buffer: buffer.clone(),
+                    range: anchor_range_from_lsp(buffer_snapshot, lsp_item.range),
+                },
+                selection_range: anchor_range_from_lsp(buffer_snapshot, lsp_item.selection_range),
+                language_server_id: server_id,
+                lsp_item,
+            })?,
+        );
+    }
+    Ok(items)
+}
+
+fn serialize_type_hierarchy_item(
+    item: &TypeHierarchyItem,
+    buffer_id: BufferId,
+) -> proto::TypeHierarchyItem {
+    proto::TypeHierarchyItem {
+        location: Some(proto::Location {
+            start: Some(serialize_anchor(&item.location.range.start)),
+            end: Some(serialize_anchor(&item.location.range.end)),
+            buffer_id: buffer_id.into(),
+        }),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        language_server_id: item.language_server_id.to_proto(),
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap(),
+    }
+}
+
+fn type_hierarchy_items_to_proto(
+    items: Vec<TypeHierarchyItem>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::TypeHierarchyItem> {
+    items
+        .iter()
+        .map(|item| {
+            lsp_store
+                .buffer_store()
+                .update(cx, |buffer_store, cx| {
+                    buffer_store.create_buffer_for_peer(&item.location.buffer, peer_id, cx)
+                })
+                .detach_and_log_err(cx);
+            serialize_type_hierarchy_item(item, item.location.buffer.read(cx).remote_id())
+        })
+        .collect()
+}
+
+/// Deserializes an item located in the given `buffer`.
+fn type_hierarchy_item_from_proto(
+    item: proto::TypeHierarchyItem,
+    buffer: Entity<Buffer>,
+) -> Result<TypeHierarchyItem> {
+    let (range, selection_range) =
+        hierarchy_item_ranges_from_proto(item.location, item.selection_start, item.selection_end)?;
+    let lsp_item = serde_json::from_slice::<lsp::TypeHierarchyItem>(&item.lsp_item)
+        .context("deserializing type hierarchy item")?;
+    Ok(TypeHierarchyItem {
+        name: lsp_item.name.clone(),
+        kind: lsp_item.kind,
+        detail: lsp_item.detail.clone(),
+        location: Location { buffer, range },
+        selection_range,
+        language_server_id: LanguageServerId::from_proto(item.language_server_id),
+        lsp_item,
+    })
+}
+
+async fn type_hierarchy_items_from_proto(
+    items: Vec<proto::TypeHierarchyItem>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let mut result = Vec::with_capacity(items.len());
+    for item in items {
+        let buffer = wait_for_hierarchy_item_buffer(item.location.as_ref(), lsp_store, cx).await?;
+        let item = type_hierarchy_item_from_proto(item, buffer.clone())?;
+        wait_for_hierarchy_item_anchors(&buffer, &item.location.range, &item.selection_range, cx)
+            .await?;
+        result.push(item);
+    }
+    Ok(result)
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        type_hierarchy_supported(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(message.unwrap_or_default(), &lsp_store, server_id, &mut cx)
+            .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        type_hierarchy_supported(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(message.unwrap_or_default(), &lsp_store, server_id, &mut cx)
+            .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(serialize_type_hierarchy_item(
+                &self.item,
+                buffer.remote_id(),
+            )),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing type hierarchy item")?;
+        Ok(Self {
+            item: type_hierarchy_item_from_proto(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+
+    fn language_server_id(&self) -> Option<LanguageServerId> {
+        Some(self.item.language_server_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        type_hierarchy_supported(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(message.unwrap_or_default(), &lsp_store, server_id, &mut cx)
+            .await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(serialize_type_hierarchy_item(
+                &self.item,
+                buffer.remote_id(),
+            )),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing type hierarchy item")?;
+        Ok(Self {
+            item: type_hierarchy_item_from_proto(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+
+    fn language_server_id(&self) -> Option<LanguageServerId> {
+        Some(self.item.language_server_id)
+    }
+}
+
 fn process_related_documents(
     diagnostics: &mut HashMap<lsp::Uri, LspPullDiagnostics>,
     server_id: LanguageServerId,
@@ -10,22 +10,29 @@
 //!
 //! Most of the interesting work happens at the local layer, as bulk of the complexity is with managing the lifecycle of language servers. The actual implementation of the LSP protocol is handled by [`lsp`] crate.
 pub mod clangd_ext;
+pub mod document_links;
+pub mod folding_ranges;
 pub mod json_language_server_ext;
 pub mod log_store;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
+pub mod semantic_tokens;
 
 use crate::{
-    CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, LocationLink, LspAction,
-    LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
+    CallHierarchyCall, CallHierarchyItem, CodeAction, ColorPresentation, Completion,
+    CompletionDisplayOptions, CompletionResponse, CompletionSource, CoreCompletion, DocumentColor,
+    Hover, InlayHint, LocationLink, LspAction, LspPullDiagnostics, ManifestProvidersStore, Project,
+    ProjectItem, ProjectPath, ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
+    TypeHierarchyItem,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
     lsp_store::{
         self,
+        document_links::DocumentLinksData,
+        folding_ranges::FoldingRangesData,
         log_store::{GlobalLogStore, LanguageServerKind},
+        semantic_tokens::SemanticTokensData,
     },
     manifest_tree::{
         LanguageServerTree, LanguageServerTreeNode, LaunchDisposition, ManifestQueryDelegate,
@@ -833,6 +840,28 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
                 let this = this.clone();
@@ -3467,6 +3496,9 @@ pub struct LspStore {
         HashMap<WorktreeId, HashMap<Arc<RelPath>, HashMap<LanguageServerId, DiagnosticSummary>>>,
     pub lsp_server_capabilities: HashMap<LanguageServerId, lsp::ServerCapabilities>,
     lsp_document_colors: HashMap<BufferId, DocumentColorData>,
+    lsp_semantic_tokens: HashMap<BufferId, SemanticTokensData>,
+    lsp_folding_ranges: HashMap<BufferId, FoldingRangesData>,
+    lsp_document_links: HashMap<BufferId, DocumentLinksData>,
     lsp_code_lens: HashMap<BufferId, CodeLensData>,
     running_lsp_requests: HashMap<TypeId, (Global, HashMap<LspRequestId, Task<()>>)>,
 }
@@ -3519,6 +3551,7 @@ pub enum LspStoreEvent {
     Notification(String),
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         server_id: LanguageServerId,
         paths: Vec<ProjectPath>,
@@ -3592,10 +3625,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_inlay_hints);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_get_color_presentation);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3607,6 +3642,11 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
 
         client.add_entity_request_handler(Self::handle_lsp_ext_cancel_flycheck);
         client.add_entity_request_handler(Self::handle_lsp_ext_run_flycheck);
@@ -3740,6 +3780,9 @@ impl LspStore {
             diagnostic_summaries: HashMap::default(),
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
+            lsp_semantic_tokens: HashMap::default(),
+            lsp_folding_ranges: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             lsp_code_lens: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
@@ -3801,6 +3844,9 @@ impl LspStore {
             diagnostic_summaries: HashMap::default(),
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
+            lsp_semantic_tokens: HashMap::default(),
+            lsp_folding_ranges: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             lsp_code_lens: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
@@ -4000,6 +4046,9 @@ impl LspStore {
                     };
                     if refcount == 0 {
                         lsp_store.lsp_document_colors.remove(&buffer_id);
+                        lsp_store.lsp_semantic_tokens.remove(&buffer_id);
+                        lsp_store.lsp_folding_ranges.remove(&buffer_id);
+                        lsp_store.lsp_document_links.remove(&buffer_id);
                         lsp_store.lsp_code_lens.remove(&buffer_id);
                         let local = lsp_store.as_local_mut().unwrap();
                         local.registered_buffers.remove(&buffer_id);
@@ -4989,6 +5038,21 @@ impl LspStore {
         )
     }
 
+    /// Queries the selection ranges around each of the positions, from the innermost to the outermost.
+    pub fn selection_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
     fn apply_on_type_formatting(
         &mut self,
         buffer: Entity<Buffer>,
@@ -5434,6 +5498,178 @@ impl LspStore {
         }
     }
 
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: PointUtf16,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<CallHierarchyItem>>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = PrepareCallHierarchy { position };
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = upstream_client.request_lsp(
+                project_id,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_project, cx| {
+                let Some(project) = weak_project.upgrade() else {
+                    return Ok(None);
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(None);
+                };
+
+                let items = join_all(responses.payload.into_iter().map(|lsp_response| {
+                    PrepareCallHierarchy { position }.response_from_proto(
+                        lsp_response.response,
+                        project.clone(),
+                        buffer.clone(),
+                        cx.clone(),
+                    )
+                }))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<Vec<_>>>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+                Ok(Some(items))
+            })
+        } else {
+            let items_task = self.request_multiple_lsp_locally(
+                buffer,
+                Some(position),
+                PrepareCallHierarchy { position },
+                cx,
+            );
+            cx.background_spawn(async move {
+                Ok(Some(
+                    items_task
+                        .await
+                        .into_iter()
+                        .flat_map(|(_, items)| items)
+                        .collect(),
+                ))
+            })
+        }
+    }
+
+    /// Queries the callers of an item, returned by [`LspStore::prepare_call_hierarchy`] or a previous calls query,
+    /// from the language server that reported the item.
+    pub fn incoming_calls(
+        &mut self,
+        item: CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let buffer = item.location.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.language_server_id);
+        self.request_lsp(buffer, server, GetIncomingCalls { item }, cx)
+    }
+
+    /// Queries the callees of an item, returned by [`LspStore::prepare_call_hierarchy`] or a previous calls query,
+    /// from the language server that reported the item.
+    pub fn outgoing_calls(
+        &mut self,
+        item: CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let buffer = item.location.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.language_server_id);
+        self.request_lsp(buffer, server, GetOutgoingCalls { item }, cx)
+    }
+
+    pub fn prepare_type_hierarchy(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: PointUtf16,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<TypeHierarchyItem>>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = PrepareTypeHierarchy { position };
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = upstream_client.request_lsp(
+                project_id,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_project, cx| {
+                let Some(project) = weak_project.upgrade() else {
+                    return Ok(None);
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(None);
+                };
+
+                let items = join_all(responses.payload.into_iter().map(|lsp_response| {
+                    PrepareTypeHierarchy { position }.response_from_proto(
+                        lsp_response.response,
+                        project.clone(),
+                        buffer.clone(),
+                        cx.clone(),
+                    )
+                }))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<Vec<_>>>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+                Ok(Some(items))
+            })
+        } else {
+            let items_task = self.request_multiple_lsp_locally(
+                buffer,
+                Some(position),
+                PrepareTypeHierarchy { position },
+                cx,
+            );
+            cx.background_spawn(async move {
+                Ok(Some(
+                    items_task
+                        .await
+                        .into_iter()
+                        .flat_map(|(_, items)| items)
+                        .collect(),
+                ))
+            })
+        }
+    }
+
+    /// Queries the supertypes of an item, returned by [`LspStore::prepare_type_hierarchy`] or a previous
+    /// super- or subtypes query, from the language server that reported the item.
+    pub fn supertypes(
+        &mut self,
+        item: TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let buffer = item.location.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.language_server_id);
+        self.request_lsp(buffer, server, GetSupertypes { item }, cx)
+    }
+
+    /// Queries the subtypes of an item, returned by [`LspStore::prepare_type_hierarchy`] or a previous
+    /// super- or subtypes query, from the language server that reported the item.
+    pub fn subtypes(
+        &mut self,
+        item: TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let buffer = item.location.buffer.clone();
+        let server = LanguageServerToQuery::Other(item.language_server_id);
+        self.request_lsp(buffer, server, GetSubtypes { item }, cx)
+    }
+
     pub fn code_actions(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -7915,14 +8151,13 @@ impl LspStore {
             cx.clone(),
         )
         .await?;
+        let server = request.language_server_id().map_or(
+            LanguageServerToQuery::FirstCapable,
+            LanguageServerToQuery::Other,
+        );
         let response = this
             .update(&mut cx, |this, cx| {
-                this.request_lsp(
-                    buffer_handle.clone(),
-                    LanguageServerToQuery::FirstCapable,
-                    request,
-                    cx,
-                )
+                this.request_lsp(buffer_handle.clone(), server, request, cx)
             })?
             .await?;
         this.update(&mut cx, |this, cx| {
@@ -7969,6 +8204,69 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::PrepareCallHierarchy(prepare_call_hierarchy) => {
+                let position = prepare_call_hierarchy
+                    .position
+                    .clone()
+                    .and_then(deserialize_anchor);
+                Self::query_lsp_locally::<PrepareCallHierarchy>(
+                    lsp_store,
+                    sender_id,
+                    lsp_request_id,
+                    prepare_call_hierarchy,
+                    position,
+                    cx.clone(),
+                )
+                .await?;
+            }
+            Request::PrepareTypeHierarchy(prepare_type_hierarchy) => {
+                let position = prepare_type_hierarchy
+                    .position
+                    .clone()
+                    .and_then(deserialize_anchor);
+                Self::query_lsp_locally::<PrepareTypeHierarchy>(
+                    lsp_store,
+                    sender_id,
+                    lsp_request_id,
+                    prepare_type_hierarchy,
+                    position,
+                    cx.clone(),
+                )
+                .await?;
+            }
+            Request::GetSemanticTokens(get_semantic_tokens) => {
+                Self::query_lsp_locally::<GetSemanticTokens>(
+                    lsp_store,
+                    sender_id,
+                    lsp_request_id,
+                    get_semantic_tokens,
+                    None,
+                    cx.clone(),
+                )
+                .await?;
+            }
+            Request::GetFoldingRanges(get_folding_ranges) => {
+                Self::query_lsp_locally::<GetFoldingRanges>(
+                    lsp_store,
+                    sender_id,
+                    lsp_request_id,
+                    get_folding_ranges,
+                    None,
+                    cx.clone(),
+                )
+                .await?;
+            }
+            Request::GetDocumentLinks(get_document_links) => {
+                Self::query_lsp_locally::<GetDocumentLinks>(
+                    lsp_store,
+                    sender_id,
+                    lsp_request_id,
+                    get_document_links,
+                    None,
+                    cx.clone(),
+                )
+                .await?;
+            }
             Request::GetHover(get_hover) => {
                 let position = get_hover.position.clone().and_then(deserialize_anchor);
                 Self::query_lsp_locally::<GetHover>(
@@ -9335,6 +9633,17 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -11046,6 +11355,15 @@ impl LspStore {
             buffer_colors.colors.remove(&for_server);
             buffer_colors.cache_version += 1;
         }
+        for buffer_tokens in self.lsp_semantic_tokens.values_mut() {
+            buffer_tokens.remove_server(for_server);
+        }
+        for buffer_ranges in self.lsp_folding_ranges.values_mut() {
+            buffer_ranges.remove_server(for_server);
+        }
+        for buffer_links in self.lsp_document_links.values_mut() {
+            buffer_links.remove_server(for_server);
+        }
         for buffer_lens in self.lsp_code_lens.values_mut() {
             buffer_lens.lens.remove(&for_server);
         }
@@ -11479,6 +11797,81 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/semanticTokens" => {
+                    if let Some(options) = reg
+                        .register_options
+                        .map(serde_json::from_value::<lsp::SemanticTokensRegistrationOptions>)
+                        .transpose()?
+                    {
+                        server.update_capabilities(|capabilities| {
+                            capabilities.semantic_tokens_provider = Some(
+                                lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                                    options,
+                                ),
+                            );
+                        });
+                        notify_server_capabilities_updated(&server, cx);
+                    }
+                }
+                "textDocument/prepareCallHierarchy" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::CallHierarchyServerCapability::Simple(value),
+                        OneOf::Right(caps) => lsp::CallHierarchyServerCapability::Options(caps),
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/prepareTypeHierarchy" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::TypeHierarchyServerCapability::Simple(value),
+                        OneOf::Right(caps) => lsp::TypeHierarchyServerCapability::Options(caps),
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.type_hierarchy_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/foldingRange" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::FoldingRangeProviderCapability::Simple(value),
+                        OneOf::Right(caps) => {
+                            lsp::FoldingRangeProviderCapability::FoldingProvider(caps)
+                        }
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.folding_range_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/selectionRange" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::SelectionRangeProviderCapability::Simple(value),
+                        OneOf::Right(caps) => {
+                            lsp::SelectionRangeProviderCapability::RegistrationOptions(caps)
+                        }
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.selection_range_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/documentLink" => {
+                    let options = reg
+                        .register_options
+                        .map(serde_json::from_value)
+                        .transpose()?
+                        .unwrap_or_default();
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = Some(options);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability registration: {reg:?}"),
             }
         }
@@ -11649,6 +12042,42 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/semanticTokens" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.semantic_tokens_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/prepareCallHierarchy" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/prepareTypeHierarchy" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.type_hierarchy_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/foldingRange" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.folding_range_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/selectionRange" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.selection_range_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/documentLink" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
             }
         }
@@ -0,0 +1,350 @@
+use std::{str::FromStr as _, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use client::{TypedEnvelope, proto};
+use clock::Global;
+use collections::HashMap;
+use futures::future::{Shared, join_all};
+use gpui::{AppContext as _, AsyncApp, Context, Entity, Task};
+use language::{Bias, Buffer, Point};
+use lsp::{LSP_REQUEST_TIMEOUT, LanguageServerId, ServerCapabilities};
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{
+    DocumentLink, DocumentLinkTarget, Location,
+    lsp_command::{
+        GetDocumentLinks, LspCommand as _, document_link_from_proto, document_link_to_proto,
+        location_from_proto, location_to_proto,
+    },
+    lsp_store::LspStore,
+};
+
+pub type DocumentLinksTask =
+    Shared<Task<std::result::Result<Arc<[DocumentLink]>, Arc<anyhow::Error>>>>;
+
+#[derive(Debug, Default)]
+pub(super) struct DocumentLinksData {
+    /// The buffer version the links were fetched for, `None` if they were never fetched.
+    links_for_version: Option<Global>,
+    links: HashMap<LanguageServerId, Vec<DocumentLink>>,
+    update: Option<(Global, DocumentLinksTask)>,
+}
+
+impl DocumentLinksData {
+    pub(super) fn remove_server(&mut self, server_id: LanguageServerId) {
+        self.links.remove(&server_id);
+    }
+
+    fn buffer_links(&self, buffer: &Buffer) -> Arc<[DocumentLink]> {
+        let mut links = self.links.values().flatten().cloned().collect::<Vec<_>>();
+        links.sort_by(|a, b| a.range.start.cmp(&b.range.start, buffer));
+        links.into()
+    }
+}
+
+impl LspStore {
+    /// Returns the links reported by all language servers of the buffer, sorted by their start.
+    /// Links fetched for the current buffer version are reused.
+    pub fn document_links(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> DocumentLinksTask {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        if let Some(cached_data) = self.lsp_document_links.get(&buffer_id)
+            && cached_data
+                .links_for_version
+                .as_ref()
+                .is_some_and(|links_for_version| {
+                    !version_queried_for.changed_since(links_for_version)
+                })
+        {
+            let has_different_servers = self.as_local().is_some_and(|local| {
+                local
+                    .buffers_opened_in_servers
+                    .get(&buffer_id)
+                    .cloned()
+                    .unwrap_or_default()
+                    != cached_data.links.keys().copied().collect()
+            });
+            if !has_different_servers {
+                return Task::ready(Ok(cached_data.buffer_links(buffer.read(cx)))).shared();
+            }
+        }
+
+        let lsp_data = self.lsp_document_links.entry(buffer_id).or_default();
+        if let Some((updating_for, running_update)) = &lsp_data.update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return running_update.clone();
+        }
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                let fetched_links = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_document_links_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching document links")
+                    .map_err(Arc::new);
+                let fetched_links = match fetched_links {
+                    Ok(fetched_links) => fetched_links,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                lsp_store
+                                    .lsp_document_links
+                                    .entry(buffer_id)
+                                    .or_default()
+                                    .update = None;
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.lsp_document_links.entry(buffer_id).or_default();
+                        lsp_data.update = None;
+                        // Links fetched for an outdated version still point at valid anchors,
+                        // but are not cached, so that the next query refetches them.
+                        if buffer.read(cx).version() == query_version_queried_for {
+                            lsp_data.links_for_version = Some(query_version_queried_for);
+                            lsp_data.links = fetched_links;
+                            lsp_data.buffer_links(buffer.read(cx))
+                        } else {
+                            DocumentLinksData {
+                                links: fetched_links,
+                                ..DocumentLinksData::default()
+                            }
+                            .buffer_links(buffer.read(cx))
+                        }
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        lsp_data.update = Some((version_queried_for, new_task.clone()));
+        new_task
+    }
+
+    fn fetch_document_links_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<HashMap<LanguageServerId, Vec<DocumentLink>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetDocumentLinks;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(HashMap::default()));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(HashMap::default());
+                };
+                let links = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|links_response| {
+                            let response = request.response_from_proto(
+                                links_response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move {
+                                (
+                                    LanguageServerId::from_proto(links_response.server_id),
+                                    response.await.log_err().unwrap_or_default(),
+                                )
+                            }
+                        }),
+                )
+                .await
+                .into_iter()
+                .collect();
+                Ok(links)
+            })
+        } else {
+            let links_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetDocumentLinks, cx);
+            cx.background_spawn(async move { Ok(links_task.await.into_iter().collect()) })
+        }
+    }
+
+    /// Resolves the target of the link, querying its language server if the target was not reported yet.
+    pub fn resolve_document_link(
+        &mut self,
+        buffer: Entity<Buffer>,
+        link: DocumentLink,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<DocumentLinkTarget>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                link: Some(document_link_to_proto(&link)),
+            };
+            return cx.spawn(async move |lsp_store, cx| {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto resolve request")?;
+                let lsp_store = lsp_store.upgrade().context("lsp store dropped")?;
+                match response.target {
+                    Some(proto::resolve_document_link_response::Target::Location(location)) => {
+                        let location = location_from_proto(location, &lsp_store, cx).await?;
+                        Ok(Some(DocumentLinkTarget::Location(location)))
+                    }
+                    Some(proto::resolve_document_link_response::Target::Url(url)) => {
+                        Ok(Some(DocumentLinkTarget::Url(url)))
+                    }
+                    None => Ok(None),
+                }
+            });
+        }
+
+        let server_id = link.language_server_id;
+        let lang_server = buffer.update(cx, |buffer, cx| {
+            self.language_server_for_local_buffer(buffer, server_id, cx)
+                .map(|(_, server)| server.clone())
+        });
+        cx.spawn(async move |lsp_store, cx| {
+            let mut lsp_link = link.lsp_link;
+            if lsp_link.target.is_none()
+                && let Some(lang_server) = lang_server
+                && can_resolve_document_links(&lang_server.capabilities())
+            {
+                lsp_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(lsp_link)
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")?;
+            }
+            let Some(target) = lsp_link.target else {
+                return Ok(None);
+            };
+            let Some(lsp_store) = lsp_store.upgrade() else {
+                return Ok(None);
+            };
+            document_link_target_from_lsp(target, server_id, &lsp_store, cx)
+                .await
+                .map(Some)
+        })
+    }
+
+    pub(super) async fn handle_resolve_document_link(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let link = document_link_from_proto(
+            envelope
+                .payload
+                .link
+                .context("missing document link to resolve")?,
+        )?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let target = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_document_link(buffer, link, cx)
+            })?
+            .await?;
+        let target = match target {
+            Some(DocumentLinkTarget::Location(location)) => this.update(&mut cx, |this, cx| {
+                Some(proto::resolve_document_link_response::Target::Location(
+                    location_to_proto(&location, this, sender_id, cx),
+                ))
+            })?,
+            Some(DocumentLinkTarget::Url(url)) => {
+                Some(proto::resolve_document_link_response::Target::Url(url))
+            }
+            None => None,
+        };
+        Ok(proto::ResolveDocumentLinkResponse { target })
+    }
+}
+
+fn can_resolve_document_links(capabilities: &ServerCapabilities) -> bool {
+    capabilities
+        .document_link_provider
+        .as_ref()
+        .and_then(|options| options.resolve_provider)
+        .unwrap_or(false)
+}
+
+/// Opens `file` targets in a buffer, at the position of their `#L<line>[,<column>]` fragment.
+/// Other targets are left for the platform to open.
+async fn document_link_target_from_lsp(
+    target: lsp::Uri,
+    server_id: LanguageServerId,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<DocumentLinkTarget> {
+    let mut url = url::Url::parse(target.as_str()).context("parsing document link target")?;
+    if url.scheme() != "file" {
+        return Ok(DocumentLinkTarget::Url(url.to_string()));
+    }
+    let position = url.fragment().and_then(parse_line_fragment);
+    url.set_fragment(None);
+    let target = lsp::Uri::from_str(url.as_str()).context("parsing document link target")?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(target, server_id, cx)
+        })?
+        .await?;
+    let anchor = buffer.read_with(cx, |buffer, _| {
+        let point = buffer.clip_point(position.unwrap_or_default(), Bias::Left);
+        buffer.anchor_before(point)
+    })?;
+    Ok(DocumentLinkTarget::Location(Location {
+        buffer,
+        range: anchor..anchor,
+    }))
+}
+
+/// Parses a `L<line>[,<column>]` fragment of 1-based numbers, ignoring the end of `-` separated ranges.
+fn parse_line_fragment(fragment: &str) -> Option<Point> {
+    let start = fragment.strip_prefix('L')?.split('-').next()?;
+    let (row, column) = match start.split_once(',') {
+        Some((row, column)) => (row, column.parse::<u32>().ok()?),
+        None => (start, 1),
+    };
+    let row = row.parse::<u32>().ok()?;
+    Some(Point::new(row.saturating_sub(1), column.saturating_sub(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_fragment() {
+        assert_eq!(parse_line_fragment("L12"), Some(Point::new(11, 0)));
+        assert_eq!(parse_line_fragment("L12,5"), Some(Point::new(11, 4)));
+        assert_eq!(parse_line_fragment("L1,1-4,4"), Some(Point::new(0, 0)));
+        assert_eq!(parse_line_fragment("section"), None);
+        assert_eq!(parse_line_fragment("Lx"), None);
+    }
+}
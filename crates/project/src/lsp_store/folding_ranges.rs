@@ -0,0 +1,220 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use clock::Global;
+use collections::HashMap;
+use futures::future::{Shared, join_all};
+use gpui::{AppContext as _, Context, Entity, Task};
+use language::{Anchor, Buffer};
+use lsp::{LSP_REQUEST_TIMEOUT, LanguageServerId};
+use util::ResultExt as _;
+
+use crate::{
+    lsp_command::{GetFoldingRanges, LspCommand as _},
+    lsp_store::{LspFetchStrategy, LspStore},
+};
+
+#[derive(Debug, Default, Clone)]
+pub struct BufferFoldingRanges {
+    /// Folding ranges of all language servers of the buffer, sorted by their start.
+    pub ranges: Arc<[Range<Anchor>]>,
+    pub cache_version: Option<usize>,
+}
+
+pub type FoldingRangesTask =
+    Shared<Task<std::result::Result<BufferFoldingRanges, Arc<anyhow::Error>>>>;
+
+#[derive(Debug, Default)]
+pub(super) struct FoldingRangesData {
+    ranges_for_version: Global,
+    ranges: HashMap<LanguageServerId, Vec<Range<Anchor>>>,
+    cache_version: usize,
+    update: Option<(Global, FoldingRangesTask)>,
+}
+
+impl FoldingRangesData {
+    pub(super) fn remove_server(&mut self, server_id: LanguageServerId) {
+        if self.ranges.remove(&server_id).is_some() {
+            self.cache_version += 1;
+        }
+    }
+
+    fn buffer_ranges(&self, buffer: &Buffer) -> BufferFoldingRanges {
+        let mut ranges = self.ranges.values().flatten().cloned().collect::<Vec<_>>();
+        ranges.sort_by(|a, b| {
+            a.start
+                .cmp(&b.start, buffer)
+                .then_with(|| b.end.cmp(&a.end, buffer))
+        });
+        BufferFoldingRanges {
+            ranges: ranges.into(),
+            cache_version: Some(self.cache_version),
+        }
+    }
+}
+
+impl LspStore {
+    pub fn folding_ranges(
+        &mut self,
+        fetch_strategy: LspFetchStrategy,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<FoldingRangesTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        if let LspFetchStrategy::UseCache {
+            known_cache_version,
+        } = fetch_strategy
+            && let Some(cached_data) = self.lsp_folding_ranges.get(&buffer_id)
+            && !version_queried_for.changed_since(&cached_data.ranges_for_version)
+        {
+            let has_different_servers = self.as_local().is_some_and(|local| {
+                local
+                    .buffers_opened_in_servers
+                    .get(&buffer_id)
+                    .cloned()
+                    .unwrap_or_default()
+                    != cached_data.ranges.keys().copied().collect()
+            });
+            if !has_different_servers {
+                if Some(cached_data.cache_version) == known_cache_version {
+                    return None;
+                } else {
+                    return Some(
+                        Task::ready(Ok(cached_data.buffer_ranges(buffer.read(cx)))).shared(),
+                    );
+                }
+            }
+        }
+
+        let lsp_data = self.lsp_folding_ranges.entry(buffer_id).or_default();
+        if let Some((updating_for, running_update)) = &lsp_data.update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return Some(running_update.clone());
+        }
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_ranges = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_folding_ranges_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching folding ranges")
+                    .map_err(Arc::new);
+                let fetched_ranges = match fetched_ranges {
+                    Ok(fetched_ranges) => {
+                        if fetch_strategy != LspFetchStrategy::IgnoreCache
+                            && Some(true)
+                                == buffer
+                                    .update(cx, |buffer, _| {
+                                        buffer.version() != query_version_queried_for
+                                    })
+                                    .ok()
+                        {
+                            return Ok(BufferFoldingRanges::default());
+                        }
+                        fetched_ranges
+                    }
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                lsp_store
+                                    .lsp_folding_ranges
+                                    .entry(buffer_id)
+                                    .or_default()
+                                    .update = None;
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.lsp_folding_ranges.entry(buffer_id).or_default();
+                        if let Some(fetched_ranges) = fetched_ranges {
+                            if lsp_data.ranges_for_version == query_version_queried_for {
+                                lsp_data.ranges.extend(fetched_ranges);
+                                lsp_data.cache_version += 1;
+                            } else if !lsp_data
+                                .ranges_for_version
+                                .changed_since(&query_version_queried_for)
+                            {
+                                lsp_data.ranges_for_version = query_version_queried_for;
+                                lsp_data.ranges = fetched_ranges;
+                                lsp_data.cache_version += 1;
+                            }
+                        }
+                        lsp_data.update = None;
+                        lsp_data.buffer_ranges(buffer.read(cx))
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        lsp_data.update = Some((version_queried_for, new_task.clone()));
+        Some(new_task)
+    }
+
+    fn fetch_folding_ranges_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, Vec<Range<Anchor>>>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetFoldingRanges;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let ranges = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|ranges_response| {
+                            let response = request.response_from_proto(
+                                ranges_response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move {
+                                (
+                                    LanguageServerId::from_proto(ranges_response.server_id),
+                                    response.await.log_err().unwrap_or_default(),
+                                )
+                            }
+                        }),
+                )
+                .await
+                .into_iter()
+                .collect();
+                Ok(Some(ranges))
+            })
+        } else {
+            let ranges_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetFoldingRanges, cx);
+            cx.background_spawn(async move { Ok(Some(ranges_task.await.into_iter().collect())) })
+        }
+    }
+}
@@ -0,0 +1,550 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use clock::Global;
+use collections::HashMap;
+use futures::{
+    FutureExt as _, StreamExt as _,
+    future::{Shared, join_all},
+    stream::FuturesUnordered,
+};
+use gpui::{AppContext as _, Context, Entity, SharedString, Task};
+use language::{Buffer, PointUtf16};
+use lsp::{LSP_REQUEST_TIMEOUT, LanguageServerId};
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{
+    lsp_command::{GetSemanticTokens, LspCommand as _, make_text_document_identifier},
+    lsp_store::{File, LanguageServerToQuery, LspFetchStrategy, LspStore},
+};
+
+/// A single semantic token, decoded with the legend of the language server that reported it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<PointUtf16>,
+    pub token_type: SharedString,
+    pub token_modifiers: Vec<SharedString>,
+}
+
+/// Semantic tokens in the LSP wire format: five integers per token,
+/// with every token positioned relative to the previous one.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawSemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+impl RawSemanticTokens {
+    pub(crate) fn flatten(tokens: Vec<lsp::SemanticToken>) -> Vec<u32> {
+        tokens
+            .into_iter()
+            .flat_map(|token| {
+                [
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    token.token_type,
+                    token.token_modifiers_bitset,
+                ]
+            })
+            .collect()
+    }
+
+    /// Applies the edits of a `textDocument/semanticTokens/full/delta` response.
+    /// Edit offsets refer to the original data, hence the edits are applied back to front.
+    pub(crate) fn apply_edits(&mut self, mut edits: Vec<lsp::SemanticTokensEdit>) {
+        edits.sort_by_key(|edit| edit.start);
+        for edit in edits.into_iter().rev() {
+            let start = (edit.start as usize).min(self.data.len());
+            let end = (start + edit.delete_count as usize).min(self.data.len());
+            self.data
+                .splice(start..end, Self::flatten(edit.data.unwrap_or_default()));
+        }
+    }
+
+    pub(crate) fn decode(&self, legend: &lsp::SemanticTokensLegend) -> Vec<SemanticToken> {
+        let token_types = legend
+            .token_types
+            .iter()
+            .map(|token_type| SharedString::from(token_type.as_str().to_owned()))
+            .collect::<Vec<_>>();
+        let token_modifiers = legend
+            .token_modifiers
+            .iter()
+            .map(|modifier| SharedString::from(modifier.as_str().to_owned()))
+            .collect::<Vec<_>>();
+
+        let mut line = 0;
+        let mut start = 0;
+        self.data
+            .chunks_exact(5)
+            .filter_map(|chunk| {
+                let &[
+                    delta_line,
+                    delta_start,
+                    length,
+                    token_type,
+                    modifiers_bitset,
+                ] = chunk
+                else {
+                    return None;
+                };
+                if delta_line > 0 {
+                    line += delta_line;
+                    start = delta_start;
+                } else {
+                    start += delta_start;
+                }
+                let token_type = token_types.get(token_type as usize)?.clone();
+                let token_modifiers = token_modifiers
+                    .iter()
+                    .take(u32::BITS as usize)
+                    .enumerate()
+                    .filter(|(ix, _)| modifiers_bitset & (1 << ix) != 0)
+                    .map(|(_, modifier)| modifier.clone())
+                    .collect();
+                Some(SemanticToken {
+                    range: PointUtf16::new(line, start)..PointUtf16::new(line, start + length),
+                    token_type,
+                    token_modifiers,
+                })
+            })
+            .collect()
+    }
+}
+
+impl From<lsp::SemanticTokens> for RawSemanticTokens {
+    fn from(tokens: lsp::SemanticTokens) -> Self {
+        Self {
+            result_id: tokens.result_id,
+            data: Self::flatten(tokens.data),
+        }
+    }
+}
+
+pub(crate) fn semantic_tokens_options(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
+fn supports_delta(capabilities: &lsp::ServerCapabilities) -> bool {
+    semantic_tokens_options(capabilities).is_some_and(|options| {
+        matches!(
+            options.full,
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+        )
+    })
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BufferSemanticTokens {
+    pub tokens: HashMap<LanguageServerId, Arc<[SemanticToken]>>,
+    pub cache_version: Option<usize>,
+}
+
+pub type SemanticTokensTask =
+    Shared<Task<std::result::Result<BufferSemanticTokens, Arc<anyhow::Error>>>>;
+
+#[derive(Debug, Default)]
+pub(super) struct SemanticTokensData {
+    tokens_for_version: Global,
+    raw_tokens: HashMap<LanguageServerId, RawSemanticTokens>,
+    tokens: HashMap<LanguageServerId, Arc<[SemanticToken]>>,
+    cache_version: usize,
+    update: Option<(Global, SemanticTokensTask)>,
+}
+
+impl SemanticTokensData {
+    pub(super) fn remove_server(&mut self, server_id: LanguageServerId) {
+        self.raw_tokens.remove(&server_id);
+        if self.tokens.remove(&server_id).is_some() {
+            self.cache_version += 1;
+        }
+    }
+
+    fn buffer_tokens(&self) -> BufferSemanticTokens {
+        BufferSemanticTokens {
+            tokens: self.tokens.clone(),
+            cache_version: Some(self.cache_version),
+        }
+    }
+}
+
+impl LspStore {
+    pub fn semantic_tokens(
+        &mut self,
+        fetch_strategy: LspFetchStrategy,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<SemanticTokensTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        if let LspFetchStrategy::UseCache {
+            known_cache_version,
+        } = fetch_strategy
+            && let Some(cached_data) = self.lsp_semantic_tokens.get(&buffer_id)
+            && !version_queried_for.changed_since(&cached_data.tokens_for_version)
+        {
+            let has_different_servers = self.as_local().is_some_and(|local| {
+                local
+                    .buffers_opened_in_servers
+                    .get(&buffer_id)
+                    .cloned()
+                    .unwrap_or_default()
+                    != cached_data.tokens.keys().copied().collect()
+            });
+            if !has_different_servers {
+                if Some(cached_data.cache_version) == known_cache_version {
+                    return None;
+                } else {
+                    return Some(Task::ready(Ok(cached_data.buffer_tokens())).shared());
+                }
+            }
+        }
+
+        let lsp_data = self.lsp_semantic_tokens.entry(buffer_id).or_default();
+        if let Some((updating_for, running_update)) = &lsp_data.update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return Some(running_update.clone());
+        }
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_tokens = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_semantic_tokens_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching semantic tokens")
+                    .map_err(Arc::new);
+                let fetched_tokens = match fetched_tokens {
+                    Ok(fetched_tokens) => {
+                        if fetch_strategy != LspFetchStrategy::IgnoreCache
+                            && Some(true)
+                                == buffer
+                                    .update(cx, |buffer, _| {
+                                        buffer.version() != query_version_queried_for
+                                    })
+                                    .ok()
+                        {
+                            return Ok(BufferSemanticTokens::default());
+                        }
+                        fetched_tokens
+                    }
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                lsp_store
+                                    .lsp_semantic_tokens
+                                    .entry(buffer_id)
+                                    .or_default()
+                                    .update = None;
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        let decoded_tokens = fetched_tokens.as_ref().map(|fetched_tokens| {
+                            fetched_tokens
+                                .iter()
+                                .map(|(server_id, raw_tokens)| {
+                                    let tokens = lsp_store
+                                        .lsp_server_capabilities
+                                        .get(server_id)
+                                        .and_then(semantic_tokens_options)
+                                        .map(|options| raw_tokens.decode(&options.legend))
+                                        .unwrap_or_default();
+                                    (*server_id, Arc::<[SemanticToken]>::from(tokens))
+                                })
+                                .collect::<HashMap<_, _>>()
+                        });
+                        let lsp_data = lsp_store.lsp_semantic_tokens.entry(buffer_id).or_default();
+                        if let Some((fetched_tokens, decoded_tokens)) =
+                            fetched_tokens.zip(decoded_tokens)
+                        {
+                            if lsp_data.tokens_for_version == query_version_queried_for {
+                                lsp_data.raw_tokens.extend(fetched_tokens);
+                                lsp_data.tokens.extend(decoded_tokens);
+                                lsp_data.cache_version += 1;
+                            } else if !lsp_data
+                                .tokens_for_version
+                                .changed_since(&query_version_queried_for)
+                            {
+                                lsp_data.tokens_for_version = query_version_queried_for;
+                                lsp_data.raw_tokens = fetched_tokens;
+                                lsp_data.tokens = decoded_tokens;
+                                lsp_data.cache_version += 1;
+                            }
+                        }
+                        lsp_data.update = None;
+                        lsp_data.buffer_tokens()
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        lsp_data.update = Some((version_queried_for, new_task.clone()));
+        Some(new_task)
+    }
+
+    fn fetch_semantic_tokens_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, RawSemanticTokens>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetSemanticTokens;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let tokens = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tokens_response| {
+                            let response = request.response_from_proto(
+                                tokens_response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move {
+                                (
+                                    LanguageServerId::from_proto(tokens_response.server_id),
+                                    response.await.log_err().unwrap_or_default(),
+                                )
+                            }
+                        }),
+                )
+                .await
+                .into_iter()
+                .collect();
+                Ok(Some(tokens))
+            })
+        } else {
+            self.fetch_semantic_tokens_locally(buffer, cx)
+        }
+    }
+
+    /// Queries every language server of the buffer for its semantic tokens,
+    /// asking for a delta against the previous response whenever the server supports it.
+    fn fetch_semantic_tokens_locally(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, RawSemanticTokens>>>> {
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(None));
+        };
+        let Some(abs_path) = File::from_dyn(buffer.read(cx).file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(None));
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let servers = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter(|(_, server)| {
+                    GetSemanticTokens.check_capabilities(server.adapter_server_capabilities())
+                })
+                .filter(|(_, server)| {
+                    local
+                        .buffers_opened_in_servers
+                        .get(&buffer_id)
+                        .is_some_and(|servers| servers.contains(&server.server_id()))
+                })
+                .map(|(_, server)| server.clone())
+                .collect::<Vec<_>>()
+        });
+
+        let mut responses = FuturesUnordered::new();
+        for server in servers {
+            let server_id = server.server_id();
+            let previous_tokens = self
+                .lsp_semantic_tokens
+                .get(&buffer_id)
+                .and_then(|lsp_data| lsp_data.raw_tokens.get(&server_id))
+                .filter(|previous_tokens| previous_tokens.result_id.is_some())
+                .filter(|_| supports_delta(&server.capabilities()))
+                .cloned();
+            match previous_tokens {
+                Some(mut previous_tokens) => {
+                    let params = match make_text_document_identifier(&abs_path) {
+                        Ok(text_document) => lsp::SemanticTokensDeltaParams {
+                            text_document,
+                            previous_result_id: previous_tokens
+                                .result_id
+                                .take()
+                                .unwrap_or_default(),
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                        Err(e) => return Task::ready(Err(e)),
+                    };
+                    let request =
+                        server.request::<lsp::request::SemanticTokensFullDeltaRequest>(params);
+                    responses.push(
+                        async move {
+                            let tokens = match request.await.into_response()? {
+                                Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                                    RawSemanticTokens::from(tokens)
+                                }
+                                Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                                    previous_tokens.apply_edits(delta.edits);
+                                    previous_tokens.result_id = delta.result_id;
+                                    previous_tokens
+                                }
+                                Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta {
+                                    edits,
+                                }) => {
+                                    previous_tokens.apply_edits(edits);
+                                    previous_tokens
+                                }
+                                None => RawSemanticTokens::default(),
+                            };
+                            anyhow::Ok((server_id, tokens))
+                        }
+                        .boxed(),
+                    );
+                }
+                None => {
+                    let request = self.request_lsp(
+                        buffer.clone(),
+                        LanguageServerToQuery::Other(server_id),
+                        GetSemanticTokens,
+                        cx,
+                    );
+                    responses.push(async move { Ok((server_id, request.await?)) }.boxed());
+                }
+            }
+        }
+
+        cx.background_spawn(async move {
+            let mut tokens = HashMap::default();
+            while let Some(response) = responses.next().await {
+                if let Some((server_id, server_tokens)) = response.log_err() {
+                    tokens.insert(server_id, server_tokens);
+                }
+            }
+            Ok(Some(tokens))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legend() -> lsp::SemanticTokensLegend {
+        lsp::SemanticTokensLegend {
+            token_types: vec![
+                lsp::SemanticTokenType::FUNCTION,
+                lsp::SemanticTokenType::VARIABLE,
+            ],
+            token_modifiers: vec![
+                lsp::SemanticTokenModifier::DECLARATION,
+                lsp::SemanticTokenModifier::READONLY,
+            ],
+        }
+    }
+
+    #[test]
+    fn test_decode_semantic_tokens() {
+        let raw_tokens = RawSemanticTokens {
+            result_id: None,
+            data: vec![
+                // `main` at 0:3, a function declaration.
+                0, 3, 4, 0, 0b01, //
+                // `x` at 1:8, a readonly variable declaration.
+                1, 8, 1, 1, 0b11, //
+                // `x` at 1:12, unknown token type.
+                0, 4, 1, 7, 0, //
+                // `x` at 1:16.
+                0, 4, 1, 1, 0,
+            ],
+        };
+        let tokens = raw_tokens.decode(&legend());
+        assert_eq!(
+            tokens,
+            vec![
+                SemanticToken {
+                    range: PointUtf16::new(0, 3)..PointUtf16::new(0, 7),
+                    token_type: "function".into(),
+                    token_modifiers: vec!["declaration".into()],
+                },
+                SemanticToken {
+                    range: PointUtf16::new(1, 8)..PointUtf16::new(1, 9),
+                    token_type: "variable".into(),
+                    token_modifiers: vec!["declaration".into(), "readonly".into()],
+                },
+                SemanticToken {
+                    range: PointUtf16::new(1, 16)..PointUtf16::new(1, 17),
+                    token_type: "variable".into(),
+                    token_modifiers: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_semantic_token_edits() {
+        let mut raw_tokens = RawSemanticTokens {
+            result_id: Some("1".into()),
+            data: vec![0, 3, 4, 0, 0, 1, 8, 1, 1, 0, 0, 4, 1, 1, 0],
+        };
+        raw_tokens.apply_edits(vec![
+            lsp::SemanticTokensEdit {
+                start: 10,
+                delete_count: 5,
+                data: None,
+            },
+            lsp::SemanticTokensEdit {
+                start: 0,
+                delete_count: 0,
+                data: Some(vec![lsp::SemanticToken {
+                    delta_line: 0,
+                    delta_start: 0,
+                    length: 2,
+                    token_type: 1,
+                    token_modifiers_bitset: 0,
+                }]),
+            },
+        ]);
+        assert_eq!(
+            raw_tokens.data,
+            vec![0, 0, 2, 1, 0, 0, 3, 4, 0, 0, 1, 8, 1, 1, 0]
+        );
+    }
+}
@@ -13,6 +13,7 @@ mod manifest_tree;
 pub mod prettier_store;
 pub mod project_settings;
 pub mod search;
+mod search_index;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
@@ -80,8 +81,8 @@ use gpui::{
 };
 use language::{
     Buffer, BufferEvent, Capability, CodeLabel, CursorShape, Language, LanguageName,
-    LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainMetadata,
-    ToolchainScope, Transaction, Unclipped, language_settings::InlayHintKind,
+    LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16, Toolchain,
+    ToolchainMetadata, ToolchainScope, Transaction, Unclipped, language_settings::InlayHintKind,
     proto::split_operations,
 };
 use lsp::{
@@ -139,7 +140,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskSourceKind, TaskWithDependencies,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -339,6 +340,7 @@ pub enum Event {
     Rejoined,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -735,6 +737,58 @@ pub struct LocationLink {
     pub target: Location,
 }
 
+#[derive(Debug, Clone)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range enclosing the symbol, including its body.
+    pub location: Location,
+    /// The range to reveal when navigating to the symbol, e.g. its name.
+    pub selection_range: Range<language::Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The item as returned by the language server, sent back when querying its calls.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+#[derive(Debug, Clone)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, the callee for outgoing ones.
+    pub item: CallHierarchyItem,
+    /// The call expressions, located in the caller.
+    pub call_sites: Vec<Location>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range enclosing the type, including its body.
+    pub location: Location,
+    /// The range to reveal when navigating to the type, e.g. its name.
+    pub selection_range: Range<language::Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The item as returned by the language server, sent back when querying its super- and subtypes.
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The link as returned by the language server, sent back when resolving its target.
+    pub lsp_link: lsp::DocumentLink,
+}
+
+#[derive(Debug, Clone)]
+pub enum DocumentLinkTarget {
+    /// A non-file URI, to be opened externally.
+    Url(String),
+    /// A position in a project or local file.
+    Location(Location),
+}
+
 #[derive(Debug)]
 pub struct DocumentHighlight {
     pub range: Range<language::Anchor>,
@@ -2998,6 +3052,7 @@ impl Project {
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3635,6 +3690,106 @@ impl Project {
         })
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<CallHierarchyItem>>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.prepare_call_hierarchy(buffer, position, cx)
+        });
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.incoming_calls(item, cx));
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.outgoing_calls(item, cx));
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Vec<TypeHierarchyItem>>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.prepare_type_hierarchy(buffer, position, cx)
+        });
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.supertypes(item, cx));
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.subtypes(item, cx));
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
     pub fn document_highlights<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -3742,6 +3897,47 @@ impl Project {
         })
     }
 
+    /// Queries the selection ranges around each of the positions, from the innermost to the outermost.
+    pub fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<Anchor>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.selection_ranges(buffer, positions, cx)
+        })
+    }
+
+    /// Queries the links reported by all language servers of the buffer, sorted by their start.
+    pub fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        let links_task = self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.document_links(buffer.clone(), cx)
+        });
+        cx.background_spawn(async move {
+            links_task
+                .await
+                .map(|links| links.to_vec())
+                .map_err(|e| anyhow!("{e:#}"))
+        })
+    }
+
+    /// Resolves the target of the link, returning `None` if the language server reported none.
+    pub fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: DocumentLink,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<DocumentLinkTarget>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(buffer.clone(), link, cx)
+        })
+    }
+
     pub fn completions<T: ToOffset + ToPointUtf16>(
         &self,
         buffer: &Entity<Buffer>,
@@ -3950,7 +4146,7 @@ impl Project {
             self.find_search_candidate_buffers(&query, MAX_SEARCH_RESULT_FILES + 1, cx)
         };
 
-        cx.spawn(async move |_, cx| {
+        cx.spawn(async move |this, cx| {
             let mut range_count = 0;
             let mut buffer_count = 0;
             let mut limit_reached = false;
@@ -3963,6 +4159,16 @@ impl Project {
             // ranges in the buffer matched by the query.
             let mut chunks = pin!(chunks);
             'outer: while let Some(matching_buffer_chunk) = chunks.next().await {
+                if query.is_structural() {
+                    let parsed = this.update(cx, |this, cx| {
+                        matching_buffer_chunk
+                            .iter()
+                            .map(|buffer| this.wait_for_buffer_syntax(buffer, cx))
+                            .collect::<Vec<_>>()
+                    })?;
+                    futures::future::join_all(parsed).await;
+                }
+
                 let mut chunk_results = Vec::with_capacity(matching_buffer_chunk.len());
                 for buffer in matching_buffer_chunk {
                     let query = query.clone();
@@ -3984,6 +4190,11 @@ impl Project {
                 let chunk_results = futures::future::join_all(chunk_results).await;
                 for result in chunk_results {
                     if let Some((buffer, ranges)) = result.log_err() {
+                        // Open buffers are always candidates, and structural queries only
+                        // prefilter files by a word they require, so candidates may not match.
+                        if ranges.is_empty() {
+                            continue;
+                        }
                         range_count += ranges.len();
                         buffer_count += 1;
                         result_tx
@@ -4010,6 +4221,52 @@ impl Project {
         result_rx
     }
 
+    /// Assigns a language to a buffer found by a structural search if it has none yet, and
+    /// waits for the buffer to be parsed.
+    fn wait_for_buffer_syntax(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        let language = {
+            let buffer = buffer.read(cx);
+            if buffer.language().is_none()
+                && let Some(file) = buffer.file()
+            {
+                self.languages
+                    .language_for_file(file, Some(buffer.as_rope()), cx)
+                    .map(|available_language| self.languages.load_language(&available_language))
+            } else {
+                None
+            }
+        };
+        let buffer = buffer.clone();
+        cx.spawn(async move |this, cx| {
+            if let Some(language) = language
+                && let Ok(Some(language)) = language.await.map(|language| language.log_err())
+            {
+                this.update(cx, |this, cx| {
+                    if buffer.read(cx).language().is_none() {
+                        this.lsp_store.update(cx, |lsp_store, cx| {
+                            lsp_store.set_language_for_buffer(&buffer, language, cx)
+                        });
+                    }
+                })
+                .ok();
+            }
+
+            let Ok(mut parse_status) = buffer.read_with(cx, |buffer, _| buffer.parse_status())
+            else {
+                return;
+            };
+            while *parse_status.borrow() != ParseStatus::Idle {
+                if parse_status.changed().await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
     fn find_search_candidate_buffers(
         &mut self,
         query: &SearchQuery,
@@ -5173,6 +5430,35 @@ impl Project {
         })
     }
 
+    pub fn any_language_server_supports_selection_ranges(
+        &self,
+        buffer: &Buffer,
+        cx: &mut App,
+    ) -> bool {
+        let Some(language) = buffer.language().cloned() else {
+            return false;
+        };
+        self.lsp_store.update(cx, |lsp_store, _| {
+            let relevant_language_servers = lsp_store
+                .languages
+                .lsp_adapters(&language.name())
+                .into_iter()
+                .map(|lsp_adapter| lsp_adapter.name())
+                .collect::<HashSet<_>>();
+            lsp_store
+                .language_server_statuses()
+                .filter_map(|(server_id, server_status)| {
+                    relevant_language_servers
+                        .contains(&server_status.name)
+                        .then_some(server_id)
+                })
+                .filter_map(|server_id| lsp_store.lsp_server_capabilities.get(&server_id))
+                .any(|capabilities| {
+                    GetSelectionRanges::check_server_capabilities(capabilities.clone())
+                })
+        })
+    }
+
     pub fn language_server_id_for_name(
         &self,
         buffer: &Buffer,
@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use context_server::ContextServerCommand;
+use context_server::{ContextServerCommand, ContextServerEndpoint};
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
@@ -125,6 +125,14 @@ pub enum ContextServerSettings {
         #[serde(flatten)]
         command: ContextServerCommand,
     },
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+
+        #[serde(flatten)]
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         /// Whether the context server is enabled.
         #[serde(default = "default_true")]
@@ -143,6 +151,9 @@ impl From<settings::ContextServerSettingsContent> for ContextServerSettings {
             settings::ContextServerSettingsContent::Custom { enabled, command } => {
                 ContextServerSettings::Custom { enabled, command }
             }
+            settings::ContextServerSettingsContent::Http { enabled, endpoint } => {
+                ContextServerSettings::Http { enabled, endpoint }
+            }
             settings::ContextServerSettingsContent::Extension { enabled, settings } => {
                 ContextServerSettings::Extension { enabled, settings }
             }
@@ -155,6 +166,9 @@ impl Into<settings::ContextServerSettingsContent> for ContextServerSettings {
             ContextServerSettings::Custom { enabled, command } => {
                 settings::ContextServerSettingsContent::Custom { enabled, command }
             }
+            ContextServerSettings::Http { enabled, endpoint } => {
+                settings::ContextServerSettingsContent::Http { enabled, endpoint }
+            }
             ContextServerSettings::Extension { enabled, settings } => {
                 settings::ContextServerSettingsContent::Extension { enabled, settings }
             }
@@ -173,6 +187,7 @@ impl ContextServerSettings {
     pub fn enabled(&self) -> bool {
         match self {
             ContextServerSettings::Custom { enabled, .. } => *enabled,
+            ContextServerSettings::Http { enabled, .. } => *enabled,
             ContextServerSettings::Extension { enabled, .. } => *enabled,
         }
     }
@@ -180,6 +195,7 @@ impl ContextServerSettings {
     pub fn set_enabled(&mut self, enabled: bool) {
         match self {
             ContextServerSettings::Custom { enabled: e, .. } => *e = enabled,
+            ContextServerSettings::Http { enabled: e, .. } => *e = enabled,
             ContextServerSettings::Extension { enabled: e, .. } => *e = enabled,
         }
     }
@@ -553,29 +569,42 @@ impl Settings for ProjectSettings {
             env: Option<HashMap<String, String>>,
             // note: we don't support envFile and type
         }
+        #[derive(Deserialize)]
+        struct VsCodeContextServerUrl {
+            url: String,
+            headers: Option<HashMap<String, String>>,
+        }
         if let Some(mcp) = vscode.read_value("mcp").and_then(|v| v.as_object()) {
             current
                 .project
                 .context_servers
                 .extend(mcp.iter().filter_map(|(k, v)| {
-                    Some((
-                        k.clone().into(),
+                    let settings = if let Ok(cmd) =
+                        serde_json::from_value::<VsCodeContextServerCommand>(v.clone())
+                    {
                         settings::ContextServerSettingsContent::Custom {
                             enabled: true,
-                            command: serde_json::from_value::<VsCodeContextServerCommand>(
-                                v.clone(),
-                            )
-                            .ok()
-                            .map(|cmd| {
-                                settings::ContextServerCommand {
-                                    path: cmd.command,
-                                    args: cmd.args.unwrap_or_default(),
-                                    env: cmd.env,
-                                    timeout: None,
-                                }
-                            })?,
-                        },
-                    ))
+                            command: settings::ContextServerCommand {
+                                path: cmd.command,
+                                args: cmd.args.unwrap_or_default(),
+                                env: cmd.env,
+                                timeout: None,
+                            },
+                        }
+                    } else {
+                        let server =
+                            serde_json::from_value::<VsCodeContextServerUrl>(v.clone()).ok()?;
+                        settings::ContextServerSettingsContent::Http {
+                            enabled: true,
+                            endpoint: settings::ContextServerEndpoint {
+                                url: server.url,
+                                headers: server.headers,
+                                bearer_token: None,
+                                timeout: None,
+                            },
+                        }
+                    };
+                    Some((k.clone().into(), settings))
                 }));
         }
 
@@ -5453,6 +5453,84 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "main.rs": "fn main() {\n    foo(a, \"x\");\n    foo(b, c);\n    foo(d, \"y\", e);\n}\n",
+            "lib.rs": "fn f() { foo(x, \"z\"); }",
+            "notes.txt": "foo(a, \"x\")",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let structural_query = |pattern: &str| {
+        SearchQuery::structural(
+            pattern,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        search(&project, structural_query(r#"foo($A, "x")"#), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/main.rs").to_string(), vec![16..27])])
+    );
+    assert_eq!(
+        search(&project, structural_query("foo($$$ARGS)"), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([
+            (
+                path!("dir/main.rs").to_string(),
+                vec![16..27, 33..42, 48..62]
+            ),
+            (path!("dir/lib.rs").to_string(), vec![9..20]),
+        ])
+    );
+    // Calls to `foo` whose second and last argument is a string literal.
+    assert_eq!(
+        search(
+            &project,
+            structural_query(
+                r#"(call_expression
+                    function: (identifier) @name (#eq? @name "foo")
+                    arguments: (arguments . (_) . (string_literal) .)) @match"#
+            ),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([
+            (path!("dir/main.rs").to_string(), vec![16..27]),
+            (path!("dir/lib.rs").to_string(), vec![9..20]),
+        ])
+    );
+
+    let rust = rust_lang();
+    let query = structural_query("foo($A, $$$REST)").with_replacement("bar($$$REST, ${A})".into());
+    assert_eq!(
+        query
+            .replacement_for_language(r#"foo(d, "y", e)"#, Some(&rust))
+            .as_deref(),
+        Some(r#"bar("y", e, d)"#)
+    );
+    assert_eq!(query.replacement_for_language("baz(d)", Some(&rust)), None);
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
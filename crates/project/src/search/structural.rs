@@ -0,0 +1,608 @@
+//! Syntax-aware matching for structural searches.
+//!
+//! A code pattern is parsed with the language of each searched buffer, and matches every syntax
+//! node of the same shape. Inside a pattern, `$NAME` matches any single node, `$$$NAME` matches
+//! any number of sibling nodes, and `$_` matches a node without binding it. A metavariable used
+//! more than once must match the same text every time.
+//!
+//! A pattern starting with `(` that captures nodes with `@` is a raw tree-sitter query instead.
+//! The node captured as `@match` is reported as the match, or the first captured node if the
+//! query has no such capture.
+//!
+//! Replacements refer to the text bound to metavariables, or to query captures, as `$NAME`,
+//! `${NAME}` or `$$$NAME`.
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use language::{BufferSnapshot, Grammar, GrammarId, Node, Tree, with_parser, with_query_cursor};
+use parking_lot::Mutex;
+use std::{fmt, ops::Range, sync::Arc};
+use streaming_iterator::StreamingIterator as _;
+use tree_sitter::Query;
+
+/// The identifiers metavariables are replaced with before parsing a pattern,
+/// so that they parse as ordinary identifiers in most languages.
+const METAVARIABLE_PREFIX: &str = "__zed_mv_";
+const MULTI_METAVARIABLE_PREFIX: &str = "__zed_seq_";
+const MATCH_CAPTURE: &str = "match";
+
+type Bindings = HashMap<String, String>;
+
+pub struct StructuralPattern {
+    source: Arc<str>,
+    kind: PatternKind,
+    /// The pattern compiled for each grammar it was used with, or `None` if it is not valid in
+    /// that grammar.
+    compiled: Mutex<HashMap<GrammarId, Option<Arc<CompiledPattern>>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatternKind {
+    Code,
+    Query,
+}
+
+enum CompiledPattern {
+    Code {
+        ts_language: tree_sitter::Language,
+        /// The pattern, with its metavariables replaced by placeholder identifiers.
+        text: String,
+        tree: Tree,
+        root: Range<usize>,
+    },
+    Query {
+        ts_language: tree_sitter::Language,
+        query: Query,
+        match_capture_ix: Option<u32>,
+    },
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralPattern")
+            .field("source", &self.source)
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StructuralPattern {
+    pub fn new(source: &str) -> Result<Self> {
+        let trimmed = source.trim();
+        let kind = if trimmed.starts_with('(') && trimmed.contains('@') {
+            PatternKind::Query
+        } else {
+            PatternKind::Code
+        };
+        if kind == PatternKind::Code {
+            let text = replace_metavariables(trimmed);
+            anyhow::ensure!(
+                metavariable_name(&text).is_none(),
+                "a structural pattern must contain more than a metavariable"
+            );
+        }
+        Ok(Self {
+            source: source.into(),
+            kind,
+            compiled: Mutex::new(HashMap::default()),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_query(&self) -> bool {
+        self.kind == PatternKind::Query
+    }
+
+    /// A word every file matching this pattern must contain, used to skip files without
+    /// parsing them.
+    pub fn required_word(&self) -> Option<String> {
+        let words: Vec<&str> = match self.kind {
+            PatternKind::Code => {
+                let mut words = Vec::new();
+                let mut chars = self.source.char_indices().peekable();
+                while let Some((start, c)) = chars.next() {
+                    if c == '$' {
+                        while chars
+                            .next_if(|(_, c)| *c == '$' || *c == '{' || is_word_char(*c))
+                            .is_some()
+                        {}
+                    } else if is_word_char(c) {
+                        let mut end = start + c.len_utf8();
+                        while let Some((ix, c)) = chars.next_if(|(_, c)| is_word_char(*c)) {
+                            end = ix + c.len_utf8();
+                        }
+                        words.push(&self.source[start..end]);
+                    }
+                }
+                words
+            }
+            // Only `#eq?` predicates restrict the text of a match.
+            PatternKind::Query => self
+                .source
+                .split("#eq?")
+                .skip(1)
+                .filter_map(|predicate| {
+                    let literal = predicate.split('"').nth(1)?;
+                    let before = predicate.split('"').next()?;
+                    (!before.contains(')')).then_some(literal)
+                })
+                .collect(),
+        };
+        words
+            .into_iter()
+            .max_by_key(|word| word.len())
+            .map(ToString::to_string)
+    }
+
+    /// Returns the ranges of all nodes in the buffer matching this pattern, in order.
+    pub fn search(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(grammar) = layer.language.grammar() else {
+                continue;
+            };
+            let Some(compiled) = self.compiled_for(grammar) else {
+                continue;
+            };
+            let root = layer.node();
+            if root.end_byte() <= range.start || root.start_byte() >= range.end {
+                continue;
+            }
+            let text = |range: Range<usize>| buffer.text_for_range(range).collect::<String>();
+            match compiled.as_ref() {
+                CompiledPattern::Code {
+                    text: pattern_text,
+                    tree,
+                    root: pattern_root,
+                    ..
+                } => {
+                    let Some(pattern_root) = node_for_range(tree, pattern_root.clone()) else {
+                        continue;
+                    };
+                    let matcher = Matcher {
+                        pattern_text,
+                        target_text: &text,
+                    };
+                    matcher.find_all(pattern_root, root, &range, &mut matches);
+                }
+                CompiledPattern::Query {
+                    query,
+                    match_capture_ix,
+                    ..
+                } => {
+                    for (match_range, _) in
+                        query_matches(query, *match_capture_ix, root, range.clone(), &text)
+                    {
+                        matches.push(match_range);
+                    }
+                }
+            }
+        }
+        matches.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+        // Nested matches, including ones from injected languages, are dropped.
+        let mut last_end = 0;
+        matches.retain(|range| {
+            let keep = range.start >= last_end;
+            if keep {
+                last_end = range.end;
+            }
+            keep
+        });
+        matches
+    }
+
+    /// Substitutes the metavariables in `replacement` with the text they are bound to when
+    /// matching `text`, which must be a match of this pattern in a buffer of the given grammar.
+    ///
+    /// Without a grammar, every grammar the pattern was searched with is tried.
+    pub fn replace(
+        &self,
+        text: &str,
+        replacement: &str,
+        grammar: Option<&Arc<Grammar>>,
+    ) -> Option<String> {
+        let candidates = match grammar {
+            Some(grammar) => self.compiled_for(grammar).into_iter().collect::<Vec<_>>(),
+            None => self.compiled.lock().values().flatten().cloned().collect(),
+        };
+        candidates
+            .iter()
+            .find_map(|compiled| compiled.bindings_for(text))
+            .map(|bindings| substitute(replacement, &bindings))
+    }
+
+    fn compiled_for(&self, grammar: &Arc<Grammar>) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| {
+                let compiled = match self.kind {
+                    PatternKind::Code => compile_code(&self.source, &grammar.ts_language),
+                    PatternKind::Query => compile_query(&self.source, &grammar.ts_language),
+                };
+                compiled.ok().map(Arc::new)
+            })
+            .clone()
+    }
+}
+
+impl CompiledPattern {
+    /// Matches a single node spanning the whole `text`, and returns its bindings.
+    fn bindings_for(&self, text: &str) -> Option<Bindings> {
+        let target_text = |range: Range<usize>| text[range].to_string();
+        match self {
+            CompiledPattern::Code {
+                ts_language,
+                text: pattern_text,
+                tree,
+                root,
+            } => {
+                let (target_tree, target_root) = parse_fragment(ts_language, text).ok()?;
+                let pattern_root = node_for_range(tree, root.clone())?;
+                let target = node_for_range(&target_tree, target_root)?;
+                let matcher = Matcher {
+                    pattern_text,
+                    target_text: &target_text,
+                };
+                let mut bindings = Bindings::default();
+                matcher
+                    .match_node(pattern_root, target, &mut bindings)
+                    .then_some(bindings)
+            }
+            CompiledPattern::Query {
+                ts_language,
+                query,
+                match_capture_ix,
+            } => {
+                let tree = with_parser(|parser| {
+                    parser.set_language(ts_language).ok()?;
+                    parser.parse(text, None)
+                })?;
+                let whole = text.len() - text.trim_start().len()..text.trim_end().len();
+                query_matches(
+                    query,
+                    *match_capture_ix,
+                    tree.root_node(),
+                    0..text.len(),
+                    &target_text,
+                )
+                .into_iter()
+                .find(|(range, _)| *range == whole)
+                .map(|(_, bindings)| bindings)
+            }
+        }
+    }
+}
+
+fn compile_code(source: &str, ts_language: &tree_sitter::Language) -> Result<CompiledPattern> {
+    let text = replace_metavariables(source.trim());
+    let (tree, root) = parse_fragment(ts_language, &text)?;
+    Ok(CompiledPattern::Code {
+        ts_language: ts_language.clone(),
+        text,
+        tree,
+        root,
+    })
+}
+
+fn compile_query(source: &str, ts_language: &tree_sitter::Language) -> Result<CompiledPattern> {
+    let query = Query::new(ts_language, source)?;
+    let match_capture_ix = query.capture_index_for_name(MATCH_CAPTURE);
+    Ok(CompiledPattern::Query {
+        ts_language: ts_language.clone(),
+        query,
+        match_capture_ix,
+    })
+}
+
+/// Parses a code fragment, returning its tree and the range of the node spanning all of it.
+///
+/// Fragments that are not valid on their own, like a C expression without its semicolon,
+/// are parsed again as a statement.
+fn parse_fragment(ts_language: &tree_sitter::Language, text: &str) -> Result<(Tree, Range<usize>)> {
+    let content = text.len() - text.trim_start().len()..text.trim_end().len();
+    for candidate in [text.to_string(), format!("{};", text.trim_end())] {
+        let tree = with_parser(|parser| {
+            parser.set_language(ts_language)?;
+            parser
+                .parse(&candidate, None)
+                .context("parsing was cancelled")
+        })?;
+        if tree.root_node().has_error() {
+            continue;
+        }
+        let Some(node) = tree
+            .root_node()
+            .named_descendant_for_byte_range(content.start, content.end)
+        else {
+            continue;
+        };
+        let root = node.byte_range();
+        return Ok((tree, root));
+    }
+    anyhow::bail!("pattern is not valid in this language")
+}
+
+/// Finds the innermost node spanning the given range, skipping the statements and other
+/// wrappers a fragment is parsed into.
+fn node_for_range(tree: &Tree, range: Range<usize>) -> Option<Node<'_>> {
+    tree.root_node()
+        .named_descendant_for_byte_range(range.start, range.end)
+}
+
+fn query_matches(
+    query: &Query,
+    match_capture_ix: Option<u32>,
+    root: Node,
+    range: Range<usize>,
+    text: &dyn Fn(Range<usize>) -> String,
+) -> Vec<(Range<usize>, Bindings)> {
+    with_query_cursor(|cursor| {
+        cursor.set_byte_range(range);
+        let mut results = Vec::new();
+        let mut matches = cursor.matches(query, root, |node: Node| {
+            std::iter::once(text(node.byte_range()))
+        });
+        while let Some(mat) = matches.next() {
+            let matched = match match_capture_ix {
+                Some(ix) => mat
+                    .captures
+                    .iter()
+                    .find(|capture| capture.index == ix)
+                    .map(|capture| capture.node.byte_range()),
+                None => mat
+                    .captures
+                    .first()
+                    .map(|capture| capture.node.byte_range()),
+            };
+            let Some(matched) = matched else {
+                continue;
+            };
+            let bindings = mat
+                .captures
+                .iter()
+                .map(|capture| {
+                    let name = query.capture_names()[capture.index as usize].to_string();
+                    (name, text(capture.node.byte_range()))
+                })
+                .collect();
+            results.push((matched, bindings));
+        }
+        results
+    })
+}
+
+struct Matcher<'a> {
+    pattern_text: &'a str,
+    target_text: &'a dyn Fn(Range<usize>) -> String,
+}
+
+impl Matcher<'_> {
+    /// Pushes the range of every node under `root` matching the pattern, skipping the
+    /// descendants of matching nodes.
+    fn find_all(
+        &self,
+        pattern: Node,
+        root: Node,
+        range: &Range<usize>,
+        matches: &mut Vec<Range<usize>>,
+    ) {
+        let mut cursor = root.walk();
+        loop {
+            let node = cursor.node();
+            let overlaps = node.end_byte() > range.start && node.start_byte() < range.end;
+            let mut bindings = Bindings::default();
+            if overlaps
+                && node.kind_id() == pattern.kind_id()
+                && self.match_node(pattern, node, &mut bindings)
+            {
+                matches.push(node.byte_range());
+            } else if overlaps && cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn match_node(&self, pattern: Node, target: Node, bindings: &mut Bindings) -> bool {
+        let pattern_text = &self.pattern_text[pattern.byte_range()];
+        if let Some(name) = metavariable_name(pattern_text) {
+            return bind(name, (self.target_text)(target.byte_range()), bindings);
+        }
+        if pattern.kind_id() != target.kind_id() {
+            return false;
+        }
+
+        let pattern_children = children(pattern);
+        let target_children = children(target);
+        if pattern_children.is_empty() {
+            return target_children.is_empty()
+                && pattern_text == (self.target_text)(target.byte_range());
+        }
+        self.match_sequence(&pattern_children, &target_children, bindings)
+    }
+
+    fn match_sequence(&self, patterns: &[Node], targets: &[Node], bindings: &mut Bindings) -> bool {
+        let Some((pattern, rest)) = patterns.split_first() else {
+            return targets.is_empty();
+        };
+
+        if let Some(name) = multi_metavariable_name(&self.pattern_text[pattern.byte_range()]) {
+            for count in 0..=targets.len() {
+                let text = match (targets[..count].first(), targets[..count].last()) {
+                    (Some(first), Some(last)) => {
+                        (self.target_text)(first.start_byte()..last.end_byte())
+                    }
+                    _ => String::new(),
+                };
+                let mut candidate = bindings.clone();
+                if bind(name, text, &mut candidate)
+                    && self.match_sequence(rest, &targets[count..], &mut candidate)
+                {
+                    *bindings = candidate;
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        let Some((target, target_rest)) = targets.split_first() else {
+            return false;
+        };
+        let mut candidate = bindings.clone();
+        if self.match_node(*pattern, *target, &mut candidate)
+            && self.match_sequence(rest, target_rest, &mut candidate)
+        {
+            *bindings = candidate;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The children of a node, without comments and other extras.
+fn children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra())
+        .collect()
+}
+
+fn bind(name: &str, text: String, bindings: &mut Bindings) -> bool {
+    if name == "_" {
+        return true;
+    }
+    match bindings.get(name) {
+        Some(bound) => *bound == text,
+        None => {
+            bindings.insert(name.to_string(), text);
+            true
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn metavariable_name(text: &str) -> Option<&str> {
+    text.strip_prefix(METAVARIABLE_PREFIX)
+        .filter(|name| !name.is_empty() && name.chars().all(is_word_char))
+}
+
+fn multi_metavariable_name(text: &str) -> Option<&str> {
+    text.strip_prefix(MULTI_METAVARIABLE_PREFIX)
+        .filter(|name| !name.is_empty() && name.chars().all(is_word_char))
+}
+
+/// Replaces `$NAME` and `$$$NAME` with placeholder identifiers. A bare `$$$` is the same
+/// as `$$$_`.
+fn replace_metavariables(pattern: &str) -> String {
+    let mut result = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(ix) = rest.find('$') {
+        result.push_str(&rest[..ix]);
+        rest = &rest[ix..];
+        let (prefix, after) = match rest.strip_prefix("$$$") {
+            Some(after) => (MULTI_METAVARIABLE_PREFIX, after),
+            None => (METAVARIABLE_PREFIX, &rest[1..]),
+        };
+        let name_len = after
+            .find(|c: char| !is_word_char(c))
+            .unwrap_or(after.len());
+        if name_len == 0 && prefix == METAVARIABLE_PREFIX {
+            result.push('$');
+            rest = after;
+            continue;
+        }
+        result.push_str(prefix);
+        result.push_str(if name_len == 0 {
+            "_"
+        } else {
+            &after[..name_len]
+        });
+        rest = &after[name_len..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Substitutes `$NAME`, `${NAME}` and `$$$NAME` in a replacement with their bindings.
+/// Unbound names are left as they are.
+fn substitute(replacement: &str, bindings: &Bindings) -> String {
+    let mut result = String::with_capacity(replacement.len());
+    let mut rest = replacement;
+    while let Some(ix) = rest.find('$') {
+        result.push_str(&rest[..ix]);
+        rest = &rest[ix..];
+        let after = rest.strip_prefix("$$$").unwrap_or(&rest[1..]);
+        let (name, after_name) = if let Some(braced) = after.strip_prefix('{')
+            && let Some(end) = braced.find('}')
+        {
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let len = after
+                .find(|c: char| !is_word_char(c))
+                .unwrap_or(after.len());
+            (&after[..len], &after[len..])
+        };
+        match bindings.get(name) {
+            Some(text) => {
+                result.push_str(text);
+                rest = after_name;
+            }
+            None => {
+                result.push('$');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_metavariables() {
+        assert_eq!(
+            replace_metavariables("foo($A, $$$REST) + $$$ + $"),
+            "foo(__zed_mv_A, __zed_seq_REST) + __zed_seq__ + $"
+        );
+    }
+
+    #[test]
+    fn test_substitute() {
+        let bindings = Bindings::from_iter([
+            ("A".to_string(), "x".to_string()),
+            ("ARGS".to_string(), "1, 2".to_string()),
+        ]);
+        assert_eq!(
+            substitute("bar(${A}_suffix, $$$ARGS, $B, $)", &bindings),
+            "bar(x_suffix, 1, 2, $B, $)"
+        );
+    }
+
+    #[test]
+    fn test_required_word() {
+        let pattern = StructuralPattern::new(r#"foo($A, "literal_text")"#).unwrap();
+        assert_eq!(pattern.required_word().as_deref(), Some("literal_text"));
+
+        let pattern =
+            StructuralPattern::new(r#"((identifier) @name (#eq? @name "needle")) @match"#).unwrap();
+        assert!(pattern.is_query());
+        assert_eq!(pattern.required_word().as_deref(), Some("needle"));
+
+        assert!(StructuralPattern::new("$A").is_err());
+    }
+}
@@ -44,6 +44,10 @@ impl SearchHistory {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
     pub fn add(&mut self, cursor: &mut SearchHistoryCursor, search_string: String) {
         if self.insertion_behavior == QueryInsertionBehavior::ReplacePreviousIfContains
             && let Some(previously_searched) = self.history.back_mut()
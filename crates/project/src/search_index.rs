@@ -0,0 +1,654 @@
+//! A persistent trigram index of the files in a local worktree, used to skip files that cannot
+//! contain the literal text of a search query without reading them.
+//!
+//! The index is enabled with the `search_index` setting. It is stored in Zed's data directory,
+//! loaded when the worktree is opened, reconciled with the files on disk once the worktree is
+//! scanned, and then updated as the worktree reports changed files.
+
+use crate::search::SearchQuery;
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::{Fs, MTime};
+use futures::{StreamExt as _, channel::mpsc};
+use gpui::{AppContext as _, Context, Entity, Subscription, Task, WeakEntity};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsLocation, SettingsStore};
+use sha2::{Digest as _, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::{PathChange, Worktree, WorktreeSettings};
+
+/// Bumped whenever the format of persisted indexes changes, discarding older ones.
+const INDEX_VERSION: u32 = 1;
+/// Files larger than this are not indexed, and are always searched.
+const MAX_INDEXED_FILE_SIZE: usize = 4 * 1024 * 1024;
+const MAX_CONCURRENT_FILE_READS: usize = 16;
+/// How long to wait for more changes before updating and saving the index.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+type Trigram = u32;
+type FileId = u32;
+
+#[derive(Default, Serialize, Deserialize)]
+struct TrigramIndex {
+    version: u32,
+    files: Vec<Option<IndexedFile>>,
+    /// The files containing each trigram, in increasing order.
+    postings: HashMap<Trigram, Vec<FileId>>,
+    #[serde(skip)]
+    file_ids: HashMap<Arc<RelPath>, FileId>,
+    #[serde(skip)]
+    removed_file_count: usize,
+    /// Incremented whenever file ids are reassigned.
+    #[serde(skip)]
+    generation: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedFile {
+    path: String,
+    mtime: (u64, u32),
+    /// Whether the trigrams of the file are indexed, which is not the case for binary and very
+    /// large files.
+    indexed: bool,
+}
+
+impl TrigramIndex {
+    fn new() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            ..Default::default()
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut index: Self = bincode::deserialize(bytes)?;
+        anyhow::ensure!(
+            index.version == INDEX_VERSION,
+            "unsupported search index version {}",
+            index.version
+        );
+        for (id, file) in index.files.iter().enumerate() {
+            match file {
+                Some(file) => {
+                    let path = RelPath::unix(&file.path)?.into_arc();
+                    index.file_ids.insert(path, id as FileId);
+                }
+                None => index.removed_file_count += 1,
+            }
+        }
+        Ok(index)
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    fn file(&self, path: &RelPath) -> Option<(FileId, &IndexedFile)> {
+        let id = *self.file_ids.get(path)?;
+        Some((id, self.files[id as usize].as_ref()?))
+    }
+
+    fn is_current(&self, path: &RelPath, mtime: Option<MTime>) -> bool {
+        self.file(path)
+            .is_some_and(|(_, file)| Some(file.mtime) == persisted_mtime(mtime))
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &Arc<RelPath>> {
+        self.file_ids.keys()
+    }
+
+    fn insert(&mut self, path: Arc<RelPath>, mtime: MTime, trigrams: Option<Vec<Trigram>>) {
+        self.remove(&path);
+        let Some(mtime) = mtime.to_seconds_and_nanos_for_persistence() else {
+            return;
+        };
+        let id = self.files.len() as FileId;
+        for trigram in trigrams.iter().flatten() {
+            self.postings.entry(*trigram).or_default().push(id);
+        }
+        self.files.push(Some(IndexedFile {
+            path: path.as_unix_str().to_string(),
+            mtime,
+            indexed: trigrams.is_some(),
+        }));
+        self.file_ids.insert(path, id);
+    }
+
+    fn remove(&mut self, path: &RelPath) {
+        if let Some(id) = self.file_ids.remove(path) {
+            self.files[id as usize] = None;
+            self.removed_file_count += 1;
+        }
+    }
+
+    /// Drops removed files from the postings once they make up most of the index.
+    fn compact(&mut self) {
+        if self.removed_file_count * 2 <= self.files.len() {
+            return;
+        }
+
+        let mut new_ids = vec![None; self.files.len()];
+        let mut files = Vec::with_capacity(self.files.len() - self.removed_file_count);
+        for (old_id, file) in self.files.drain(..).enumerate() {
+            if let Some(file) = file {
+                new_ids[old_id] = Some(files.len() as FileId);
+                files.push(Some(file));
+            }
+        }
+        self.files = files;
+        self.postings.retain(|_, ids| {
+            ids.retain_mut(|id| match new_ids[*id as usize] {
+                Some(new_id) => {
+                    *id = new_id;
+                    true
+                }
+                None => false,
+            });
+            !ids.is_empty()
+        });
+        for id in self.file_ids.values_mut() {
+            *id = new_ids[*id as usize].expect("indexed files are not removed");
+        }
+        self.removed_file_count = 0;
+        self.generation += 1;
+    }
+
+    /// Returns the files containing all the given trigrams.
+    fn files_with_trigrams(&self, trigrams: &[Trigram]) -> HashSet<FileId> {
+        let mut postings = Vec::with_capacity(trigrams.len());
+        for trigram in trigrams {
+            match self.postings.get(trigram) {
+                Some(ids) => postings.push(ids.as_slice()),
+                None => return HashSet::default(),
+            }
+        }
+        postings.sort_by_key(|ids| ids.len());
+        let Some((shortest, rest)) = postings.split_first() else {
+            return HashSet::default();
+        };
+        shortest
+            .iter()
+            .copied()
+            .filter(|id| rest.iter().all(|ids| ids.binary_search(id).is_ok()))
+            .collect()
+    }
+}
+
+fn persisted_mtime(mtime: Option<MTime>) -> Option<(u64, u32)> {
+    mtime?.to_seconds_and_nanos_for_persistence()
+}
+
+fn trigram(bytes: &[u8]) -> Trigram {
+    (bytes[0].to_ascii_lowercase() as Trigram) << 16
+        | (bytes[1].to_ascii_lowercase() as Trigram) << 8
+        | bytes[2].to_ascii_lowercase() as Trigram
+}
+
+/// Returns the trigrams of a file, ignoring ASCII case, or `None` if the file is not indexed.
+fn file_trigrams(content: &[u8]) -> Option<Vec<Trigram>> {
+    if content.len() > MAX_INDEXED_FILE_SIZE || std::str::from_utf8(content).is_err() {
+        return None;
+    }
+    let mut trigrams = content.windows(3).map(trigram).collect::<Vec<_>>();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    Some(trigrams)
+}
+
+/// Returns the trigrams every file matching the query must contain, or `None` if the query
+/// has no literal text to narrow the search with.
+fn query_trigrams(query: &SearchQuery) -> Option<Vec<Trigram>> {
+    let (literals, case_sensitive) = match query {
+        SearchQuery::Text { .. } => (vec![query.as_str().to_string()], query.case_sensitive()),
+        // Inline flags can make any part of a regex case insensitive.
+        SearchQuery::Regex { regex, .. } => (regex_literals(regex.as_str()), false),
+        SearchQuery::Structural { pattern, .. } => {
+            (pattern.required_word().into_iter().collect(), true)
+        }
+    };
+
+    let mut trigrams = Vec::new();
+    for literal in &literals {
+        for window in literal.as_bytes().windows(3) {
+            // Line endings may differ between the file on disk and the searched buffer, and
+            // only ASCII case is ignored when indexing.
+            if window.iter().any(|byte| *byte == b'\n' || *byte == b'\r')
+                || (!case_sensitive && !window.is_ascii())
+            {
+                continue;
+            }
+            trigrams.push(trigram(window));
+        }
+    }
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    (!trigrams.is_empty()).then_some(trigrams)
+}
+
+/// Returns strings that every match of the regex contains.
+///
+/// This errs on the side of returning fewer literals: groups, classes and escapes end a
+/// literal, and alternations outside of groups make the whole regex return none.
+fn regex_literals(pattern: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut current = String::new();
+    let mut chars = pattern.chars().peekable();
+    let mut flush = |current: &mut String| {
+        if !current.is_empty() {
+            literals.push(std::mem::take(current));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped) if escaped.is_ascii_punctuation() => current.push(escaped),
+                _ => flush(&mut current),
+            },
+            '|' => return Vec::new(),
+            '(' | '[' => {
+                flush(&mut current);
+                skip_group(c, &mut chars);
+            }
+            // The preceding character is optional.
+            '?' | '*' => {
+                current.pop();
+                flush(&mut current);
+            }
+            '{' if chars.peek().is_some_and(|c| c.is_ascii_digit()) => {
+                let mut repetition = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    repetition.push(c);
+                }
+                if repetition.split(',').next().is_none_or(|min| min == "0") {
+                    current.pop();
+                }
+                flush(&mut current);
+            }
+            '+' | '.' | '^' | '$' => flush(&mut current),
+            c => current.push(c),
+        }
+    }
+    flush(&mut current);
+    literals
+}
+
+/// Skips the rest of a group or character class, given the character that opened it.
+fn skip_group(open: char, chars: &mut std::iter::Peekable<std::str::Chars>) {
+    let mut depth = 1;
+    let mut in_class = open == '[';
+    if in_class {
+        // A `]` right after the opening bracket is part of the class.
+        chars.next_if_eq(&'^');
+        chars.next_if_eq(&']');
+    }
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' if !in_class => {
+                in_class = true;
+                chars.next_if_eq(&'^');
+                chars.next_if_eq(&']');
+            }
+            ']' if in_class => {
+                in_class = false;
+                if open == '[' {
+                    return;
+                }
+            }
+            '(' if !in_class => depth += 1,
+            ')' if !in_class => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The files of a worktree that the index rules out for a query.
+pub(crate) struct IndexedCandidates {
+    index: Arc<Mutex<TrigramIndex>>,
+    generation: usize,
+    file_count: usize,
+    matching_files: HashSet<FileId>,
+}
+
+impl IndexedCandidates {
+    /// Whether the file at the given path cannot match the query. Files that changed since
+    /// they were indexed are never excluded.
+    pub(crate) fn excludes(&self, path: &RelPath, mtime: Option<MTime>) -> bool {
+        let index = self.index.lock();
+        if index.generation != self.generation || !index.is_current(path, mtime) {
+            return false;
+        }
+        let Some((id, file)) = index.file(path) else {
+            return false;
+        };
+        file.indexed && (id as usize) < self.file_count && !self.matching_files.contains(&id)
+    }
+}
+
+pub(crate) struct WorktreeSearchIndex {
+    worktree: WeakEntity<Worktree>,
+    fs: Arc<dyn Fs>,
+    state: Option<IndexState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct IndexState {
+    index: Arc<Mutex<TrigramIndex>>,
+    /// Whether the index was reconciled with the files on disk.
+    ready: Arc<AtomicBool>,
+    changes_tx: mpsc::UnboundedSender<Vec<(Arc<RelPath>, Option<MTime>)>>,
+    _maintain_index: Task<()>,
+}
+
+impl WorktreeSearchIndex {
+    pub(crate) fn new(
+        worktree: &Entity<Worktree>,
+        fs: Arc<dyn Fs>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.observe_global::<SettingsStore>(Self::update_enabled),
+            cx.subscribe(worktree, |this, worktree, event, cx| {
+                if let worktree::Event::UpdatedEntries(changes) = event {
+                    this.files_changed(&worktree, changes, cx);
+                }
+            }),
+        ];
+        let mut this = Self {
+            worktree: worktree.downgrade(),
+            fs,
+            state: None,
+            _subscriptions,
+        };
+        this.update_enabled(cx);
+        this
+    }
+
+    /// Returns the files of the worktree that cannot match the query, if the index is up to
+    /// date and the query contains literal text.
+    pub(crate) fn candidates(&self, query: &SearchQuery) -> Option<IndexedCandidates> {
+        let state = self.state.as_ref()?;
+        if !state.ready.load(Ordering::Acquire) {
+            return None;
+        }
+        let trigrams = query_trigrams(query)?;
+        let index = state.index.lock();
+        Some(IndexedCandidates {
+            index: state.index.clone(),
+            generation: index.generation,
+            file_count: index.files.len(),
+            matching_files: index.files_with_trigrams(&trigrams),
+        })
+    }
+
+    fn update_enabled(&mut self, cx: &mut Context<Self>) {
+        let Some(worktree) = self.worktree.upgrade() else {
+            return;
+        };
+        let enabled = WorktreeSettings::get(
+            Some(SettingsLocation {
+                worktree_id: worktree.read(cx).id(),
+                path: RelPath::empty(),
+            }),
+            cx,
+        )
+        .search_index;
+        if !enabled {
+            self.state = None;
+        } else if self.state.is_none() {
+            self.state = self.start(&worktree, cx);
+        }
+    }
+
+    fn start(&self, worktree: &Entity<Worktree>, cx: &mut Context<Self>) -> Option<IndexState> {
+        let scan_complete = worktree.read(cx).as_local()?.scan_complete();
+        let root = worktree.read(cx).abs_path();
+        let index_path = index_path(&root);
+        let fs = self.fs.clone();
+        let index = Arc::new(Mutex::new(TrigramIndex::new()));
+        let ready = Arc::new(AtomicBool::new(false));
+        let (changes_tx, mut changes_rx) = mpsc::unbounded();
+
+        let _maintain_index = cx.spawn({
+            let index = index.clone();
+            let ready = ready.clone();
+            async move |this, cx| {
+                if let Ok(bytes) = fs.load_bytes(&index_path).await
+                    && let Some(loaded) = TrigramIndex::from_bytes(&bytes)
+                        .with_context(|| format!("loading search index {index_path:?}"))
+                        .log_err()
+                {
+                    *index.lock() = loaded;
+                }
+
+                scan_complete.await;
+                let Ok(Some(snapshot)) = this.read_with(cx, |this, cx| {
+                    this.worktree
+                        .upgrade()
+                        .map(|worktree| worktree.read(cx).snapshot())
+                }) else {
+                    return;
+                };
+                let files = snapshot
+                    .files(false, 0)
+                    .filter(|entry| !entry.is_fifo)
+                    .map(|entry| (entry.path.clone(), entry.mtime))
+                    .collect::<Vec<_>>();
+                let mut changes = {
+                    let index = index.lock();
+                    let existing = files
+                        .iter()
+                        .map(|(path, _)| path.clone())
+                        .collect::<HashSet<_>>();
+                    let mut changes = index
+                        .paths()
+                        .filter(|path| !existing.contains(*path))
+                        .map(|path| (path.clone(), None))
+                        .collect::<Vec<_>>();
+                    changes.extend(
+                        files
+                            .into_iter()
+                            .filter(|(path, mtime)| !index.is_current(path, *mtime)),
+                    );
+                    changes
+                };
+
+                loop {
+                    update_index(&fs, &root, &index, changes).await;
+                    ready.store(true, Ordering::Release);
+                    save_index(&fs, &index_path, &index).await.log_err();
+
+                    let Some(next_changes) = changes_rx.next().await else {
+                        return;
+                    };
+                    changes = next_changes;
+                    cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+                    while let Ok(Some(more_changes)) = changes_rx.try_next() {
+                        changes.extend(more_changes);
+                    }
+                }
+            }
+        });
+
+        Some(IndexState {
+            index,
+            ready,
+            changes_tx,
+            _maintain_index,
+        })
+    }
+
+    fn files_changed(
+        &mut self,
+        worktree: &Entity<Worktree>,
+        changes: &[(Arc<RelPath>, worktree::ProjectEntryId, PathChange)],
+        cx: &mut Context<Self>,
+    ) {
+        let Some(state) = &self.state else {
+            return;
+        };
+        let snapshot = worktree.read(cx).snapshot();
+        let changes = changes
+            .iter()
+            // Files found by the initial scan are reconciled with the index at once.
+            .filter(|(_, _, change)| *change != PathChange::Loaded)
+            .map(|(path, _, _)| {
+                let mtime = snapshot
+                    .entry_for_path(path)
+                    .filter(|entry| entry.is_file() && !entry.is_ignored && !entry.is_fifo)
+                    .and_then(|entry| entry.mtime);
+                (path.clone(), mtime)
+            })
+            .collect::<Vec<_>>();
+        if !changes.is_empty() {
+            state.changes_tx.unbounded_send(changes).ok();
+        }
+    }
+}
+
+fn index_path(worktree_root: &Path) -> PathBuf {
+    let hash = Sha256::digest(worktree_root.to_string_lossy().as_bytes());
+    let name = hash
+        .iter()
+        .take(16)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    paths::data_dir()
+        .join("search_index")
+        .join(format!("{name}.bin"))
+}
+
+/// Indexes the given files, or removes them from the index when they have no modification time.
+async fn update_index(
+    fs: &Arc<dyn Fs>,
+    root: &Path,
+    index: &Arc<Mutex<TrigramIndex>>,
+    changes: Vec<(Arc<RelPath>, Option<MTime>)>,
+) {
+    futures::stream::iter(changes)
+        .for_each_concurrent(MAX_CONCURRENT_FILE_READS, |(path, mtime)| async move {
+            let Some(mtime) = mtime else {
+                index.lock().remove(&path);
+                return;
+            };
+            match fs.load_bytes(&root.join(path.as_std_path())).await {
+                Ok(content) => {
+                    let trigrams = file_trigrams(&content);
+                    index.lock().insert(path, mtime, trigrams);
+                }
+                Err(_) => index.lock().remove(&path),
+            }
+        })
+        .await;
+    index.lock().compact();
+}
+
+async fn save_index(
+    fs: &Arc<dyn Fs>,
+    index_path: &Path,
+    index: &Arc<Mutex<TrigramIndex>>,
+) -> Result<()> {
+    let bytes = index.lock().to_bytes()?;
+    if let Some(dir) = index_path.parent() {
+        fs.create_dir(dir).await?;
+    }
+    fs.write(index_path, &bytes)
+        .await
+        .with_context(|| format!("saving search index {index_path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::paths::PathMatcher;
+
+    fn rel_path(path: &str) -> Arc<RelPath> {
+        RelPath::unix(path).unwrap().into_arc()
+    }
+
+    #[test]
+    fn test_regex_literals() {
+        assert_eq!(regex_literals(r"foo\.bar"), ["foo.bar"]);
+        assert_eq!(regex_literals(r"fooo?bar+baz"), ["foo", "bar", "baz"]);
+        assert_eq!(
+            regex_literals(r"\bstruct\s+(\w+)Builder"),
+            ["struct", "Builder"]
+        );
+        assert_eq!(
+            regex_literals(r"abc[)|]def{0,2}ghi{3}"),
+            ["abc", "de", "ghi"]
+        );
+        assert!(regex_literals("foo|bar").is_empty());
+        assert_eq!(regex_literals("(foo|bar)baz"), ["baz"]);
+    }
+
+    #[test]
+    fn test_trigram_index() {
+        let mtime = MTime::from_seconds_and_nanos(1, 0);
+        let mut index = TrigramIndex::new();
+        index.insert(rel_path("a.rs"), mtime, file_trigrams(b"fn Foo() {}"));
+        index.insert(rel_path("b.rs"), mtime, file_trigrams(b"fn bar() {}"));
+        index.insert(rel_path("c.bin"), mtime, file_trigrams(b"\xff\xfe"));
+
+        let query = SearchQuery::text(
+            "foo",
+            false,
+            false,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+            false,
+            None,
+        )
+        .unwrap();
+        let trigrams = query_trigrams(&query).unwrap();
+        let index = Arc::new(Mutex::new(index));
+        let candidates = IndexedCandidates {
+            index: index.clone(),
+            generation: 0,
+            file_count: 3,
+            matching_files: index.lock().files_with_trigrams(&trigrams),
+        };
+        assert!(!candidates.excludes(&rel_path("a.rs"), Some(mtime)));
+        assert!(candidates.excludes(&rel_path("b.rs"), Some(mtime)));
+        // Files that are not indexed, or changed since, are always searched.
+        assert!(!candidates.excludes(&rel_path("c.bin"), Some(mtime)));
+        assert!(!candidates.excludes(&rel_path("d.rs"), Some(mtime)));
+        assert!(!candidates.excludes(&rel_path("b.rs"), Some(MTime::from_seconds_and_nanos(2, 0))));
+
+        let mut index = index.lock();
+        index.remove(&rel_path("a.rs"));
+        index.remove(&rel_path("c.bin"));
+        index.compact();
+        assert_eq!(index.generation, 1);
+        assert_eq!(index.files.len(), 1);
+        assert!(index.files_with_trigrams(&trigrams).is_empty());
+
+        let restored = TrigramIndex::from_bytes(&index.to_bytes().unwrap()).unwrap();
+        assert!(restored.is_current(&rel_path("b.rs"), Some(mtime)));
+        assert_eq!(
+            restored.files_with_trigrams(&[trigram(b"bar")]),
+            HashSet::from_iter([0])
+        );
+    }
+}
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -155,6 +155,14 @@ pub enum TaskSourceKind {
     },
 }
 
+/// A task along with the tasks it depends on, resolved to run before it.
+#[derive(Debug, Clone)]
+pub struct TaskWithDependencies {
+    pub task_source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    pub dependencies: Vec<TaskWithDependencies>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -609,6 +617,89 @@ impl Inventory {
         self.last_scheduled_tasks.retain(|(_, task)| &task.id != id);
     }
 
+    /// Resolves the tasks the given one depends on, and their own dependencies, in the context the task was resolved in.
+    /// Dependencies are looked up by label among the tasks from `tasks.json` files: first the ones of the task's worktree, then the global ones.
+    ///
+    /// Fails if a dependency is not found or cannot be resolved, or if tasks depend on each other.
+    pub fn resolve_dependencies(
+        &self,
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+    ) -> Result<TaskWithDependencies> {
+        self.resolve_dependencies_of(task_source_kind, task, &mut Vec::new())
+    }
+
+    fn resolve_dependencies_of(
+        &self,
+        task_source_kind: TaskSourceKind,
+        task: ResolvedTask,
+        dependents: &mut Vec<String>,
+    ) -> Result<TaskWithDependencies> {
+        let label = &task.original_task().label;
+        if let Some(ix) = dependents.iter().position(|dependent| dependent == label) {
+            anyhow::bail!(
+                "Tasks depend on each other: {}",
+                dependents[ix..].iter().chain([label]).join(" -> ")
+            );
+        }
+        let Some(task_context) = task.dependencies_context() else {
+            return Ok(TaskWithDependencies {
+                task_source_kind,
+                task,
+                dependencies: Vec::new(),
+            });
+        };
+
+        dependents.push(label.clone());
+        let mut dependencies = Vec::new();
+        for dependency_label in &task.original_task().depends_on {
+            let (dependency_source_kind, template) = self
+                .dependency_template(&task_source_kind, dependency_label)
+                .with_context(|| {
+                    format!("Task {label:?} depends on unknown task {dependency_label:?}")
+                })?;
+            let dependency = template
+                .resolve_task(&dependency_source_kind.to_id_base(), task_context)
+                .with_context(|| format!("Could not resolve task {dependency_label:?}"))?;
+            dependencies.push(self.resolve_dependencies_of(
+                dependency_source_kind,
+                dependency,
+                dependents,
+            )?);
+        }
+        dependents.pop();
+
+        Ok(TaskWithDependencies {
+            task_source_kind,
+            task,
+            dependencies,
+        })
+    }
+
+    fn dependency_template(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        label: &str,
+    ) -> Option<(TaskSourceKind, TaskTemplate)> {
+        let worktree_template = match task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => self
+                .worktree_templates_from_settings(*id)
+                .find(|(_, template)| template.label == label),
+            _ => self
+                .templates_from_settings
+                .worktree
+                .keys()
+                .find_map(|worktree| {
+                    self.worktree_templates_from_settings(*worktree)
+                        .find(|(_, template)| template.label == label)
+                }),
+        };
+        worktree_template.or_else(|| {
+            self.global_templates_from_settings()
+                .find(|(_, template)| template.label == label)
+        })
+    }
+
     fn global_templates_from_settings(
         &self,
     ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
@@ -1543,6 +1634,92 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_resolving_task_dependencies(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(|cx| Inventory::new(cx));
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(tasks_file()),
+                    Some(
+                        &json!([
+                            {
+                                "label": "build",
+                                "command": "cargo build",
+                            },
+                            {
+                                "label": "test",
+                                "command": "cargo test",
+                                "depends_on": ["build"],
+                            },
+                            {
+                                "label": "check all",
+                                "depends_on": ["build", "test"],
+                                "depends_order": "sequence",
+                            },
+                            {
+                                "label": "ping",
+                                "command": "echo",
+                                "depends_on": ["pong"],
+                            },
+                            {
+                                "label": "pong",
+                                "command": "echo",
+                                "depends_on": ["ping"],
+                            },
+                            {
+                                "label": "deploy",
+                                "command": "echo",
+                                "depends_on": ["publish"],
+                            },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let resolve = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, _| {
+                let (task_source_kind, template) = inventory
+                    .global_templates_from_settings()
+                    .find(|(_, template)| template.label == label)
+                    .unwrap();
+                let task = template
+                    .resolve_task(&task_source_kind.to_id_base(), &TaskContext::default())
+                    .unwrap();
+                inventory.resolve_dependencies(task_source_kind, task)
+            })
+        };
+
+        fn labels(task: &TaskWithDependencies) -> String {
+            let label = task.task.original_task().label.clone();
+            if task.dependencies.is_empty() {
+                label
+            } else {
+                format!(
+                    "{label}({})",
+                    task.dependencies.iter().map(labels).join(", ")
+                )
+            }
+        }
+
+        let check_all = resolve("check all", cx).unwrap();
+        assert!(check_all.task.is_compound());
+        assert_eq!(labels(&check_all), "check all(build, test(build))");
+        assert_eq!(labels(&resolve("build", cx).unwrap()), "build");
+
+        assert_eq!(
+            resolve("ping", cx).unwrap_err().to_string(),
+            "Tasks depend on each other: ping -> pong -> ping"
+        );
+        assert_eq!(
+            resolve("deploy", cx).unwrap_err().to_string(),
+            "Task \"deploy\" depends on unknown task \"publish\""
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         zlog::init_test();
         TaskStore::init(None);
@@ -35,7 +35,11 @@ use worktree::{
     WorktreeId, WorktreeSettings,
 };
 
-use crate::{ProjectPath, search::SearchQuery};
+use crate::{
+    ProjectPath,
+    search::SearchQuery,
+    search_index::{IndexedCandidates, WorktreeSearchIndex},
+};
 
 struct MatchingEntry {
     worktree_root: Arc<Path>,
@@ -63,6 +67,7 @@ pub struct WorktreeStore {
     #[allow(clippy::type_complexity)]
     loading_worktrees:
         HashMap<Arc<SanitizedPath>, Shared<Task<Result<Entity<Worktree>, Arc<anyhow::Error>>>>>,
+    search_indexes: HashMap<WorktreeId, Entity<WorktreeSearchIndex>>,
     state: WorktreeStoreState,
 }
 
@@ -97,6 +102,7 @@ impl WorktreeStore {
             worktrees: Vec::new(),
             worktrees_reordered: false,
             retain_worktrees,
+            search_indexes: HashMap::default(),
             state: WorktreeStoreState::Local { fs },
         }
     }
@@ -114,6 +120,7 @@ impl WorktreeStore {
             worktrees: Vec::new(),
             worktrees_reordered: false,
             retain_worktrees,
+            search_indexes: HashMap::default(),
             state: WorktreeStoreState::Remote {
                 upstream_client,
                 upstream_project_id,
@@ -622,6 +629,15 @@ impl WorktreeStore {
         cx.emit(WorktreeStoreEvent::WorktreeAdded(worktree.clone()));
         self.send_project_updates(cx);
 
+        if let WorktreeStoreState::Local { fs } = &self.state
+            && worktree.read(cx).is_local()
+            && worktree.read(cx).is_visible()
+        {
+            let fs = fs.clone();
+            let search_index = cx.new(|cx| WorktreeSearchIndex::new(worktree, fs, cx));
+            self.search_indexes.insert(worktree_id, search_index);
+        }
+
         let handle_id = worktree.entity_id();
         cx.subscribe(worktree, |_, worktree, event, cx| {
             let worktree_id = worktree.read(cx).id();
@@ -645,6 +661,7 @@ impl WorktreeStore {
         })
         .detach();
         cx.observe_release(worktree, move |this, worktree, cx| {
+            this.search_indexes.remove(&worktree.id());
             cx.emit(WorktreeStoreEvent::WorktreeReleased(
                 handle_id,
                 worktree.id(),
@@ -662,6 +679,7 @@ impl WorktreeStore {
         self.worktrees.retain(|worktree| {
             if let Some(worktree) = worktree.upgrade() {
                 if worktree.read(cx).id() == id_to_remove {
+                    self.search_indexes.remove(&id_to_remove);
                     cx.emit(WorktreeStoreEvent::WorktreeRemoved(
                         worktree.entity_id(),
                         id_to_remove,
@@ -920,7 +938,11 @@ impl WorktreeStore {
             .visible_worktrees(cx)
             .filter_map(|tree| {
                 let tree = tree.read(cx);
-                Some((tree.snapshot(), tree.as_local()?.settings()))
+                let candidates = self
+                    .search_indexes
+                    .get(&tree.id())
+                    .and_then(|search_index| search_index.read(cx).candidates(&query));
+                Some((tree.snapshot(), tree.as_local()?.settings(), candidates))
             })
             .collect::<Vec<_>>();
 
@@ -1068,13 +1090,17 @@ impl WorktreeStore {
 
     async fn find_candidate_paths(
         fs: Arc<dyn Fs>,
-        snapshots: Vec<(worktree::Snapshot, WorktreeSettings)>,
+        snapshots: Vec<(
+            worktree::Snapshot,
+            WorktreeSettings,
+            Option<IndexedCandidates>,
+        )>,
         open_entries: HashSet<ProjectEntryId>,
         query: SearchQuery,
         filter_tx: Sender<MatchingEntry>,
         output_tx: Sender<oneshot::Receiver<ProjectPath>>,
     ) -> Result<()> {
-        for (snapshot, settings) in snapshots {
+        for (snapshot, settings, candidates) in snapshots {
             for entry in snapshot.entries(query.include_ignored(), 0) {
                 if entry.is_dir() && entry.is_ignored {
                     if !settings.is_path_excluded(&entry.path) {
@@ -1108,9 +1134,18 @@ impl WorktreeStore {
                     }
                 }
 
+                let is_open = open_entries.contains(&entry.id);
+                if !is_open
+                    && candidates
+                        .as_ref()
+                        .is_some_and(|candidates| candidates.excludes(&entry.path, entry.mtime))
+                {
+                    continue;
+                }
+
                 let (mut tx, rx) = oneshot::channel();
 
-                if open_entries.contains(&entry.id) {
+                if is_open {
                     tx.send(ProjectPath {
                         worktree_id: snapshot.id(),
                         path: entry.path.clone(),
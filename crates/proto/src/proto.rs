@@ -220,6 +220,29 @@ messages!(
     (GetDocumentColorResponse, Background),
     (GetColorPresentation, Background),
     (GetColorPresentationResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (RefreshCodeLens, Background),
     (GetCodeLens, Background),
     (GetCodeLensResponse, Background),
@@ -316,6 +339,34 @@ messages!(
     (PullWorkspaceDiagnostics, Background),
     (GetDefaultBranch, Background),
     (GetDefaultBranchResponse, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitStartRebase, Background),
+    (GitRebase, Background),
+    (GitGetRebaseProgress, Background),
+    (GitRebaseProgressResponse, Background),
+    (GitListWorktrees, Background),
+    (GitListWorktreesResponse, Background),
+    (GitAddWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitPruneWorktrees, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitResumeSequencer, Background),
+    (GitSequencerResponse, Background),
+    (GitListTags, Background),
+    (GitListTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitPushTags, Background),
+    (GitGetStoppedSequencer, Background),
+    (GitStoppedSequencerResponse, Background),
+    (GitBisectStart, Background),
+    (GitBisectMark, Background),
+    (GitBisectReset, Background),
+    (GitGetBisectStatus, Background),
+    (GitBisectStatusResponse, Background),
     (GitClone, Background),
     (GitCloneResponse, Background),
     (ToggleLspLogs, Background),
@@ -398,6 +449,7 @@ request_messages!(
     (PrepareRename, PrepareRenameResponse),
     (RefreshInlayHints, Ack),
     (RefreshCodeLens, Ack),
+    (RefreshSemanticTokens, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -416,6 +468,17 @@ request_messages!(
     (ResolveInlayHint, ResolveInlayHintResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetColorPresentation, GetColorPresentationResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
@@ -497,6 +560,26 @@ request_messages!(
     (GetDocumentDiagnostics, GetDocumentDiagnosticsResponse),
     (PullWorkspaceDiagnostics, Ack),
     (GetDefaultBranch, GetDefaultBranchResponse),
+    (GitLog, GitLogResponse),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitStartRebase, GitRebaseProgressResponse),
+    (GitRebase, GitRebaseProgressResponse),
+    (GitGetRebaseProgress, GitRebaseProgressResponse),
+    (GitListWorktrees, GitListWorktreesResponse),
+    (GitAddWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitPruneWorktrees, Ack),
+    (GitCherryPick, GitSequencerResponse),
+    (GitRevert, GitSequencerResponse),
+    (GitResumeSequencer, GitSequencerResponse),
+    (GitListTags, GitListTagsResponse),
+    (GitCreateTag, Ack),
+    (GitPushTags, RemoteMessageResponse),
+    (GitGetStoppedSequencer, GitStoppedSequencerResponse),
+    (GitBisectStart, Ack),
+    (GitBisectMark, Ack),
+    (GitBisectReset, Ack),
+    (GitGetBisectStatus, GitBisectStatusResponse),
     (GitClone, GitCloneResponse),
     (ToggleLspLogs, Ack),
     (GetDirectoryEnvironment, DirectoryEnvironment),
@@ -517,6 +600,11 @@ lsp_messages!(
     (GetDeclaration, GetDeclarationResponse, true),
     (GetTypeDefinition, GetTypeDefinitionResponse, true),
     (GetImplementation, GetImplementationResponse, true),
+    (GetSemanticTokens, GetSemanticTokensResponse, true),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse, true),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse, true),
+    (GetFoldingRanges, GetFoldingRangesResponse, true),
+    (GetDocumentLinks, GetDocumentLinksResponse, true),
 );
 
 entity_messages!(
@@ -535,6 +623,8 @@ entity_messages!(
     CreateBufferForPeer,
     CreateProjectEntry,
     GetDocumentColor,
+    GetDocumentLinks,
+    GetFoldingRanges,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -547,12 +637,18 @@ entity_messages!(
     GetDefinition,
     GetDeclaration,
     GetImplementation,
+    GetIncomingCalls,
+    GetOutgoingCalls,
     GetDocumentHighlights,
     GetDocumentSymbols,
     GetHover,
     GetProjectSymbols,
     GetReferences,
+    GetSelectionRanges,
+    GetSemanticTokens,
     GetSignatureHelp,
+    GetSubtypes,
+    GetSupertypes,
     OpenUnstagedDiff,
     OpenUncommittedDiff,
     GetTypeDefinition,
@@ -572,13 +668,17 @@ entity_messages!(
     OpenBufferForSymbol,
     OpenCommitMessageBuffer,
     PerformRename,
+    PrepareCallHierarchy,
     PrepareRename,
+    PrepareTypeHierarchy,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
     ResolveCompletionDocumentation,
+    ResolveDocumentLink,
     ResolveInlayHint,
     SaveBuffer,
     Stage,
@@ -658,6 +758,26 @@ entity_messages!(
     GetDocumentDiagnostics,
     PullWorkspaceDiagnostics,
     GetDefaultBranch,
+    GitLog,
+    GitRebaseTodo,
+    GitStartRebase,
+    GitRebase,
+    GitGetRebaseProgress,
+    GitListWorktrees,
+    GitAddWorktree,
+    GitRemoveWorktree,
+    GitPruneWorktrees,
+    GitCherryPick,
+    GitRevert,
+    GitResumeSequencer,
+    GitListTags,
+    GitCreateTag,
+    GitPushTags,
+    GitGetStoppedSequencer,
+    GitBisectStart,
+    GitBisectMark,
+    GitBisectReset,
+    GitGetBisectStatus,
     GitClone,
     GetAgentServerCommand,
     ExternalAgentsUpdated,
@@ -847,6 +967,11 @@ impl LspQuery {
             Some(lsp_query::Request::GetImplementation(_)) => ("GetImplementation", false),
             Some(lsp_query::Request::GetReferences(_)) => ("GetReferences", false),
             Some(lsp_query::Request::GetDocumentColor(_)) => ("GetDocumentColor", false),
+            Some(lsp_query::Request::GetSemanticTokens(_)) => ("GetSemanticTokens", false),
+            Some(lsp_query::Request::PrepareCallHierarchy(_)) => ("PrepareCallHierarchy", false),
+            Some(lsp_query::Request::PrepareTypeHierarchy(_)) => ("PrepareTypeHierarchy", false),
+            Some(lsp_query::Request::GetFoldingRanges(_)) => ("GetFoldingRanges", false),
+            Some(lsp_query::Request::GetDocumentLinks(_)) => ("GetDocumentLinks", false),
             None => ("<unknown>", true),
         }
     }
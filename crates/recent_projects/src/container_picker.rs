@@ -0,0 +1,303 @@
+use std::{path::PathBuf, sync::Arc};
+
+use gpui::{AppContext, DismissEvent, Entity, EventEmitter, Focusable, Subscription, Task};
+use picker::Picker;
+use remote::{ContainerRuntime, DockerConnectionOptions, RemoteConnectionOptions};
+use ui::{
+    App, Color, Context, HighlightedLabel, Icon, IconName, InteractiveElement, Label, LabelCommon,
+    LabelSize, ListItem, ParentElement, Render, Styled, StyledExt, Toggleable, Window, div, h_flex,
+    rems,
+};
+use util::ResultExt as _;
+use workspace::{ModalView, Workspace};
+
+use crate::open_remote_project;
+
+#[derive(Clone, Debug)]
+pub struct ContainerSelected {
+    pub secondary: bool,
+    pub connection_options: DockerConnectionOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct ContainerPickerDismissed;
+
+pub(crate) struct ContainerPickerDelegate {
+    selected_index: usize,
+    containers: Vec<(ContainerRuntime, String)>,
+    matches: Vec<fuzzy::StringMatch>,
+}
+
+impl ContainerPickerDelegate {
+    pub fn new() -> Self {
+        ContainerPickerDelegate {
+            selected_index: 0,
+            containers: Vec::new(),
+            matches: Vec::new(),
+        }
+    }
+
+    /// Lists the running containers of every runtime installed on this machine.
+    async fn fetch_containers() -> Vec<(ContainerRuntime, String)> {
+        let mut containers = Vec::new();
+        for runtime in [ContainerRuntime::Docker, ContainerRuntime::Podman] {
+            if let Ok(names) = DockerConnectionOptions::running_containers(runtime).await {
+                containers.extend(names.into_iter().map(|name| (runtime, name)));
+            }
+        }
+        containers
+    }
+}
+
+impl EventEmitter<ContainerSelected> for Picker<ContainerPickerDelegate> {}
+
+impl EventEmitter<ContainerPickerDismissed> for Picker<ContainerPickerDelegate> {}
+
+impl picker::PickerDelegate for ContainerPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        Arc::from("Enter container name")
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<ui::SharedString> {
+        Some("No running containers".into())
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        use fuzzy::StringMatchCandidate;
+        use ordered_float::OrderedFloat;
+
+        let candidates = self
+            .containers
+            .iter()
+            .enumerate()
+            .map(|(id, (_, name))| StringMatchCandidate::new(id, name))
+            .collect::<Vec<_>>();
+
+        let query = query.trim_start();
+        let smart_case = query.chars().any(|c| c.is_uppercase());
+        self.matches = smol::block_on(fuzzy::match_strings(
+            candidates.as_slice(),
+            query,
+            smart_case,
+            true,
+            100,
+            &Default::default(),
+            cx.background_executor().clone(),
+        ));
+        self.matches.sort_unstable_by_key(|m| m.candidate_id);
+
+        self.selected_index = self
+            .matches
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, m)| OrderedFloat(m.score))
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some((runtime, container)) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|m| self.containers.get(m.candidate_id))
+        {
+            cx.emit(ContainerSelected {
+                secondary,
+                connection_options: DockerConnectionOptions {
+                    container: container.clone(),
+                    runtime: *runtime,
+                    user: None,
+                },
+            });
+        }
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(ContainerPickerDismissed);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let matched = self.matches.get(ix)?;
+        let (runtime, _) = self.containers.get(matched.candidate_id)?;
+        Some(
+            ListItem::new(ix)
+                .toggle_state(selected)
+                .inset(true)
+                .spacing(ui::ListItemSpacing::Sparse)
+                .child(
+                    h_flex()
+                        .flex_grow()
+                        .gap_3()
+                        .child(Icon::new(IconName::Server))
+                        .child(HighlightedLabel::new(
+                            matched.string.clone(),
+                            matched.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(runtime.program())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
+
+pub(crate) struct ContainerOpenModal {
+    create_new_window: bool,
+    picker: Entity<Picker<ContainerPickerDelegate>>,
+    _fetch_containers: Task<()>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl ContainerOpenModal {
+    pub fn new(create_new_window: bool, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let delegate = ContainerPickerDelegate::new();
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx).modal(false));
+
+        let selected = cx.subscribe_in(
+            &picker,
+            window,
+            |this, _, event: &ContainerSelected, window, cx| {
+                this.confirm(
+                    event.connection_options.clone(),
+                    event.secondary,
+                    window,
+                    cx,
+                );
+            },
+        );
+
+        let dismissed = cx.subscribe_in(
+            &picker,
+            window,
+            |this, _, _: &ContainerPickerDismissed, window, cx| {
+                this.cancel(&menu::Cancel, window, cx);
+            },
+        );
+
+        let fetch_containers = cx.spawn_in(window, async move |this, cx| {
+            let containers = ContainerPickerDelegate::fetch_containers().await;
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.containers = containers;
+                    picker.refresh(window, cx);
+                })
+            })
+            .log_err();
+        });
+
+        ContainerOpenModal {
+            create_new_window,
+            picker,
+            _fetch_containers: fetch_containers,
+            _subscriptions: [selected, dismissed],
+        }
+    }
+
+    fn confirm(
+        &mut self,
+        connection_options: DockerConnectionOptions,
+        secondary: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let app_state = workspace::AppState::global(cx);
+        let Some(app_state) = app_state.upgrade() else {
+            return;
+        };
+
+        let replace_current_window = match self.create_new_window {
+            true => secondary,
+            false => !secondary,
+        };
+        let replace_window = match replace_current_window {
+            true => window.window_handle().downcast::<Workspace>(),
+            false => None,
+        };
+        let open_options = workspace::OpenOptions {
+            replace_window,
+            ..Default::default()
+        };
+
+        cx.emit(DismissEvent);
+        cx.spawn_in(window, async move |_, cx| {
+            let working_dir = connection_options
+                .working_dir()
+                .await
+                .log_err()
+                .flatten()
+                .unwrap_or_else(|| "~".to_string());
+            open_remote_project(
+                RemoteConnectionOptions::Docker(connection_options),
+                vec![PathBuf::from(working_dir)],
+                app_state,
+                open_options,
+                cx,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for ContainerOpenModal {}
+
+impl Focusable for ContainerOpenModal {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ContainerOpenModal {}
+
+impl Render for ContainerOpenModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl ui::IntoElement {
+        div()
+            .on_mouse_down_out(cx.listener(|_, _, _, cx| cx.emit(DismissEvent)))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(34.))
+            .flex_1()
+            .overflow_hidden()
+            .child(self.picker.clone())
+    }
+}
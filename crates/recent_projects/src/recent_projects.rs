@@ -1,3 +1,4 @@
+mod container_picker;
 pub mod disconnected_overlay;
 mod remote_connections;
 mod remote_servers;
@@ -6,7 +7,8 @@ mod ssh_config;
 #[cfg(target_os = "windows")]
 mod wsl_picker;
 
-use remote::RemoteConnectionOptions;
+use anyhow::Context as _;
+use remote::{ContainerRuntime, RemoteConnectionOptions};
 pub use remote_connections::open_remote_project;
 
 use disconnected_overlay::DisconnectedOverlay;
@@ -23,12 +25,17 @@ use picker::{
 pub use remote_connections::SshSettings;
 pub use remote_servers::RemoteServerProjects;
 use settings::Settings;
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*, tooltip_container};
 use util::{ResultExt, paths::PathExt};
 use workspace::{
     CloseIntent, HistoryManager, ModalView, OpenOptions, PathList, SerializedWorkspaceLocation,
-    WORKSPACE_DB, Workspace, WorkspaceId, with_active_or_new_workspace,
+    Toast, WORKSPACE_DB, Workspace, WorkspaceId,
+    notifications::{DetachAndPromptErr as _, NotificationId},
+    with_active_or_new_workspace,
 };
 use zed_actions::{OpenRecent, OpenRemote};
 
@@ -102,6 +109,26 @@ pub fn init(cx: &mut App) {
         });
     });
 
+    cx.on_action(
+        |open_container: &zed_actions::container_actions::OpenContainer, cx| {
+            let create_new_window = open_container.create_new_window;
+            with_active_or_new_workspace(cx, move |workspace, window, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    crate::container_picker::ContainerOpenModal::new(create_new_window, window, cx)
+                });
+            });
+        },
+    );
+
+    cx.on_action(
+        |reopen: &zed_actions::container_actions::ReopenInDevContainer, cx| {
+            let create_new_window = reopen.create_new_window;
+            with_active_or_new_workspace(cx, move |workspace, window, cx| {
+                reopen_in_dev_container(workspace, create_new_window, window, cx);
+            });
+        },
+    );
+
     cx.on_action(|open_recent: &OpenRecent, cx| {
         let create_new_window = open_recent.create_new_window;
         with_active_or_new_workspace(cx, move |workspace, window, cx| {
@@ -136,6 +163,76 @@ pub fn init(cx: &mut App) {
     cx.observe_new(DisconnectedOverlay::register).detach();
 }
 
+/// Starts the dev container of the local project, and opens the project inside it.
+fn reopen_in_dev_container(
+    workspace: &mut Workspace,
+    create_new_window: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let Some(project_dir) = project
+        .is_local()
+        .then(|| project.visible_worktrees(cx).next())
+        .flatten()
+        .map(|worktree| worktree.read(cx).abs_path())
+    else {
+        workspace.show_error(&"Dev containers can only be started for local projects", cx);
+        return;
+    };
+    let app_state = workspace.app_state().clone();
+    let replace_window = match create_new_window {
+        true => None,
+        false => window.window_handle().downcast::<Workspace>(),
+    };
+
+    struct StartingDevContainer;
+    let notification_id = NotificationId::unique::<StartingDevContainer>();
+    workspace.show_toast(
+        Toast::new(notification_id.clone(), "Starting dev container…"),
+        cx,
+    );
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let dev_container = async {
+            let config_path = remote::devcontainer::find_config(&project_dir)
+                .await
+                .context("the project has no devcontainer.json")?;
+            remote::devcontainer::start_dev_container(
+                &project_dir,
+                &config_path,
+                ContainerRuntime::detect(),
+            )
+            .await
+        }
+        .await;
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.dismiss_toast(&notification_id, cx)
+            })
+            .ok();
+        let dev_container = dev_container?;
+
+        open_remote_project(
+            RemoteConnectionOptions::Docker(dev_container.connection_options),
+            vec![PathBuf::from(dev_container.workspace_folder)],
+            app_state,
+            OpenOptions {
+                replace_window,
+                ..Default::default()
+            },
+            cx,
+        )
+        .await
+    })
+    .detach_and_prompt_err(
+        "Failed to start the dev container",
+        window,
+        cx,
+        |_, _, _| None,
+    );
+}
+
 pub struct RecentProjects {
     pub picker: Entity<Picker<RecentProjectsDelegate>>,
     rem_width: f32,
@@ -494,6 +591,7 @@ impl PickerDelegate for RecentProjectsDelegate {
                                     Icon::new(match options {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
+                                        RemoteConnectionOptions::Docker { .. } => IconName::Server,
                                     })
                                     .color(Color::Muted)
                                     .into_any_element()
@@ -132,6 +132,9 @@ enum ProjectPickerData {
     Wsl {
         distro_name: SharedString,
     },
+    Container {
+        container: SharedString,
+    },
 }
 
 struct ProjectPicker {
@@ -205,6 +208,9 @@ impl ProjectPicker {
             RemoteConnectionOptions::Wsl(connection) => ProjectPickerData::Wsl {
                 distro_name: connection.distro_name.clone().into(),
             },
+            RemoteConnectionOptions::Docker(connection) => ProjectPickerData::Container {
+                container: connection.container.clone().into(),
+            },
         };
         let _path_task = cx
             .spawn_in(window, {
@@ -321,6 +327,13 @@ impl gpui::Render for ProjectPicker {
                     is_wsl: true,
                 }
                 .render(window, cx),
+                ProjectPickerData::Container { container } => SshConnectionHeader {
+                    connection_string: container.clone(),
+                    paths: Default::default(),
+                    nickname: None,
+                    is_wsl: false,
+                }
+                .render(window, cx),
             })
             .child(
                 div()
@@ -0,0 +1,344 @@
+//! Starts the containers described by `devcontainer.json` files, so that Zed can connect to them.
+//!
+//! Only the properties needed to create and start a container from an image or a Dockerfile
+//! are supported; features, Docker Compose and port forwarding are not.
+
+use crate::{
+    ContainerRuntime, DockerConnectionOptions,
+    transport::docker::{docker_exec_command, run_runtime_command},
+};
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use util::ResultExt as _;
+
+/// The label identifying the container created for a local folder.
+const LOCAL_FOLDER_LABEL: &str = "dev.zed.devcontainer.local_folder";
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<DevContainerBuild>,
+    /// The legacy location of `build.dockerfile`.
+    pub docker_file: Option<String>,
+    pub workspace_folder: Option<String>,
+    pub remote_user: Option<String>,
+    pub container_user: Option<String>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    #[serde(default)]
+    pub mounts: Vec<String>,
+    pub post_create_command: Option<LifecycleCommand>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerBuild {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+}
+
+/// A command run in the container, either through a shell or as a program and its arguments.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    Shell(String),
+    Args(Vec<String>),
+}
+
+/// A started dev container, and the folder of the project inside it.
+#[derive(Debug, Clone)]
+pub struct DevContainer {
+    pub connection_options: DockerConnectionOptions,
+    pub workspace_folder: String,
+}
+
+/// Returns the `devcontainer.json` of the project, if it has one.
+pub async fn find_config(project_dir: &Path) -> Option<PathBuf> {
+    for candidate in [
+        project_dir.join(".devcontainer").join("devcontainer.json"),
+        project_dir.join(".devcontainer.json"),
+    ] {
+        if smol::fs::metadata(&candidate).await.is_ok() {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+/// Starts the dev container of the project, creating it from its configuration if it was
+/// never started before.
+pub async fn start_dev_container(
+    project_dir: &Path,
+    config_path: &Path,
+    runtime: ContainerRuntime,
+) -> Result<DevContainer> {
+    let contents = smol::fs::read_to_string(config_path)
+        .await
+        .with_context(|| format!("failed to read {}", config_path.display()))?;
+    let config = serde_json_lenient::from_str::<DevContainerConfig>(&contents)
+        .with_context(|| format!("failed to parse {}", config_path.display()))?;
+    let config_dir = config_path.parent().unwrap_or(project_dir);
+    let workspace_folder = config
+        .workspace_folder
+        .clone()
+        .unwrap_or_else(|| default_workspace_folder(project_dir));
+    let user = config
+        .remote_user
+        .clone()
+        .or_else(|| config.container_user.clone());
+
+    let label = format!("{LOCAL_FOLDER_LABEL}={}", project_dir.display());
+    let existing = run_runtime_command(
+        runtime,
+        &[
+            "ps",
+            "--all",
+            "--quiet",
+            "--filter",
+            &format!("label={label}"),
+        ],
+    )
+    .await?;
+
+    let container = if let Some(container) = existing.lines().next() {
+        log::info!("starting existing dev container {container}");
+        run_runtime_command(runtime, &["start", container]).await?;
+        container.to_string()
+    } else {
+        let image = match &config.image {
+            Some(image) => image.clone(),
+            None => build_image(&config, config_dir, project_dir, runtime).await?,
+        };
+        let args = run_args(&config, project_dir, &workspace_folder, &label, &image);
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let container = run_runtime_command(runtime, &args).await?;
+        log::info!("created dev container {container}");
+
+        if let Some(command) = &config.post_create_command {
+            let options = DockerConnectionOptions {
+                container: container.clone(),
+                runtime,
+                user: user.clone(),
+            };
+            if let Err(error) = run_lifecycle_command(&options, &workspace_folder, command).await {
+                // A half set-up container would be reused by the next start without running
+                // the command again, so remove it and let the next start create it anew.
+                run_runtime_command(runtime, &["rm", "--force", &container])
+                    .await
+                    .log_err();
+                return Err(error.context("postCreateCommand failed"));
+            }
+        }
+        container
+    };
+
+    Ok(DevContainer {
+        connection_options: DockerConnectionOptions {
+            container,
+            runtime,
+            user,
+        },
+        workspace_folder,
+    })
+}
+
+fn default_workspace_folder(project_dir: &Path) -> String {
+    let name = project_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "workspace".to_string());
+    format!("/workspaces/{name}")
+}
+
+async fn build_image(
+    config: &DevContainerConfig,
+    config_dir: &Path,
+    project_dir: &Path,
+    runtime: ContainerRuntime,
+) -> Result<String> {
+    let build = config.build.clone().unwrap_or_default();
+    let dockerfile = build
+        .dockerfile
+        .or_else(|| config.docker_file.clone())
+        .context("devcontainer.json must specify an image or a Dockerfile")?;
+    let context = config_dir.join(build.context.as_deref().unwrap_or("."));
+    let tag = format!(
+        "zed-devcontainer-{}",
+        project_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+            .replace(
+                |c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_',
+                "-"
+            )
+    );
+
+    let mut args = vec![
+        "build".to_string(),
+        "--file".to_string(),
+        config_dir.join(dockerfile).to_string_lossy().into_owned(),
+        "--tag".to_string(),
+        tag.clone(),
+    ];
+    for (name, value) in &build.args {
+        args.push("--build-arg".to_string());
+        args.push(format!("{name}={value}"));
+    }
+    args.push(context.to_string_lossy().into_owned());
+
+    log::info!("building dev container image {tag}");
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    run_runtime_command(runtime, &args).await?;
+    Ok(tag)
+}
+
+/// The arguments creating the container, which keeps running after its entrypoint returns.
+fn run_args(
+    config: &DevContainerConfig,
+    project_dir: &Path,
+    workspace_folder: &str,
+    label: &str,
+    image: &str,
+) -> Vec<String> {
+    let mut args = vec![
+        "run".to_string(),
+        "--detach".to_string(),
+        "--label".to_string(),
+        label.to_string(),
+        "--mount".to_string(),
+        format!(
+            "type=bind,source={},target={workspace_folder}",
+            project_dir.display()
+        ),
+        "--workdir".to_string(),
+        workspace_folder.to_string(),
+    ];
+    if let Some(user) = &config.container_user {
+        args.push("--user".to_string());
+        args.push(user.clone());
+    }
+    for (name, value) in &config.container_env {
+        args.push("--env".to_string());
+        args.push(format!("{name}={value}"));
+    }
+    for mount in &config.mounts {
+        args.push("--mount".to_string());
+        args.push(mount.clone());
+    }
+    args.extend(config.run_args.iter().cloned());
+    args.extend([
+        "--entrypoint".to_string(),
+        "/bin/sh".to_string(),
+        image.to_string(),
+        "-c".to_string(),
+        "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done".to_string(),
+    ]);
+    args
+}
+
+async fn run_lifecycle_command(
+    options: &DockerConnectionOptions,
+    working_dir: &str,
+    command: &LifecycleCommand,
+) -> Result<()> {
+    let mut command = match command {
+        LifecycleCommand::Shell(script) => {
+            docker_exec_command(options, Some(working_dir), "sh", &["-c", script.as_str()])
+        }
+        LifecycleCommand::Args(args) => {
+            let (program, args) = args.split_first().context("empty command")?;
+            docker_exec_command(options, Some(working_dir), program, args)
+        }
+    };
+    let output = command.output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = serde_json_lenient::from_str::<DevContainerConfig>(
+            r#"{
+                // Comments and trailing commas are allowed.
+                "name": "Rust",
+                "build": { "dockerfile": "Dockerfile", "args": { "VARIANT": "bookworm" } },
+                "remoteUser": "vscode",
+                "containerEnv": { "CARGO_HOME": "/cargo" },
+                "postCreateCommand": ["cargo", "fetch"],
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.name.as_deref(), Some("Rust"));
+        assert_eq!(
+            config.build.as_ref().unwrap().dockerfile.as_deref(),
+            Some("Dockerfile")
+        );
+        assert_eq!(config.remote_user.as_deref(), Some("vscode"));
+        assert_eq!(
+            config.post_create_command,
+            Some(LifecycleCommand::Args(vec![
+                "cargo".to_string(),
+                "fetch".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_run_args() {
+        let config = DevContainerConfig {
+            image: Some("rust:latest".to_string()),
+            container_env: BTreeMap::from_iter([("A".to_string(), "1".to_string())]),
+            run_args: vec!["--privileged".to_string()],
+            ..Default::default()
+        };
+        let project_dir = Path::new("/home/user/project");
+        let workspace_folder = default_workspace_folder(project_dir);
+        assert_eq!(workspace_folder, "/workspaces/project");
+
+        let args = run_args(
+            &config,
+            project_dir,
+            &workspace_folder,
+            "label=value",
+            "rust:latest",
+        );
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--detach",
+                "--label",
+                "label=value",
+                "--mount",
+                "type=bind,source=/home/user/project,target=/workspaces/project",
+                "--workdir",
+                "/workspaces/project",
+                "--env",
+                "A=1",
+                "--privileged",
+                "--entrypoint",
+                "/bin/sh",
+                "rust:latest",
+                "-c",
+                "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done",
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod devcontainer;
 pub mod json_log;
 pub mod protocol;
 pub mod proxy;
@@ -8,5 +9,6 @@ pub use remote_client::{
     ConnectionIdentifier, ConnectionState, RemoteClient, RemoteClientDelegate, RemoteClientEvent,
     RemoteConnectionOptions, RemotePlatform,
 };
+pub use transport::docker::{ContainerRuntime, DockerConnectionOptions};
 pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
 pub use transport::wsl::WslConnectionOptions;
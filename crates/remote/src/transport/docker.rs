@@ -0,0 +1,601 @@
+use crate::{
+    RemoteClientDelegate, RemotePlatform,
+    remote_client::{CommandTemplate, RemoteConnection, RemoteConnectionOptions},
+};
+use anyhow::{Context as _, Result, anyhow, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AppContext as _, AsyncApp, SemanticVersion, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use smol::{fs, process};
+use std::{
+    ffi::OsStr,
+    fmt::{self, Write as _},
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Instant,
+};
+use util::paths::{PathStyle, RemotePathBuf};
+
+/// The program used to run the containers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+
+    /// The runtime installed on this machine, preferring Docker when both are.
+    pub fn detect() -> Self {
+        if which::which("docker").is_err() && which::which("podman").is_ok() {
+            ContainerRuntime::Podman
+        } else {
+            ContainerRuntime::Docker
+        }
+    }
+}
+
+impl fmt::Display for ContainerRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.program())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DockerConnectionOptions {
+    /// The name or the ID of the container.
+    pub container: String,
+    pub runtime: ContainerRuntime,
+    pub user: Option<String>,
+}
+
+impl DockerConnectionOptions {
+    /// Lists the names of the running containers.
+    pub async fn running_containers(runtime: ContainerRuntime) -> Result<Vec<String>> {
+        let output = run_runtime_command(runtime, &["ps", "--format", "{{.Names}}"]).await?;
+        Ok(output
+            .lines()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// The working directory of the container's configuration, in which projects are usually located.
+    pub async fn working_dir(&self) -> Result<Option<String>> {
+        let working_dir = run_runtime_command(
+            self.runtime,
+            &[
+                "inspect",
+                "--format",
+                "{{.Config.WorkingDir}}",
+                &self.container,
+            ],
+        )
+        .await?;
+        Ok(Some(working_dir).filter(|working_dir| !working_dir.is_empty()))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DockerRemoteConnection {
+    remote_binary_path: Option<String>,
+    platform: RemotePlatform,
+    shell: String,
+    home_dir: String,
+    connection_options: DockerConnectionOptions,
+}
+
+impl DockerRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: DockerConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "Connecting to {} container {} with user {:?}",
+            connection_options.runtime,
+            connection_options.container,
+            connection_options.user
+        );
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+
+        let mut this = Self {
+            connection_options,
+            remote_binary_path: None,
+            platform: RemotePlatform { os: "", arch: "" },
+            shell: String::new(),
+            home_dir: String::new(),
+        };
+        delegate.set_status(Some("Starting container"), cx);
+        this.ensure_container_running().await?;
+        delegate.set_status(Some("Detecting container environment"), cx);
+        this.shell = this.detect_shell().await;
+        this.home_dir = this.run_docker_command("sh", &["-c", "echo $HOME"]).await?;
+        this.platform = this.detect_platform().await?;
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+        log::debug!("Detected container environment: {this:#?}");
+
+        Ok(this)
+    }
+
+    /// Starts the container if it was stopped, e.g. when reopening a project after a reboot.
+    async fn ensure_container_running(&self) -> Result<()> {
+        let options = &self.connection_options;
+        let running = run_runtime_command(
+            options.runtime,
+            &[
+                "inspect",
+                "--format",
+                "{{.State.Running}}",
+                &options.container,
+            ],
+        )
+        .await
+        .with_context(|| format!("container {} not found", options.container))?;
+        if running != "true" {
+            run_runtime_command(options.runtime, &["start", &options.container])
+                .await
+                .with_context(|| format!("failed to start container {}", options.container))?;
+        }
+        Ok(())
+    }
+
+    async fn detect_platform(&self) -> Result<RemotePlatform> {
+        let os = self.run_docker_command("uname", &["-s"]).await?;
+        anyhow::ensure!(
+            os.eq_ignore_ascii_case("linux"),
+            "unsupported container OS: {os}"
+        );
+        let arch_str = self.run_docker_command("uname", &["-m"]).await?;
+        let arch = match arch_str.as_str() {
+            "x86_64" => "x86_64",
+            "aarch64" | "arm64" => "aarch64",
+            _ => bail!("unsupported container architecture: {arch_str}"),
+        };
+        Ok(RemotePlatform { os: "linux", arch })
+    }
+
+    async fn detect_shell(&self) -> String {
+        self.run_docker_command("sh", &["-c", "echo $SHELL"])
+            .await
+            .ok()
+            .filter(|shell| !shell.is_empty())
+            .unwrap_or_else(|| "/bin/sh".to_string())
+    }
+
+    fn docker_command(&self, program: &str, args: &[impl AsRef<OsStr>]) -> process::Command {
+        docker_exec_command(&self.connection_options, None, program, args)
+    }
+
+    async fn run_docker_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        let output = self.docker_command(program, args).output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "Command '{}' failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<String> {
+        let version_str = match release_channel {
+            ReleaseChannel::Nightly => {
+                let commit = commit.map(|s| s.full()).unwrap_or_default();
+                format!("{}-{}", version, commit)
+            }
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+        let server_dir = format!(
+            "{}/{}",
+            self.home_dir,
+            paths::remote_server_dir_relative().display(PathStyle::Posix)
+        );
+        let dst_path = format!("{server_dir}/{binary_name}");
+
+        self.run_docker_command("mkdir", &["-p", &server_dir])
+            .await
+            .map_err(|e| anyhow!("Failed to create directory: {}", e))?;
+
+        #[cfg(debug_assertions)]
+        if let Some(remote_server_path) =
+            super::build_remote_server_from_source(&self.platform, delegate.as_ref(), cx).await?
+        {
+            let tmp_path = format!(
+                "{server_dir}/download-{}-{}",
+                std::process::id(),
+                remote_server_path.file_name().unwrap().to_string_lossy()
+            );
+            self.upload_file(&remote_server_path, &tmp_path, delegate, cx)
+                .await?;
+            self.extract_and_install(&tmp_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        if self
+            .run_docker_command(&dst_path, &["version"])
+            .await
+            .is_ok()
+        {
+            return Ok(dst_path);
+        }
+
+        delegate.set_status(Some("Installing remote server"), cx);
+
+        let wanted_version = match release_channel {
+            ReleaseChannel::Nightly | ReleaseChannel::Dev => None,
+            _ => Some(cx.update(|cx| AppVersion::global(cx))?),
+        };
+
+        let src_path = delegate
+            .download_server_binary_locally(self.platform, release_channel, wanted_version, cx)
+            .await?;
+
+        let tmp_path = format!("{dst_path}.{}.gz", std::process::id());
+        self.upload_file(&src_path, &tmp_path, delegate, cx).await?;
+        self.extract_and_install(&tmp_path, &dst_path, delegate, cx)
+            .await?;
+
+        Ok(dst_path)
+    }
+
+    async fn upload_file(
+        &self,
+        src_path: &Path,
+        dst_path: &str,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Uploading remote server to container"), cx);
+
+        let t0 = Instant::now();
+        let src_stat = fs::metadata(&src_path).await?;
+        let size = src_stat.len();
+        log::info!(
+            "uploading remote server to container {:?} ({}kb)",
+            dst_path,
+            size / 1024
+        );
+
+        copy_to_container(&self.connection_options, src_path, dst_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to copy file {} to container {:?}",
+                    src_path.display(),
+                    dst_path
+                )
+            })?;
+
+        log::info!("uploaded remote server in {:?}", t0.elapsed());
+        Ok(())
+    }
+
+    async fn extract_and_install(
+        &self,
+        tmp_path: &str,
+        dst_path: &str,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Extracting remote server"), cx);
+
+        let script = if tmp_path.ends_with(".gz") {
+            let uncompressed = tmp_path.trim_end_matches(".gz");
+            format!(
+                "set -e; gunzip -f '{}' && chmod 755 '{}' && mv -f '{}' '{}'",
+                tmp_path, uncompressed, uncompressed, dst_path
+            )
+        } else {
+            format!(
+                "set -e; chmod 755 '{}' && mv -f '{}' '{}'",
+                tmp_path, tmp_path, dst_path
+            )
+        };
+
+        self.run_docker_command("sh", &["-c", &script])
+            .await
+            .map_err(|e| anyhow!("Failed to extract server binary: {}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for DockerRemoteConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut proxy_args = vec![];
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Some(value) = std::env::var(env_var).ok() {
+                proxy_args.push(format!("{}={}", env_var, value));
+            }
+        }
+        proxy_args.push(remote_binary_path.clone());
+        proxy_args.push("proxy".to_owned());
+        proxy_args.push("--identifier".to_owned());
+        proxy_args.push(unique_identifier);
+
+        if reconnect {
+            proxy_args.push("--reconnect".to_owned());
+        }
+        let proxy_process = match self
+            .docker_command("env", &proxy_args)
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let options = self.connection_options.clone();
+        cx.background_spawn(async move {
+            copy_to_container(&options, &src_path, &dest_path.to_string())
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to upload directory {} -> {}",
+                        src_path.display(),
+                        dest_path.to_string()
+                    )
+                })
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        false
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        if port_forward.is_some() {
+            bail!("port forwarding is not supported for containers");
+        }
+
+        let working_dir = working_dir
+            .map(|working_dir| RemotePathBuf::new(working_dir, PathStyle::Posix).to_string())
+            .map(|working_dir| match working_dir.strip_prefix("~") {
+                Some(rest) => format!("{}{rest}", self.home_dir),
+                None => working_dir,
+            })
+            .unwrap_or_else(|| self.home_dir.clone());
+
+        let mut exec = String::from("exec env ");
+        for (k, v) in env.iter() {
+            if let Some((k, v)) = shlex::try_quote(k).ok().zip(shlex::try_quote(v).ok()) {
+                write!(exec, "{}={} ", k, v).unwrap();
+            }
+        }
+        if let Some(program) = program {
+            write!(exec, "{}", shlex::try_quote(&program)?).unwrap();
+            for arg in args {
+                let arg = shlex::try_quote(&arg)?;
+                write!(exec, " {}", &arg).unwrap();
+            }
+        } else {
+            write!(&mut exec, "{} -l", self.shell).unwrap();
+        }
+
+        let mut docker_args = vec![
+            "exec".to_string(),
+            "--interactive".to_string(),
+            "--tty".to_string(),
+            "--workdir".to_string(),
+            working_dir,
+        ];
+        if let Some(user) = &self.connection_options.user {
+            docker_args.push("--user".to_string());
+            docker_args.push(user.clone());
+        }
+        docker_args.extend([
+            self.connection_options.container.clone(),
+            self.shell.clone(),
+            "-c".to_string(),
+            exec,
+        ]);
+
+        Ok(CommandTemplate {
+            program: self.connection_options.runtime.program().to_string(),
+            args: docker_args,
+            env: HashMap::default(),
+        })
+    }
+
+    fn build_forward_port_command(
+        &self,
+        _: u16,
+        _: String,
+        _: u16,
+    ) -> anyhow::Result<CommandTemplate> {
+        Err(anyhow!("port forwarding is not supported for containers"))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Docker(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+}
+
+pub(crate) async fn run_runtime_command(runtime: ContainerRuntime, args: &[&str]) -> Result<String> {
+    let output = util::command::new_smol_command(runtime.program())
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("failed to run {runtime}"))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "{runtime} {} failed: {}",
+        args.first().copied().unwrap_or_default(),
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+async fn copy_to_container(
+    options: &DockerConnectionOptions,
+    src_path: &Path,
+    dst_path: &str,
+) -> Result<()> {
+    let destination = format!("{}:{}", options.container, dst_path);
+    let src_path = src_path.to_string_lossy();
+    run_runtime_command(options.runtime, &["cp", &src_path, &destination]).await?;
+
+    let output = docker_exec_command(options, None, "sh", &["-c", "echo \"$(id -u):$(id -g)\""])
+        .output()
+        .await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to detect the container user: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    let owner = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    if let Some(chown_args) = chown_args(&options.container, &owner, dst_path) {
+        run_runtime_command(options.runtime, &chown_args).await?;
+    }
+    Ok(())
+}
+
+/// The runtime arguments handing files copied into the container over to `owner`, a `uid:gid`
+/// pair. `cp` creates them as root, so commands run as another user could not modify them.
+fn chown_args<'a>(container: &'a str, owner: &'a str, path: &'a str) -> Option<[&'a str; 8]> {
+    (owner != "0:0").then_some(["exec", "--user", "0", container, "chown", "-R", owner, path])
+}
+
+/// Creates a new `docker exec` command that runs the given program with the given arguments,
+/// with its stdio piped to Zed.
+pub(crate) fn docker_exec_command(
+    options: &DockerConnectionOptions,
+    working_dir: Option<&str>,
+    program: &str,
+    args: &[impl AsRef<OsStr>],
+) -> process::Command {
+    let mut command = util::command::new_smol_command(options.runtime.program());
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .arg("exec")
+        .arg("--interactive");
+
+    if let Some(user) = &options.user {
+        command.arg("--user").arg(user);
+    }
+    if let Some(working_dir) = working_dir {
+        command.arg("--workdir").arg(working_dir);
+    }
+
+    command.arg(&options.container).arg(program).args(args);
+
+    log::debug!("{} {:?}", options.runtime, command);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chown_args_for_non_root_user() {
+        assert_eq!(
+            chown_args("dev", "1000:1000", "/home/vscode/.zed_server/zed.gz"),
+            Some([
+                "exec",
+                "--user",
+                "0",
+                "dev",
+                "chown",
+                "-R",
+                "1000:1000",
+                "/home/vscode/.zed_server/zed.gz",
+            ])
+        );
+        assert_eq!(chown_args("dev", "0:0", "/root/.zed_server/zed.gz"), None);
+    }
+}
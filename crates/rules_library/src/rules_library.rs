@@ -1075,6 +1075,7 @@ impl RulesLibrary {
                                     tool_choice: None,
                                     stop: Vec::new(),
                                     temperature: None,
+                                    max_output_tokens: None,
                                     thinking_allowed: true,
                                 },
                                 cx,
@@ -838,6 +838,17 @@ impl BufferSearchBar {
         self.replacement_editor.read(cx).text(cx)
     }
 
+    /// Fills an empty query history, e.g. with queries restored from a previous session.
+    pub fn restore_search_history(&mut self, queries: impl IntoIterator<Item = String>) {
+        if !self.search_history.is_empty() {
+            return;
+        }
+        for query in queries {
+            self.search_history.add(&mut self.search_history_cursor, query);
+        }
+        self.search_history_cursor.reset();
+    }
+
     pub fn query_suggestion(
         &mut self,
         window: &mut Window,
@@ -120,6 +120,10 @@ pub struct SettingsContent {
 
     pub outline_panel: Option<OutlinePanelSettingsContent>,
 
+    pub hierarchy_panel: Option<HierarchyPanelSettingsContent>,
+
+    pub undo_tree_panel: Option<UndoTreePanelSettingsContent>,
+
     pub project_panel: Option<ProjectPanelSettingsContent>,
 
     /// Configuration for the Message Editor
@@ -652,6 +656,15 @@ pub struct VimSettingsContent {
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
     pub highlight_on_yank_duration: Option<u64>,
     pub cursor_shape: Option<CursorShapeSettings>,
+    /// The shell command run by `:make`.
+    ///
+    /// Default: "make"
+    pub make_program: Option<String>,
+    /// Patterns, in vim's `errorformat` syntax, used to find file locations in the
+    /// output of `:make` and `:cbuffer`.
+    pub error_format: Option<Vec<String>>,
+    /// Which registers and history to keep between sessions.
+    pub persistence: Option<VimPersistenceSettingsContent>,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Debug)]
@@ -674,6 +687,29 @@ pub enum UseSystemClipboard {
     OnYank,
 }
 
+/// The settings for persisting vim registers, macros and history between sessions.
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct VimPersistenceSettingsContent {
+    /// Whether to save registers, macros, command history and search history
+    /// when Zed quits, and restore them when a workspace is opened.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The number of entries to keep in the command and search histories.
+    ///
+    /// Default: 100
+    pub history_size: Option<usize>,
+    /// Registers larger than this many bytes are not saved.
+    ///
+    /// Default: 10000
+    pub max_register_bytes: Option<usize>,
+    /// Registers that are never saved, e.g. `["a", "-"]`.
+    ///
+    /// Default: []
+    pub excluded_registers: Option<Vec<char>>,
+}
+
 /// The settings for cursor shape.
 #[skip_serializing_none]
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
@@ -773,6 +809,48 @@ pub struct OutlinePanelSettingsContent {
     pub expand_outlines_with_depth: Option<usize>,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct HierarchyPanelSettingsContent {
+    /// Whether to show the hierarchy panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Customize default width (in pixels) taken by hierarchy panel
+    ///
+    /// Default: 300
+    pub default_width: Option<f32>,
+    /// The position of hierarchy panel
+    ///
+    /// Default: left
+    pub dock: Option<DockSide>,
+    /// Amount of indentation (in pixels) for nested calls.
+    ///
+    /// Default: 20
+    pub indent_size: Option<f32>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct UndoTreePanelSettingsContent {
+    /// Whether to show the undo tree panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Customize default width (in pixels) taken by undo tree panel
+    ///
+    /// Default: 300
+    pub default_width: Option<f32>,
+    /// The position of undo tree panel
+    ///
+    /// Default: right
+    pub dock: Option<DockSide>,
+    /// Amount of indentation (in pixels) for undo branches.
+    ///
+    /// Default: 20
+    pub indent_size: Option<f32>,
+}
+
 #[derive(
     Clone,
     Copy,
@@ -61,6 +61,8 @@ pub struct EditorSettingsContent {
     pub scrollbar: Option<ScrollbarContent>,
     /// Minimap related settings
     pub minimap: Option<MinimapContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -291,6 +293,22 @@ pub struct MinimapContent {
     pub max_width_columns: Option<num::NonZeroU32>,
 }
 
+/// Sticky scroll related settings
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the first lines of the scopes enclosing the top of the editor, such as
+    /// functions, classes and impls, while scrolling through them.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+
+    /// The maximum number of nested scopes to pin.
+    ///
+    /// Default: 5
+    pub max_depth: Option<u32>,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Default)]
@@ -323,6 +323,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to highlight the code with semantic tokens from the language server,
+    /// layered on top of the tree-sitter syntax highlighting.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
     /// Whether indentation should be adjusted based on the context whilst typing.
     ///
     /// Default: true
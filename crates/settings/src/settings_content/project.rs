@@ -88,6 +88,12 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: ["**/.env*", "**/*.pem", "**/*.key", "**/*.cert", "**/*.crt", "**/secrets.yml"]
     pub private_files: Option<ExtendingVec<String>>,
+
+    /// Whether to keep a persistent index of the worktree's file contents, which speeds
+    /// up project search in large worktrees at the cost of disk space.
+    ///
+    /// Default: false
+    pub search_index: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -181,6 +187,16 @@ pub enum ContextServerSettingsContent {
         #[serde(flatten)]
         command: ContextServerCommand,
     },
+    /// A remote context server, reached over the Streamable HTTP transport,
+    /// or the legacy HTTP with SSE one for servers which do not support it.
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+
+        #[serde(flatten)]
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         /// Whether the context server is enabled.
         #[serde(default = "default_true")]
@@ -201,6 +217,12 @@ impl ContextServerSettingsContent {
             } => {
                 *custom_enabled = enabled;
             }
+            ContextServerSettingsContent::Http {
+                enabled: http_enabled,
+                endpoint: _,
+            } => {
+                *http_enabled = enabled;
+            }
             ContextServerSettingsContent::Extension {
                 enabled: ext_enabled,
                 settings: _,
@@ -245,6 +267,39 @@ impl std::fmt::Debug for ContextServerCommand {
     }
 }
 
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct ContextServerEndpoint {
+    /// The URL of the MCP endpoint, e.g. `https://example.com/mcp`.
+    pub url: String,
+    /// Headers to send with every request to the server.
+    pub headers: Option<HashMap<String, String>>,
+    /// A token to send in the `Authorization: Bearer <token>` header.
+    pub bearer_token: Option<String>,
+    /// Timeout for tool calls in milliseconds. Defaults to 60000 (60 seconds) if not specified.
+    pub timeout: Option<u64>,
+}
+
+impl std::fmt::Debug for ContextServerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Header values commonly hold credentials, so only their names are printed.
+        let header_names = self
+            .headers
+            .as_ref()
+            .map(|headers| headers.keys().collect::<Vec<_>>());
+
+        f.debug_struct("ContextServerEndpoint")
+            .field("url", &self.url)
+            .field("headers", &header_names)
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Copy, Clone, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct GitSettings {
@@ -91,6 +91,18 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: true
     pub keep_selection_on_copy: Option<bool>,
+    /// Whether to set up shell integration for bash, zsh and fish, so that the
+    /// terminal knows where each command and its output start and end.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// Whether to display images that programs print with the sixel, kitty
+    /// graphics and iTerm2 inline image protocols.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub inline_images: Option<bool>,
     /// Whether to show the terminal button in the status bar.
     ///
     /// Default: true
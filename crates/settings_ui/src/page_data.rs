@@ -1356,6 +1356,53 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                         metadata: None,
                         files: USER,
                     }),
+                    SettingsPageItem::SectionHeader("Sticky Scroll"),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Enabled",
+                        description: "Pin the first lines of the enclosing scopes to the top of the editor",
+                        field: Box::new(SettingField {
+                            pick: |settings_content| {
+                                if let Some(sticky_scroll) = &settings_content.editor.sticky_scroll
+                                {
+                                    &sticky_scroll.enabled
+                                } else {
+                                    &None
+                                }
+                            },
+                            pick_mut: |settings_content| {
+                                &mut settings_content
+                                    .editor
+                                    .sticky_scroll
+                                    .get_or_insert_default()
+                                    .enabled
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Max Depth",
+                        description: "Maximum number of nested scopes to pin",
+                        field: Box::new(SettingField {
+                            pick: |settings_content| {
+                                if let Some(sticky_scroll) = &settings_content.editor.sticky_scroll
+                                {
+                                    &sticky_scroll.max_depth
+                                } else {
+                                    &None
+                                }
+                            },
+                            pick_mut: |settings_content| {
+                                &mut settings_content
+                                    .editor
+                                    .sticky_scroll
+                                    .get_or_insert_default()
+                                    .max_depth
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
                     SettingsPageItem::SectionHeader("Toolbar"),
                     SettingsPageItem::SettingItem(SettingItem {
                         title: "Breadcrumbs",
@@ -3354,6 +3401,176 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     ),
                     metadata: None,
                 }),
+                SettingsPageItem::SectionHeader("Hierarchy Panel"),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Hierarchy Panel Button",
+                    description: "Show the hierarchy panel button in the status bar",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(hierarchy_panel) = &settings_content.hierarchy_panel {
+                                &hierarchy_panel.button
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .hierarchy_panel
+                                .get_or_insert_default()
+                                .button
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Hierarchy Panel Dock",
+                    description: "Where to dock the hierarchy panel",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(hierarchy_panel) = &settings_content.hierarchy_panel {
+                                &hierarchy_panel.dock
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .hierarchy_panel
+                                .get_or_insert_default()
+                                .dock
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Hierarchy Panel Default Width",
+                    description: "Default width of the hierarchy panel in pixels",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(hierarchy_panel) = &settings_content.hierarchy_panel {
+                                &hierarchy_panel.default_width
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .hierarchy_panel
+                                .get_or_insert_default()
+                                .default_width
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Hierarchy Panel Indent Size",
+                    description: "Amount of indentation (in pixels) for nested calls",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(hierarchy_panel) = &settings_content.hierarchy_panel {
+                                &hierarchy_panel.indent_size
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .hierarchy_panel
+                                .get_or_insert_default()
+                                .indent_size
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SectionHeader("Undo Tree Panel"),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Undo Tree Panel Button",
+                    description: "Show the undo tree panel button in the status bar",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(undo_tree_panel) = &settings_content.undo_tree_panel {
+                                &undo_tree_panel.button
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .undo_tree_panel
+                                .get_or_insert_default()
+                                .button
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Undo Tree Panel Dock",
+                    description: "Where to dock the undo tree panel",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(undo_tree_panel) = &settings_content.undo_tree_panel {
+                                &undo_tree_panel.dock
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .undo_tree_panel
+                                .get_or_insert_default()
+                                .dock
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Undo Tree Panel Default Width",
+                    description: "Default width of the undo tree panel in pixels",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(undo_tree_panel) = &settings_content.undo_tree_panel {
+                                &undo_tree_panel.default_width
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .undo_tree_panel
+                                .get_or_insert_default()
+                                .default_width
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Undo Tree Panel Indent Size",
+                    description: "Amount of indentation (in pixels) for undo branches",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(undo_tree_panel) = &settings_content.undo_tree_panel {
+                                &undo_tree_panel.indent_size
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .undo_tree_panel
+                                .get_or_insert_default()
+                                .indent_size
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SectionHeader("Git Panel"),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Git Panel Button",
@@ -4246,6 +4463,48 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     metadata: None,
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Shell Integration",
+                    description: "Whether to set up shell integration for bash, zsh and fish to track commands and their output",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(terminal) = &settings_content.terminal {
+                                &terminal.shell_integration
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .shell_integration
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Inline Images",
+                    description: "Whether to display images printed with the sixel, kitty graphics and iTerm2 image protocols",
+                    field: Box::new(SettingField {
+                        pick: |settings_content| {
+                            if let Some(terminal) = &settings_content.terminal {
+                                &terminal.inline_images
+                            } else {
+                                &None
+                            }
+                        },
+                        pick_mut: |settings_content| {
+                            &mut settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .inline_images
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SectionHeader("Layout Settings"),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Default Width",
@@ -6151,6 +6410,22 @@ fn non_editor_language_settings_data() -> Vec<SettingsPageItem> {
             metadata: None,
             files: USER | LOCAL,
         }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Semantic Tokens",
+            description: "Whether to highlight the code with semantic tokens from the LS, on top of the tree-sitter highlighting",
+            field: Box::new(SettingField {
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| &language.semantic_tokens)
+                },
+                pick_mut: |settings_content| {
+                    language_settings_field_mut(settings_content, |language| {
+                        &mut language.semantic_tokens
+                    })
+                },
+            }),
+            metadata: None,
+            files: USER | LOCAL,
+        }),
         SettingsPageItem::SettingItem(SettingItem {
             title: "Go To Definition Fallback",
             description: "Whether to follow-up empty go to definition responses from the language server",
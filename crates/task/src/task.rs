@@ -26,7 +26,7 @@ pub use debug_format::{
 };
 pub use shell_builder::{ShellBuilder, ShellKind};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use vscode_debug_format::VsCodeDebugTaskFile;
@@ -123,6 +123,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task was resolved with, kept to resolve the tasks it depends on.
+    dependencies_context: Option<TaskContext>,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -143,6 +145,16 @@ impl ResolvedTask {
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// The context to resolve the tasks this task depends on with, if it depends on any.
+    pub fn dependencies_context(&self) -> Option<&TaskContext> {
+        self.dependencies_context.as_ref()
+    }
+
+    /// Whether the task only runs the tasks it depends on, without a command of its own.
+    pub fn is_compound(&self) -> bool {
+        self.original_task.command.trim().is_empty()
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
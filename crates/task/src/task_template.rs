@@ -72,6 +72,15 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks to run before this one, which only starts if they all succeed.
+    /// A task that depends on others may have no command, to only run them.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Whether the tasks in `depends_on` run all at once, or one after another in the order given:
+    /// * `parallel` — start them all at once (default)
+    /// * `sequence` — start each one after the previous one succeeded
+    #[serde(default)]
+    pub depends_order: DependsOrder,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +118,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How the tasks that a task depends on are run.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all of them at once.
+    #[default]
+    Parallel,
+    /// Start each one after the previous one succeeded.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -133,7 +153,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -245,6 +267,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            dependencies_context: (!self.depends_on.is_empty()).then(|| cx.clone()),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -469,6 +492,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_templates_with_dependencies() {
+        let compound_task = TaskTemplate {
+            label: "build and test".to_string(),
+            depends_on: vec!["build".to_string(), "test".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        let task_cx = TaskContext {
+            cwd: Some(PathBuf::from("/dir")),
+            ..TaskContext::default()
+        };
+
+        let resolved_task = compound_task
+            .resolve_task(TEST_ID_BASE, &task_cx)
+            .expect("should resolve a task without a command that depends on others");
+        assert!(resolved_task.is_compound());
+        assert_eq!(resolved_task.dependencies_context(), Some(&task_cx));
+
+        let task = TaskTemplate {
+            label: "test".to_string(),
+            command: "cargo test".to_string(),
+            ..TaskTemplate::default()
+        };
+        let resolved_task = task.resolve_task(TEST_ID_BASE, &task_cx).unwrap();
+        assert!(!resolved_task.is_compound());
+        assert_eq!(resolved_task.dependencies_context(), None);
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -1,9 +1,9 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{DependsOrder, EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -42,23 +42,41 @@ enum Command {
 }
 
 impl VsCodeTaskDefinition {
-    fn into_zed_format(
-        self,
-        replacer: &EnvVariableReplacer,
-    ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            None => Vec::new(),
+            Some(serde_json_lenient::Value::String(label)) => vec![label.clone()],
+            Some(serde_json_lenient::Value::Array(labels)) => labels
+                .iter()
+                .map(|label| {
+                    label
+                        .as_str()
+                        .map(ToOwned::to_owned)
+                        .context("`dependsOn` must only contain task labels")
+                })
+                .collect::<anyhow::Result<_>>()?,
+            Some(_) => bail!("`dependsOn` must be a task label or a list of them"),
+        };
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            Some("sequence") => DependsOrder::Sequence,
+            _ => DependsOrder::Parallel,
+        };
+
+        // `type` might not be set in tasks that only run the tasks they depend on.
         let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+            if depends_on.is_empty() {
+                bail!("Missing `type` field in task");
+            }
+            return Ok(TaskTemplate {
+                label: self.label,
+                depends_on,
+                depends_order,
+                ..TaskTemplate::default()
+            });
         };
 
         let (command, args) = match command {
@@ -73,13 +91,15 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
-        Ok(Some(template))
+        Ok(template)
     }
 }
 
@@ -108,12 +128,7 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| {
-                vscode_definition
-                    .into_zed_format(&replacer)
-                    .log_err()
-                    .flatten()
-            })
+            .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
             .collect();
         Ok(Self(templates))
     }
@@ -354,6 +369,19 @@ mod tests {
                 args: vec!["run".to_string(), "pretest".to_string()],
                 ..Default::default()
             },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                ..Default::default()
+            },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
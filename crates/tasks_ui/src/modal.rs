@@ -461,6 +461,14 @@ impl PickerDelegate for TasksModalDelegate {
             tooltip_label_text.push_str(&resolved_task.resolved.command_label);
         }
 
+        if !template.depends_on.is_empty() {
+            if !tooltip_label_text.trim().is_empty() {
+                tooltip_label_text.push('\n');
+            }
+            tooltip_label_text.push_str("Runs after: ");
+            tooltip_label_text.push_str(&template.depends_on.join(", "));
+        }
+
         if !template.tags.is_empty() {
             tooltip_label_text.push('\n');
             tooltip_label_text.push_str(
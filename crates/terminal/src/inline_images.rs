@@ -0,0 +1,843 @@
+//! Images that programs display in the terminal with the sixel, kitty graphics and iTerm2 inline
+//! image protocols.
+//!
+//! The [`InterceptingPty`] removes the escape sequences carrying images from the program's output
+//! and decodes them on the PTY's thread. In their place, it hands the terminal line feeds that
+//! make room for the image in the grid. The [`Terminal`] places the image at the cursor position
+//! in its [`InlineImages`], so that it scrolls along with the text around it.
+//!
+//! [`InterceptingPty`]: crate::intercepting_pty::InterceptingPty
+//! [`Terminal`]: crate::Terminal
+
+mod sixel;
+
+use crate::shell_integration::HistoryPoint;
+use alacritty_terminal::{event::WindowSize, index::Point as AlacPoint};
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::VecDeque;
+use gpui::RenderImage;
+use image::{Frame, ImageReader, Limits, RgbaImage};
+use std::{io::Cursor, sync::Arc};
+use util::ResultExt as _;
+
+/// The length of the longest image sequence that is decoded. Longer ones are skipped.
+pub(crate) const MAX_ENCODED_IMAGE_LEN: usize = 32 * 1024 * 1024;
+
+/// The largest width or height of an image, in pixels.
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+
+/// How many bytes of decoded images a terminal displays. The oldest images are dropped first.
+const MAX_IMAGE_BYTES: usize = 256 * 1024 * 1024;
+
+/// How many images transmitted with the kitty graphics protocol are kept to be displayed later.
+const MAX_KITTY_IMAGES: usize = 64;
+
+/// Image data is split into chunks that are padded separately, so padding may appear anywhere.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ImageKind {
+    /// A DCS sequence with sixel data.
+    Sixel,
+    /// An APC sequence with a kitty graphics protocol command.
+    Kitty,
+    /// An OSC 1337 sequence with an iTerm2 inline file.
+    ITerm2,
+}
+
+/// Where the cursor goes after an image is displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorMovement {
+    /// To the start of the line below the image.
+    NextLine,
+    /// To the cell after the image, on its last line.
+    AfterImage,
+    /// The cursor stays at the top left corner of the image.
+    None,
+}
+
+/// A decoded image, and the number of cells it covers.
+pub(crate) struct InlineImage {
+    image: Arc<RenderImage>,
+    columns: usize,
+    rows: usize,
+    /// The id the image was given with the kitty graphics protocol.
+    kitty_id: Option<u32>,
+    cursor_movement: CursorMovement,
+}
+
+impl InlineImage {
+    fn new(
+        image: Arc<RenderImage>,
+        width: Dimension,
+        height: Dimension,
+        window_size: WindowSize,
+        cursor_movement: CursorMovement,
+    ) -> Self {
+        let image_size = image.size(0);
+        let cell_width = f32::from(window_size.cell_width.max(1));
+        let cell_height = f32::from(window_size.cell_height.max(1));
+        let image_width = image_size.width.0.max(1) as f32;
+        let image_height = image_size.height.0.max(1) as f32;
+        let terminal_width = f32::from(window_size.num_cols) * cell_width;
+        let terminal_height = f32::from(window_size.num_lines) * cell_height;
+
+        let (mut width, mut height) = match (
+            width.to_pixels(cell_width, terminal_width),
+            height.to_pixels(cell_height, terminal_height),
+        ) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, width * image_height / image_width),
+            (None, Some(height)) => (height * image_width / image_height, height),
+            (None, None) => (image_width, image_height),
+        };
+        // Images are scaled down to fit the width of the terminal.
+        if width > terminal_width && terminal_width > 0. {
+            height *= terminal_width / width;
+            width = terminal_width;
+        }
+
+        Self {
+            image,
+            columns: (width / cell_width).ceil().max(1.) as usize,
+            rows: (height / cell_height).ceil().max(1.) as usize,
+            kitty_id: None,
+            cursor_movement,
+        }
+    }
+
+    /// The output that moves the cursor past the image, which scrolls the grid to make room for
+    /// it.
+    fn cursor_movement(&self) -> Vec<u8> {
+        let line_feeds = "\n".repeat(self.rows - 1);
+        match self.cursor_movement {
+            CursorMovement::NextLine => format!("{line_feeds}\r\n").into_bytes(),
+            CursorMovement::AfterImage => {
+                format!("{line_feeds}\x1b[{}C", self.columns).into_bytes()
+            }
+            CursorMovement::None => Vec::new(),
+        }
+    }
+}
+
+/// A requested width or height of an image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Dimension {
+    /// The image's own size, or the size that keeps its aspect ratio.
+    #[default]
+    Auto,
+    Cells(u32),
+    Pixels(u32),
+    /// A percentage of the terminal's size.
+    Percent(u32),
+}
+
+impl Dimension {
+    /// Parses an iTerm2 `width` or `height` argument, such as `10`, `200px`, `50%` or `auto`.
+    fn parse_iterm2(value: &str) -> Self {
+        let parsed = if let Some(pixels) = value.strip_suffix("px") {
+            pixels.parse().map(Self::Pixels)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse().map(Self::Percent)
+        } else {
+            value.parse().map(Self::Cells)
+        };
+        parsed.unwrap_or_default()
+    }
+
+    fn to_pixels(self, cell_size: f32, terminal_size: f32) -> Option<f32> {
+        match self {
+            Self::Auto | Self::Cells(0) | Self::Pixels(0) | Self::Percent(0) => None,
+            Self::Cells(cells) => Some(cells as f32 * cell_size),
+            Self::Pixels(pixels) => Some(pixels as f32),
+            Self::Percent(percent) => Some(percent.min(100) as f32 * terminal_size / 100.),
+        }
+    }
+}
+
+/// What the terminal does in response to an image sequence.
+pub(crate) enum ImageCommand {
+    /// Displays an image at the cursor.
+    Place(InlineImage),
+    /// Deletes the images displayed with the kitty graphics protocol, either all of them or the
+    /// ones with the given id.
+    Delete { kitty_id: Option<u32> },
+    /// Sends a response to the program.
+    Reply(Vec<u8>),
+}
+
+impl ImageCommand {
+    pub(crate) fn is_positioned(&self) -> bool {
+        matches!(self, Self::Place(_))
+    }
+
+    /// The output that takes the place of the image sequence.
+    pub(crate) fn replacement(&self) -> Vec<u8> {
+        match self {
+            Self::Place(image) => image.cursor_movement(),
+            Self::Delete { .. } | Self::Reply(_) => Vec::new(),
+        }
+    }
+}
+
+/// Decodes image sequences, keeping the state that the kitty graphics protocol needs across them.
+#[derive(Default)]
+pub(crate) struct ImageDecoder {
+    /// Transmitted images that can be displayed by id, from oldest to newest.
+    kitty_images: VecDeque<(u32, Arc<RenderImage>)>,
+    kitty_images_byte_len: usize,
+    /// The first command of a transmission split into chunks, and the data received so far.
+    kitty_transmission: Option<(KittyCommand, Vec<u8>)>,
+}
+
+impl ImageDecoder {
+    pub(crate) fn decode(
+        &mut self,
+        kind: ImageKind,
+        payload: &[u8],
+        window_size: WindowSize,
+    ) -> Vec<ImageCommand> {
+        match kind {
+            ImageKind::Sixel => {
+                let Some(image) = sixel::decode(payload, MAX_IMAGE_DIMENSION) else {
+                    log::debug!("skipping empty or oversized sixel image");
+                    return Vec::new();
+                };
+                let image = InlineImage::new(
+                    render_image(image),
+                    Dimension::Auto,
+                    Dimension::Auto,
+                    window_size,
+                    CursorMovement::NextLine,
+                );
+                vec![ImageCommand::Place(image)]
+            }
+            ImageKind::ITerm2 => decode_iterm2(payload, window_size)
+                .context("decoding iTerm2 inline image")
+                .log_err()
+                .flatten()
+                .map(ImageCommand::Place)
+                .into_iter()
+                .collect(),
+            ImageKind::Kitty => self.decode_kitty(payload, window_size),
+        }
+    }
+
+    fn decode_kitty(&mut self, payload: &[u8], window_size: WindowSize) -> Vec<ImageCommand> {
+        let (control, data) = match payload.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&payload[..ix], &payload[ix + 1..]),
+            None => (payload, &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+
+        // Chunks after the first one only say whether more chunks follow.
+        let (command, data) = match self.kitty_transmission.take() {
+            Some((first_command, mut received)) => {
+                received.extend_from_slice(data);
+                if received.len() > MAX_ENCODED_IMAGE_LEN {
+                    log::warn!("skipping kitty image larger than {MAX_ENCODED_IMAGE_LEN} bytes");
+                    return Vec::new();
+                }
+                if command.more_chunks {
+                    self.kitty_transmission = Some((first_command, received));
+                    return Vec::new();
+                }
+                (first_command, received)
+            }
+            None => {
+                if command.more_chunks && matches!(command.action, b't' | b'T' | b'q') {
+                    self.kitty_transmission = Some((command, data.to_vec()));
+                    return Vec::new();
+                }
+                (command, data.to_vec())
+            }
+        };
+
+        let placement = match command.action {
+            b't' | b'T' | b'q' => self.kitty_transmit(&command, &data, window_size),
+            b'p' => self.kitty_put(&command, window_size).map(Some),
+            b'd' => return self.kitty_delete(&command).into_iter().collect(),
+            action => Err(anyhow!("EINVAL:unsupported action {:?}", action as char)),
+        };
+
+        let mut commands = Vec::new();
+        if let Some(reply) = command.reply(&placement) {
+            commands.push(ImageCommand::Reply(reply));
+        }
+        if let Ok(Some(image)) = placement {
+            commands.push(ImageCommand::Place(image));
+        }
+        commands
+    }
+
+    /// Decodes transmitted image data, returning the image to display if the command displays it.
+    fn kitty_transmit(
+        &mut self,
+        command: &KittyCommand,
+        data: &[u8],
+        window_size: WindowSize,
+    ) -> Result<Option<InlineImage>> {
+        if command.transmission != b'd' {
+            bail!("EINVAL:only direct transmission is supported");
+        }
+        if command.compression.is_some() {
+            bail!("EINVAL:compression is not supported");
+        }
+        let data = decode_base64(data).map_err(|error| anyhow!("EINVAL:{error}"))?;
+        let image = match command.format {
+            24 | 32 => {
+                let (width, height) = (command.width, command.height);
+                if width == 0 || height == 0 {
+                    bail!("EINVAL:missing image size");
+                }
+                if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+                    bail!("EFBIG:image is too large");
+                }
+                let pixels = width as usize * height as usize;
+                let rgba = if command.format == 24 {
+                    data.get(..pixels * 3)
+                        .map(|rgb| {
+                            rgb.chunks_exact(3)
+                                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+                                .collect()
+                        })
+                        .context("ENODATA:insufficient image data")?
+                } else {
+                    data.get(..pixels * 4)
+                        .context("ENODATA:insufficient image data")?
+                        .to_vec()
+                };
+                RgbaImage::from_raw(width, height, rgba).context("EINVAL:invalid image data")?
+            }
+            100 => load_image(&data).map_err(|error| anyhow!("EBADPNG:{error}"))?,
+            format => bail!("EINVAL:unsupported format {format}"),
+        };
+        if command.action == b'q' {
+            return Ok(None);
+        }
+
+        let image = render_image(image);
+        if command.image_id != 0 {
+            self.free_kitty_images(|id| id == command.image_id);
+            self.kitty_images_byte_len += byte_len(&image);
+            self.kitty_images
+                .push_back((command.image_id, image.clone()));
+            while self.kitty_images.len() > MAX_KITTY_IMAGES
+                || self.kitty_images_byte_len > MAX_IMAGE_BYTES
+            {
+                let Some((_, oldest)) = self.kitty_images.pop_front() else {
+                    break;
+                };
+                self.kitty_images_byte_len -= byte_len(&oldest);
+            }
+        }
+        Ok((command.action == b'T').then(|| command.placement(image, window_size)))
+    }
+
+    fn kitty_put(&self, command: &KittyCommand, window_size: WindowSize) -> Result<InlineImage> {
+        let (_, image) = self
+            .kitty_images
+            .iter()
+            .find(|(id, _)| *id == command.image_id)
+            .context("ENOENT:image not found")?;
+        Ok(command.placement(image.clone(), window_size))
+    }
+
+    fn kitty_delete(&mut self, command: &KittyCommand) -> Option<ImageCommand> {
+        // Upper case targets also free the transmitted image data.
+        let free_data = command.delete.is_ascii_uppercase();
+        match command.delete.to_ascii_lowercase() {
+            b'a' => {
+                if free_data {
+                    self.free_kitty_images(|_| true);
+                }
+                Some(ImageCommand::Delete { kitty_id: None })
+            }
+            b'i' => {
+                if free_data {
+                    self.free_kitty_images(|id| id == command.image_id);
+                }
+                Some(ImageCommand::Delete {
+                    kitty_id: Some(command.image_id),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn free_kitty_images(&mut self, mut free: impl FnMut(u32) -> bool) {
+        let mut byte_len = self.kitty_images_byte_len;
+        self.kitty_images.retain(|(id, image)| {
+            let keep = !free(*id);
+            if !keep {
+                byte_len -= self::byte_len(image);
+            }
+            keep
+        });
+        self.kitty_images_byte_len = byte_len;
+    }
+}
+
+/// The keys of a kitty graphics protocol command that are supported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    transmission: u8,
+    compression: Option<u8>,
+    width: u32,
+    height: u32,
+    columns: u32,
+    rows: u32,
+    image_id: u32,
+    placement_id: u32,
+    more_chunks: bool,
+    quiet: u32,
+    cursor_movement: u32,
+    delete: u8,
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            transmission: b'd',
+            compression: None,
+            width: 0,
+            height: 0,
+            columns: 0,
+            rows: 0,
+            image_id: 0,
+            placement_id: 0,
+            more_chunks: false,
+            quiet: 0,
+            cursor_movement: 0,
+            delete: b'a',
+        };
+        for key_value in control.split(|&byte| byte == b',') {
+            let [key, b'=', value @ ..] = key_value else {
+                continue;
+            };
+            let number = || {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse::<u32>().ok())
+                    .unwrap_or(0)
+            };
+            let character = value.first().copied();
+            match key {
+                b'a' => command.action = character.unwrap_or(b't'),
+                b'f' => command.format = number(),
+                b't' => command.transmission = character.unwrap_or(b'd'),
+                b'o' => command.compression = character,
+                b's' => command.width = number(),
+                b'v' => command.height = number(),
+                b'c' => command.columns = number(),
+                b'r' => command.rows = number(),
+                b'i' => command.image_id = number(),
+                b'p' => command.placement_id = number(),
+                b'm' => command.more_chunks = number() == 1,
+                b'q' => command.quiet = number(),
+                b'C' => command.cursor_movement = number(),
+                b'd' => command.delete = character.unwrap_or(b'a'),
+                _ => {}
+            }
+        }
+        command
+    }
+
+    fn placement(&self, image: Arc<RenderImage>, window_size: WindowSize) -> InlineImage {
+        let mut image = InlineImage::new(
+            image,
+            Dimension::Cells(self.columns),
+            Dimension::Cells(self.rows),
+            window_size,
+            if self.cursor_movement == 1 {
+                CursorMovement::None
+            } else {
+                CursorMovement::AfterImage
+            },
+        );
+        image.kitty_id = (self.image_id != 0).then_some(self.image_id);
+        image
+    }
+
+    /// The response to the command, which is only sent for commands that identify the image and
+    /// don't ask to be quiet.
+    fn reply(&self, result: &Result<Option<InlineImage>>) -> Option<Vec<u8>> {
+        if self.image_id == 0 {
+            return None;
+        }
+        let message = match result {
+            Ok(_) if self.quiet == 0 => "OK".to_string(),
+            Err(error) if self.quiet < 2 => error.to_string(),
+            _ => return None,
+        };
+        let mut reply = format!("\x1b_Gi={}", self.image_id);
+        if self.placement_id != 0 {
+            reply.push_str(&format!(",p={}", self.placement_id));
+        }
+        reply.push_str(&format!(";{message}\x1b\\"));
+        Some(reply.into_bytes())
+    }
+}
+
+fn decode_iterm2(payload: &[u8], window_size: WindowSize) -> Result<Option<InlineImage>> {
+    let separator = payload
+        .iter()
+        .position(|&byte| byte == b':')
+        .context("missing file contents")?;
+    let args = std::str::from_utf8(&payload[..separator])?;
+    let mut width = Dimension::Auto;
+    let mut height = Dimension::Auto;
+    let mut inline = false;
+    for arg in args.split(';') {
+        match arg.split_once('=') {
+            Some(("width", value)) => width = Dimension::parse_iterm2(value),
+            Some(("height", value)) => height = Dimension::parse_iterm2(value),
+            Some(("inline", value)) => inline = value == "1",
+            _ => {}
+        }
+    }
+    // Files that are not displayed inline are downloads, which are not supported.
+    if !inline {
+        return Ok(None);
+    }
+
+    let data = decode_base64(&payload[separator + 1..])?;
+    let image = render_image(load_image(&data)?);
+    Ok(Some(InlineImage::new(
+        image,
+        width,
+        height,
+        window_size,
+        CursorMovement::NextLine,
+    )))
+}
+
+fn decode_base64(data: &[u8]) -> Result<Vec<u8>> {
+    let data = data
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    Ok(BASE64.decode(data)?)
+}
+
+fn load_image(data: &[u8]) -> Result<RgbaImage> {
+    let mut reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_IMAGE_BYTES as u64);
+    reader.limits(limits);
+    Ok(reader.decode()?.into_rgba8())
+}
+
+fn render_image(mut image: RgbaImage) -> Arc<RenderImage> {
+    // Convert from RGBA to BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Arc::new(RenderImage::new(vec![Frame::new(image)]))
+}
+
+fn byte_len(image: &RenderImage) -> usize {
+    let size = image.size(0);
+    size.width.0.max(0) as usize * size.height.0.max(0) as usize * 4
+}
+
+/// An image in the terminal's grid.
+#[derive(Clone)]
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// The cell at the image's top left corner.
+    pub point: AlacPoint,
+    /// The number of cells the image covers horizontally, which it is scaled down to fit in.
+    pub columns: usize,
+    /// The number of lines the image covers, which it is scaled down to fit in.
+    pub rows: usize,
+}
+
+struct PlacedImage {
+    image: InlineImage,
+    origin: HistoryPoint,
+    alt_screen: bool,
+}
+
+/// The images displayed in a terminal, and where they are in its grid.
+#[derive(Default)]
+pub(crate) struct InlineImages {
+    images: VecDeque<PlacedImage>,
+    /// The size of the scrollback when images were last placed or pruned.
+    history_size: usize,
+    byte_len: usize,
+}
+
+impl InlineImages {
+    /// Places an image with its top left corner at the given cursor position.
+    pub(crate) fn place(
+        &mut self,
+        image: InlineImage,
+        cursor: AlacPoint,
+        history_size: usize,
+        alt_screen: bool,
+    ) {
+        self.prune(history_size, alt_screen);
+        let origin = HistoryPoint::new(cursor, history_size);
+        // Programs that animate or refresh an image draw it in the same place again.
+        self.retain(|placed| placed.origin != origin || placed.alt_screen != alt_screen);
+
+        self.byte_len += byte_len(&image.image);
+        self.images.push_back(PlacedImage {
+            image,
+            origin,
+            alt_screen,
+        });
+        while self.byte_len > MAX_IMAGE_BYTES
+            && let Some(oldest) = self.images.pop_front()
+        {
+            self.byte_len -= byte_len(&oldest.image.image);
+        }
+    }
+
+    /// Deletes the images displayed with the kitty graphics protocol, either all of them or the
+    /// ones with the given id.
+    pub(crate) fn delete(&mut self, kitty_id: Option<u32>) {
+        self.retain(|placed| match (placed.image.kitty_id, kitty_id) {
+            (Some(_), None) => false,
+            (Some(placed_id), Some(kitty_id)) => placed_id != kitty_id,
+            (None, _) => true,
+        });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.images.clear();
+        self.byte_len = 0;
+    }
+
+    /// Forgets the images that can no longer be displayed: the ones in a scrollback that has been
+    /// cleared, and the ones on the alternate screen once it is left.
+    pub(crate) fn prune(&mut self, history_size: usize, alt_screen: bool) {
+        // The alternate screen has no scrollback of its own.
+        if alt_screen {
+            return;
+        }
+        // Positions are relative to the start of the scrollback, so they are meaningless once the
+        // scrollback has been cleared.
+        let scrollback_cleared = history_size < self.history_size;
+        self.history_size = history_size;
+        self.retain(|placed| !placed.alt_screen && !scrollback_cleared);
+    }
+
+    /// The images on the given screen that are at least partly visible.
+    pub(crate) fn visible(
+        &self,
+        history_size: usize,
+        display_offset: usize,
+        screen_lines: usize,
+        alt_screen: bool,
+    ) -> Vec<TerminalImage> {
+        let top_line = -(display_offset as i32);
+        let bottom_line = top_line + screen_lines as i32;
+        self.images
+            .iter()
+            .filter(|placed| placed.alt_screen == alt_screen)
+            .filter_map(|placed| {
+                let point = placed.origin.to_point(history_size);
+                let visible = point.line.0 < bottom_line
+                    && point.line.0 + placed.image.rows as i32 > top_line;
+                visible.then(|| TerminalImage {
+                    image: placed.image.image.clone(),
+                    point,
+                    columns: placed.image.columns,
+                    rows: placed.image.rows,
+                })
+            })
+            .collect()
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&PlacedImage) -> bool) {
+        let mut byte_len = self.byte_len;
+        self.images.retain(|placed| {
+            let keep = keep(placed);
+            if !keep {
+                byte_len -= self::byte_len(&placed.image.image);
+            }
+            keep
+        });
+        self.byte_len = byte_len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::index::{Column, Line};
+
+    fn window_size() -> WindowSize {
+        WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 10,
+            cell_height: 20,
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        RgbaImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut data), image::ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn placed_images(commands: &[ImageCommand]) -> Vec<(usize, usize, Option<u32>)> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                ImageCommand::Place(image) => Some((image.columns, image.rows, image.kitty_id)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn replies(commands: &[ImageCommand]) -> Vec<String> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                ImageCommand::Reply(reply) => Some(String::from_utf8(reply.clone()).unwrap()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_iterm2() {
+        let mut decoder = ImageDecoder::default();
+        let data = BASE64.encode(png(25, 30));
+
+        let payload = format!("name=eA==;inline=1:{data}");
+        let commands = decoder.decode(ImageKind::ITerm2, payload.as_bytes(), window_size());
+        assert_eq!(placed_images(&commands), [(3, 2, None)]);
+        assert_eq!(commands[0].replacement(), b"\n\r\n");
+
+        let payload = format!("width=4;height=50%;inline=1:{data}");
+        let commands = decoder.decode(ImageKind::ITerm2, payload.as_bytes(), window_size());
+        assert_eq!(placed_images(&commands), [(4, 12, None)]);
+
+        // Images wider than the terminal are scaled down to fit.
+        let payload = format!("width=1600px;inline=1:{data}");
+        let commands = decoder.decode(ImageKind::ITerm2, payload.as_bytes(), window_size());
+        assert_eq!(placed_images(&commands), [(80, 48, None)]);
+
+        let payload = format!("inline=0:{data}");
+        let commands = decoder.decode(ImageKind::ITerm2, payload.as_bytes(), window_size());
+        assert!(commands.is_empty(), "downloads are not displayed");
+    }
+
+    #[test]
+    fn test_decode_kitty() {
+        let mut decoder = ImageDecoder::default();
+        let rgb = BASE64.encode([255; 20 * 20 * 3]);
+        let (first_chunk, last_chunk) = rgb.split_at(rgb.len() / 8 * 4);
+
+        // A chunked transmission is displayed once its last chunk arrives.
+        let payload = format!("a=T,f=24,s=20,v=20,i=7,m=1;{first_chunk}");
+        let commands = decoder.decode(ImageKind::Kitty, payload.as_bytes(), window_size());
+        assert!(commands.is_empty());
+        let payload = format!("m=0;{last_chunk}");
+        let commands = decoder.decode(ImageKind::Kitty, payload.as_bytes(), window_size());
+        assert_eq!(replies(&commands), ["\x1b_Gi=7;OK\x1b\\"]);
+        assert_eq!(placed_images(&commands), [(2, 1, Some(7))]);
+        assert_eq!(commands[1].replacement(), b"\x1b[2C");
+
+        // Transmitted images can be displayed again, in a given number of cells.
+        let commands = decoder.decode(ImageKind::Kitty, b"a=p,i=7,c=4,q=1", window_size());
+        assert_eq!(replies(&commands), Vec::<String>::new());
+        assert_eq!(placed_images(&commands), [(4, 2, Some(7))]);
+
+        let commands = decoder.decode(ImageKind::Kitty, b"a=p,i=8", window_size());
+        assert_eq!(
+            replies(&commands),
+            ["\x1b_Gi=8;ENOENT:image not found\x1b\\"]
+        );
+        assert_eq!(placed_images(&commands), []);
+
+        // Queries check the image without displaying it.
+        let payload = format!("a=q,f=32,s=1,v=1,i=31;{}", BASE64.encode([0; 4]));
+        let commands = decoder.decode(ImageKind::Kitty, payload.as_bytes(), window_size());
+        assert_eq!(replies(&commands), ["\x1b_Gi=31;OK\x1b\\"]);
+        assert_eq!(placed_images(&commands), []);
+
+        let commands = decoder.decode(ImageKind::Kitty, b"a=d,d=I,i=7", window_size());
+        assert!(matches!(
+            commands[..],
+            [ImageCommand::Delete { kitty_id: Some(7) }]
+        ));
+        let commands = decoder.decode(ImageKind::Kitty, b"a=p,i=7", window_size());
+        assert_eq!(
+            replies(&commands),
+            ["\x1b_Gi=7;ENOENT:image not found\x1b\\"]
+        );
+    }
+
+    #[test]
+    fn test_place_images() {
+        let mut decoder = ImageDecoder::default();
+        let mut image = |columns| {
+            let payload = format!("a=T,f=32,s=1,v=1,c={columns};{}", BASE64.encode([0; 4]));
+            match decoder
+                .decode(ImageKind::Kitty, payload.as_bytes(), window_size())
+                .pop()
+            {
+                Some(ImageCommand::Place(image)) => image,
+                _ => panic!("image was not placed"),
+            }
+        };
+        let point = |line, column| AlacPoint::new(Line(line), Column(column));
+        let visible_columns = |images: &InlineImages, history_size, display_offset, alt_screen| {
+            images
+                .visible(history_size, display_offset, 24, alt_screen)
+                .iter()
+                .map(|image| (image.point, image.columns))
+                .collect::<Vec<_>>()
+        };
+
+        let mut images = InlineImages::default();
+        images.place(image(1), point(0, 0), 0, false);
+        images.place(image(2), point(5, 3), 0, false);
+        // An image drawn in the same place replaces the one that was there.
+        images.place(image(3), point(5, 3), 0, false);
+        assert_eq!(
+            visible_columns(&images, 0, 0, false),
+            [(point(0, 0), 1), (point(5, 3), 3)]
+        );
+
+        // Images scroll along with the text, and out of view.
+        assert_eq!(visible_columns(&images, 3, 0, false), [(point(2, 3), 3)]);
+        assert_eq!(
+            visible_columns(&images, 30, 30, false),
+            [(point(-30, 0), 1), (point(-25, 3), 3)]
+        );
+
+        // Images on the alternate screen are only displayed there, until it is left.
+        images.place(image(4), point(1, 0), 0, true);
+        assert_eq!(visible_columns(&images, 0, 0, true), [(point(1, 0), 4)]);
+        assert_eq!(visible_columns(&images, 3, 0, false).len(), 1);
+        images.prune(30, false);
+        assert_eq!(visible_columns(&images, 0, 0, true), []);
+
+        // Clearing the scrollback forgets the images that were in it.
+        images.prune(0, false);
+        assert_eq!(visible_columns(&images, 0, 0, false), []);
+    }
+}
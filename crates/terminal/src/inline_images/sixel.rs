@@ -0,0 +1,228 @@
+//! A decoder for sixel images, which DEC terminals drew six rows of pixels at a time.
+
+use image::{Rgba, RgbaImage};
+
+/// The colors of the VT340's palette, in percent, which are used until an image defines its own.
+const DEFAULT_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+const PALETTE_SIZE: usize = 256;
+
+/// Decodes the payload of a sixel DCS sequence, starting with its parameters. Pixels that are not
+/// drawn are transparent.
+pub(super) fn decode(payload: &[u8], max_dimension: u32) -> Option<RgbaImage> {
+    let data_start = payload.iter().position(|&byte| byte == b'q')? + 1;
+    let data = &payload[data_start..];
+
+    // Sixels can be drawn anywhere, so the size is found before drawing them.
+    let mut width = 0;
+    let mut height = 0;
+    let (declared_width, declared_height) = walk(data, |x, y, bits, count, _| {
+        width = width.max(x.saturating_add(count));
+        height = height.max(y + (u8::BITS - bits.leading_zeros()));
+    });
+    let width = width.max(declared_width);
+    let height = height.max(declared_height);
+    if width == 0 || height == 0 || width > max_dimension || height > max_dimension {
+        return None;
+    }
+
+    let mut image = RgbaImage::new(width, height);
+    walk(data, |x, y, bits, count, color| {
+        for row in 0..6 {
+            if bits & (1 << row) == 0 {
+                continue;
+            }
+            for column in x..x + count {
+                image.put_pixel(column, y + row, color);
+            }
+        }
+    });
+    Some(image)
+}
+
+/// Calls `draw` with each run of sixels in `data`: the position of its top left pixel, the bits
+/// of the sixel, how many times it is repeated horizontally, and its color. Returns the size the
+/// image declared in its raster attributes, if any.
+fn walk(data: &[u8], mut draw: impl FnMut(u32, u32, u8, u32, Rgba<u8>)) -> (u32, u32) {
+    let mut palette = [Rgba([0, 0, 0, 255]); PALETTE_SIZE];
+    for (color, [r, g, b]) in palette.iter_mut().zip(DEFAULT_PALETTE) {
+        *color = rgb_from_percent(r.into(), g.into(), b.into());
+    }
+    let mut color = palette[0];
+    let mut declared_size = (0, 0);
+    let mut x = 0u32;
+    let mut y = 0u32;
+
+    let mut ix = 0;
+    while ix < data.len() {
+        let byte = data[ix];
+        ix += 1;
+        match byte {
+            b'?'..=b'~' => {
+                let bits = byte - b'?';
+                if bits != 0 {
+                    draw(x, y, bits, 1, color);
+                }
+                x += 1;
+            }
+            b'!' => {
+                let (params, len) = parse_params(&data[ix..]);
+                ix += len;
+                let count = params.first().copied().unwrap_or(1).max(1);
+                if let Some(&byte @ b'?'..=b'~') = data.get(ix) {
+                    ix += 1;
+                    let bits = byte - b'?';
+                    if bits != 0 {
+                        draw(x, y, bits, count, color);
+                    }
+                    x = x.saturating_add(count);
+                }
+            }
+            b'#' => {
+                let (params, len) = parse_params(&data[ix..]);
+                ix += len;
+                let Some(&index) = params.first() else {
+                    continue;
+                };
+                let index = index as usize % PALETTE_SIZE;
+                if let [_, space, a, b, c, ..] = params[..] {
+                    palette[index] = match space {
+                        1 => rgb_from_hls(a, b, c),
+                        _ => rgb_from_percent(a, b, c),
+                    };
+                }
+                color = palette[index];
+            }
+            b'"' => {
+                let (params, len) = parse_params(&data[ix..]);
+                ix += len;
+                if let [_, _, width, height, ..] = params[..] {
+                    declared_size = (width, height);
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                y = y.saturating_add(6);
+            }
+            _ => {}
+        }
+    }
+    declared_size
+}
+
+/// Parses numeric parameters separated by semicolons, returning them and the number of bytes
+/// they took up.
+fn parse_params(data: &[u8]) -> (Vec<u32>, usize) {
+    let mut params = Vec::new();
+    let mut current = None::<u32>;
+    let mut len = 0;
+    for &byte in data {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = u32::from(byte - b'0');
+                current = Some(
+                    current
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+            }
+            b';' => params.push(current.take().unwrap_or(0)),
+            _ => break,
+        }
+        len += 1;
+    }
+    if let Some(param) = current {
+        params.push(param);
+    }
+    (params, len)
+}
+
+fn rgb_from_percent(r: u32, g: u32, b: u32) -> Rgba<u8> {
+    let channel = |percent: u32| (percent.min(100) * 255 / 100) as u8;
+    Rgba([channel(r), channel(g), channel(b), 255])
+}
+
+/// Converts a sixel HLS color, whose hues start at blue rather than red, to RGB.
+fn rgb_from_hls(hue: u32, lightness: u32, saturation: u32) -> Rgba<u8> {
+    let hue = ((hue + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    if saturation == 0. {
+        let gray = (lightness * 255.).round() as u8;
+        return Rgba([gray, gray, gray, 255]);
+    }
+
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.);
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 1. / 2. {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    Rgba([
+        channel(hue + 1. / 3.),
+        channel(hue),
+        channel(hue - 1. / 3.),
+        255,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_sixel() {
+        // A red 3x7 image: a column of six pixels, repeated three times, and a row of one more.
+        let image = decode(b"0;1q\"1;1;3;7#1;2;100;0;0#1!3~-!3@", 100).unwrap();
+        assert_eq!(image.dimensions(), (3, 7));
+        for (x, y, pixel) in image.enumerate_pixels() {
+            assert_eq!(*pixel, Rgba([255, 0, 0, 255]), "pixel at {x}, {y}");
+        }
+
+        // Undrawn pixels are transparent, and the default palette is used.
+        let image = decode(b"q#2A$#3_", 100).unwrap();
+        assert_eq!(image.dimensions(), (1, 6));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+        assert_eq!(*image.get_pixel(0, 1), rgb_from_percent(80, 13, 13));
+        assert_eq!(*image.get_pixel(0, 5), rgb_from_percent(20, 80, 20));
+
+        assert_eq!(rgb_from_hls(120, 50, 100), Rgba([255, 0, 0, 255]));
+        assert_eq!(
+            decode(b"q!200~", 100),
+            None,
+            "images over the limit are skipped"
+        );
+    }
+}
@@ -0,0 +1,673 @@
+//! A PTY wrapper that finds the escape sequences Alacritty does not handle in a program's output,
+//! before the terminal parses it: the semantic prompt marks used for
+//! [shell integration](crate::shell_integration), and [inline images](crate::inline_images).
+//!
+//! The position of such a sequence is the terminal's cursor position right after the bytes
+//! preceding it have been parsed. To read it, the wrapper stops handing bytes to the terminal
+//! after each sequence until the [`Terminal`] has processed it and resumed the PTY with the
+//! [`PtyPause`].
+//!
+//! [`Terminal`]: crate::Terminal
+
+use crate::{
+    inline_images::{ImageCommand, ImageDecoder, ImageKind, MAX_ENCODED_IMAGE_LEN},
+    shell_integration::ShellMark,
+};
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{self, ChildEvent, EventedPty, EventedReadWrite},
+};
+use futures::channel::mpsc::UnboundedSender;
+use polling::{PollMode, Poller};
+use std::{
+    io::{self, Read as _},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+#[cfg(unix)]
+use std::{io::Write as _, os::unix::net::UnixStream};
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// Marks are short, so longer OSC payloads (such as hyperlinks) are not inspected.
+const MAX_MARK_LEN: usize = 32;
+
+/// The start of the OSC payload of an iTerm2 inline image.
+const ITERM2_IMAGE_PREFIX: &[u8] = b"1337;File=";
+
+/// DCS sequences are held back until their parameters show whether they are sixel images, which
+/// have at most three numeric parameters.
+const MAX_DCS_PARAMS_LEN: usize = 32;
+
+/// Without a way to wake up the PTY event loop once a sequence is processed, bytes following a
+/// sequence are handed to the terminal right away, and the sequence is placed where the cursor is
+/// when the [`Terminal`](crate::Terminal) gets to it.
+const PAUSE_AT_SEQUENCES: bool = cfg!(unix);
+
+/// An escape sequence found in a program's output, for the [`Terminal`](crate::Terminal) to
+/// process.
+pub(crate) enum InterceptedSequence {
+    ShellMark(ShellMark),
+    Image(ImageCommand),
+}
+
+impl InterceptedSequence {
+    /// Whether the sequence applies at the cursor position, so that the output following it is
+    /// held back until it is processed.
+    fn is_positioned(&self) -> bool {
+        match self {
+            Self::ShellMark(_) => true,
+            Self::Image(command) => command.is_positioned(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ScannedSequence {
+    /// A semantic prompt mark, which is passed on to the terminal.
+    ShellMark(ShellMark),
+    /// An image, which is removed from the output, with the payload following the sequence's
+    /// introducer.
+    Image(ImageKind, Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    /// An OSC sequence that may be an iTerm2 image.
+    OscPrefix,
+    Osc,
+    OscEscape,
+    /// A DCS sequence that may be a sixel image.
+    DcsParams,
+    /// An APC sequence that may be a kitty graphics command.
+    ApcStart,
+    Image(ImageKind),
+    ImageEscape(ImageKind),
+}
+
+/// Finds intercepted sequences in a stream of bytes, which may split them across reads.
+struct SequenceScanner {
+    shell_marks: bool,
+    images: bool,
+    state: ScanState,
+    /// Bytes that may start an image, held back until it is known whether they do.
+    held: Vec<u8>,
+    payload: Vec<u8>,
+    /// Whether the current image is larger than the limit, and is being skipped.
+    payload_overflowed: bool,
+}
+
+impl SequenceScanner {
+    fn new(shell_marks: bool, images: bool) -> Self {
+        Self {
+            shell_marks,
+            images,
+            state: ScanState::Ground,
+            held: Vec::new(),
+            payload: Vec::new(),
+            payload_overflowed: false,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.shell_marks || self.images
+    }
+
+    /// Copies `input` to `output`, leaving out images, up to the first intercepted sequence.
+    /// Returns the number of bytes consumed and the sequence. Bytes after the sequence are not
+    /// consumed, and should be scanned again.
+    fn scan(&mut self, input: &[u8], output: &mut Vec<u8>) -> (usize, Option<ScannedSequence>) {
+        if !self.is_enabled() {
+            output.extend_from_slice(input);
+            return (input.len(), None);
+        }
+        for (ix, &byte) in input.iter().enumerate() {
+            if let Some(sequence) = self.advance(byte, output) {
+                return (ix + 1, Some(sequence));
+            }
+        }
+        (input.len(), None)
+    }
+
+    fn advance(&mut self, byte: u8, output: &mut Vec<u8>) -> Option<ScannedSequence> {
+        match self.state {
+            ScanState::Ground => {
+                if byte == ESC {
+                    self.state = ScanState::Escape;
+                    if self.images {
+                        self.held.push(byte);
+                        return None;
+                    }
+                }
+                output.push(byte);
+            }
+            ScanState::Escape => match byte {
+                b']' => {
+                    self.payload.clear();
+                    if self.images {
+                        self.held.push(byte);
+                        self.state = ScanState::OscPrefix;
+                    } else {
+                        output.push(byte);
+                        self.state = ScanState::Osc;
+                    }
+                }
+                b'P' if self.images => {
+                    self.held.push(byte);
+                    self.state = ScanState::DcsParams;
+                }
+                b'_' if self.images => {
+                    self.held.push(byte);
+                    self.state = ScanState::ApcStart;
+                }
+                _ => return self.release(byte, ScanState::Ground, output),
+            },
+            ScanState::OscPrefix => {
+                if ITERM2_IMAGE_PREFIX.get(self.payload.len()) == Some(&byte) {
+                    self.held.push(byte);
+                    self.payload.push(byte);
+                    if self.payload.len() == ITERM2_IMAGE_PREFIX.len() {
+                        self.start_image(ImageKind::ITerm2);
+                    }
+                } else {
+                    return self.release(byte, ScanState::Osc, output);
+                }
+            }
+            ScanState::Osc => {
+                output.push(byte);
+                match byte {
+                    BEL => {
+                        self.state = ScanState::Ground;
+                        return self.finish_osc();
+                    }
+                    ESC => self.state = ScanState::OscEscape,
+                    _ => {
+                        if self.payload.len() <= MAX_MARK_LEN {
+                            self.payload.push(byte);
+                        }
+                    }
+                }
+            }
+            ScanState::OscEscape => match byte {
+                b'\\' => {
+                    output.push(byte);
+                    self.state = ScanState::Ground;
+                    return self.finish_osc();
+                }
+                b']' => {
+                    output.push(byte);
+                    self.payload.clear();
+                    self.state = ScanState::Osc;
+                }
+                _ => {
+                    self.state = ScanState::Ground;
+                    return self.advance(byte, output);
+                }
+            },
+            ScanState::DcsParams => match byte {
+                b'0'..=b'9' | b';' if self.held.len() < MAX_DCS_PARAMS_LEN => {
+                    self.held.push(byte);
+                }
+                b'q' => {
+                    // The sixel decoder reads the parameters, such as how to fill the background.
+                    let mut params = self.held.split_off(2);
+                    params.push(byte);
+                    self.start_image(ImageKind::Sixel);
+                    self.payload = params;
+                }
+                _ => return self.release(byte, ScanState::Ground, output),
+            },
+            ScanState::ApcStart => {
+                if byte == b'G' {
+                    self.start_image(ImageKind::Kitty);
+                } else {
+                    return self.release(byte, ScanState::Ground, output);
+                }
+            }
+            ScanState::Image(kind) => match byte {
+                ESC => self.state = ScanState::ImageEscape(kind),
+                BEL if kind == ImageKind::ITerm2 => {
+                    self.state = ScanState::Ground;
+                    return self.finish_image(kind);
+                }
+                _ => {
+                    if self.payload.len() < MAX_ENCODED_IMAGE_LEN {
+                        self.payload.push(byte);
+                    } else {
+                        self.payload_overflowed = true;
+                    }
+                }
+            },
+            ScanState::ImageEscape(kind) => {
+                self.state = ScanState::Ground;
+                if byte == b'\\' {
+                    return self.finish_image(kind);
+                }
+                // An escape sequence interrupts the image, like it does any other sequence.
+                self.payload.clear();
+                self.advance(ESC, output);
+                return self.advance(byte, output);
+            }
+        }
+        None
+    }
+
+    /// Hands the held back bytes to the terminal, and continues scanning from `state`.
+    fn release(
+        &mut self,
+        byte: u8,
+        state: ScanState,
+        output: &mut Vec<u8>,
+    ) -> Option<ScannedSequence> {
+        output.append(&mut self.held);
+        self.state = state;
+        self.advance(byte, output)
+    }
+
+    fn start_image(&mut self, kind: ImageKind) {
+        self.held.clear();
+        self.payload.clear();
+        self.payload_overflowed = false;
+        self.state = ScanState::Image(kind);
+    }
+
+    fn finish_osc(&mut self) -> Option<ScannedSequence> {
+        if !self.shell_marks || self.payload.len() > MAX_MARK_LEN {
+            return None;
+        }
+        ShellMark::parse(&self.payload).map(ScannedSequence::ShellMark)
+    }
+
+    fn finish_image(&mut self, kind: ImageKind) -> Option<ScannedSequence> {
+        let payload = std::mem::take(&mut self.payload);
+        if self.payload_overflowed {
+            log::warn!("skipping {kind:?} image larger than {MAX_ENCODED_IMAGE_LEN} bytes");
+            return None;
+        }
+        Some(ScannedSequence::Image(kind, payload))
+    }
+}
+
+#[cfg(unix)]
+type WakeReceiver = UnixStream;
+#[cfg(not(unix))]
+type WakeReceiver = ();
+
+/// Stops the PTY event loop from handing more bytes to the terminal until an intercepted
+/// sequence is processed.
+#[derive(Clone)]
+pub(crate) struct PtyPause(Arc<PtyPauseState>);
+
+struct PtyPauseState {
+    paused: AtomicBool,
+    #[cfg(unix)]
+    wake_tx: UnixStream,
+}
+
+impl PtyPause {
+    /// Returns the pause, and what the PTY event loop waits on to be woken up when it is resumed.
+    fn new() -> io::Result<(Self, WakeReceiver)> {
+        #[cfg(unix)]
+        let (wake_rx, wake_tx) = {
+            let (wake_rx, wake_tx) = UnixStream::pair()?;
+            wake_rx.set_nonblocking(true)?;
+            wake_tx.set_nonblocking(true)?;
+            (wake_rx, wake_tx)
+        };
+        #[cfg(not(unix))]
+        let wake_rx = ();
+        let pause = Self(Arc::new(PtyPauseState {
+            paused: AtomicBool::new(false),
+            #[cfg(unix)]
+            wake_tx,
+        }));
+        Ok((pause, wake_rx))
+    }
+
+    fn pause(&self) {
+        self.0.paused.store(true, Ordering::Release);
+    }
+
+    fn is_paused(&self) -> bool {
+        self.0.paused.load(Ordering::Acquire)
+    }
+
+    /// Lets the PTY event loop continue, waking it up to read the bytes it held back.
+    pub(crate) fn resume(&self) {
+        #[cfg(unix)]
+        (&self.0.wake_tx).write_all(&[0]).ok();
+        self.0.paused.store(false, Ordering::Release);
+    }
+}
+
+/// A PTY that reports the escape sequences in the program's output that Alacritty does not
+/// handle.
+pub(crate) struct InterceptingPty {
+    pty: tty::Pty,
+    scanner: SequenceScanner,
+    images: ImageDecoder,
+    window_size: WindowSize,
+    sequences_tx: UnboundedSender<InterceptedSequence>,
+    /// Bytes read from the PTY that have not been scanned yet.
+    input: Vec<u8>,
+    /// Scanned bytes that have not been handed to the terminal yet.
+    output: Vec<u8>,
+    /// A sequence to report once the output preceding it has been handed to the terminal, and the
+    /// output that replaces it.
+    positioned: Option<(InterceptedSequence, Vec<u8>)>,
+    pause: PtyPause,
+    awaiting_resume: bool,
+    child_exited: bool,
+    /// Registered with the event loop's poller under the PTY's key, so that resuming wakes it up.
+    #[cfg_attr(not(unix), allow(dead_code))]
+    wake_rx: WakeReceiver,
+}
+
+impl InterceptingPty {
+    pub(crate) fn new(
+        pty: tty::Pty,
+        window_size: WindowSize,
+        sequences_tx: UnboundedSender<InterceptedSequence>,
+        shell_marks: bool,
+        images: bool,
+    ) -> io::Result<(Self, PtyPause)> {
+        let (pause, wake_rx) = PtyPause::new()?;
+        let pty = Self {
+            pty,
+            scanner: SequenceScanner::new(shell_marks, images),
+            images: ImageDecoder::default(),
+            window_size,
+            sequences_tx,
+            input: Vec::new(),
+            output: Vec::new(),
+            positioned: None,
+            pause: pause.clone(),
+            awaiting_resume: false,
+            child_exited: false,
+            wake_rx,
+        };
+        Ok((pty, pause))
+    }
+
+    fn scan_input(&mut self) {
+        let (consumed, sequence) = self.scanner.scan(&self.input, &mut self.output);
+        self.input.drain(..consumed);
+        match sequence {
+            Some(ScannedSequence::ShellMark(mark)) => {
+                self.positioned = Some((InterceptedSequence::ShellMark(mark), Vec::new()));
+            }
+            Some(ScannedSequence::Image(kind, payload)) => {
+                for command in self.images.decode(kind, &payload, self.window_size) {
+                    if command.is_positioned() {
+                        let replacement = command.replacement();
+                        self.positioned = Some((InterceptedSequence::Image(command), replacement));
+                    } else {
+                        self.sequences_tx
+                            .unbounded_send(InterceptedSequence::Image(command))
+                            .ok();
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn report_positioned(&mut self) {
+        let Some((sequence, replacement)) = self.positioned.take() else {
+            return;
+        };
+        debug_assert!(sequence.is_positioned());
+        if PAUSE_AT_SEQUENCES {
+            self.pause.pause();
+        }
+        if self.sequences_tx.unbounded_send(sequence).is_ok() {
+            self.awaiting_resume = PAUSE_AT_SEQUENCES;
+        }
+        self.output = replacement;
+    }
+
+    fn clear_wakeups(&mut self) {
+        #[cfg(unix)]
+        {
+            let mut buf = [0; 16];
+            while matches!(self.wake_rx.read(&mut buf), Ok(len) if len > 0) {}
+        }
+    }
+}
+
+impl io::Read for InterceptingPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.scanner.is_enabled() {
+            return self.pty.reader().read(buf);
+        }
+
+        if self.awaiting_resume {
+            // Once the program has exited, the event loop drains its remaining output and stops.
+            if self.pause.is_paused() && !self.child_exited {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            self.awaiting_resume = false;
+            self.clear_wakeups();
+        }
+
+        loop {
+            if !self.output.is_empty() {
+                let len = self.output.len().min(buf.len());
+                buf[..len].copy_from_slice(&self.output[..len]);
+                self.output.drain(..len);
+                if self.output.is_empty() {
+                    self.report_positioned();
+                }
+                return Ok(len);
+            }
+
+            if self.positioned.is_some() {
+                self.report_positioned();
+                if self.awaiting_resume {
+                    return Err(io::ErrorKind::WouldBlock.into());
+                }
+                continue;
+            }
+
+            if self.input.is_empty() {
+                let len = self.pty.reader().read(buf)?;
+                if len == 0 {
+                    return Ok(0);
+                }
+                self.input.extend_from_slice(&buf[..len]);
+            }
+            self.scan_input();
+        }
+    }
+}
+
+impl EventedReadWrite for InterceptingPty {
+    type Reader = Self;
+    type Writer = <tty::Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: polling::Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe {
+            self.pty.register(poll, interest, mode)?;
+            #[cfg(unix)]
+            poll.add_with_mode(&self.wake_rx, polling::Event::readable(interest.key), mode)?;
+        }
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: polling::Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)?;
+        #[cfg(unix)]
+        poll.modify_with_mode(&self.wake_rx, polling::Event::readable(interest.key), mode)?;
+        Ok(())
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)?;
+        #[cfg(unix)]
+        poll.delete(&self.wake_rx)?;
+        Ok(())
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for InterceptingPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        let event = self.pty.next_child_event();
+        if let Some(ChildEvent::Exited(..)) = event {
+            self.child_exited = true;
+        }
+        event
+    }
+}
+
+impl OnResize for InterceptingPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.window_size = window_size;
+        self.pty.on_resize(window_size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_all(
+        scanner: &mut SequenceScanner,
+        input: &[u8],
+    ) -> (Vec<u8>, Vec<(usize, ScannedSequence)>) {
+        let mut output = Vec::new();
+        let mut sequences = Vec::new();
+        let mut offset = 0;
+        while offset < input.len() {
+            let (consumed, sequence) = scanner.scan(&input[offset..], &mut output);
+            offset += consumed;
+            sequences.extend(sequence.map(|sequence| (offset, sequence)));
+        }
+        (output, sequences)
+    }
+
+    #[test]
+    fn test_scan_marks() {
+        let mut scanner = SequenceScanner::new(true, true);
+        let input = b"\x1b]133;D;1\x07\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\r\n\x1b]133;C\x07out";
+        let (output, sequences) = scan_all(&mut scanner, input);
+        assert_eq!(output, input, "marks are passed on to the terminal");
+        assert_eq!(
+            sequences,
+            [
+                (
+                    10,
+                    ScannedSequence::ShellMark(ShellMark::CommandFinished { exit_code: Some(1) })
+                ),
+                (18, ScannedSequence::ShellMark(ShellMark::PromptStart)),
+                (29, ScannedSequence::ShellMark(ShellMark::CommandStart)),
+                (41, ScannedSequence::ShellMark(ShellMark::CommandExecuted)),
+            ]
+        );
+
+        // Marks may be split across reads, and other OSC sequences are ignored.
+        let mut scanner = SequenceScanner::new(true, true);
+        let mut output = Vec::new();
+        assert_eq!(
+            scanner.scan(b"\x1b]0;title\x07\x1b]63", &mut output).1,
+            None
+        );
+        assert_eq!(
+            scanner.scan(b"3;D\x1b", &mut output).1,
+            None,
+            "an unterminated mark is not reported"
+        );
+        assert_eq!(
+            scanner.scan(b"\\rest", &mut output),
+            (
+                1,
+                Some(ScannedSequence::ShellMark(ShellMark::CommandFinished {
+                    exit_code: None
+                }))
+            )
+        );
+        assert_eq!(
+            scanner.scan(b"\x1b]8;;https://zed.dev\x07", &mut output).1,
+            None
+        );
+        assert_eq!(
+            output,
+            b"\x1b]0;title\x07\x1b]633;D\x1b\\\x1b]8;;https://zed.dev\x07"
+        );
+
+        // Marks are only reported with shell integration enabled.
+        let mut scanner = SequenceScanner::new(false, true);
+        assert_eq!(scan_all(&mut scanner, input), (input.to_vec(), Vec::new()));
+    }
+
+    #[test]
+    fn test_scan_images() {
+        let mut scanner = SequenceScanner::new(false, true);
+        let input =
+            b"a\x1bP0;1q#0~-\x1b\\b\x1b_Ga=T,f=100;AAAA\x1b\\c\x1b]1337;File=inline=1:AAAA\x07d";
+        let (output, sequences) = scan_all(&mut scanner, input);
+        assert_eq!(output, b"abcd", "images are removed from the output");
+        assert_eq!(
+            sequences,
+            [
+                (
+                    13,
+                    ScannedSequence::Image(ImageKind::Sixel, b"0;1q#0~-".to_vec())
+                ),
+                (
+                    33,
+                    ScannedSequence::Image(ImageKind::Kitty, b"a=T,f=100;AAAA".to_vec())
+                ),
+                (
+                    60,
+                    ScannedSequence::Image(ImageKind::ITerm2, b"inline=1:AAAA".to_vec())
+                ),
+            ]
+        );
+
+        // Other DCS, APC and OSC sequences are passed on, even when split across reads.
+        let mut scanner = SequenceScanner::new(false, true);
+        let mut output = Vec::new();
+        assert_eq!(scanner.scan(b"\x1bP1$", &mut output).1, None);
+        assert_eq!(scanner.scan(b"r\x1b\\\x1b_", &mut output).1, None);
+        assert_eq!(
+            scanner.scan(b"X\x1b\\\x1b]1337;SetMark\x07", &mut output).1,
+            None
+        );
+        assert_eq!(output, b"\x1bP1$r\x1b\\\x1b_X\x1b\\\x1b]1337;SetMark\x07");
+
+        // An image interrupted by another escape sequence is dropped.
+        let mut scanner = SequenceScanner::new(false, true);
+        let (output, sequences) = scan_all(&mut scanner, b"\x1b_Ga=T;AA\x1b[1mB");
+        assert_eq!(output, b"\x1b[1mB");
+        assert_eq!(sequences, []);
+
+        // Images are left alone when disabled.
+        let mut scanner = SequenceScanner::new(true, false);
+        assert_eq!(scan_all(&mut scanner, input), (input.to_vec(), Vec::new()));
+    }
+}
@@ -0,0 +1,352 @@
+//! Support for the semantic prompt escape sequences that shells emit around their prompts,
+//! command lines and command output (OSC 133, and the OSC 633 variant used by VS Code).
+//!
+//! Alacritty ignores these sequences, so they are found in the shell's output by the
+//! [`InterceptingPty`], which reports each mark to the [`Terminal`] to record in its
+//! [`ShellCommands`].
+//!
+//! [`InterceptingPty`]: crate::intercepting_pty::InterceptingPty
+//! [`Terminal`]: crate::Terminal
+
+use alacritty_terminal::index::{Column, Line, Point as AlacPoint};
+use anyhow::{Context as _, Result};
+use collections::{HashMap, VecDeque};
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use task::Shell;
+use util::ResultExt as _;
+
+/// The number of commands to remember per terminal.
+const MAX_COMMANDS: usize = 1000;
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSHENV_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const ZPROFILE_SCRIPT: &str = include_str!("shell_integration/zprofile.zsh");
+const ZSHRC_SCRIPT: &str = include_str!("shell_integration/zshrc.zsh");
+const ZLOGIN_SCRIPT: &str = include_str!("shell_integration/zlogin.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShellMark {
+    /// `A`: the shell is about to print its prompt.
+    PromptStart,
+    /// `B`: the prompt has been printed, and the user types the command after it.
+    CommandStart,
+    /// `C`: the command has been entered and starts running.
+    CommandExecuted,
+    /// `D`: the command has finished, with the given exit status if the shell reported it.
+    CommandFinished { exit_code: Option<i32> },
+}
+
+impl ShellMark {
+    pub(crate) fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let mut params = payload.split(';');
+        if !matches!(params.next()?, "133" | "633") {
+            return None;
+        }
+        match params.next()? {
+            "A" => Some(Self::PromptStart),
+            "B" => Some(Self::CommandStart),
+            "C" => Some(Self::CommandExecuted),
+            "D" => Some(Self::CommandFinished {
+                exit_code: params.next().and_then(|code| code.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A position in the terminal grid that stays the same as lines scroll into the scrollback,
+/// counted from the oldest line in the scrollback.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct HistoryPoint {
+    line: usize,
+    column: Column,
+}
+
+impl HistoryPoint {
+    pub(crate) fn new(point: AlacPoint, history_size: usize) -> Self {
+        Self {
+            line: (point.line.0 + history_size as i32).max(0) as usize,
+            column: point.column,
+        }
+    }
+
+    pub(crate) fn to_point(self, history_size: usize) -> AlacPoint {
+        AlacPoint::new(Line(self.line as i32 - history_size as i32), self.column)
+    }
+}
+
+/// A command that the shell reported through semantic prompt marks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ShellCommand {
+    prompt: HistoryPoint,
+    input: Option<HistoryPoint>,
+    output: Option<HistoryPoint>,
+    end: Option<HistoryPoint>,
+    exit_code: Option<i32>,
+}
+
+/// The commands run in a terminal, and where their prompts and output are in its grid.
+#[derive(Default)]
+pub(crate) struct ShellCommands {
+    commands: VecDeque<ShellCommand>,
+    history_size: usize,
+}
+
+impl ShellCommands {
+    /// Records a mark at the given cursor position.
+    pub(crate) fn record(&mut self, mark: ShellMark, cursor: AlacPoint, history_size: usize) {
+        // Positions are relative to the start of the scrollback, so they are meaningless once the
+        // scrollback has been cleared.
+        if history_size < self.history_size {
+            self.commands.clear();
+        }
+        self.history_size = history_size;
+        let point = HistoryPoint::new(cursor, history_size);
+
+        match mark {
+            ShellMark::PromptStart => {
+                if self.commands.len() == MAX_COMMANDS {
+                    self.commands.pop_front();
+                }
+                self.commands.push_back(ShellCommand {
+                    prompt: point,
+                    input: None,
+                    output: None,
+                    end: None,
+                    exit_code: None,
+                });
+            }
+            ShellMark::CommandStart => {
+                if let Some(command) = self.commands.back_mut() {
+                    command.input = Some(point);
+                }
+            }
+            ShellMark::CommandExecuted => {
+                if let Some(command) = self.commands.back_mut()
+                    && command.output.is_none()
+                {
+                    command.output = Some(point);
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                // Shells also report the previous status when no command was entered at a prompt.
+                if let Some(command) = self.commands.back_mut()
+                    && command.output.is_some()
+                    && command.end.is_none()
+                {
+                    command.end = Some(point);
+                    command.exit_code = exit_code;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// The lines of the prompts that are still in the terminal, from oldest to newest.
+    pub(crate) fn prompt_lines(&self, history_size: usize) -> impl Iterator<Item = Line> + '_ {
+        self.commands
+            .iter()
+            .map(move |command| command.prompt.to_point(history_size).line)
+            .filter(move |line| line.0 >= -(history_size as i32))
+    }
+
+    /// The prompt lines of commands that exited with a non-zero status, from oldest to newest.
+    pub(crate) fn failed_prompt_lines(
+        &self,
+        history_size: usize,
+    ) -> impl Iterator<Item = Line> + '_ {
+        self.commands
+            .iter()
+            .filter(|command| command.exit_code.is_some_and(|code| code != 0))
+            .map(move |command| command.prompt.to_point(history_size).line)
+            .filter(move |line| line.0 >= -(history_size as i32))
+    }
+
+    /// The output of the most recent command that has finished and printed something.
+    pub(crate) fn last_output(
+        &self,
+        history_size: usize,
+        last_column: Column,
+    ) -> Option<RangeInclusive<AlacPoint>> {
+        self.commands.iter().rev().find_map(|command| {
+            let start = command.output?.to_point(history_size);
+            let end = command.end?.to_point(history_size);
+            if end <= start || start.line.0 < -(history_size as i32) {
+                return None;
+            }
+            // The command ends right before the cursor position where it finished.
+            let end = if end.column.0 == 0 {
+                AlacPoint::new(Line(end.line.0 - 1), last_column)
+            } else {
+                AlacPoint::new(end.line, Column(end.column.0 - 1))
+            };
+            Some(start..=end)
+        })
+    }
+}
+
+/// How to start a shell so that it loads Zed's shell integration.
+pub(crate) struct IntegratedShell {
+    /// The program and arguments to start instead of the requested shell, if the integration has
+    /// to be passed as an argument.
+    pub program: Option<(String, Vec<String>)>,
+    /// Environment variables that make the shell load the integration.
+    pub env: HashMap<String, String>,
+}
+
+/// Returns how to start `shell` with Zed's shell integration, if it is an interactive bash, zsh or
+/// fish shell.
+pub(crate) fn integrate_shell(
+    shell: &Shell,
+    env: &HashMap<String, String>,
+) -> Option<IntegratedShell> {
+    let (program, login) = match shell {
+        // Alacritty starts a login shell on macOS.
+        Shell::System => (util::shell::get_system_shell(), cfg!(target_os = "macos")),
+        Shell::Program(program) => (program.clone(), false),
+        Shell::WithArguments { program, args, .. } => match args.as_slice() {
+            [] => (program.clone(), false),
+            [arg] if arg == "-l" || arg == "--login" => (program.clone(), true),
+            _ => return None,
+        },
+    };
+    let program_name = Path::new(&program).file_stem()?.to_str()?;
+    if !matches!(program_name, "bash" | "zsh" | "fish") {
+        return None;
+    }
+
+    let dir = write_scripts()
+        .context("writing shell integration scripts")
+        .log_err()?;
+    let mut integration_env = HashMap::default();
+    let program = match program_name {
+        "bash" => {
+            if login {
+                integration_env.insert("ZED_SHELL_INTEGRATION_LOGIN".to_string(), "1".to_string());
+            }
+            let init_file = dir.join("zed.bash").to_string_lossy().into_owned();
+            Some((program, vec!["--init-file".to_string(), init_file]))
+        }
+        "zsh" => {
+            let user_zdotdir = env
+                .get("ZDOTDIR")
+                .cloned()
+                .or_else(|| std::env::var("ZDOTDIR").ok())
+                .unwrap_or_else(|| paths::home_dir().to_string_lossy().into_owned());
+            integration_env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            integration_env.insert(
+                "ZDOTDIR".to_string(),
+                dir.join("zsh").to_string_lossy().into_owned(),
+            );
+            None
+        }
+        _ => {
+            let data_dir = dir.to_string_lossy().into_owned();
+            let data_dirs = env
+                .get("XDG_DATA_DIRS")
+                .cloned()
+                .or_else(|| std::env::var("XDG_DATA_DIRS").ok())
+                .filter(|data_dirs| !data_dirs.is_empty())
+                .unwrap_or_else(|| "/usr/local/share/:/usr/share/".to_string());
+            integration_env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{data_dir}:{data_dirs}"),
+            );
+            integration_env.insert("ZED_SHELL_INTEGRATION_DATA_DIR".to_string(), data_dir);
+            None
+        }
+    };
+
+    Some(IntegratedShell {
+        program,
+        env: integration_env,
+    })
+}
+
+fn write_scripts() -> Result<PathBuf> {
+    let dir = paths::temp_dir().join("shell_integration");
+    for (path, contents) in [
+        ("zed.bash", BASH_SCRIPT),
+        ("zsh/.zshenv", ZSHENV_SCRIPT),
+        ("zsh/.zprofile", ZPROFILE_SCRIPT),
+        ("zsh/.zshrc", ZSHRC_SCRIPT),
+        ("zsh/.zlogin", ZLOGIN_SCRIPT),
+        ("fish/vendor_conf.d/zed.fish", FISH_SCRIPT),
+    ] {
+        let path = dir.join(path);
+        // Other terminals may be starting a shell with these scripts right now.
+        if std::fs::read(&path).ok().as_deref() == Some(contents.as_bytes()) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, contents).with_context(|| format!("writing {path:?}"))?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_commands() {
+        let mut commands = ShellCommands::default();
+        let point = |line, column| AlacPoint::new(Line(line), Column(column));
+
+        commands.record(ShellMark::PromptStart, point(0, 0), 0);
+        commands.record(ShellMark::CommandStart, point(0, 2), 0);
+        commands.record(ShellMark::CommandExecuted, point(1, 0), 0);
+        commands.record(
+            ShellMark::CommandFinished { exit_code: Some(2) },
+            point(3, 0),
+            0,
+        );
+        // An empty command line is not reported as finishing with the previous status.
+        commands.record(ShellMark::PromptStart, point(3, 0), 0);
+        commands.record(ShellMark::CommandStart, point(3, 2), 0);
+        commands.record(
+            ShellMark::CommandFinished { exit_code: Some(2) },
+            point(4, 0),
+            0,
+        );
+        commands.record(ShellMark::PromptStart, point(4, 0), 0);
+
+        assert_eq!(
+            commands.prompt_lines(0).collect::<Vec<_>>(),
+            [Line(0), Line(3), Line(4)]
+        );
+        assert_eq!(
+            commands.failed_prompt_lines(0).collect::<Vec<_>>(),
+            [Line(0)]
+        );
+        assert_eq!(
+            commands.last_output(0, Column(79)),
+            Some(point(1, 0)..=point(2, 79))
+        );
+
+        // Scrolling moves the recorded positions along with the text.
+        assert_eq!(
+            commands.prompt_lines(2).collect::<Vec<_>>(),
+            [Line(-2), Line(1), Line(2)]
+        );
+        assert_eq!(
+            commands.last_output(2, Column(79)),
+            Some(point(-1, 0)..=point(0, 79))
+        );
+
+        // Clearing the scrollback forgets the commands that were in it.
+        commands.record(ShellMark::PromptStart, point(0, 0), 10);
+        commands.record(ShellMark::PromptStart, point(0, 0), 0);
+        assert_eq!(commands.prompt_lines(0).collect::<Vec<_>>(), [Line(0)]);
+    }
+}
@@ -0,0 +1,43 @@
+# Zed shell integration for bash.
+#
+# Zed starts bash with `--init-file` pointing at this script. It loads the user's own startup
+# files, and then reports prompts, commands and their exit status to the terminal with OSC 133
+# escape sequences.
+
+if [[ -n "${ZED_SHELL_INTEGRATION_LOGIN:-}" ]]; then
+    builtin unset ZED_SHELL_INTEGRATION_LOGIN
+    # `--init-file` only applies to non-login shells, so run what a login shell would.
+    if [[ -r /etc/profile ]]; then
+        . /etc/profile
+    fi
+    if [[ -r ~/.bash_profile ]]; then
+        . ~/.bash_profile
+    elif [[ -r ~/.bash_login ]]; then
+        . ~/.bash_login
+    elif [[ -r ~/.profile ]]; then
+        . ~/.profile
+    fi
+elif [[ -r ~/.bashrc ]]; then
+    . ~/.bashrc
+fi
+
+__zed_last_status=0
+
+# Runs before any other prompt command, so that it sees the exit status of the command.
+__zed_prompt_start() {
+    __zed_last_status=$?
+    builtin printf '\e]133;D;%s\a\e]133;A\a' "$__zed_last_status"
+    return $__zed_last_status
+}
+
+# Runs after all other prompt commands, which may have replaced PS1.
+__zed_prompt_end() {
+    if [[ "$PS1" != *'133;B'* ]]; then
+        PS1+='\[\e]133;B\a\]'
+    fi
+    return $__zed_last_status
+}
+
+PROMPT_COMMAND="__zed_prompt_start${PROMPT_COMMAND:+; $PROMPT_COMMAND}; __zed_prompt_end"
+# PS0 is printed after a command is read and before it runs.
+PS0="${PS0:-}"'\e]133;C\a'
@@ -0,0 +1,30 @@
+# Zed shell integration for fish.
+#
+# Zed adds the directory that contains this file's `fish/vendor_conf.d` directory to
+# XDG_DATA_DIRS, which makes fish load it on startup. It reports prompts, commands and their exit
+# status to the terminal with OSC 133 escape sequences.
+
+set -gx XDG_DATA_DIRS (string replace -- "$ZED_SHELL_INTEGRATION_DATA_DIR:" "" "$XDG_DATA_DIRS")
+set -e ZED_SHELL_INTEGRATION_DATA_DIR
+
+status is-interactive; or exit
+
+function __zed_preexec --on-event fish_preexec
+    printf '\e]133;C\a'
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
+
+function __zed_prompt --on-event fish_prompt
+    printf '\e]133;A\a'
+    # Prompts are often defined after this file runs, so wrap them when the first one is shown.
+    if functions -q fish_prompt; and not functions -q __zed_fish_prompt
+        functions --copy fish_prompt __zed_fish_prompt
+        function fish_prompt
+            __zed_fish_prompt
+            printf '\e]133;B\a'
+        end
+    end
+end
@@ -1,8 +1,12 @@
 pub mod mappings;
 
 pub use alacritty_terminal;
+pub use inline_images::TerminalImage;
 
+mod inline_images;
+mod intercepting_pty;
 mod pty_info;
+mod shell_integration;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
@@ -41,9 +45,12 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use inline_images::{ImageCommand, InlineImages};
+use intercepting_pty::{InterceptedSequence, InterceptingPty, PtyPause};
 use pty_info::{ProcessIdGetter, PtyProcessInfo};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{ShellCommands, ShellMark};
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, SpawnInTerminal};
 use terminal_hyperlinks::RegexSearches;
@@ -105,6 +112,14 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls up to the prompt of the previous command.
+        ScrollToPreviousCommand,
+        /// Scrolls down to the prompt of the next command.
+        ScrollToNextCommand,
+        /// Selects the output of the last command that finished.
+        SelectLastCommandOutput,
+        /// Copies the output of the last command that finished to the clipboard.
+        CopyLastCommandOutput,
     ]
 );
 
@@ -336,6 +351,7 @@ pub const MAX_SCROLL_HISTORY_LINES: usize = 100_000;
 pub struct TerminalBuilder {
     terminal: Terminal,
     events_rx: UnboundedReceiver<AlacTermEvent>,
+    intercepted_rx: Option<UnboundedReceiver<InterceptedSequence>>,
 }
 
 impl TerminalBuilder {
@@ -402,11 +418,15 @@ impl TerminalBuilder {
                 window_id,
             },
             child_exited: None,
+            pty_pause: None,
+            shell_commands: None,
+            inline_images: None,
         };
 
         Ok(TerminalBuilder {
             terminal,
             events_rx,
+            intercepted_rx: None,
         })
     }
 
@@ -501,19 +521,41 @@ impl TerminalBuilder {
         // supported remoting into windows.
         let shell_kind = shell.shell_kind(cfg!(windows));
 
+        let shell_integration = task.is_none()
+            && TerminalSettings::try_get(cx).is_some_and(|settings| settings.shell_integration);
+        let inline_images =
+            TerminalSettings::try_get(cx).is_some_and(|settings| settings.inline_images);
+        // Remote shells cannot load the scripts from this machine.
+        let integrated_shell = if shell_integration && !is_ssh_terminal && cfg!(not(windows)) {
+            shell_integration::integrate_shell(&shell, &env)
+        } else {
+            None
+        };
+
         let pty_options = {
-            let alac_shell = shell_params.as_ref().map(|params| {
-                alacritty_terminal::tty::Shell::new(
-                    params.program.clone(),
-                    params.args.clone().unwrap_or_default(),
-                )
-            });
+            let alac_shell = match integrated_shell
+                .as_ref()
+                .and_then(|integrated_shell| integrated_shell.program.clone())
+            {
+                Some((program, args)) => Some(alacritty_terminal::tty::Shell::new(program, args)),
+                None => shell_params.as_ref().map(|params| {
+                    alacritty_terminal::tty::Shell::new(
+                        params.program.clone(),
+                        params.args.clone().unwrap_or_default(),
+                    )
+                }),
+            };
+
+            let mut pty_env = env.clone();
+            if let Some(integrated_shell) = integrated_shell {
+                pty_env.extend(integrated_shell.env);
+            }
 
             alacritty_terminal::tty::Options {
                 shell: alac_shell,
                 working_directory: working_directory.clone(),
                 drain_on_exit: true,
-                env: env.clone().into_iter().collect(),
+                env: pty_env.into_iter().collect(),
                 // We do not want to escape arguments if we are using CMD as our shell.
                 // If we do we end up with too many quotes/escaped quotes for CMD to handle.
                 #[cfg(windows)]
@@ -571,6 +613,16 @@ impl TerminalBuilder {
 
         let pty_info = PtyProcessInfo::new(&pty);
 
+        let (intercepted_tx, intercepted_rx) = unbounded();
+        let (pty, pty_pause) = InterceptingPty::new(
+            pty,
+            TerminalBounds::default().into(),
+            intercepted_tx,
+            shell_integration,
+            inline_images,
+        )
+        .context("failed to set up the pty")?;
+
         //And connect them together
         let event_loop = EventLoop::new(
             term.clone(),
@@ -623,6 +675,9 @@ impl TerminalBuilder {
                 window_id,
             },
             child_exited: None,
+            pty_pause: Some(pty_pause),
+            shell_commands: shell_integration.then(ShellCommands::default),
+            inline_images: inline_images.then(InlineImages::default),
         };
 
         if !activation_script.is_empty() && no_task {
@@ -649,10 +704,24 @@ impl TerminalBuilder {
         Ok(TerminalBuilder {
             terminal,
             events_rx,
+            intercepted_rx: Some(intercepted_rx),
         })
     }
 
     pub fn subscribe(mut self, cx: &Context<Terminal>) -> Terminal {
+        // Output stops at each intercepted sequence until it is processed, so they are not batched.
+        if let Some(mut intercepted_rx) = self.intercepted_rx.take() {
+            cx.spawn(async move |terminal, cx| {
+                while let Some(sequence) = intercepted_rx.next().await {
+                    terminal.update(cx, |terminal, cx| {
+                        terminal.process_intercepted_sequence(sequence, cx)
+                    })?;
+                }
+                anyhow::Ok(())
+            })
+            .detach();
+        }
+
         //Event loop
         cx.spawn(async move |terminal, cx| {
             while let Some(event) = self.events_rx.next().await {
@@ -767,6 +836,10 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The visible prompt lines of commands that exited with a non-zero status.
+    pub failed_command_lines: Vec<Line>,
+    /// The images that are at least partly visible.
+    pub images: Vec<TerminalImage>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -793,6 +866,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            failed_command_lines: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -838,6 +913,12 @@ pub struct Terminal {
     template: CopyTemplate,
     activation_script: Vec<String>,
     child_exited: Option<ExitStatus>,
+    /// Resumes the output held back after a sequence that the terminal has to process.
+    pty_pause: Option<PtyPause>,
+    /// Commands reported by the shell, if shell integration is enabled.
+    shell_commands: Option<ShellCommands>,
+    /// Images displayed by programs, if inline images are enabled.
+    inline_images: Option<InlineImages>,
 }
 
 struct CopyTemplate {
@@ -964,6 +1045,58 @@ impl Terminal {
         }
     }
 
+    fn process_intercepted_sequence(
+        &mut self,
+        sequence: InterceptedSequence,
+        cx: &mut Context<Self>,
+    ) {
+        match sequence {
+            InterceptedSequence::ShellMark(mark) => {
+                self.process_shell_mark(mark);
+                self.resume_pty();
+            }
+            InterceptedSequence::Image(ImageCommand::Place(image)) => {
+                if let Some(inline_images) = &mut self.inline_images {
+                    let term = self.term.lock();
+                    inline_images.place(
+                        image,
+                        term.grid().cursor.point,
+                        term.history_size(),
+                        term.mode().contains(TermMode::ALT_SCREEN),
+                    );
+                }
+                self.resume_pty();
+                cx.emit(Event::Wakeup);
+            }
+            InterceptedSequence::Image(ImageCommand::Delete { kitty_id }) => {
+                if let Some(inline_images) = &mut self.inline_images {
+                    inline_images.delete(kitty_id);
+                    cx.emit(Event::Wakeup);
+                }
+            }
+            InterceptedSequence::Image(ImageCommand::Reply(reply)) => {
+                self.write_to_pty(reply);
+            }
+        }
+    }
+
+    fn process_shell_mark(&mut self, mark: ShellMark) {
+        let Some(shell_commands) = self.shell_commands.as_mut() else {
+            return;
+        };
+        let term = self.term.lock();
+        // Marks printed by a shell running inside a full screen application are not tracked.
+        if !term.mode().contains(TermMode::ALT_SCREEN) {
+            shell_commands.record(mark, term.grid().cursor.point, term.history_size());
+        }
+    }
+
+    fn resume_pty(&self) {
+        if let Some(pty_pause) = &self.pty_pause {
+            pty_pause.resume();
+        }
+    }
+
     pub fn selection_started(&self) -> bool {
         self.selection_phase == SelectionPhase::Selecting
     }
@@ -992,6 +1125,12 @@ impl Terminal {
             }
             InternalEvent::Clear => {
                 trace!("Clearing");
+                if let Some(shell_commands) = &mut self.shell_commands {
+                    shell_commands.clear();
+                }
+                if let Some(inline_images) = &mut self.inline_images {
+                    inline_images.clear();
+                }
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1372,6 +1511,69 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    /// Scrolls up so that the prompt of the previous command is at the top of the terminal.
+    pub fn scroll_to_previous_command(&mut self) {
+        let Some(shell_commands) = &self.shell_commands else {
+            return;
+        };
+        let history_size = self.term.lock().history_size();
+        let top_line = -(self.last_content.display_offset as i32);
+        if let Some(prompt_line) = shell_commands
+            .prompt_lines(history_size)
+            .filter(|line| line.0 < top_line)
+            .last()
+        {
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                    top_line - prompt_line.0,
+                )));
+        }
+    }
+
+    /// Scrolls down so that the prompt of the next command is at the top of the terminal, or to
+    /// the bottom after the last command.
+    pub fn scroll_to_next_command(&mut self) {
+        let Some(shell_commands) = &self.shell_commands else {
+            return;
+        };
+        let history_size = self.term.lock().history_size();
+        let top_line = -(self.last_content.display_offset as i32);
+        let scroll = match shell_commands
+            .prompt_lines(history_size)
+            .find(|line| line.0 > top_line)
+        {
+            Some(prompt_line) => AlacScroll::Delta(top_line - prompt_line.0),
+            None => AlacScroll::Bottom,
+        };
+        self.events.push_back(InternalEvent::Scroll(scroll));
+    }
+
+    fn last_command_output(&self) -> Option<RangeInclusive<AlacPoint>> {
+        let shell_commands = self.shell_commands.as_ref()?;
+        let term = self.term.lock();
+        shell_commands.last_output(term.history_size(), term.last_column())
+    }
+
+    /// Selects the output of the last command that finished, and scrolls to its start.
+    pub fn select_last_command_output(&mut self) {
+        if let Some(output) = self.last_command_output() {
+            self.set_selection(Some((make_selection(&output), *output.end())));
+            self.events
+                .push_back(InternalEvent::ScrollToAlacPoint(*output.start()));
+        }
+    }
+
+    /// Copies the output of the last command that finished to the clipboard.
+    pub fn copy_last_command_output(&mut self, cx: &mut Context<Self>) {
+        if let Some(output) = self.last_command_output() {
+            let text = self
+                .term
+                .lock()
+                .bounds_to_string(*output.start(), *output.end());
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1552,6 +1754,26 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        if let Some(shell_commands) = &self.shell_commands {
+            let history_size = terminal.history_size();
+            let display_offset = self.last_content.display_offset as i32;
+            let visible_lines = -display_offset..terminal.screen_lines() as i32 - display_offset;
+            self.last_content.failed_command_lines = shell_commands
+                .failed_prompt_lines(history_size)
+                .filter(|line| visible_lines.contains(&line.0))
+                .collect();
+        }
+        if let Some(inline_images) = &mut self.inline_images {
+            let history_size = terminal.history_size();
+            let alt_screen = terminal.mode().contains(TermMode::ALT_SCREEN);
+            inline_images.prune(history_size, alt_screen);
+            self.last_content.images = inline_images.visible(
+                history_size,
+                self.last_content.display_offset,
+                terminal.screen_lines(),
+                alt_screen,
+            );
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1584,6 +1806,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            failed_command_lines: Vec::new(),
+            images: Vec::new(),
         }
     }
 
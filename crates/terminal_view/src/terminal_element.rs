@@ -1,12 +1,12 @@
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, PaintQuad, Pixels, Point, RenderImage, ShapedLine, StatefulInteractiveElement,
+    StrikethroughStyle, Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity,
+    WhiteSpace, Window, div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
@@ -32,7 +32,7 @@ use util::ResultExt;
 use workspace::Workspace;
 
 use std::mem;
-use std::{fmt::Debug, ops::RangeInclusive, rc::Rc};
+use std::{fmt::Debug, ops::RangeInclusive, rc::Rc, sync::Arc};
 
 use crate::{BlockContext, BlockProperties, ContentMode, TerminalMode, TerminalView};
 
@@ -49,6 +49,8 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    failed_command_markers: Vec<PaintQuad>,
+    images: Vec<(Bounds<Pixels>, Arc<RenderImage>)>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
@@ -1039,11 +1041,55 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    failed_command_lines,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let failed_command_markers = failed_command_lines
+                    .iter()
+                    .map(|line| {
+                        let top = bounds.origin.y - scroll_top
+                            + (line.0 + display_offset as i32) as f32 * dimensions.line_height();
+                        fill(
+                            Bounds::new(
+                                point(bounds.origin.x + gutter / 4., top),
+                                size(gutter / 2., dimensions.line_height()),
+                            ),
+                            theme.status().error,
+                        )
+                    })
+                    .collect();
+
+                let images = images
+                    .iter()
+                    .map(|image| {
+                        let origin = bounds.origin + point(gutter, px(0.))
+                            - point(px(0.), scroll_top)
+                            + point(
+                                image.point.column.0 as f32 * dimensions.cell_width(),
+                                (image.point.line.0 + display_offset as i32) as f32
+                                    * dimensions.line_height(),
+                            );
+                        let available_size = size(
+                            image.columns as f32 * dimensions.cell_width(),
+                            image.rows as f32 * dimensions.line_height(),
+                        );
+                        // Images are scaled down to fit their cells, keeping their aspect ratio.
+                        let image_size = image.image.size(0);
+                        let (image_width, image_height) = (
+                            image_size.width.0.max(1) as f32,
+                            image_size.height.0.max(1) as f32,
+                        );
+                        let scale = (f32::from(available_size.width) / image_width)
+                            .min(f32::from(available_size.height) / image_height);
+                        let image_size = size(px(image_width * scale), px(image_height * scale));
+                        (Bounds::new(origin, image_size), image.image.clone())
+                    })
+                    .collect();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -1189,6 +1235,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    failed_command_markers,
+                    images,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1271,6 +1319,10 @@ impl Element for TerminalElement {
                         }
                     });
 
+                    for marker in layout.failed_command_markers.drain(..) {
+                        window.paint_quad(marker);
+                    }
+
                     for rect in &layout.rects {
                         rect.paint(origin, &layout.dimensions, window);
                     }
@@ -1304,6 +1356,12 @@ impl Element for TerminalElement {
                     }
                     let text_paint_time = text_paint_start.elapsed();
 
+                    for (image_bounds, image) in layout.images.drain(..) {
+                        window
+                            .paint_image(image_bounds, Corners::default(), image, 0, false)
+                            .log_err();
+                    }
+
                     if let Some(text_to_mark) = &marked_text_cloned
                         && !text_to_mark.is_empty()
                             && let Some(cursor_layout) = &original_cursor {
@@ -622,6 +622,190 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_undo_tree() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc");
+
+    let mut edit = |buffer: &mut Buffer, range: Range<usize>, text: &str| {
+        now += Duration::from_secs(1);
+        buffer.start_transaction_at(now);
+        buffer.edit([(range, text)]);
+        buffer.end_transaction_at(now).unwrap().0
+    };
+
+    let transaction_1 = edit(&mut buffer, 0..0, "1");
+    let transaction_2 = edit(&mut buffer, 4..4, "2");
+    assert_eq!(buffer.text(), "1abc2");
+
+    // Undoing and then editing keeps the undone transaction as a separate branch.
+    buffer.undo();
+    let transaction_3 = edit(&mut buffer, 1..2, "3");
+    assert_eq!(buffer.text(), "13bc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "13bc");
+
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current(), Some(transaction_3));
+    assert_eq!(
+        tree.children(Some(transaction_1))
+            .map(|node| node.transaction_id)
+            .collect::<Vec<_>>(),
+        [transaction_2, transaction_3]
+    );
+    assert_eq!(
+        tree.path_to(Some(transaction_2)),
+        [transaction_1, transaction_2]
+    );
+
+    // Changes are visited in the order they were made, regardless of their branch.
+    assert_eq!(tree.earlier(1), Some(transaction_2));
+    assert_eq!(tree.earlier(2), Some(transaction_1));
+    assert_eq!(tree.earlier(10), None);
+    assert_eq!(tree.later(1), Some(transaction_3));
+    assert_eq!(tree.earlier_by(Duration::from_secs(1)), Some(transaction_2));
+    assert_eq!(tree.earlier_by(Duration::from_secs(10)), None);
+
+    assert_eq!(
+        buffer
+            .snapshot_for_undo_state(Some(transaction_2))
+            .unwrap()
+            .text(),
+        "1abc2"
+    );
+    assert_eq!(buffer.text(), "13bc");
+
+    buffer.travel_to_undo_state(Some(transaction_2));
+    assert_eq!(buffer.text(), "1abc2");
+    assert_eq!(buffer.undo_tree().current(), Some(transaction_2));
+    buffer.undo();
+    assert_eq!(buffer.text(), "1abc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "1abc2");
+
+    // After traveling to a fork, redoing follows the most recently created branch.
+    buffer.travel_to_undo_state(Some(transaction_1));
+    assert_eq!(buffer.text(), "1abc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "13bc");
+
+    buffer.travel_to_undo_state(None);
+    assert_eq!(buffer.text(), "abc");
+    let later = buffer.undo_tree().later_by(Duration::from_secs(1));
+    assert_eq!(later, Some(transaction_2));
+    buffer.travel_to_undo_state(later);
+    assert_eq!(buffer.text(), "1abc2");
+    assert_eq!(buffer.undo_tree().nodes().len(), 3);
+}
+
+#[test]
+fn test_group_undo_tree_branches() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc");
+
+    let mut edit = |buffer: &mut Buffer, offset: usize, text: &str| {
+        now += Duration::from_secs(1);
+        buffer.start_transaction_at(now);
+        buffer.edit([(offset..offset, text)]);
+        buffer.end_transaction_at(now).unwrap().0
+    };
+
+    let transaction_0 = edit(&mut buffer, 0, "0");
+    let transaction_1 = edit(&mut buffer, 4, "1");
+    let transaction_2 = edit(&mut buffer, 5, "2");
+    let transaction_3 = edit(&mut buffer, 6, "3");
+    buffer.travel_to_undo_state(Some(transaction_1));
+    let transaction_4 = edit(&mut buffer, 0, "4");
+    assert_eq!(buffer.text(), "40abc1");
+    buffer.travel_to_undo_state(Some(transaction_1));
+    assert_eq!(buffer.text(), "0abc1");
+
+    // Branches forked off the last grouped transaction now fork off the grouped one.
+    buffer.history.group_trailing(1);
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current(), Some(transaction_0));
+    assert_eq!(
+        tree.children(Some(transaction_0))
+            .map(|node| node.transaction_id)
+            .collect::<Vec<_>>(),
+        [transaction_2, transaction_4]
+    );
+    buffer.travel_to_undo_state(Some(transaction_3));
+    assert_eq!(buffer.text(), "0abc123");
+    buffer.travel_to_undo_state(None);
+    assert_eq!(buffer.text(), "abc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "0abc1");
+
+    // Branches forked off the other grouped transactions are dropped.
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc");
+    let transaction_0 = edit(&mut buffer, 0, "0");
+    edit(&mut buffer, 4, "1");
+    edit(&mut buffer, 5, "2");
+    buffer.undo();
+    edit(&mut buffer, 5, "3");
+    assert_eq!(buffer.text(), "0abc13");
+    assert_eq!(buffer.undo_tree().nodes().len(), 4);
+
+    buffer.history.group_trailing(2);
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current(), Some(transaction_0));
+    assert_eq!(tree.nodes().len(), 1);
+    buffer.undo();
+    assert_eq!(buffer.text(), "abc");
+    buffer.redo();
+    assert_eq!(buffer.text(), "0abc13");
+}
+
+#[test]
+fn test_forget_undo_tree_branches() {
+    let mut now = Instant::now();
+    let mut buffer = Buffer::new(0, BufferId::new(1).unwrap(), "abc");
+
+    let mut edit = |buffer: &mut Buffer, offset: usize, text: &str| {
+        now += Duration::from_secs(1);
+        buffer.start_transaction_at(now);
+        buffer.edit([(offset..offset, text)]);
+        buffer.end_transaction_at(now).unwrap().0
+    };
+
+    let transaction_1 = edit(&mut buffer, 0, "1");
+    let transaction_2 = edit(&mut buffer, 4, "2");
+    let transaction_3 = edit(&mut buffer, 5, "3");
+    buffer.travel_to_undo_state(Some(transaction_1));
+    let transaction_4 = edit(&mut buffer, 0, "4");
+    assert_eq!(buffer.text(), "41abc");
+
+    // Detached transactions can be found and forgotten, and their children take their place.
+    buffer.merge_transactions(transaction_4, transaction_2);
+    assert!(buffer.undo_tree().node(transaction_4).is_none());
+    assert_eq!(
+        buffer
+            .get_transaction(transaction_2)
+            .unwrap()
+            .edit_ids
+            .len(),
+        2
+    );
+    assert_eq!(
+        buffer.forget_transaction(transaction_2).unwrap().id,
+        transaction_2
+    );
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current(), Some(transaction_1));
+    assert_eq!(
+        tree.node(transaction_3).unwrap().parent,
+        Some(transaction_1)
+    );
+
+    // Forgetting a transaction on the undo stack keeps the branches forked off it.
+    buffer.forget_transaction(transaction_1);
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current(), None);
+    assert_eq!(tree.node(transaction_3).unwrap().parent, None);
+    assert_eq!(tree.nodes().len(), 1);
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -9,6 +9,7 @@ pub mod subscription;
 #[cfg(test)]
 mod tests;
 mod undo_map;
+mod undo_tree;
 
 pub use anchor::*;
 use anyhow::{Context as _, Result};
@@ -39,6 +40,7 @@ pub use subscription::*;
 pub use sum_tree::Bias;
 use sum_tree::{Dimensions, FilterCursor, SumTree, TreeMap, TreeSet};
 use undo_map::UndoMap;
+pub use undo_tree::*;
 
 #[cfg(any(test, feature = "test-support"))]
 use util::RandomCharIter;
@@ -142,15 +144,24 @@ impl HistoryEntry {
     }
 }
 
+#[derive(Clone)]
 struct History {
     base_text: Rope,
     operations: TreeMap<clock::Lamport, Operation>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    /// Undone entries that were replaced by new edits, kept so that the history forms a tree.
+    detached: Vec<DetachedHistoryEntry>,
     transaction_depth: usize,
     group_interval: Duration,
 }
 
+#[derive(Clone, Debug)]
+struct DetachedHistoryEntry {
+    entry: HistoryEntry,
+    parent: Option<TransactionId>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct InsertionSlice {
     edit_id: clock::Lamport,
@@ -191,6 +202,7 @@ impl History {
             operations: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            detached: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -244,7 +256,7 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                self.detach_redo_stack(self.undo_stack.len() - 1);
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -254,6 +266,138 @@ impl History {
         }
     }
 
+    /// Moves the redo stack into the detached branches of the undo tree. The branch forks off
+    /// after the first `fork_len` entries of the undo stack.
+    fn detach_redo_stack(&mut self, fork_len: usize) {
+        if self.redo_stack.is_empty() {
+            return;
+        }
+
+        let mut parent = None;
+        if let Some(fork) = fork_len
+            .checked_sub(1)
+            .and_then(|ix| self.undo_stack.get_mut(ix))
+        {
+            // Grouping new edits into the fork would change the states of the detached branch.
+            fork.suppress_grouping = true;
+            parent = Some(fork.transaction.id);
+        }
+        for entry in self.redo_stack.drain(..).rev() {
+            let transaction_id = entry.transaction.id;
+            self.detached.push(DetachedHistoryEntry { entry, parent });
+            parent = Some(transaction_id);
+        }
+    }
+
+    fn remove_detached_branches(&mut self, parent: TransactionId) {
+        let mut parents = vec![parent];
+        while let Some(parent) = parents.pop() {
+            self.detached.retain(|detached| {
+                if detached.parent == Some(parent) {
+                    parents.push(detached.entry.transaction.id);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+
+    fn reparent_detached_branches(
+        &mut self,
+        parent: TransactionId,
+        new_parent: Option<TransactionId>,
+    ) {
+        for detached in &mut self.detached {
+            if detached.parent == Some(parent) {
+                detached.parent = new_parent;
+            }
+        }
+    }
+
+    fn undo_tree(&self) -> UndoTree {
+        let mut nodes = Vec::new();
+        let mut parent = None;
+        for entry in self.undo_stack.iter().chain(self.redo_stack.iter().rev()) {
+            nodes.push(UndoTreeNode {
+                transaction_id: entry.transaction.id,
+                parent,
+                first_edit_at: entry.first_edit_at,
+                last_edit_at: entry.last_edit_at,
+            });
+            parent = Some(entry.transaction.id);
+        }
+        nodes.extend(self.detached.iter().map(|detached| UndoTreeNode {
+            transaction_id: detached.entry.transaction.id,
+            parent: detached.parent,
+            first_edit_at: detached.entry.first_edit_at,
+            last_edit_at: detached.entry.last_edit_at,
+        }));
+        UndoTree::new(
+            nodes,
+            self.undo_stack.last().map(|entry| entry.transaction.id),
+        )
+    }
+
+    /// Rearranges the history so that `target` is the top of the undo stack, possibly switching
+    /// to another branch of the undo tree. Returns the transactions that need to be undone or
+    /// redone to reach that state, in order.
+    fn travel_to(&mut self, target: Option<TransactionId>) -> Option<Vec<Transaction>> {
+        assert_eq!(self.transaction_depth, 0);
+        let tree = self.undo_tree();
+        if let Some(target) = target {
+            tree.node(target)?;
+        }
+        let path = tree.path_to(target);
+        let common_len = self
+            .undo_stack
+            .iter()
+            .zip(&path)
+            .take_while(|(entry, transaction_id)| entry.transaction.id == **transaction_id)
+            .count();
+
+        let mut transactions = Vec::new();
+        while self.undo_stack.len() > common_len {
+            let entry = self.undo_stack.pop().unwrap();
+            transactions.push(entry.transaction.clone());
+            self.redo_stack.push(entry);
+        }
+
+        self.detach_redo_stack(self.undo_stack.len());
+        for transaction_id in &path[common_len..] {
+            let Some(ix) = self
+                .detached
+                .iter()
+                .position(|detached| detached.entry.transaction.id == *transaction_id)
+            else {
+                break;
+            };
+            let entry = self.detached.remove(ix).entry;
+            transactions.push(entry.transaction.clone());
+            self.undo_stack.push(entry);
+        }
+        if let Some(entry) = self.undo_stack.last_mut() {
+            entry.suppress_grouping = true;
+        }
+
+        // Continue the redo stack along the most recent branch.
+        let mut tip = self.undo_stack.last().map(|entry| entry.transaction.id);
+        while let Some(ix) = self
+            .detached
+            .iter()
+            .enumerate()
+            .filter(|(_, detached)| detached.parent == tip)
+            .max_by_key(|(_, detached)| detached.entry.transaction.id)
+            .map(|(ix, _)| ix)
+        {
+            let entry = self.detached.remove(ix).entry;
+            tip = Some(entry.transaction.id);
+            self.redo_stack.insert(0, entry);
+        }
+
+        Some(transactions)
+    }
+
     fn group(&mut self) -> Option<TransactionId> {
         let mut count = 0;
         let mut entries = self.undo_stack.iter();
@@ -288,6 +432,31 @@ impl History {
 
     fn group_trailing(&mut self, n: usize) -> Option<TransactionId> {
         let new_len = self.undo_stack.len() - n;
+        if n > 0
+            && let Some(fork_id) = new_len
+                .checked_sub(1)
+                .map(|ix| self.undo_stack[ix].transaction.id)
+        {
+            // Branches forked off the entry or off the entries merged into it don't apply on top
+            // of the grouped entry, except for the ones forked off the last merged entry, which
+            // has the same state.
+            let merged_ids = self.undo_stack[new_len - 1..]
+                .iter()
+                .map(|entry| entry.transaction.id)
+                .collect::<Vec<_>>();
+            let (last_merged_id, forked_ids) = merged_ids.split_last().unwrap();
+            for transaction_id in forked_ids {
+                self.remove_detached_branches(*transaction_id);
+            }
+            self.reparent_detached_branches(*last_merged_id, Some(fork_id));
+            if self
+                .detached
+                .iter()
+                .any(|detached| detached.parent == Some(fork_id))
+            {
+                self.undo_stack[new_len - 1].suppress_grouping = true;
+            }
+        }
         let (entries_to_keep, entries_to_merge) = self.undo_stack.split_at_mut(new_len);
         if let Some(last_entry) = entries_to_keep.last_mut() {
             for entry in &*entries_to_merge {
@@ -402,21 +571,40 @@ impl History {
 
     fn forget(&mut self, transaction_id: TransactionId) -> Option<Transaction> {
         assert_eq!(self.transaction_depth, 0);
-        if let Some(entry_ix) = self
+        let (entry, parent) = if let Some(entry_ix) = self
             .undo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
-            Some(self.undo_stack.remove(entry_ix).transaction)
+            let parent = entry_ix
+                .checked_sub(1)
+                .map(|ix| self.undo_stack[ix].transaction.id);
+            (self.undo_stack.remove(entry_ix), parent)
         } else if let Some(entry_ix) = self
             .redo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
-            Some(self.redo_stack.remove(entry_ix).transaction)
+            // The redo stack is in reverse order, so an entry applies after the one above it.
+            let parent = self
+                .redo_stack
+                .get(entry_ix + 1)
+                .or(self.undo_stack.last())
+                .map(|entry| entry.transaction.id);
+            (self.redo_stack.remove(entry_ix), parent)
+        } else if let Some(entry_ix) = self
+            .detached
+            .iter()
+            .position(|detached| detached.entry.transaction.id == transaction_id)
+        {
+            let detached = self.detached.remove(entry_ix);
+            (detached.entry, detached.parent)
         } else {
-            None
-        }
+            return None;
+        };
+        // Keep the branches forked off the forgotten entry in the tree.
+        self.reparent_detached_branches(transaction_id, parent);
+        Some(entry.transaction)
     }
 
     fn transaction(&self, transaction_id: TransactionId) -> Option<&Transaction> {
@@ -428,6 +616,12 @@ impl History {
                 self.redo_stack
                     .iter()
                     .rfind(|entry| entry.transaction.id == transaction_id)
+            })
+            .or_else(|| {
+                self.detached
+                    .iter()
+                    .map(|detached| &detached.entry)
+                    .find(|entry| entry.transaction.id == transaction_id)
             })?;
         Some(&entry.transaction)
     }
@@ -441,6 +635,12 @@ impl History {
                 self.redo_stack
                     .iter_mut()
                     .rfind(|entry| entry.transaction.id == transaction_id)
+            })
+            .or_else(|| {
+                self.detached
+                    .iter_mut()
+                    .map(|detached| &mut detached.entry)
+                    .find(|entry| entry.transaction.id == transaction_id)
             })?;
         Some(&mut entry.transaction)
     }
@@ -1498,6 +1698,34 @@ impl Buffer {
             .collect()
     }
 
+    pub fn undo_tree(&self) -> UndoTree {
+        self.history.undo_tree()
+    }
+
+    /// Undoes and redoes transactions until the buffer is in the state produced by `target`, or in
+    /// its original state if `target` is `None`. The target can be on any branch of the undo tree.
+    pub fn travel_to_undo_state(&mut self, target: Option<TransactionId>) -> Vec<Operation> {
+        let Some(transactions) = self.history.travel_to(target) else {
+            return Vec::new();
+        };
+
+        transactions
+            .into_iter()
+            .map(|transaction| self.undo_or_redo(transaction))
+            .collect()
+    }
+
+    /// Returns what the buffer would look like in the given state of the undo tree, without
+    /// changing the buffer.
+    pub fn snapshot_for_undo_state(&self, target: Option<TransactionId>) -> Option<BufferSnapshot> {
+        let transactions = self.history.clone().travel_to(target)?;
+        let mut branch = self.branch();
+        for transaction in transactions {
+            branch.undo_or_redo(transaction);
+        }
+        Some(branch.snapshot)
+    }
+
     fn undo_or_redo(&mut self, transaction: Transaction) -> Operation {
         let mut counts = HashMap::default();
         for edit_id in transaction.edit_ids {
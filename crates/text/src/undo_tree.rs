@@ -0,0 +1,124 @@
+use crate::TransactionId;
+use std::time::{Duration, Instant};
+
+/// A transaction in a buffer's undo tree, along with the transaction it was applied on top of.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The transaction this one was applied after, or `None` if it was applied to the buffer's
+    /// original text.
+    pub parent: Option<TransactionId>,
+    pub first_edit_at: Instant,
+    pub last_edit_at: Instant,
+}
+
+/// A buffer's undo history, including the branches that were undone and then replaced by other
+/// edits.
+///
+/// States of the buffer are identified by the transaction that produced them, with `None`
+/// standing for the buffer's original text.
+#[derive(Clone, Debug, Default)]
+pub struct UndoTree {
+    /// Sorted by transaction id, which is the order in which the transactions were started.
+    nodes: Vec<UndoTreeNode>,
+    current: Option<TransactionId>,
+}
+
+impl UndoTree {
+    pub(crate) fn new(mut nodes: Vec<UndoTreeNode>, current: Option<TransactionId>) -> Self {
+        nodes.sort_by_key(|node| node.transaction_id);
+        Self { nodes, current }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[UndoTreeNode] {
+        &self.nodes
+    }
+
+    /// The transaction that produced the buffer's current state.
+    pub fn current(&self) -> Option<TransactionId> {
+        self.current
+    }
+
+    pub fn node(&self, transaction_id: TransactionId) -> Option<&UndoTreeNode> {
+        self.nodes
+            .binary_search_by_key(&transaction_id, |node| node.transaction_id)
+            .ok()
+            .map(|ix| &self.nodes[ix])
+    }
+
+    pub fn children(
+        &self,
+        parent: Option<TransactionId>,
+    ) -> impl DoubleEndedIterator<Item = &UndoTreeNode> {
+        self.nodes.iter().filter(move |node| node.parent == parent)
+    }
+
+    /// The transactions leading from the original text to the given state, oldest first.
+    pub fn path_to(&self, mut state: Option<TransactionId>) -> Vec<TransactionId> {
+        let mut path = Vec::new();
+        while let Some(node) = state.and_then(|id| self.node(id)) {
+            path.push(node.transaction_id);
+            state = node.parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// The state `count` changes before the current one in the order the changes were made,
+    /// regardless of the branch they are on. This is how vim's `g-` moves through the tree.
+    pub fn earlier(&self, count: usize) -> Option<TransactionId> {
+        self.state_at_index(self.current_index().saturating_sub(count))
+    }
+
+    /// The state `count` changes after the current one in the order the changes were made,
+    /// like vim's `g+`.
+    pub fn later(&self, count: usize) -> Option<TransactionId> {
+        self.state_at_index(self.current_index().saturating_add(count))
+    }
+
+    /// The most recent state that existed `duration` before the current state was created.
+    pub fn earlier_by(&self, duration: Duration) -> Option<TransactionId> {
+        let current_edit_at = self.current.and_then(|id| self.node(id))?.last_edit_at;
+        self.latest_state_at(current_edit_at.checked_sub(duration)?)
+    }
+
+    /// The most recent state that existed `duration` after the current state was created.
+    pub fn later_by(&self, duration: Duration) -> Option<TransactionId> {
+        let current_edit_at = match self.current.and_then(|id| self.node(id)) {
+            Some(node) => node.last_edit_at,
+            None => self.nodes.iter().map(|node| node.first_edit_at).min()?,
+        };
+        let Some(time) = current_edit_at.checked_add(duration) else {
+            return self.state_at_index(self.nodes.len());
+        };
+        self.latest_state_at(time).or(self.current)
+    }
+
+    fn latest_state_at(&self, time: Instant) -> Option<TransactionId> {
+        self.nodes
+            .iter()
+            .filter(|node| node.last_edit_at <= time)
+            .max_by_key(|node| (node.last_edit_at, node.transaction_id))
+            .map(|node| node.transaction_id)
+    }
+
+    /// The index of the current state among all states, where `0` is the original text.
+    fn current_index(&self) -> usize {
+        self.current
+            .and_then(|current| {
+                self.nodes
+                    .binary_search_by_key(&current, |node| node.transaction_id)
+                    .ok()
+            })
+            .map_or(0, |ix| ix + 1)
+    }
+
+    fn state_at_index(&self, ix: usize) -> Option<TransactionId> {
+        let ix = ix.min(self.nodes.len());
+        ix.checked_sub(1).map(|ix| self.nodes[ix].transaction_id)
+    }
+}
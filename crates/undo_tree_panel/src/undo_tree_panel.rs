@@ -0,0 +1,818 @@
+mod undo_tree_panel_settings;
+
+use std::{
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, ClickEvent, Context, ElementId, Entity, EventEmitter,
+    FocusHandle, Focusable, InteractiveElement, IntoElement, KeyContext, ParentElement, Pixels,
+    Render, ScrollStrategy, Styled, Subscription, Task, UniformListScrollHandle, WeakEntity,
+    Window, actions, px, uniform_list,
+};
+use language::{Buffer, TransactionId, UndoTree, unified_diff};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::Fs;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use ui::{
+    ActiveTheme, Color, DynamicSpacing, FluentBuilder, Label, LabelCommon, LabelSize, ListItem,
+    h_flex, v_flex,
+};
+use undo_tree_panel_settings::{DockSide, UndoTreePanelSettings};
+use util::ResultExt;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    undo_tree_panel,
+    [
+        /// Toggles focus on the undo tree panel.
+        ToggleFocus,
+    ]
+);
+
+const UNDO_TREE_PANEL_KEY: &str = "UndoTreePanel";
+
+pub struct UndoTreePanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    workspace: WeakEntity<Workspace>,
+    active: bool,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    pending_serialization: Task<Option<()>>,
+    active_editor: Option<ActiveEditor>,
+    tree: UndoTree,
+    entries: Vec<UndoTreeEntry>,
+    selected_entry: Option<usize>,
+    preview_buffer: Entity<Buffer>,
+    preview: Entity<Editor>,
+    preview_task: Task<()>,
+    _workspace_subscription: Subscription,
+}
+
+struct ActiveEditor {
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    _buffer_subscription: Subscription,
+}
+
+#[derive(Debug)]
+struct UndoTreeEntry {
+    /// The state of the buffer, `None` being its original text.
+    state: Option<TransactionId>,
+    /// The number of the change in the order the changes were made, `0` being the original text.
+    change_number: usize,
+    /// Older branches at a fork are indented below the fork, the newest one continues at the
+    /// fork's depth.
+    depth: usize,
+    last_edit_at: Option<Instant>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedUndoTreePanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut App) {
+    UndoTreePanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut App) {
+    init_settings(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<UndoTreePanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+impl UndoTreePanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = match workspace
+            .read_with(&cx, |workspace, _| {
+                UndoTreePanel::serialization_key(workspace)
+            })
+            .ok()
+            .flatten()
+        {
+            Some(serialization_key) => cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(&serialization_key) })
+                .await
+                .context("loading undo tree panel")
+                .log_err()
+                .flatten()
+                .map(|panel| serde_json::from_str::<SerializedUndoTreePanel>(&panel))
+                .transpose()
+                .log_err()
+                .flatten(),
+            None => None,
+        };
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let panel = Self::new(workspace, window, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let languages = workspace.project().read(cx).languages().clone();
+        let active_editor = workspace_active_editor(workspace, cx);
+        let workspace_handle = cx.entity();
+        cx.new(|cx| {
+            let preview_buffer = cx.new(|cx| Buffer::local("", cx));
+            let diff_language = languages.language_for_name("Diff");
+            cx.spawn({
+                let preview_buffer = preview_buffer.downgrade();
+                async move |_, cx| {
+                    let language = diff_language.await.ok();
+                    preview_buffer.update(cx, |buffer, cx| buffer.set_language(language, cx))
+                }
+            })
+            .detach();
+            let preview = cx.new(|cx| {
+                let mut editor = Editor::for_buffer(preview_buffer.clone(), None, window, cx);
+                editor.set_read_only(true);
+                editor.set_show_gutter(false, cx);
+                editor.disable_scrollbars_and_minimap(window, cx);
+                editor
+            });
+
+            let workspace_subscription = cx.subscribe_in(
+                &workspace_handle,
+                window,
+                |panel, workspace, event, _, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        let editor = workspace_active_editor(workspace.read(cx), cx);
+                        panel.set_active_editor(editor, cx);
+                    }
+                },
+            );
+
+            let mut panel = Self {
+                fs,
+                width: None,
+                workspace: workspace_handle.downgrade(),
+                active: false,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                pending_serialization: Task::ready(None),
+                active_editor: None,
+                tree: UndoTree::default(),
+                entries: Vec::new(),
+                selected_entry: None,
+                preview_buffer,
+                preview,
+                preview_task: Task::ready(()),
+                _workspace_subscription: workspace_subscription,
+            };
+            panel.set_active_editor(active_editor, cx);
+            panel
+        })
+    }
+
+    fn serialization_key(workspace: &Workspace) -> Option<String> {
+        workspace
+            .database_id()
+            .map(|id| i64::from(id).to_string())
+            .or(workspace.session_id())
+            .map(|id| format!("{}-{:?}", UNDO_TREE_PANEL_KEY, id))
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let Some(serialization_key) = self
+            .workspace
+            .read_with(cx, |workspace, _| {
+                UndoTreePanel::serialization_key(workspace)
+            })
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        serialization_key,
+                        serde_json::to_string(&SerializedUndoTreePanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("UndoTreePanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn set_active_editor(&mut self, editor: Option<Entity<Editor>>, cx: &mut Context<Self>) {
+        let buffer = editor
+            .as_ref()
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+        if let Some(active_editor) = &self.active_editor
+            && buffer.as_ref() == Some(&active_editor.buffer)
+        {
+            return;
+        }
+
+        self.entries.clear();
+        self.selected_entry = None;
+        self.active_editor = editor.zip(buffer).map(|(editor, buffer)| ActiveEditor {
+            editor: editor.downgrade(),
+            _buffer_subscription: cx.observe(&buffer, |panel, _, cx| panel.refresh(cx)),
+            buffer,
+        });
+        self.refresh(cx);
+    }
+
+    /// Rebuilds the entries from the active buffer's undo tree. The selection follows the
+    /// buffer's current state whenever it changes.
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let previous_state = self.tree.current();
+        let selected_state = self
+            .selected_entry
+            .and_then(|entry_ix| self.entries.get(entry_ix))
+            .map(|entry| entry.state);
+        self.tree = self
+            .active_editor
+            .as_ref()
+            .map(|active_editor| active_editor.buffer.read(cx).undo_tree())
+            .unwrap_or_default();
+        self.entries = if self.active_editor.is_some() {
+            tree_entries(&self.tree)
+        } else {
+            Vec::new()
+        };
+
+        let current_state = self.tree.current();
+        let state_to_select = match selected_state {
+            Some(selected_state) if current_state == previous_state => selected_state,
+            _ => current_state,
+        };
+        self.selected_entry = self
+            .entries
+            .iter()
+            .position(|entry| entry.state == state_to_select);
+        if selected_state != Some(state_to_select)
+            && let Some(entry_ix) = self.selected_entry
+        {
+            self.scroll_handle
+                .scroll_to_item(entry_ix, ScrollStrategy::Center);
+        }
+        if selected_state != Some(state_to_select) || current_state != previous_state {
+            self.update_preview(cx);
+        }
+        cx.notify();
+    }
+
+    /// Shows the changes made by the selected state, compared to the state it was applied to.
+    fn update_preview(&mut self, cx: &mut Context<Self>) {
+        let states = self
+            .active_editor
+            .as_ref()
+            .zip(self.selected_entry)
+            .and_then(|(active_editor, entry_ix)| {
+                let state = self.entries.get(entry_ix)?.state?;
+                let parent = self.tree.node(state)?.parent;
+                let buffer = active_editor.buffer.read(cx);
+                Some((
+                    buffer.snapshot_for_undo_state(parent)?,
+                    buffer.snapshot_for_undo_state(Some(state))?,
+                ))
+            });
+        self.preview_task = cx.spawn(async move |panel, cx| {
+            let diff = match states {
+                Some((old, new)) => {
+                    cx.background_spawn(async move { unified_diff(&old.text(), &new.text()) })
+                        .await
+                }
+                None => String::new(),
+            };
+            panel
+                .update(cx, |panel, cx| {
+                    panel
+                        .preview_buffer
+                        .update(cx, |buffer, cx| buffer.set_text(diff, cx));
+                })
+                .ok();
+        });
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let entry_ix = self
+            .selected_entry
+            .map_or(0, |selected_entry| selected_entry + 1);
+        if entry_ix < self.entries.len() {
+            self.select_entry(entry_ix, cx);
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(selected_entry) = self.selected_entry {
+            self.select_entry(selected_entry.saturating_sub(1), cx);
+        } else if !self.entries.is_empty() {
+            self.select_entry(self.entries.len() - 1, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_entry(0, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.select_entry(self.entries.len() - 1, cx);
+        }
+    }
+
+    fn select_entry(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        self.selected_entry = Some(entry_ix);
+        self.scroll_handle
+            .scroll_to_item(entry_ix, ScrollStrategy::Center);
+        self.update_preview(cx);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry_ix) = self.selected_entry {
+            self.restore_entry(entry_ix, window, cx);
+        }
+    }
+
+    /// Moves the active buffer to the state of the given entry.
+    fn restore_entry(&mut self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(entry_ix) else {
+            return;
+        };
+        let Some(active_editor) = &self.active_editor else {
+            return;
+        };
+        let state = entry.state;
+        if let Some(editor) = active_editor.editor.upgrade() {
+            editor.update(cx, |editor, cx| {
+                editor.travel_to_undo_state(state, window, cx);
+            });
+        } else {
+            active_editor.buffer.update(cx, |buffer, cx| {
+                buffer.travel_to_undo_state(state, cx);
+            });
+        }
+    }
+
+    fn render_entry(
+        &self,
+        entry_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ListItem {
+        let settings = UndoTreePanelSettings::get_global(cx);
+        let entry = &self.entries[entry_ix];
+        let is_selected = self.selected_entry == Some(entry_ix);
+        let is_current = entry.state == self.tree.current();
+        let label = if entry.state.is_some() {
+            format!("Change {}", entry.change_number)
+        } else {
+            "Original".to_string()
+        };
+        let age = entry
+            .last_edit_at
+            .map(|last_edit_at| format_age(last_edit_at.elapsed()));
+
+        ListItem::new(ElementId::Integer(entry_ix as u64))
+            .indent_level(entry.depth)
+            .indent_step_size(px(settings.indent_size))
+            .toggle_state(is_selected)
+            .focused(is_selected && self.focus_handle.contains_focused(window, cx))
+            .on_click(cx.listener(move |panel, event: &ClickEvent, window, cx| {
+                panel.select_entry(entry_ix, cx);
+                if event.click_count() > 1 {
+                    panel.restore_entry(entry_ix, window, cx);
+                }
+            }))
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Label::new(label)
+                            .color(if is_current {
+                                Color::Accent
+                            } else {
+                                Color::Default
+                            })
+                            .single_line(),
+                    )
+                    .when_some(age, |this, age| {
+                        this.child(Label::new(age).size(LabelSize::Small).color(Color::Muted))
+                    })
+                    .when(is_current, |this| {
+                        this.child(
+                            Label::new("(current)")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+    }
+}
+
+/// Lists the states of the tree depth-first from the original text. At each fork, the older
+/// branches are listed first, indented, followed by the newest branch at the fork's depth.
+fn tree_entries(tree: &UndoTree) -> Vec<UndoTreeEntry> {
+    let mut children = HashMap::<Option<TransactionId>, Vec<TransactionId>>::default();
+    let mut change_numbers = HashMap::default();
+    for (ix, node) in tree.nodes().iter().enumerate() {
+        children
+            .entry(node.parent)
+            .or_default()
+            .push(node.transaction_id);
+        change_numbers.insert(node.transaction_id, ix + 1);
+    }
+
+    let mut entries = Vec::new();
+    let mut stack = vec![(None, 0)];
+    while let Some((state, depth)) = stack.pop() {
+        entries.push(UndoTreeEntry {
+            state,
+            change_number: state.map_or(0, |state| change_numbers[&state]),
+            depth,
+            last_edit_at: state
+                .and_then(|state| tree.node(state))
+                .map(|node| node.last_edit_at),
+        });
+
+        let mut branches = children.remove(&state).unwrap_or_default();
+        if let Some(newest_branch) = branches.pop() {
+            stack.push((Some(newest_branch), depth));
+            stack.extend(
+                branches
+                    .into_iter()
+                    .rev()
+                    .map(|branch| (Some(branch), depth + 1)),
+            );
+        }
+    }
+    entries
+}
+
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+fn workspace_active_editor(workspace: &Workspace, cx: &App) -> Option<Entity<Editor>> {
+    workspace
+        .active_item(cx)?
+        .act_as::<Editor>(cx)
+        .filter(|editor| editor.read(cx).buffer().read(cx).is_singleton())
+}
+
+impl Panel for UndoTreePanel {
+    fn persistent_name() -> &'static str {
+        "Undo Tree Panel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        match UndoTreePanelSettings::get_global(cx).dock {
+            DockSide::Left => DockPosition::Left,
+            DockSide::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            let dock = match position {
+                DockPosition::Left | DockPosition::Bottom => DockSide::Left,
+                DockPosition::Right => DockSide::Right,
+            };
+            settings.undo_tree_panel.get_or_insert_default().dock = Some(dock);
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| UndoTreePanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, window: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+        cx.defer_in(window, |this, _, cx| {
+            this.serialize(cx);
+        });
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<ui::IconName> {
+        UndoTreePanelSettings::get_global(cx)
+            .button
+            .then_some(ui::IconName::HistoryRerun)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Undo Tree Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _window: &Window, _: &App) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.active != active {
+            self.active = active;
+            self.serialize(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        8
+    }
+}
+
+impl Focusable for UndoTreePanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for UndoTreePanel {}
+
+impl Render for UndoTreePanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let contents =
+            if self.entries.is_empty() {
+                v_flex()
+                    .id("empty-undo-tree-state")
+                    .flex_1()
+                    .size_full()
+                    .justify_center()
+                    .child(h_flex().justify_center().child(
+                        Label::new("Open a buffer to see its undo tree").color(Color::Muted),
+                    ))
+                    .into_any_element()
+            } else {
+                v_flex()
+                    .size_full()
+                    .child(
+                        uniform_list(
+                            "undo-tree-entries",
+                            self.entries.len(),
+                            cx.processor(|panel, range: Range<usize>, window, cx| {
+                                range
+                                    .map(|entry_ix| panel.render_entry(entry_ix, window, cx))
+                                    .collect()
+                            }),
+                        )
+                        .flex_1()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                    .child(
+                        v_flex()
+                            .h_1_2()
+                            .flex_shrink_0()
+                            .border_t_1()
+                            .border_color(cx.theme().colors().border)
+                            .child(
+                                h_flex()
+                                    .px_2()
+                                    .h(DynamicSpacing::Base32.px(cx))
+                                    .child(Label::new("Changes").size(LabelSize::Small)),
+                            )
+                            .child(self.preview.clone()),
+                    )
+                    .into_any_element()
+            };
+
+        v_flex()
+            .id("undo-tree-panel")
+            .size_full()
+            .overflow_hidden()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .child(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use gpui::{TestAppContext, VisualTestContext, WindowHandle};
+    use pretty_assertions::assert_eq;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::OpenOptions;
+
+    use super::*;
+
+    const SELECTED_MARKER: &str = "  <==== selected";
+
+    #[gpui::test]
+    async fn test_undo_tree_panel(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "main.rs": "one\n" }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        let workspace = add_undo_tree_panel(&project, cx).await;
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let editor = workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.open_abs_path(
+                    PathBuf::from(path!("/project/main.rs")),
+                    OpenOptions::default(),
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        let panel = undo_tree_panel(&workspace, cx);
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.move_to_end(&editor::actions::MoveToEnd, window, cx);
+            editor.insert("two\n", window, cx);
+            editor.insert("three\n", window, cx);
+            editor.undo(&editor::actions::Undo, window, cx);
+            editor.insert("four\n", window, cx);
+        });
+        cx.run_until_parked();
+        panel.update(cx, |panel, _| {
+            assert_eq!(
+                display_entries(panel),
+                "\
+Original
+Change 1
+  Change 2
+Change 3  <==== selected
+"
+            );
+        });
+        assert_eq!(
+            preview_text(&panel, cx),
+            "@@ -1,2 +1,3 @@\n one\n two\n+four\n"
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.select_previous(&SelectPrevious, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            preview_text(&panel, cx),
+            "@@ -1,2 +1,3 @@\n one\n two\n+three\n"
+        );
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "one\ntwo\nfour\n"
+        );
+
+        panel.update_in(cx, |panel, window, cx| {
+            panel.confirm(&Confirm, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor.text(cx)),
+            "one\ntwo\nthree\n"
+        );
+        panel.update(cx, |panel, _| {
+            assert_eq!(
+                display_entries(panel),
+                "\
+Original
+Change 1
+  Change 2  <==== selected
+Change 3
+"
+            );
+        });
+    }
+
+    fn display_entries(panel: &UndoTreePanel) -> String {
+        let mut display_string = String::new();
+        for (entry_ix, entry) in panel.entries.iter().enumerate() {
+            display_string.push_str(&"  ".repeat(entry.depth));
+            if entry.state.is_some() {
+                display_string.push_str(&format!("Change {}", entry.change_number));
+            } else {
+                display_string.push_str("Original");
+            }
+            if panel.selected_entry == Some(entry_ix) {
+                display_string.push_str(SELECTED_MARKER);
+            }
+            display_string.push('\n');
+        }
+        display_string
+    }
+
+    fn preview_text(panel: &Entity<UndoTreePanel>, cx: &mut VisualTestContext) -> String {
+        panel.update(cx, |panel, cx| panel.preview_buffer.read(cx).text())
+    }
+
+    async fn add_undo_tree_panel(
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> WindowHandle<Workspace> {
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let panel = window
+            .update(cx, |_, window, cx| {
+                cx.spawn_in(window, async |this, cx| {
+                    UndoTreePanel::load(this, cx.clone()).await
+                })
+            })
+            .unwrap()
+            .await
+            .expect("Failed to load undo tree panel");
+        window
+            .update(cx, |workspace, window, cx| {
+                workspace.add_panel(panel, window, cx);
+            })
+            .unwrap();
+        window
+    }
+
+    fn undo_tree_panel(
+        workspace: &WindowHandle<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<UndoTreePanel> {
+        workspace
+            .update(cx, |workspace, _, cx| {
+                workspace
+                    .panel::<UndoTreePanel>(cx)
+                    .expect("no undo tree panel")
+            })
+            .unwrap()
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+
+            theme::init(theme::LoadThemes::JustBase, cx);
+
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            super::init(cx);
+        });
+    }
+}
@@ -0,0 +1,22 @@
+use gpui::Pixels;
+pub use settings::{DockSide, Settings};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UndoTreePanelSettings {
+    pub button: bool,
+    pub default_width: Pixels,
+    pub dock: DockSide,
+    pub indent_size: f32,
+}
+
+impl Settings for UndoTreePanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.undo_tree_panel.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            default_width: panel.default_width.map(gpui::px).unwrap(),
+            dock: panel.dock.unwrap(),
+            indent_size: panel.indent_size.unwrap(),
+        }
+    }
+}
@@ -29,7 +29,7 @@ use std::{
 use task::{HideStrategy, RevealStrategy, SpawnInTerminal, TaskId};
 use ui::ActiveTheme;
 use util::{ResultExt, rel_path::RelPath};
-use workspace::{Item, SaveIntent, notifications::NotifyResultExt};
+use workspace::{Item, SaveIntent, Workspace, notifications::NotifyResultExt};
 use workspace::{SplitDirection, notifications::DetachAndPromptErr};
 use zed_actions::{OpenDocs, RevealTarget};
 
@@ -41,6 +41,10 @@ use crate::{
         search::{FindCommand, ReplaceCommand, Replacement},
     },
     object::Object,
+    quickfix::{
+        ListKind, QuickfixDo, QuickfixFill, QuickfixJump, QuickfixOpen, QuickfixSource,
+        QuickfixTarget,
+    },
     state::{Mark, Mode},
     visual::VisualDeleteLine,
 };
@@ -76,6 +80,14 @@ pub struct WithCount {
     action: WrappedAction,
 }
 
+/// Executes a command typed into the command palette and adds it to the command history.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct ExCommand {
+    command: String,
+    action: WrappedAction,
+}
+
 #[derive(Clone, Deserialize, JsonSchema, PartialEq)]
 pub enum VimOption {
     Wrap(bool),
@@ -1032,6 +1044,16 @@ impl CommandRange {
         }
     }
 
+    pub(crate) fn is_current_line(&self) -> bool {
+        matches!(
+            self,
+            CommandRange {
+                start: Position::CurrentLine { offset: 0 },
+                end: None,
+            }
+        )
+    }
+
     pub fn as_count(&self) -> Option<u32> {
         if let CommandRange {
             start: Position::Line { row, offset: 0 },
@@ -1259,30 +1281,296 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
             save_intent: Some(SaveIntent::Skip),
         }),
         VimCommand::str(("cl", "ist"), "diagnostics::Deploy"),
-        VimCommand::new(("cc", ""), editor::actions::Hover),
-        VimCommand::new(("ll", ""), editor::actions::Hover),
-        VimCommand::new(("cn", "ext"), editor::actions::GoToDiagnostic::default())
-            .range(wrap_count),
+        VimCommand::new(
+            ("cc", ""),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::Current,
+            },
+        )
+        .args(QuickfixJump::with_args)
+        .range(jump_to_count),
+        VimCommand::new(
+            ("ll", ""),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::Current,
+            },
+        )
+        .args(QuickfixJump::with_args)
+        .range(jump_to_count),
+        VimCommand::new(
+            ("cn", "ext"),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::Next,
+            },
+        )
+        .range(wrap_count),
+        VimCommand::new(
+            ("ln", "ext"),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::Next,
+            },
+        )
+        .range(wrap_count),
         VimCommand::new(
             ("cp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::Previous,
+            },
         )
         .range(wrap_count),
         VimCommand::new(
             ("cN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::Previous,
+            },
         )
         .range(wrap_count),
         VimCommand::new(
             ("lp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::Previous,
+            },
         )
         .range(wrap_count),
         VimCommand::new(
             ("lN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::Previous,
+            },
         )
         .range(wrap_count),
+        VimCommand::new(
+            ("cfir", "st"),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::First,
+            },
+        ),
+        VimCommand::new(
+            ("cr", "ewind"),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::First,
+            },
+        ),
+        VimCommand::new(
+            ("cla", "st"),
+            QuickfixJump {
+                list: ListKind::Quickfix,
+                target: QuickfixTarget::Last,
+            },
+        ),
+        VimCommand::new(
+            ("lfir", "st"),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::First,
+            },
+        ),
+        VimCommand::new(
+            ("lr", "ewind"),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::First,
+            },
+        ),
+        VimCommand::new(
+            ("lla", "st"),
+            QuickfixJump {
+                list: ListKind::Location,
+                target: QuickfixTarget::Last,
+            },
+        ),
+        VimCommand::new(
+            ("cope", "n"),
+            QuickfixOpen {
+                list: ListKind::Quickfix,
+            },
+        ),
+        VimCommand::new(
+            ("lop", "en"),
+            QuickfixOpen {
+                list: ListKind::Location,
+            },
+        ),
+        VimCommand::new(
+            ("mak", "e"),
+            QuickfixFill {
+                list: ListKind::Quickfix,
+                source: QuickfixSource::Make(String::new()),
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Quickfix,
+            source: QuickfixSource::Make(String::new()),
+            jump: false,
+        })
+        .args(QuickfixFill::with_args),
+        VimCommand::new(
+            ("lmak", "e"),
+            QuickfixFill {
+                list: ListKind::Location,
+                source: QuickfixSource::Make(String::new()),
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Location,
+            source: QuickfixSource::Make(String::new()),
+            jump: false,
+        })
+        .args(QuickfixFill::with_args),
+        VimCommand::new(
+            ("vim", "grep"),
+            QuickfixFill {
+                list: ListKind::Quickfix,
+                source: QuickfixSource::Grep(String::new()),
+                jump: true,
+            },
+        )
+        .args(QuickfixFill::with_args),
+        VimCommand::new(
+            ("lv", "imgrep"),
+            QuickfixFill {
+                list: ListKind::Location,
+                source: QuickfixSource::Grep(String::new()),
+                jump: true,
+            },
+        )
+        .args(QuickfixFill::with_args),
+        VimCommand::new(
+            ("cb", "uffer"),
+            QuickfixFill {
+                list: ListKind::Quickfix,
+                source: QuickfixSource::Buffer,
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Quickfix,
+            source: QuickfixSource::Buffer,
+            jump: false,
+        }),
+        VimCommand::new(
+            ("lb", "uffer"),
+            QuickfixFill {
+                list: ListKind::Location,
+                source: QuickfixSource::Buffer,
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Location,
+            source: QuickfixSource::Buffer,
+            jump: false,
+        }),
+        VimCommand::new(
+            ("cdia", "gnostics"),
+            QuickfixFill {
+                list: ListKind::Quickfix,
+                source: QuickfixSource::Diagnostics,
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Quickfix,
+            source: QuickfixSource::Diagnostics,
+            jump: false,
+        }),
+        VimCommand::new(
+            ("ldia", "gnostics"),
+            QuickfixFill {
+                list: ListKind::Location,
+                source: QuickfixSource::Diagnostics,
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Location,
+            source: QuickfixSource::Diagnostics,
+            jump: false,
+        }),
+        VimCommand::new(
+            ("cse", "arch"),
+            QuickfixFill {
+                list: ListKind::Quickfix,
+                source: QuickfixSource::ProjectSearch,
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Quickfix,
+            source: QuickfixSource::ProjectSearch,
+            jump: false,
+        }),
+        VimCommand::new(
+            ("lse", "arch"),
+            QuickfixFill {
+                list: ListKind::Location,
+                source: QuickfixSource::ProjectSearch,
+                jump: true,
+            },
+        )
+        .bang(QuickfixFill {
+            list: ListKind::Location,
+            source: QuickfixSource::ProjectSearch,
+            jump: false,
+        }),
+        VimCommand::new(
+            ("cdo", ""),
+            QuickfixDo {
+                list: ListKind::Quickfix,
+                per_file: false,
+                command: String::new(),
+            },
+        )
+        .args(QuickfixDo::with_args),
+        VimCommand::new(
+            ("cfdo", ""),
+            QuickfixDo {
+                list: ListKind::Quickfix,
+                per_file: true,
+                command: String::new(),
+            },
+        )
+        .args(QuickfixDo::with_args),
+        VimCommand::new(
+            ("ldo", ""),
+            QuickfixDo {
+                list: ListKind::Location,
+                per_file: false,
+                command: String::new(),
+            },
+        )
+        .args(QuickfixDo::with_args),
+        VimCommand::new(
+            ("lfdo", ""),
+            QuickfixDo {
+                list: ListKind::Location,
+                per_file: true,
+                command: String::new(),
+            },
+        )
+        .args(QuickfixDo::with_args),
+        VimCommand::new(("ea", "rlier"), editor::actions::UndoEarlier::default()).args(
+            |_, args| {
+                let (count, seconds) = parse_undo_travel(&args)?;
+                Some(editor::actions::UndoEarlier { count, seconds }.boxed_clone())
+            },
+        ),
+        VimCommand::new(("lat", "er"), editor::actions::UndoLater::default()).args(|_, args| {
+            let (count, seconds) = parse_undo_travel(&args)?;
+            Some(editor::actions::UndoLater { count, seconds }.boxed_clone())
+        }),
         VimCommand::new(("j", "oin"), JoinLines).range(select_range),
         VimCommand::new(("fo", "ld"), editor::actions::FoldSelectedRanges).range(act_on_range),
         VimCommand::new(("foldo", "pen"), editor::actions::UnfoldLines)
@@ -1373,6 +1661,25 @@ fn select_range(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn
     )
 }
 
+fn jump_to_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn Action>> {
+    QuickfixJump::with_index(action, range.as_count()? as usize)
+}
+
+/// Parses the argument of `:earlier` and `:later`, which is either a number of changes or a
+/// duration like `10s`, `5m`, `1h` or `2d`.
+fn parse_undo_travel(args: &str) -> Option<(Option<usize>, Option<u64>)> {
+    let args = args.trim();
+    let unit = match args.chars().last()? {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => return Some((Some(args.parse().ok()?), None)),
+    };
+    let amount = args[..args.len() - 1].parse::<u64>().ok()?;
+    Some((None, Some(amount.saturating_mul(unit))))
+}
+
 fn wrap_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn Action>> {
     range.as_count().map(|count| {
         WithCount {
@@ -1383,6 +1690,34 @@ fn wrap_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn A
     })
 }
 
+/// Intercepts command palette queries, wrapping the results so that running one of them
+/// adds the query to the command history.
+pub(crate) fn palette_interceptor(input: &str, cx: &App) -> Vec<CommandInterceptResult> {
+    let command = input.trim_start_matches(':').trim();
+    command_interceptor(input, cx)
+        .into_iter()
+        .map(|result| CommandInterceptResult {
+            action: ExCommand {
+                command: command.to_string(),
+                action: WrappedAction(result.action),
+            }
+            .boxed_clone(),
+            ..result
+        })
+        .collect()
+}
+
+impl ExCommand {
+    pub(crate) fn register(workspace: &mut Workspace, _window: Option<&mut Window>) {
+        workspace.register_action(|_, action: &ExCommand, window, cx| {
+            Vim::update_globals(cx, |globals, cx| {
+                globals.push_command_history(action.command.clone(), cx)
+            });
+            window.dispatch_action(action.action.boxed_clone(), cx);
+        });
+    }
+}
+
 pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptResult> {
     // NOTE: We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
@@ -96,6 +96,10 @@ actions!(
         Redo,
         /// Undoes all changes to the most recently changed line.
         UndoLastLine,
+        /// Goes back to an earlier state of the buffer, across undo branches.
+        UndoEarlier,
+        /// Goes forward to a later state of the buffer, across undo branches.
+        UndoLater,
         /// Go to tab page (with count support).
         GoToTab,
         /// Go to previous tab page (with count support).
@@ -222,6 +226,34 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             }
         });
     });
+    Vim::action(editor, cx, |vim, _: &UndoEarlier, window, cx| {
+        let count = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        vim.update_editor(cx, |_, editor, cx| {
+            editor.undo_earlier(
+                &editor::actions::UndoEarlier {
+                    count,
+                    seconds: None,
+                },
+                window,
+                cx,
+            );
+        });
+    });
+    Vim::action(editor, cx, |vim, _: &UndoLater, window, cx| {
+        let count = Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        vim.update_editor(cx, |_, editor, cx| {
+            editor.undo_later(
+                &editor::actions::UndoLater {
+                    count,
+                    seconds: None,
+                },
+                window,
+                cx,
+            );
+        });
+    });
     Vim::action(editor, cx, |vim, _: &UndoLastLine, window, cx| {
         Vim::take_forced_motion(cx);
         vim.update_editor(cx, |vim, editor, cx| {
@@ -2168,6 +2200,30 @@ mod test {
         cx.shared_state().await.assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_undo_earlier_later(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("x x u r a");
+        assert_eq!(cx.buffer_text(), "ae");
+
+        // `g-` and `g+` also visit the undone "e", which `u` and `ctrl-r` can no longer reach.
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "e");
+        cx.simulate_keystrokes("g -");
+        assert_eq!(cx.buffer_text(), "ne");
+        cx.simulate_keystrokes("g +");
+        assert_eq!(cx.buffer_text(), "e");
+        cx.simulate_keystrokes("g +");
+        assert_eq!(cx.buffer_text(), "ae");
+        cx.simulate_keystrokes("3 g -");
+        assert_eq!(cx.buffer_text(), "one");
+
+        cx.simulate_keystrokes(": l a t e r space 2 enter");
+        assert_eq!(cx.buffer_text(), "e");
+    }
+
     #[gpui::test]
     async fn test_undo_last_line_multicursor(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -1,6 +1,8 @@
 use editor::{Editor, EditorSettings};
-use gpui::{Action, Context, Window, actions};
+use gpui::{Action, Context, Task, Window, actions};
+use itertools::Itertools;
 use language::Point;
+use multi_buffer::MultiBufferRow;
 use schemars::JsonSchema;
 use search::{BufferSearchBar, SearchOptions, buffer_search};
 use serde::Deserialize;
@@ -161,6 +163,7 @@ impl Vim {
         let count = Vim::take_count(cx).unwrap_or(1);
         Vim::take_forced_motion(cx);
         let prior_selections = self.editor_selections(window, cx);
+        let search_history = Vim::globals(cx).search_history.clone();
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
                 search_bar.update(cx, |search_bar, cx| {
@@ -168,6 +171,7 @@ impl Vim {
                         return;
                     }
 
+                    search_bar.restore_search_history(search_history);
                     search_bar.select_query(window, cx);
                     cx.focus_self(window);
 
@@ -247,8 +251,11 @@ impl Vim {
                 let prior_mode = self.search.prior_mode;
                 let prior_operator = self.search.prior_operator.take();
 
-                let query = search_bar.query(cx).into();
-                Vim::globals(cx).registers.insert('/', query);
+                let query = search_bar.query(cx);
+                Vim::update_globals(cx, |globals, cx| {
+                    globals.push_search_history(query.clone(), cx);
+                    globals.registers.insert('/', query.into());
+                });
                 Some((prior_selections, prior_mode, prior_operator))
             })
         });
@@ -459,22 +466,49 @@ impl Vim {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.replace(action, window, cx).detach_and_log_err(cx);
+    }
+
+    /// Runs a `:s` command, returning once the matches are replaced.
+    pub(crate) fn replace(
+        &mut self,
+        action: &ReplaceCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
         let replacement = action.replacement.clone();
         let Some(((pane, workspace), editor)) = self
             .pane(window, cx)
             .zip(self.workspace(window))
             .zip(self.editor())
         else {
-            return;
+            return Task::ready(Ok(()));
         };
         if let Some(result) = self.update_editor(cx, |vim, editor, cx| {
-            let range = action.range.buffer_range(vim, editor, window, cx)?;
+            // With multiple cursors (from `:g`), substitute on every cursor's line.
+            let rows = if action.range.is_current_line() {
+                editor
+                    .selections
+                    .all::<Point>(cx)
+                    .into_iter()
+                    .map(|selection| MultiBufferRow(selection.head().row))
+                    .dedup()
+                    .map(|row| row..row)
+                    .collect()
+            } else {
+                vec![action.range.buffer_range(vim, editor, window, cx)?]
+            };
             let snapshot = editor.snapshot(window, cx);
             let snapshot = snapshot.buffer_snapshot();
-            let end_point = Point::new(range.end.0, snapshot.line_len(range.end));
-            let range = snapshot.anchor_before(Point::new(range.start.0, 0))
-                ..snapshot.anchor_after(end_point);
-            editor.set_search_within_ranges(&[range], cx);
+            let ranges = rows
+                .into_iter()
+                .map(|range| {
+                    let end_point = Point::new(range.end.0, snapshot.line_len(range.end));
+                    snapshot.anchor_before(Point::new(range.start.0, 0))
+                        ..snapshot.anchor_after(end_point)
+                })
+                .collect::<Vec<_>>();
+            editor.set_search_within_ranges(&ranges, cx);
             anyhow::Ok(())
         }) {
             workspace.update(cx, |workspace, cx| {
@@ -484,7 +518,7 @@ impl Vim {
         let Some(search_bar) = pane.update(cx, |pane, cx| {
             pane.toolbar().read(cx).item_of_type::<BufferSearchBar>()
         }) else {
-            return;
+            return Task::ready(Ok(()));
         };
         let mut options = SearchOptions::REGEX;
         let search = search_bar.update(cx, |search_bar, cx| {
@@ -528,9 +562,11 @@ impl Vim {
                 window,
                 cx,
             );
-            return;
+            return Task::ready(Ok(()));
         }
-        let Some(search) = search else { return };
+        let Some(search) = search else {
+            return Task::ready(Ok(()));
+        };
         let search_bar = search_bar.downgrade();
         cx.spawn_in(window, async move |vim, cx| {
             search.await?;
@@ -563,7 +599,6 @@ impl Vim {
                 search_bar.set_search_options(options, cx);
             })
         })
-        .detach_and_log_err(cx);
     }
 }
 
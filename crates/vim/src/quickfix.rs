@@ -0,0 +1,1445 @@
+//! Vim's quickfix and location lists.
+//!
+//! The quickfix list belongs to the workspace, while every pane has its own location list.
+//! Both can be filled by `:make` (parsing the output with `errorformat`-style patterns),
+//! `:vimgrep`, the project diagnostics, the active project search or the current buffer,
+//! and are browsed with `:copen`, navigated with `:cnext` and friends, and batched over with
+//! `:cdo` and `:cfdo`.
+
+use std::{path::Path, process::Stdio, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use editor::{Anchor, Bias, Editor, SelectionEffects, ToPoint as _, scroll::Autoscroll};
+use futures::StreamExt as _;
+use gpui::{
+    Action, App, AppContext as _, Context, DismissEvent, Entity, EntityId, Task, WeakEntity, Window,
+};
+use itertools::Itertools as _;
+use language::{BufferSnapshot, DiagnosticSeverity, Point, ToPoint as _};
+use picker::{Picker, PickerDelegate};
+use project::{
+    Project, ProjectItem as _, ProjectPath,
+    search::{SearchQuery, SearchResult},
+};
+use regex::Regex;
+use search::ProjectSearchView;
+use settings::Settings;
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::{ResultExt as _, paths::PathMatcher};
+use workspace::{Pane, Workspace, notifications::NotifyResultExt as _};
+
+use crate::{
+    Vim, VimAddon, VimSettings, command::command_interceptor, normal::search::ReplaceCommand,
+};
+
+/// A location recorded in a quickfix or location list.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct QuickfixEntry {
+    pub path: ProjectPath,
+    pub position: Point,
+    /// The type of the entry as reported by `%t`: `e`rror, `w`arning, `i`nfo or `n`ote.
+    pub kind: Option<char>,
+    pub text: SharedString,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct QuickfixList {
+    pub title: SharedString,
+    pub entries: Vec<QuickfixEntry>,
+    pub current: usize,
+}
+
+/// Which list a quickfix command operates on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    /// The workspace's quickfix list (`:c...` commands).
+    Quickfix,
+    /// The focused pane's location list (`:l...` commands).
+    Location,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum QuickfixTarget {
+    Current,
+    Index(usize),
+    Next,
+    Previous,
+    First,
+    Last,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum QuickfixSource {
+    /// Runs the `make_program` with the given arguments and parses its output.
+    Make(String),
+    /// Searches the project for the pattern given in `:vimgrep` syntax.
+    Grep(String),
+    /// Parses the contents of the current buffer.
+    Buffer,
+    /// Collects the diagnostics of the project, or of the current buffer for location lists.
+    Diagnostics,
+    /// Collects the matches of the active project search.
+    ProjectSearch,
+}
+
+/// Jumps to an entry of the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixJump {
+    pub list: ListKind,
+    pub target: QuickfixTarget,
+}
+
+/// Opens a view listing the entries of the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixOpen {
+    pub list: ListKind,
+}
+
+/// Replaces the quickfix or location list with entries from the given source.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixFill {
+    pub list: ListKind,
+    pub source: QuickfixSource,
+    /// Whether to jump to the first entry once the list is filled.
+    pub jump: bool,
+}
+
+/// Runs an ex command on every entry (or every file) of the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixDo {
+    pub list: ListKind,
+    pub per_file: bool,
+    pub command: String,
+}
+
+impl QuickfixFill {
+    pub(crate) fn with_args(action: Box<dyn Action>, args: String) -> Option<Box<dyn Action>> {
+        let mut action = action.as_any().downcast_ref::<Self>()?.clone();
+        match &mut action.source {
+            QuickfixSource::Make(current) | QuickfixSource::Grep(current) => *current = args,
+            _ => return None,
+        }
+        Some(action.boxed_clone())
+    }
+}
+
+impl QuickfixJump {
+    pub(crate) fn with_args(action: Box<dyn Action>, args: String) -> Option<Box<dyn Action>> {
+        let index = args.parse::<usize>().ok()?;
+        Self::with_index(action, index)
+    }
+
+    pub(crate) fn with_index(action: Box<dyn Action>, index: usize) -> Option<Box<dyn Action>> {
+        let action = action.as_any().downcast_ref::<Self>()?;
+        Some(
+            Self {
+                list: action.list,
+                target: QuickfixTarget::Index(index.saturating_sub(1)),
+            }
+            .boxed_clone(),
+        )
+    }
+}
+
+impl QuickfixDo {
+    pub(crate) fn with_args(action: Box<dyn Action>, command: String) -> Option<Box<dyn Action>> {
+        let action = action.as_any().downcast_ref::<Self>()?;
+        Some(
+            Self {
+                list: action.list,
+                per_file: action.per_file,
+                command,
+            }
+            .boxed_clone(),
+        )
+    }
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, action: &QuickfixJump, window, cx| {
+        vim.quickfix_jump(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixOpen, window, cx| {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        let Some(owner) = vim.quickfix_owner(action.list, window, cx) else {
+            return;
+        };
+        let pane = vim.quickfix_pane(action.list, window, cx);
+        workspace.update(cx, |workspace, cx| {
+            QuickfixView::toggle(workspace, owner, pane, window, cx);
+        });
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixFill, window, cx| {
+        vim.quickfix_fill(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixDo, window, cx| {
+        vim.quickfix_do(action, window, cx)
+    });
+}
+
+impl Vim {
+    fn quickfix_owner(
+        &self,
+        list: ListKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<EntityId> {
+        match list {
+            ListKind::Quickfix => self
+                .workspace(window)
+                .map(|workspace| workspace.entity_id()),
+            ListKind::Location => self.pane(window, cx).map(|pane| pane.entity_id()),
+        }
+    }
+
+    fn quickfix_pane(
+        &self,
+        list: ListKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<WeakEntity<Pane>> {
+        match list {
+            ListKind::Quickfix => None,
+            ListKind::Location => self.pane(window, cx).map(|pane| pane.downgrade()),
+        }
+    }
+
+    fn quickfix_jump(
+        &mut self,
+        action: &QuickfixJump,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(owner) = self.quickfix_owner(action.list, window, cx) else {
+            return;
+        };
+        let entry = Vim::globals(cx)
+            .quickfix
+            .get_mut(&owner)
+            .filter(|list| !list.entries.is_empty())
+            .map(|list| {
+                let last = list.entries.len() - 1;
+                list.current = match action.target {
+                    QuickfixTarget::Current => list.current,
+                    QuickfixTarget::Index(index) => index,
+                    QuickfixTarget::Next => list.current + 1,
+                    QuickfixTarget::Previous => list.current.saturating_sub(1),
+                    QuickfixTarget::First => 0,
+                    QuickfixTarget::Last => last,
+                }
+                .min(last);
+                list.entries[list.current].clone()
+            });
+
+        let Some(entry) = entry else {
+            // Without a list, fall back to navigating diagnostics as these commands always did.
+            let fallback: Option<Box<dyn Action>> = match action.target {
+                QuickfixTarget::Current | QuickfixTarget::Index(_) => {
+                    Some(editor::actions::Hover.boxed_clone())
+                }
+                QuickfixTarget::Next => {
+                    Some(editor::actions::GoToDiagnostic::default().boxed_clone())
+                }
+                QuickfixTarget::Previous => {
+                    Some(editor::actions::GoToPreviousDiagnostic::default().boxed_clone())
+                }
+                QuickfixTarget::First | QuickfixTarget::Last => None,
+            };
+            if let Some(fallback) = fallback {
+                window.dispatch_action(fallback, cx);
+            }
+            return;
+        };
+
+        self.open_quickfix_entry(action.list, entry, window, cx);
+    }
+
+    fn open_quickfix_entry(
+        &mut self,
+        list: ListKind,
+        entry: QuickfixEntry,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let pane = self.quickfix_pane(list, window, cx);
+        workspace.update(cx, |workspace, cx| {
+            open_quickfix_entry(workspace, &entry, pane, window, cx).detach_and_log_err(cx);
+        });
+    }
+
+    fn set_quickfix_list(
+        &mut self,
+        list: ListKind,
+        title: SharedString,
+        entries: Vec<QuickfixEntry>,
+        jump: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(owner) = self.quickfix_owner(list, window, cx) else {
+            return;
+        };
+        if entries.is_empty() {
+            if let Some(workspace) = self.workspace(window) {
+                workspace.update(cx, |workspace, cx| {
+                    Err::<(), _>(anyhow!("{title}: no matching entries")).notify_err(workspace, cx);
+                });
+            }
+            return;
+        }
+
+        let first = entries[0].clone();
+        Vim::globals(cx).quickfix.insert(
+            owner,
+            QuickfixList {
+                title,
+                entries,
+                current: 0,
+            },
+        );
+        if jump {
+            self.open_quickfix_entry(list, first, window, cx);
+        }
+    }
+
+    fn quickfix_fill(
+        &mut self,
+        action: &QuickfixFill,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let mut jump = action.jump;
+
+        let (title, entries): (SharedString, Task<Result<Vec<QuickfixEntry>>>) = match &action
+            .source
+        {
+            QuickfixSource::Make(args) => {
+                let settings = VimSettings::get_global(cx);
+                let command = if args.is_empty() {
+                    settings.make_program.clone()
+                } else {
+                    format!("{} {args}", settings.make_program)
+                };
+                let formats = settings.error_format.clone();
+                (
+                    format!(":make {args}").trim_end().to_string().into(),
+                    make_entries(project, command, formats, cx),
+                )
+            }
+            QuickfixSource::Grep(args) => {
+                let Some(grep) = GrepArgs::parse(args) else {
+                    workspace.update(cx, |workspace, cx| {
+                        Err::<(), _>(anyhow!("Invalid arguments for :vimgrep: {args}"))
+                            .notify_err(workspace, cx);
+                    });
+                    return;
+                };
+                jump &= grep.jump;
+                let current_path = self.update_editor(cx, |_, editor, cx| {
+                    let buffer = editor.buffer().read(cx).as_singleton()?;
+                    let path_style = project.read(cx).path_style(cx);
+                    let path = buffer.read(cx).project_path(cx)?;
+                    Some(path.path.display(path_style).to_string())
+                });
+                (
+                    format!(":vimgrep {args}").into(),
+                    grep_entries(project, grep, current_path.flatten(), cx),
+                )
+            }
+            QuickfixSource::Buffer => {
+                let Some(text) = self.update_editor(cx, |_, editor, cx| {
+                    editor.buffer().read(cx).snapshot(cx).text()
+                }) else {
+                    return;
+                };
+                let formats = VimSettings::get_global(cx).error_format.clone();
+                let entries = cx.spawn(async move |_, cx| {
+                    let errors = cx
+                        .background_spawn(async move {
+                            let formats = ErrorFormat::parse_all(&formats)?;
+                            anyhow::Ok(parse_errors(&text, &formats))
+                        })
+                        .await?;
+                    project.read_with(cx, |project, cx| entries_from_errors(project, errors, cx))
+                });
+                (":cbuffer".into(), entries)
+            }
+            QuickfixSource::Diagnostics => {
+                let paths = match action.list {
+                    ListKind::Quickfix => project
+                        .read(cx)
+                        .diagnostic_summaries(false, cx)
+                        .map(|(path, _, _)| path)
+                        .unique()
+                        .collect(),
+                    ListKind::Location => self
+                        .update_editor(cx, |_, editor, cx| {
+                            let buffer = editor.buffer().read(cx).as_singleton()?;
+                            buffer.read(cx).project_path(cx)
+                        })
+                        .flatten()
+                        .into_iter()
+                        .collect::<Vec<_>>(),
+                };
+                ("Diagnostics".into(), diagnostic_entries(project, paths, cx))
+            }
+            QuickfixSource::ProjectSearch => {
+                let entries = project_search_entries(workspace.read(cx), cx);
+                ("Project Search".into(), Task::ready(entries))
+            }
+        };
+
+        let list = action.list;
+        cx.spawn_in(window, async move |vim, cx| {
+            let entries = match entries.await {
+                Ok(entries) => entries,
+                Err(error) => {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            Err::<(), _>(error).notify_err(workspace, cx);
+                        })
+                        .ok();
+                    return;
+                }
+            };
+            vim.update_in(cx, |vim, window, cx| {
+                vim.set_quickfix_list(list, title, entries, jump, window, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn quickfix_do(&mut self, action: &QuickfixDo, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let Some(owner) = self.quickfix_owner(action.list, window, cx) else {
+            return;
+        };
+        let Some(command) = command_interceptor(&action.command, cx)
+            .into_iter()
+            .next()
+            .map(|result| result.action)
+        else {
+            workspace.update(cx, |workspace, cx| {
+                Err::<(), _>(anyhow!("Not an editor command: {}", action.command))
+                    .notify_err(workspace, cx);
+            });
+            return;
+        };
+        let Some(entries) = Vim::globals(cx)
+            .quickfix
+            .get(&owner)
+            .map(|list| list.entries.clone())
+        else {
+            return;
+        };
+
+        // Vim runs `:cdo` at every entry in list order, and `:cfdo` at the first entry of every file.
+        let mut positions: HashMap<ProjectPath, Vec<Point>> = HashMap::default();
+        let mut runs = Vec::new();
+        for (ix, entry) in entries.into_iter().enumerate() {
+            let file_positions = positions.entry(entry.path.clone()).or_default();
+            if action.per_file && !file_positions.is_empty() {
+                continue;
+            }
+            file_positions.push(entry.position);
+            runs.push((ix, file_positions.len() - 1, entry));
+        }
+        let replace = command.as_any().downcast_ref::<ReplaceCommand>().cloned();
+
+        let pane = self.quickfix_pane(action.list, window, cx);
+        cx.spawn_in(window, async move |_, cx| {
+            // The entries of a file are anchored when it's first opened, so that the edits of the
+            // command don't move the entries that follow.
+            let mut anchors: HashMap<ProjectPath, Vec<Anchor>> = HashMap::default();
+            for (ix, position_ix, entry) in runs {
+                let editor = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        open_quickfix_entry(workspace, &entry, pane.clone(), window, cx)
+                    })?
+                    .await?;
+                let vim = editor.update_in(cx, |editor, window, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let file_anchors = anchors.entry(entry.path.clone()).or_insert_with(|| {
+                        positions[&entry.path]
+                            .iter()
+                            .map(|position| {
+                                snapshot.anchor_before(snapshot.clip_point(*position, Bias::Left))
+                            })
+                            .collect()
+                    });
+                    let anchor = file_anchors[position_ix];
+                    editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                        s.select_anchor_ranges([anchor..anchor]);
+                    });
+                    editor.addon::<VimAddon>().map(|addon| addon.entity.clone())
+                })?;
+                cx.update(|_, cx| {
+                    if let Some(list) = Vim::globals(cx).quickfix.get_mut(&owner) {
+                        list.current = ix.min(list.entries.len().saturating_sub(1));
+                    }
+                })?;
+
+                // `:s` searches in the background, and would be cancelled by the search of the
+                // next entry: wait for its replacements instead of just dispatching it.
+                match (&replace, vim) {
+                    (Some(replace), Some(vim)) => {
+                        vim.update_in(cx, |vim, window, cx| vim.replace(replace, window, cx))?
+                            .await?;
+                    }
+                    _ => editor.update_in(cx, |_, window, cx| {
+                        window.dispatch_action(command.boxed_clone(), cx);
+                    })?,
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+pub(crate) fn open_quickfix_entry(
+    workspace: &mut Workspace,
+    entry: &QuickfixEntry,
+    pane: Option<WeakEntity<Pane>>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<Result<Entity<Editor>>> {
+    let open = workspace.open_path(entry.path.clone(), pane, true, window, cx);
+    let position = entry.position;
+    cx.spawn_in(window, async move |_, cx| {
+        let editor = open
+            .await?
+            .downcast::<Editor>()
+            .context("quickfix entry did not open in an editor")?;
+        editor.update_in(cx, |editor, window, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let point = snapshot.clip_point(position, Bias::Left);
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |s| s.select_ranges([point..point]),
+            );
+        })?;
+        Ok(editor)
+    })
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> SharedString {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>()
+        .trim()
+        .to_string()
+        .into()
+}
+
+fn make_entries(
+    project: Entity<Project>,
+    command: String,
+    formats: Vec<String>,
+    cx: &mut Context<Vim>,
+) -> Task<Result<Vec<QuickfixEntry>>> {
+    let mut process = match project.read(cx).exec_in_shell(command.clone(), cx) {
+        Ok(process) => process,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    process.stdin(Stdio::null());
+    process.stdout(Stdio::piped());
+    process.stderr(Stdio::piped());
+
+    cx.spawn(async move |_, cx| {
+        let running = process.spawn()?;
+        let (status, errors) = cx
+            .background_spawn(async move {
+                let output = running.output().await?;
+                let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                let formats = ErrorFormat::parse_all(&formats)?;
+                anyhow::Ok((output.status, parse_errors(&text, &formats)))
+            })
+            .await?;
+        if errors.is_empty() && !status.success() {
+            anyhow::bail!("`{command}` failed with {status}");
+        }
+        project.read_with(cx, |project, cx| entries_from_errors(project, errors, cx))
+    })
+}
+
+fn grep_entries(
+    project: Entity<Project>,
+    grep: GrepArgs,
+    current_path: Option<String>,
+    cx: &mut Context<Vim>,
+) -> Task<Result<Vec<QuickfixEntry>>> {
+    let path_style = project.read(cx).path_style(cx);
+    let files = grep
+        .files
+        .iter()
+        .filter_map(|file| {
+            if file == "%" {
+                current_path.clone()
+            } else {
+                Some(file.clone())
+            }
+        })
+        .collect::<Vec<_>>();
+    let query = PathMatcher::new(&files, path_style)
+        .map_err(anyhow::Error::from)
+        .and_then(|files_to_include| {
+            SearchQuery::regex(
+                &grep.pattern,
+                false,
+                grep.case_sensitive,
+                false,
+                !grep.all_matches,
+                files_to_include,
+                PathMatcher::default(),
+                false,
+                None,
+            )
+        });
+    let query = match query {
+        Ok(query) => query,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    let mut results = project.update(cx, |project, cx| project.search(query, cx));
+
+    cx.spawn(async move |_, cx| {
+        let mut entries = Vec::new();
+        while let Some(result) = results.next().await {
+            let SearchResult::Buffer { buffer, ranges } = result else {
+                continue;
+            };
+            buffer.read_with(cx, |buffer, cx| {
+                let Some(path) = buffer.project_path(cx) else {
+                    return;
+                };
+                let snapshot = buffer.snapshot();
+                for range in ranges {
+                    let position = range.start.to_point(&snapshot);
+                    entries.push(QuickfixEntry {
+                        path: path.clone(),
+                        position,
+                        kind: None,
+                        text: line_text(&snapshot, position.row),
+                    });
+                }
+            })?;
+        }
+        entries.sort_by(|a, b| (&a.path, a.position).cmp(&(&b.path, b.position)));
+        Ok(entries)
+    })
+}
+
+fn diagnostic_entries(
+    project: Entity<Project>,
+    paths: Vec<ProjectPath>,
+    cx: &mut Context<Vim>,
+) -> Task<Result<Vec<QuickfixEntry>>> {
+    let buffers = paths
+        .into_iter()
+        .map(|path| {
+            let buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+            (path, buffer)
+        })
+        .collect::<Vec<_>>();
+
+    cx.spawn(async move |_, cx| {
+        let mut entries = Vec::new();
+        for (path, buffer) in buffers {
+            let Some(buffer) = buffer.await.log_err() else {
+                continue;
+            };
+            let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+            for entry in snapshot.diagnostics_in_range::<_, Point>(0..snapshot.len(), false) {
+                if !entry.diagnostic.is_primary {
+                    continue;
+                }
+                let kind = match entry.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => 'e',
+                    DiagnosticSeverity::WARNING => 'w',
+                    DiagnosticSeverity::INFORMATION => 'i',
+                    _ => 'n',
+                };
+                entries.push(QuickfixEntry {
+                    path: path.clone(),
+                    position: entry.range.start,
+                    kind: Some(kind),
+                    text: entry
+                        .diagnostic
+                        .message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                        .into(),
+                });
+            }
+        }
+        entries.sort_by(|a, b| (&a.path, a.position).cmp(&(&b.path, b.position)));
+        Ok(entries)
+    })
+}
+
+fn project_search_entries(workspace: &Workspace, cx: &App) -> Result<Vec<QuickfixEntry>> {
+    let search = workspace
+        .active_item_as::<ProjectSearchView>(cx)
+        .or_else(|| workspace.items_of_type::<ProjectSearchView>(cx).next())
+        .context("No project search is open")?;
+    let search = search.read(cx);
+    let multibuffer = search.results_editor().read(cx).buffer().read(cx);
+    let snapshot = multibuffer.snapshot(cx);
+
+    Ok(search
+        .get_matches(cx)
+        .into_iter()
+        .filter_map(|range| {
+            let (buffer, position, _) =
+                multibuffer.point_to_buffer_point(range.start.to_point(&snapshot), cx)?;
+            let buffer = buffer.read(cx);
+            Some(QuickfixEntry {
+                path: buffer.project_path(cx)?,
+                position,
+                kind: None,
+                text: line_text(&buffer.snapshot(), position.row),
+            })
+        })
+        .collect())
+}
+
+fn entries_from_errors(
+    project: &Project,
+    errors: Vec<ParsedError>,
+    cx: &App,
+) -> Vec<QuickfixEntry> {
+    errors
+        .into_iter()
+        .filter_map(|error| {
+            let path = error.path.strip_prefix("./").unwrap_or(&error.path);
+            Some(QuickfixEntry {
+                path: project.find_project_path(Path::new(path), cx)?,
+                position: Point::new(error.line.saturating_sub(1), error.column.saturating_sub(1)),
+                kind: error.kind,
+                text: error.message.into(),
+            })
+        })
+        .collect()
+}
+
+/// The arguments of `:vimgrep /{pattern}/[g][j] [{file} ...]`.
+#[derive(Debug, PartialEq)]
+struct GrepArgs {
+    pattern: String,
+    case_sensitive: bool,
+    /// Add every match instead of only the first one of each line (the `g` flag).
+    all_matches: bool,
+    /// Jump to the first match, unless the `j` flag was given.
+    jump: bool,
+    files: Vec<String>,
+}
+
+impl GrepArgs {
+    // like `:s` and `:g`, we flip \( and \) to ( and ) (and vice-versa) in the pattern,
+    // and convert \< and \> to word boundaries, so that common vim idioms work.
+    fn parse(args: &str) -> Option<Self> {
+        let mut chars = args.trim_start().chars().peekable();
+        let first = *chars.peek()?;
+        // Without a delimiter, the pattern is the first word of the arguments.
+        let delimiter = (!first.is_alphanumeric() && !matches!(first, '\\' | '"' | '|'))
+            .then(|| chars.next())
+            .flatten();
+
+        let mut pattern = String::new();
+        let mut case_sensitive = true;
+        let mut escaped = false;
+        for c in chars.by_ref() {
+            if escaped {
+                escaped = false;
+                match c {
+                    '(' | ')' => pattern.push(c),
+                    '<' | '>' => pattern.push_str(r"\b"),
+                    'c' => case_sensitive = false,
+                    'C' => case_sensitive = true,
+                    c if Some(c) == delimiter => pattern.push(c),
+                    c => {
+                        pattern.push('\\');
+                        pattern.push(c);
+                    }
+                }
+            } else if c == '\\' {
+                escaped = true;
+            } else if delimiter.map_or(c.is_whitespace(), |delimiter| c == delimiter) {
+                break;
+            } else {
+                if c == '(' || c == ')' {
+                    pattern.push('\\');
+                }
+                pattern.push(c);
+            }
+        }
+        if pattern.is_empty() {
+            return None;
+        }
+
+        let mut all_matches = false;
+        let mut jump = true;
+        if delimiter.is_some() {
+            while let Some(flag) = chars.peek() {
+                match flag {
+                    'g' => all_matches = true,
+                    'j' => jump = false,
+                    _ => break,
+                }
+                chars.next();
+            }
+        }
+
+        let files = chars
+            .collect::<String>()
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect();
+        Some(Self {
+            pattern,
+            case_sensitive,
+            all_matches,
+            jump,
+            files,
+        })
+    }
+}
+
+/// An `errorformat` pattern, compiled into a regex.
+///
+/// Supports `%f`, `%l`, `%c`, `%m`, `%t`, `%n`, `%s`, `%r`, `%p`, the regex items
+/// `%.`, `%#`, `%[...]`, `%*[...]`, `%*\x` and `%%`, and the multi-line prefixes
+/// `%E`, `%W`, `%I`, `%N`, `%A`, `%C`, `%Z` and `%-G`.
+#[derive(Debug)]
+struct ErrorFormat {
+    kind: FormatKind,
+    regex: Regex,
+    fields: Vec<FormatField>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FormatKind {
+    /// A complete, single-line entry.
+    Single,
+    /// Starts a multi-line entry, with the entry type it implies.
+    Start(Option<char>),
+    /// Adds to the current multi-line entry.
+    Continue,
+    /// Adds to and finishes the current multi-line entry.
+    End,
+    /// Lines that are skipped.
+    Ignore,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FormatField {
+    File,
+    Line,
+    Column,
+    Message,
+    Kind,
+}
+
+impl ErrorFormat {
+    fn parse_all(formats: &[String]) -> Result<Vec<Self>> {
+        formats.iter().map(|format| Self::new(format)).collect()
+    }
+
+    fn new(format: &str) -> Result<Self> {
+        let mut chars = format.chars().peekable();
+        let mut kind = FormatKind::Single;
+        if chars.peek() == Some(&'%') {
+            let mut prefix = chars.clone();
+            prefix.next();
+            let exclude = prefix.next_if_eq(&'-').is_some();
+            let prefix_kind = match prefix.next() {
+                Some('E') => Some(FormatKind::Start(Some('e'))),
+                Some('W') => Some(FormatKind::Start(Some('w'))),
+                Some('I') => Some(FormatKind::Start(Some('i'))),
+                Some('N') => Some(FormatKind::Start(Some('n'))),
+                Some('A') => Some(FormatKind::Start(None)),
+                Some('C') => Some(FormatKind::Continue),
+                Some('Z') => Some(FormatKind::End),
+                Some('G') if exclude => Some(FormatKind::Ignore),
+                Some('G') => Some(FormatKind::Single),
+                _ => None,
+            };
+            if let Some(prefix_kind) = prefix_kind {
+                kind = prefix_kind;
+                chars = prefix;
+            }
+        }
+
+        let mut pattern = String::from("^");
+        let mut fields = Vec::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => {}
+                '\\' => {
+                    if let Some(c) = chars.next() {
+                        pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                    }
+                    continue;
+                }
+                c => {
+                    pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+                    continue;
+                }
+            }
+            match chars.next() {
+                Some('f') => {
+                    fields.push(FormatField::File);
+                    pattern.push_str("(.+?)");
+                }
+                Some('l') => {
+                    fields.push(FormatField::Line);
+                    pattern.push_str(r"(\d+)");
+                }
+                Some('c') => {
+                    fields.push(FormatField::Column);
+                    pattern.push_str(r"(\d+)");
+                }
+                Some('m') => {
+                    fields.push(FormatField::Message);
+                    pattern.push_str("(.*)");
+                }
+                Some('t') => {
+                    fields.push(FormatField::Kind);
+                    pattern.push_str("(.)");
+                }
+                Some('n') => pattern.push_str(r"\d+"),
+                Some('s' | 'r') => pattern.push_str(".*"),
+                Some('p') => pattern.push_str(r"[- \t.]*"),
+                Some('.') => pattern.push('.'),
+                Some('#') => pattern.push('*'),
+                Some('[') => push_class(&mut pattern, &mut chars),
+                Some('*') => {
+                    match chars.next() {
+                        Some('[') => push_class(&mut pattern, &mut chars),
+                        Some('\\') => {
+                            pattern.push('\\');
+                            pattern.extend(chars.next());
+                        }
+                        Some(c) => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+                        None => anyhow::bail!("incomplete %* in errorformat {format:?}"),
+                    }
+                    pattern.push('*');
+                }
+                Some('%') => pattern.push('%'),
+                Some(c) => anyhow::bail!("unsupported item %{c} in errorformat {format:?}"),
+                None => anyhow::bail!("trailing % in errorformat {format:?}"),
+            }
+        }
+        pattern.push('$');
+
+        Ok(Self {
+            kind,
+            regex: Regex::new(&pattern)?,
+            fields,
+        })
+    }
+}
+
+fn push_class(pattern: &mut String, chars: &mut std::iter::Peekable<std::str::Chars>) {
+    pattern.push('[');
+    for c in chars.by_ref() {
+        pattern.push(c);
+        if c == ']' {
+            break;
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ParsedError {
+    path: String,
+    line: u32,
+    column: u32,
+    kind: Option<char>,
+    message: String,
+}
+
+impl ParsedError {
+    fn update(&mut self, format: &ErrorFormat, captures: &regex::Captures) {
+        for (field, capture) in format.fields.iter().zip(captures.iter().skip(1)) {
+            let Some(capture) = capture else {
+                continue;
+            };
+            let text = capture.as_str();
+            match field {
+                FormatField::File if self.path.is_empty() => self.path = text.trim().to_string(),
+                FormatField::Line if self.line == 0 => self.line = text.parse().unwrap_or(0),
+                FormatField::Column if self.column == 0 => self.column = text.parse().unwrap_or(0),
+                FormatField::Kind => {
+                    self.kind = text.chars().next().map(|c| c.to_ascii_lowercase())
+                }
+                FormatField::Message => {
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        if !self.message.is_empty() {
+                            self.message.push(' ');
+                        }
+                        self.message.push_str(text);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn parse_errors(output: &str, formats: &[ErrorFormat]) -> Vec<ParsedError> {
+    let mut errors = Vec::new();
+    let mut pending: Option<ParsedError> = None;
+    for line in output.lines() {
+        let Some((format, captures)) = formats
+            .iter()
+            .find_map(|format| Some((format, format.regex.captures(line)?)))
+        else {
+            errors.extend(pending.take());
+            continue;
+        };
+        match format.kind {
+            FormatKind::Ignore => {}
+            FormatKind::Single => {
+                errors.extend(pending.take());
+                let mut error = ParsedError::default();
+                error.update(format, &captures);
+                errors.push(error);
+            }
+            FormatKind::Start(kind) => {
+                errors.extend(pending.take());
+                let mut error = ParsedError {
+                    kind,
+                    ..Default::default()
+                };
+                error.update(format, &captures);
+                pending = Some(error);
+            }
+            FormatKind::Continue | FormatKind::End => {
+                if let Some(error) = pending.as_mut() {
+                    error.update(format, &captures);
+                }
+                if format.kind == FormatKind::End {
+                    errors.extend(pending.take());
+                }
+            }
+        }
+    }
+    errors.extend(pending);
+    errors.retain(|error| !error.path.is_empty());
+    errors
+}
+
+pub struct QuickfixViewDelegate {
+    owner: EntityId,
+    pane: Option<WeakEntity<Pane>>,
+    workspace: WeakEntity<Workspace>,
+    title: SharedString,
+    entries: Vec<QuickfixEntry>,
+    locations: Vec<SharedString>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for QuickfixViewDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        self.title.to_string().into()
+    }
+
+    fn update_matches(
+        &mut self,
+        _: String,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.entries.get(self.selected_index).cloned() else {
+            return;
+        };
+        if let Some(list) = Vim::globals(cx).quickfix.get_mut(&self.owner)
+            && list.entries.get(self.selected_index) == Some(&entry)
+        {
+            list.current = self.selected_index;
+        }
+        let pane = self.pane.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_quickfix_entry(workspace, &entry, pane, window, cx).detach_and_log_err(cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, _: &mut Context<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.entries.get(ix)?;
+        let location = self.locations.get(ix)?;
+        let color = match entry.kind {
+            Some('e') => Color::Error,
+            Some('w') => Color::Warning,
+            Some('i' | 'n') => Color::Info,
+            _ => Color::Muted,
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(location.clone()).color(color).buffer_font(cx))
+                        .child(Label::new(entry.text.clone()).single_line().truncate()),
+                ),
+        )
+    }
+}
+
+pub struct QuickfixView {}
+
+impl QuickfixView {
+    fn toggle(
+        workspace: &mut Workspace,
+        owner: EntityId,
+        pane: Option<WeakEntity<Pane>>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(list) = Vim::globals(cx).quickfix.get(&owner).cloned() else {
+            Err::<(), _>(anyhow!("No quickfix list")).notify_err(workspace, cx);
+            return;
+        };
+        let path_style = workspace.project().read(cx).path_style(cx);
+        let locations = list
+            .entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{}:{}:{}",
+                    entry.path.path.display(path_style),
+                    entry.position.row + 1,
+                    entry.position.column + 1
+                )
+                .into()
+            })
+            .collect();
+        let delegate = QuickfixViewDelegate {
+            owner,
+            pane,
+            workspace: cx.weak_entity(),
+            title: list.title,
+            entries: list.entries,
+            locations,
+            selected_index: list.current,
+        };
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            Picker::nonsearchable_uniform_list(delegate, window, cx)
+                .width(rems(48.))
+                .modal(true)
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use editor::ToPoint as _;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use util::path;
+    use workspace::Workspace;
+
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn formats(formats: &[&str]) -> Vec<ErrorFormat> {
+        ErrorFormat::parse_all(&formats.iter().map(ToString::to_string).collect::<Vec<_>>())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_single_line_errors() {
+        let formats = formats(&["%f:%l:%c: %t%*[^:]: %m", "%f:%l: %m"]);
+        let output = indoc! {"
+            cc -c main.c
+            main.c:3:5: error: expected ';' before 'return'
+            util.c:10: undefined reference to `foo'
+            make: *** [main.o] Error 1
+        "};
+        assert_eq!(
+            parse_errors(output, &formats),
+            vec![
+                ParsedError {
+                    path: "main.c".into(),
+                    line: 3,
+                    column: 5,
+                    kind: Some('e'),
+                    message: "expected ';' before 'return'".into(),
+                },
+                ParsedError {
+                    path: "util.c".into(),
+                    line: 10,
+                    column: 0,
+                    kind: None,
+                    message: "undefined reference to `foo'".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_multi_line_errors() {
+        let formats = formats(&[
+            "%Eerror%*[^:]: %m",
+            "%Wwarning%*[^:]: %m",
+            "%C%*[ ]--> %f:%l:%c",
+            "%-G%.%#Compiling%.%#",
+        ]);
+        let output = indoc! {"
+               Compiling demo v0.1.0
+            warning: unused variable: `x`
+              --> src/main.rs:2:9
+               |
+            error[E0425]: cannot find value `y` in this scope
+              --> src/lib.rs:7:13
+               |
+            error: could not compile `demo` due to 1 previous error
+        "};
+        assert_eq!(
+            parse_errors(output, &formats),
+            vec![
+                ParsedError {
+                    path: "src/main.rs".into(),
+                    line: 2,
+                    column: 9,
+                    kind: Some('w'),
+                    message: "unused variable: `x`".into(),
+                },
+                ParsedError {
+                    path: "src/lib.rs".into(),
+                    line: 7,
+                    column: 13,
+                    kind: Some('e'),
+                    message: "cannot find value `y` in this scope".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_grep_args() {
+        assert_eq!(
+            GrepArgs::parse(r"/\<foo\(bar\)/gj **/*.rs src/%"),
+            Some(GrepArgs {
+                pattern: r"\bfoo(bar)".into(),
+                case_sensitive: true,
+                all_matches: true,
+                jump: false,
+                files: vec!["**/*.rs".into(), "src/%".into()],
+            })
+        );
+        assert_eq!(
+            GrepArgs::parse(r"todo\c %"),
+            Some(GrepArgs {
+                pattern: "todo".into(),
+                case_sensitive: false,
+                all_matches: false,
+                jump: true,
+                files: vec!["%".into()],
+            })
+        );
+        assert_eq!(GrepArgs::parse("//"), None);
+    }
+
+    fn assert_active_position(cx: &mut VimTestContext, expected_path: &str, row: u32) {
+        cx.workspace(|workspace: &mut Workspace, _, cx| {
+            let editor = workspace.active_item_as::<Editor>(cx).unwrap();
+            let editor = editor.read(cx);
+            let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+            let path = buffer.read(cx).file().unwrap().path().clone();
+            assert_eq!(path.as_unix_str(), expected_path);
+            let head = editor
+                .selections
+                .newest_anchor()
+                .head()
+                .to_point(&editor.buffer().read(cx).snapshot(cx));
+            assert_eq!(head.row, row);
+        });
+    }
+
+    fn buffer_text(cx: &mut VimTestContext, path: &str) -> Option<String> {
+        cx.workspace(|workspace: &mut Workspace, _, cx| {
+            workspace
+                .items_of_type::<Editor>(cx)
+                .find(|editor| {
+                    let buffer = editor.read(cx).buffer().read(cx).as_singleton();
+                    buffer.is_some_and(|buffer| {
+                        buffer
+                            .read(cx)
+                            .file()
+                            .is_some_and(|file| file.path().as_unix_str() == path)
+                    })
+                })
+                .map(|editor| editor.read(cx).text(cx))
+        })
+    }
+
+    #[gpui::test]
+    async fn test_quickfix_from_buffer(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"one\ntwo\nthree\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"four\nfive\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.set_state(
+            indoc! {"
+                ˇdir/a.rs:2:1: error: first
+                not an error
+                dir/b.rs:1:3: warning: second
+                dir/missing.rs:1:1: error: dropped
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": c b enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "dir/a.rs", 1);
+
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "dir/b.rs", 0);
+
+        // There is no entry past the last one.
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "dir/b.rs", 0);
+
+        cx.simulate_keystrokes(": c c space 1 enter");
+        cx.run_until_parked();
+        assert_active_position(&mut cx, "dir/a.rs", 1);
+    }
+
+    #[gpui::test]
+    async fn test_quickfix_do(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"foo\nbar\nfoo\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"bar\nfoo\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space / f o o / j space * * / * . r s enter");
+        cx.run_until_parked();
+        let entries = cx.update(|_, cx| {
+            Vim::globals(cx)
+                .quickfix
+                .values()
+                .next()
+                .map(|list| list.entries.len())
+        });
+        assert_eq!(entries, Some(3));
+
+        cx.simulate_keystrokes(": c d o space s / f o o / b a z / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_text(&mut cx, "dir/a.rs").as_deref(),
+            Some("baz\nbar\nbaz\n")
+        );
+        assert_eq!(
+            buffer_text(&mut cx, "dir/b.rs").as_deref(),
+            Some("bar\nbaz\n")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_quickfix_do_runs_once_per_entry(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"foo foo\nbar\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space / f o o / g j space * * / * . r s enter");
+        cx.run_until_parked();
+        let entries = cx.update(|_, cx| {
+            Vim::globals(cx)
+                .quickfix
+                .values()
+                .next()
+                .map(|list| list.entries.len())
+        });
+        assert_eq!(entries, Some(2));
+
+        // Both entries are on the same line, so running the substitution once per entry
+        // replaces both matches.
+        cx.simulate_keystrokes(": c d o space s / f o o / b a z / enter");
+        cx.run_until_parked();
+        assert_eq!(
+            buffer_text(&mut cx, "dir/a.rs").as_deref(),
+            Some("baz baz\nbar\n")
+        );
+        let current = cx.update(|_, cx| {
+            Vim::globals(cx)
+                .quickfix
+                .values()
+                .next()
+                .map(|list| list.current)
+        });
+        assert_eq!(current, Some(1));
+    }
+}
@@ -1,8 +1,12 @@
-use crate::command::command_interceptor;
+use crate::command::{ExCommand, palette_interceptor};
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
+use crate::quickfix::QuickfixList;
 use crate::surrounds::SurroundsType;
-use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
+use crate::{
+    PersistenceSettings, ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon,
+    VimSettings,
+};
 use crate::{motion::Motion, object::Object};
 use anyhow::Result;
 use collections::HashMap;
@@ -24,6 +28,7 @@ use project::{Project, ProjectItem, ProjectPath};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::path::Path;
 use std::{fmt::Display, ops::Range, sync::Arc};
@@ -239,6 +244,25 @@ pub struct VimGlobals {
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+
+    /// Quickfix lists, keyed by workspace, and location lists, keyed by pane.
+    pub quickfix: HashMap<EntityId, QuickfixList>,
+
+    /// Commands run from the command palette, oldest first.
+    pub command_history: Vec<String>,
+    /// Queries submitted with `/` and `?`, oldest first.
+    pub search_history: Vec<String>,
+    persistence: PersistenceState,
+}
+
+/// Registers and history are restored from the database once per session, and
+/// are only written back after that has finished.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum PersistenceState {
+    #[default]
+    Unloaded,
+    Loading,
+    Loaded,
 }
 
 pub struct MarksState {
@@ -705,6 +729,21 @@ impl VimGlobals {
         })
         .detach();
 
+        cx.observe_new(|workspace: &mut Workspace, window, _| {
+            ExCommand::register(workspace, window);
+        })
+        .detach();
+
+        cx.on_app_quit(|cx| {
+            let state = cx.global::<VimGlobals>().persisted_state(cx);
+            async move {
+                if let Some(state) = state {
+                    DB.save_persisted_state(state).await.log_err();
+                }
+            }
+        })
+        .detach();
+
         let mut was_enabled = None;
 
         cx.observe_global::<SettingsStore>(move |cx| {
@@ -719,7 +758,7 @@ impl VimGlobals {
                     filter.show_namespace(Vim::NAMESPACE);
                 });
                 CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
-                    interceptor.set(Box::new(command_interceptor));
+                    interceptor.set(Box::new(palette_interceptor));
                 });
                 for window in cx.windows() {
                     if let Some(workspace) = window.downcast::<Workspace>() {
@@ -753,14 +792,92 @@ impl VimGlobals {
     fn register_workspace(&mut self, workspace: &Workspace, cx: &mut Context<Workspace>) {
         let entity_id = cx.entity_id();
         self.marks.insert(entity_id, MarksState::new(workspace, cx));
+        self.restore_persisted_state(cx);
         cx.observe_release(&cx.entity(), move |_, _, cx| {
-            Vim::update_globals(cx, |globals, _| {
+            Vim::update_globals(cx, |globals, cx| {
                 globals.marks.remove(&entity_id);
+                if let Some(state) = globals.persisted_state(cx) {
+                    cx.background_spawn(async move { DB.save_persisted_state(state).await })
+                        .detach_and_log_err(cx);
+                }
             })
         })
         .detach();
     }
 
+    fn restore_persisted_state(&mut self, cx: &mut App) {
+        if self.persistence != PersistenceState::Unloaded
+            || !Vim::enabled(cx)
+            || !VimSettings::get_global(cx).persistence.enabled
+        {
+            return;
+        }
+        self.persistence = PersistenceState::Loading;
+        cx.spawn(async move |cx| {
+            let state = cx
+                .background_spawn(async move { DB.get_persisted_state() })
+                .await?;
+            cx.update(|cx| {
+                Vim::update_globals(cx, |globals, cx| {
+                    // Vim may have been disabled while loading.
+                    if globals.persistence == PersistenceState::Loading {
+                        globals.persistence = PersistenceState::Loaded;
+                        globals.restore(state, cx);
+                    }
+                })
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Merges registers and history from a previous session into this one. Anything
+    /// recorded in this session takes precedence.
+    pub(crate) fn restore(&mut self, state: SerializedVimState, cx: &mut App) {
+        for (name, register) in state.registers {
+            self.registers.entry(name).or_insert(register);
+        }
+        for (name, actions) in state.recordings {
+            if self.recordings.contains_key(&name) {
+                continue;
+            }
+            if let Some(actions) = actions
+                .into_iter()
+                .map(|action| action.deserialize(cx))
+                .collect::<Option<Vec<_>>>()
+            {
+                self.recordings.insert(name, actions);
+            }
+        }
+
+        let command_history = std::mem::replace(&mut self.command_history, state.command_history);
+        for command in command_history {
+            self.push_command_history(command, cx);
+        }
+        let search_history = std::mem::replace(&mut self.search_history, state.search_history);
+        for query in search_history {
+            self.push_search_history(query, cx);
+        }
+        truncate_history(&mut self.command_history, cx);
+        truncate_history(&mut self.search_history, cx);
+    }
+
+    /// The state to write to the database, if it's been restored and persistence is enabled.
+    pub(crate) fn persisted_state(&self, cx: &App) -> Option<SerializedVimState> {
+        let settings = &VimSettings::get_global(cx).persistence;
+        if self.persistence != PersistenceState::Loaded || !settings.enabled {
+            return None;
+        }
+        Some(SerializedVimState::new(self, settings, cx))
+    }
+
+    pub(crate) fn push_command_history(&mut self, command: String, cx: &App) {
+        push_history(&mut self.command_history, command, cx);
+    }
+
+    pub(crate) fn push_search_history(&mut self, query: String, cx: &App) {
+        push_history(&mut self.search_history, query, cx);
+    }
+
     pub(crate) fn write_registers(
         &mut self,
         content: Register,
@@ -854,7 +971,8 @@ impl VimGlobals {
         };
         let lower = register.to_lowercase().next().unwrap_or(register);
         match lower {
-            '_' | ':' | '.' | '#' | '=' => None,
+            '_' | '.' | '#' | '=' => None,
+            ':' => self.command_history.last().cloned().map(Register::from),
             '+' => cx.read_from_clipboard().map(|item| item.into()),
             '*' => {
                 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
@@ -947,6 +1065,20 @@ impl VimGlobals {
     }
 }
 
+fn push_history(history: &mut Vec<String>, entry: String, cx: &App) {
+    if entry.is_empty() {
+        return;
+    }
+    history.retain(|existing| existing != &entry);
+    history.push(entry);
+    truncate_history(history, cx);
+}
+
+fn truncate_history(history: &mut Vec<String>, cx: &App) {
+    let history_size = VimSettings::get_global(cx).persistence.history_size;
+    history.drain(..history.len().saturating_sub(history_size));
+}
+
 impl Vim {
     pub fn globals(cx: &mut App) -> &mut VimGlobals {
         cx.global_mut::<VimGlobals>()
@@ -1716,6 +1848,22 @@ impl Domain for VimDb {
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_registers(
+                register_name TEXT PRIMARY KEY,
+                value TEXT,
+                clipboard_selections TEXT
+            );
+            CREATE TABLE vim_recordings(
+                register_name TEXT PRIMARY KEY,
+                actions TEXT
+            );
+            CREATE TABLE vim_history(
+                kind TEXT,
+                ix INTEGER,
+                value TEXT
+            );
+        ),
     ];
 }
 
@@ -1727,6 +1875,112 @@ struct SerializedMark {
     points: Vec<Point>,
 }
 
+/// Registers, macros and history saved between sessions.
+#[derive(Debug, Default)]
+pub(crate) struct SerializedVimState {
+    registers: Vec<(char, Register)>,
+    recordings: Vec<(char, Vec<SerializedReplayableAction>)>,
+    command_history: Vec<String>,
+    search_history: Vec<String>,
+}
+
+impl SerializedVimState {
+    pub(crate) fn new(globals: &VimGlobals, settings: &PersistenceSettings, cx: &App) -> Self {
+        let registers = globals
+            .registers
+            .iter()
+            .filter(|(name, register)| {
+                !settings.excluded_registers.contains(*name)
+                    && register.text.len() <= settings.max_register_bytes
+            })
+            .map(|(name, register)| (*name, register.clone()))
+            .collect();
+        let recordings = globals
+            .recordings
+            .iter()
+            .filter(|(name, _)| !settings.excluded_registers.contains(*name))
+            .filter_map(|(name, actions)| {
+                let actions = actions
+                    .iter()
+                    .map(|action| SerializedReplayableAction::new(action, cx))
+                    .collect::<Option<Vec<_>>>();
+                if actions.is_none() {
+                    log::debug!("Not saving the macro in register {name}");
+                }
+                Some((*name, actions?))
+            })
+            .collect();
+        Self {
+            registers,
+            recordings,
+            command_history: globals.command_history.clone(),
+            search_history: globals.search_history.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SerializedReplayableAction {
+    Action {
+        name: String,
+        input: Option<serde_json::Value>,
+    },
+    Insertion {
+        text: String,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl SerializedReplayableAction {
+    /// Actions are stored by name. Those that can't be built from their name alone also
+    /// store the input of a key binding that dispatches them, which is how they were
+    /// recorded.
+    fn new(action: &ReplayableAction, cx: &App) -> Option<Self> {
+        match action {
+            ReplayableAction::Action(action) => {
+                let name = action.name().to_string();
+                if cx
+                    .build_action(&name, None)
+                    .is_ok_and(|built| built.partial_eq(action.as_ref()))
+                {
+                    return Some(Self::Action { name, input: None });
+                }
+                let keymap = cx.key_bindings();
+                let input = RefCell::borrow(&keymap)
+                    .bindings_for_action(action.as_ref())
+                    .find_map(|binding| binding.action_input())?;
+                Some(Self::Action {
+                    name,
+                    input: Some(serde_json::from_str(&input).ok()?),
+                })
+            }
+            ReplayableAction::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Some(Self::Insertion {
+                text: text.to_string(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            }),
+        }
+    }
+
+    fn deserialize(self, cx: &App) -> Option<ReplayableAction> {
+        match self {
+            Self::Action { name, input } => Some(ReplayableAction::Action(
+                cx.build_action(&name, input).log_err()?,
+            )),
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Some(ReplayableAction::Insertion {
+                text: text.into(),
+                utf16_range_to_replace,
+            }),
+        }
+    }
+}
+
 impl VimDb {
     pub(crate) async fn set_marks(
         &self,
@@ -1834,4 +2088,108 @@ impl VimDb {
         })
         .await
     }
+
+    pub(crate) async fn save_persisted_state(&self, state: SerializedVimState) -> Result<()> {
+        log::debug!(
+            "Saving {} vim registers and {} macros",
+            state.registers.len(),
+            state.recordings.len()
+        );
+        self.write(move |conn| {
+            conn.with_savepoint("save_vim_state", || {
+                conn.exec(sql!(
+                    DELETE FROM vim_registers;
+                    DELETE FROM vim_recordings;
+                    DELETE FROM vim_history;
+                ))?()?;
+
+                let mut insert_register = conn.exec_bound(sql!(
+                    INSERT INTO vim_registers
+                        (register_name, value, clipboard_selections)
+                    VALUES
+                        (?, ?, ?)
+                ))?;
+                for (name, register) in state.registers {
+                    let clipboard_selections = register
+                        .clipboard_selections
+                        .map(|selections| serde_json::to_string(&selections))
+                        .transpose()?;
+                    insert_register((
+                        name.to_string(),
+                        register.text.to_string(),
+                        clipboard_selections,
+                    ))?;
+                }
+
+                let mut insert_recording = conn.exec_bound(sql!(
+                    INSERT INTO vim_recordings
+                        (register_name, actions)
+                    VALUES
+                        (?, ?)
+                ))?;
+                for (name, actions) in state.recordings {
+                    insert_recording((name.to_string(), serde_json::to_string(&actions)?))?;
+                }
+
+                let mut insert_history = conn.exec_bound(sql!(
+                    INSERT INTO vim_history
+                        (kind, ix, value)
+                    VALUES
+                        (?, ?, ?)
+                ))?;
+                for (ix, command) in state.command_history.into_iter().enumerate() {
+                    insert_history(("command", ix, command))?;
+                }
+                for (ix, query) in state.search_history.into_iter().enumerate() {
+                    insert_history(("search", ix, query))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    pub(crate) fn get_persisted_state(&self) -> Result<SerializedVimState> {
+        let registers: Vec<(String, String, Option<String>)> = self.select(sql!(
+            SELECT register_name, value, clipboard_selections FROM vim_registers
+        ))?()?;
+        let recordings: Vec<(String, String)> = self.select(sql!(
+            SELECT register_name, actions FROM vim_recordings
+        ))?()?;
+        let history: Vec<(String, String)> = self.select(sql!(
+            SELECT kind, value FROM vim_history ORDER BY ix
+        ))?()?;
+
+        let mut state = SerializedVimState::default();
+        for (name, text, clipboard_selections) in registers {
+            let Some(name) = name.chars().next() else {
+                continue;
+            };
+            let clipboard_selections = clipboard_selections
+                .and_then(|selections| serde_json::from_str(&selections).log_err());
+            state.registers.push((
+                name,
+                Register {
+                    text: text.into(),
+                    clipboard_selections,
+                },
+            ));
+        }
+        for (name, actions) in recordings {
+            let Some(name) = name.chars().next() else {
+                continue;
+            };
+            if let Some(actions) = serde_json::from_str(&actions).log_err() {
+                state.recordings.push((name, actions));
+            }
+        }
+        for (kind, value) in history {
+            match kind.as_str() {
+                "command" => state.command_history.push(value),
+                "search" => state.search_history.push(value),
+                _ => {}
+            }
+        }
+        Ok(state)
+    }
 }
@@ -2316,3 +2316,57 @@ async fn test_clipping_on_mode_change(cx: &mut gpui::TestAppContext) {
         Mode::Normal,
     );
 }
+
+#[gpui::test]
+async fn test_persisted_registers_and_history(cx: &mut gpui::TestAppContext) {
+    use crate::{
+        Vim, VimSettings,
+        state::{SerializedVimState, VimDb, VimGlobals},
+    };
+    use settings::Settings as _;
+
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("ˇhello world", Mode::Normal);
+    cx.simulate_keystrokes("\" a y w");
+    cx.simulate_keystrokes("q b f o r x q");
+    cx.assert_state("hellˇx world", Mode::Normal);
+    cx.simulate_keystrokes("/ w o r enter");
+    cx.assert_state("hellx ˇworld", Mode::Normal);
+    cx.simulate_keystrokes(": s / w / W / enter");
+    cx.update_editor(|editor, _, cx| assert_eq!(editor.text(cx), "hellx World"));
+
+    let state = cx.update(|_, cx| {
+        let settings = VimSettings::get_global(cx).persistence.clone();
+        SerializedVimState::new(cx.global::<VimGlobals>(), &settings, cx)
+    });
+    let db = VimDb::open_test_db("test_persisted_registers_and_history").await;
+    db.save_persisted_state(state).await.unwrap();
+    let state = db.get_persisted_state().unwrap();
+
+    cx.update(|_, cx| {
+        Vim::update_globals(cx, |globals, cx| {
+            globals.registers.clear();
+            globals.recordings.clear();
+            globals.command_history.clear();
+            globals.search_history.clear();
+            globals.restore(state, cx);
+        });
+        let globals = cx.global::<VimGlobals>();
+        assert_eq!(
+            globals
+                .registers
+                .get(&'a')
+                .map(|register| register.text.to_string()),
+            Some("hello ".to_string())
+        );
+        assert_eq!(globals.command_history, ["s/w/W/"]);
+        assert_eq!(globals.search_history, ["wor"]);
+    });
+
+    cx.set_state("ˇfoo bar", Mode::Normal);
+    cx.simulate_keystrokes("@ b");
+    cx.assert_state("fˇxo bar", Mode::Normal);
+    cx.simulate_keystrokes("\" a p");
+    cx.assert_state("fxhelloˇ o bar", Mode::Normal);
+}
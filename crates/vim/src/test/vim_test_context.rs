@@ -30,6 +30,17 @@ impl VimTestContext {
             editor::init_settings(cx);
             project::Project::init_settings(cx);
             theme::init(theme::LoadThemes::JustBase, cx);
+            // The test database is shared between tests, so only tests of
+            // persistence itself should read or write it.
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |s| {
+                    s.vim
+                        .get_or_insert_default()
+                        .persistence
+                        .get_or_insert_default()
+                        .enabled = Some(false);
+                });
+            });
         });
     }
 
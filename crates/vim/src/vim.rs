@@ -13,6 +13,7 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod quickfix;
 mod replace;
 mod rewrap;
 mod state;
@@ -865,6 +866,7 @@ impl Vim {
             helix::register(editor, cx);
             motion::register(editor, cx);
             command::register(editor, cx);
+            quickfix::register(editor, cx);
             replace::register(editor, cx);
             indent::register(editor, cx);
             rewrap::register(editor, cx);
@@ -1873,6 +1875,9 @@ struct VimSettings {
     pub custom_digraphs: HashMap<String, Arc<str>>,
     pub highlight_on_yank_duration: u64,
     pub cursor_shape: CursorShapeSettings,
+    pub make_program: String,
+    pub error_format: Vec<String>,
+    pub persistence: PersistenceSettings,
 }
 
 /// The settings for cursor shape.
@@ -1907,6 +1912,38 @@ impl From<settings::CursorShapeSettings> for CursorShapeSettings {
     }
 }
 
+/// The settings for persisting registers and history between sessions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PersistenceSettings {
+    /// Whether to save registers and history when Zed quits.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The number of entries to keep in the command and search histories.
+    ///
+    /// Default: 100
+    pub history_size: usize,
+    /// Registers larger than this many bytes are not saved.
+    ///
+    /// Default: 10000
+    pub max_register_bytes: usize,
+    /// Registers that are never saved.
+    ///
+    /// Default: []
+    pub excluded_registers: Vec<char>,
+}
+
+impl From<settings::VimPersistenceSettingsContent> for PersistenceSettings {
+    fn from(settings: settings::VimPersistenceSettingsContent) -> Self {
+        Self {
+            enabled: settings.enabled.unwrap(),
+            history_size: settings.history_size.unwrap(),
+            max_register_bytes: settings.max_register_bytes.unwrap(),
+            excluded_registers: settings.excluded_registers.unwrap(),
+        }
+    }
+}
+
 impl From<settings::ModeContent> for Mode {
     fn from(mode: ModeContent) -> Self {
         match mode {
@@ -1927,6 +1964,9 @@ impl Settings for VimSettings {
             custom_digraphs: vim.custom_digraphs.unwrap(),
             highlight_on_yank_duration: vim.highlight_on_yank_duration.unwrap(),
             cursor_shape: vim.cursor_shape.unwrap().into(),
+            make_program: vim.make_program.unwrap(),
+            error_format: vim.error_format.unwrap(),
+            persistence: vim.persistence.unwrap().into(),
         }
     }
 }
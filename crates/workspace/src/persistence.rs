@@ -20,7 +20,9 @@ use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use project::WorktreeId;
-use remote::{RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions};
+use remote::{
+    DockerConnectionOptions, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
+};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
@@ -702,6 +704,9 @@ impl Domain for WorkspaceDb {
         sql!(
             DROP TABLE ssh_connections;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN container TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1115,6 +1120,7 @@ impl WorkspaceDb {
         let mut host = None;
         let mut port = None;
         let mut distro = None;
+        let mut container = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1127,8 +1133,13 @@ impl WorkspaceDb {
                 distro = Some(options.distro_name);
                 user = options.user;
             }
+            RemoteConnectionOptions::Docker(options) => {
+                kind = RemoteConnectionKind::Container(options.runtime);
+                container = Some(options.container);
+                user = options.user;
+            }
         }
-        Self::get_or_create_remote_connection_query(this, kind, host, port, user, distro)
+        Self::get_or_create_remote_connection_query(this, kind, host, port, user, distro, container)
     }
 
     fn get_or_create_remote_connection_query(
@@ -1138,6 +1149,7 @@ impl WorkspaceDb {
         port: Option<u16>,
         user: Option<String>,
         distro: Option<String>,
+        container: Option<String>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1147,7 +1159,8 @@ impl WorkspaceDb {
                 host IS ? AND
                 port IS ? AND
                 user IS ? AND
-                distro IS ?
+                distro IS ? AND
+                container IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1155,6 +1168,7 @@ impl WorkspaceDb {
             port,
             user.clone(),
             distro.clone(),
+            container.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1164,10 +1178,11 @@ impl WorkspaceDb {
                     host,
                     port,
                     user,
-                    distro
-                ) VALUES (?1, ?2, ?3, ?4, ?5)
+                    distro,
+                    container
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
                 RETURNING id
-            ))?((kind.serialize(), host, port, user, distro))?
+            ))?((kind.serialize(), host, port, user, distro, container))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
         }
@@ -1250,15 +1265,15 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro
+                id, kind, host, port, user, distro, container
             FROM
                 remote_connections
         ))?()?
         .into_iter()
-        .filter_map(|(id, kind, host, port, user, distro)| {
+        .filter_map(|(id, kind, host, port, user, distro, container)| {
             Some((
                 RemoteConnectionId(id),
-                Self::remote_connection_from_row(kind, host, port, user, distro)?,
+                Self::remote_connection_from_row(kind, host, port, user, distro, container)?,
             ))
         })
         .collect())
@@ -1268,13 +1283,13 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro) = self.select_row_bound(sql!(
-            SELECT kind, host, port, user, distro
+        let (kind, host, port, user, distro, container) = self.select_row_bound(sql!(
+            SELECT kind, host, port, user, distro, container
             FROM remote_connections
             WHERE id = ?
         ))?(id.0)?
         .context("no such remote connection")?;
-        Self::remote_connection_from_row(kind, host, port, user, distro)
+        Self::remote_connection_from_row(kind, host, port, user, distro, container)
             .context("invalid remote_connection row")
     }
 
@@ -1284,6 +1299,7 @@ impl WorkspaceDb {
         port: Option<u16>,
         user: Option<String>,
         distro: Option<String>,
+        container: Option<String>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1296,6 +1312,13 @@ impl WorkspaceDb {
                 username: user,
                 ..Default::default()
             })),
+            RemoteConnectionKind::Container(runtime) => {
+                Some(RemoteConnectionOptions::Docker(DockerConnectionOptions {
+                    container: container?,
+                    runtime,
+                    user,
+                }))
+            }
         }
     }
 
@@ -2914,6 +2937,40 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_get_container_connections() {
+        let db = WorkspaceDb::open_test_db("test_get_container_connections").await;
+
+        let docker = RemoteConnectionOptions::Docker(DockerConnectionOptions {
+            container: "dev".to_string(),
+            runtime: remote::ContainerRuntime::Docker,
+            user: Some("vscode".to_string()),
+        });
+        let podman = RemoteConnectionOptions::Docker(DockerConnectionOptions {
+            container: "dev".to_string(),
+            runtime: remote::ContainerRuntime::Podman,
+            user: None,
+        });
+        let docker_id = db
+            .get_or_create_remote_connection(docker.clone())
+            .await
+            .unwrap();
+        let podman_id = db
+            .get_or_create_remote_connection(podman.clone())
+            .await
+            .unwrap();
+        assert_ne!(docker_id, podman_id);
+        assert_eq!(
+            db.get_or_create_remote_connection(docker.clone())
+                .await
+                .unwrap(),
+            docker_id
+        );
+
+        assert_eq!(db.remote_connection(docker_id).unwrap(), docker);
+        assert_eq!(db.remote_connection(podman_id).unwrap(), podman);
+    }
+
     #[gpui::test]
     async fn test_simple_split() {
         zlog::init_test();
@@ -1,11 +1,18 @@
 use std::process::ExitStatus;
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use futures::{
+    FutureExt as _,
+    future::{self, Shared},
+};
+use gpui::{AppContext, Context, Entity, SharedString, Task};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskSourceKind, TaskWithDependencies, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TaskTemplate,
+};
 use ui::Window;
 
 use crate::{Toast, Workspace, notifications::NotificationId};
@@ -55,6 +62,8 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let task_to_resolve_dependencies = (!resolved_task.original_task().depends_on.is_empty())
+            .then(|| (task_source_kind.clone(), resolved_task.clone()));
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -71,7 +80,39 @@ impl Workspace {
             });
         }
 
-        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+        let Some(terminal_provider) = self.terminal_provider.as_ref() else {
+            log::error!(
+                "Task {:?} was not run: no terminal to spawn it in",
+                spawn_in_terminal.label
+            );
+            return;
+        };
+
+        if let Some((task_source_kind, resolved_task)) = task_to_resolve_dependencies {
+            let task_with_dependencies = self
+                .project
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .context("no task inventory to resolve the dependencies from")
+                .and_then(|task_inventory| {
+                    task_inventory
+                        .read(cx)
+                        .resolve_dependencies(task_source_kind, resolved_task)
+                });
+            match task_with_dependencies {
+                Ok(task_with_dependencies) => {
+                    let task = run_with_dependencies(task_with_dependencies, window, cx);
+                    self.scheduled_tasks.push(task);
+                }
+                Err(e) => {
+                    log::error!("Failed to resolve task dependencies: {e:#}");
+                    let id = NotificationId::unique::<ResolvedTask>();
+                    self.show_toast(Toast::new(id, format!("{e}")), cx);
+                }
+            }
+        } else {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
             let task = cx.spawn(async |w, cx| {
@@ -132,3 +173,371 @@ impl Workspace {
         }
     }
 }
+
+type DependencyRun = Shared<Task<Result<(), SharedString>>>;
+
+/// Runs the dependencies of a task, and then the task itself if they all succeeded.
+fn run_with_dependencies(
+    task_with_dependencies: TaskWithDependencies,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<()> {
+    let TaskWithDependencies {
+        task, dependencies, ..
+    } = task_with_dependencies;
+    let dependencies = run_dependencies(
+        dependencies,
+        task.original_task().depends_order,
+        &mut HashMap::default(),
+        window,
+        cx,
+    );
+    cx.spawn_in(window, async move |workspace, cx| {
+        if let Err(e) = future::try_join_all(dependencies).await {
+            log::debug!("Task {:?} was not run: {e}", task.resolved_label);
+            _ = workspace.update(cx, |workspace, cx| {
+                let id = NotificationId::unique::<ResolvedTask>();
+                workspace.show_toast(
+                    Toast::new(
+                        id,
+                        format!("Task {:?} was not run: {e}", task.resolved_label),
+                    ),
+                    cx,
+                );
+            });
+            return;
+        }
+        if task.is_compound() {
+            return;
+        }
+
+        let Ok(task_status) = workspace.update_in(cx, |workspace, window, cx| {
+            workspace.spawn_in_terminal(task.resolved, window, cx)
+        }) else {
+            return;
+        };
+        match task_status.await {
+            Some(Ok(status)) => {
+                if status.success() {
+                    log::debug!("Task spawn succeeded");
+                } else {
+                    log::debug!("Task spawn failed, code: {:?}", status.code());
+                }
+            }
+            Some(Err(e)) => {
+                log::error!("Task spawn failed: {e:#}");
+                _ = workspace.update(cx, |workspace, cx| {
+                    let id = NotificationId::unique::<ResolvedTask>();
+                    workspace.show_toast(Toast::new(id, format!("Task spawn failed: {e}")), cx);
+                })
+            }
+            None => log::debug!("Task spawn got cancelled"),
+        }
+    })
+}
+
+/// Starts the given dependencies, either all at once or each one after the previous one, reusing
+/// the runs of the dependencies shared with other tasks.
+fn run_dependencies(
+    dependencies: Vec<TaskWithDependencies>,
+    order: DependsOrder,
+    running: &mut HashMap<TaskId, DependencyRun>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Vec<DependencyRun> {
+    let mut runs = Vec::<DependencyRun>::with_capacity(dependencies.len());
+    for dependency in dependencies {
+        let run = if let Some(run) = running.get(&dependency.task.id) {
+            run.clone()
+        } else {
+            let previous_run = match order {
+                DependsOrder::Parallel => None,
+                DependsOrder::Sequence => runs.last().cloned(),
+            };
+            let id = dependency.task.id.clone();
+            let run = run_dependency(dependency, previous_run, running, window, cx);
+            running.insert(id, run.clone());
+            run
+        };
+        runs.push(run);
+    }
+    runs
+}
+
+/// Runs a dependency in a terminal once its own dependencies, and the run it goes after, succeeded.
+/// The dependency succeeds if its command exits successfully.
+fn run_dependency(
+    task_with_dependencies: TaskWithDependencies,
+    previous_run: Option<DependencyRun>,
+    running: &mut HashMap<TaskId, DependencyRun>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> DependencyRun {
+    let TaskWithDependencies {
+        task, dependencies, ..
+    } = task_with_dependencies;
+    let dependencies = run_dependencies(
+        dependencies,
+        task.original_task().depends_order,
+        running,
+        window,
+        cx,
+    );
+    cx.spawn_in(window, async move |workspace, cx| {
+        if let Some(previous_run) = previous_run {
+            previous_run.await?;
+        }
+        future::try_join_all(dependencies).await?;
+        if task.is_compound() {
+            return Ok(());
+        }
+
+        let label = task.resolved_label.clone();
+        let task_status = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.spawn_in_terminal(task.resolved, window, cx)
+            })
+            .map_err(|_| SharedString::from(format!("task {label:?} could not be started")))?;
+        match task_status.await {
+            Some(Ok(status)) if status.success() => Ok(()),
+            Some(Ok(status)) => Err(match status.code() {
+                Some(code) => format!("task {label:?} exited with code {code}").into(),
+                None => format!("task {label:?} was terminated").into(),
+            }),
+            Some(Err(e)) => Err(format!("task {label:?} failed to spawn: {e}").into()),
+            None => Err(format!("task {label:?} was cancelled").into()),
+        }
+    })
+    .shared()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fs::FakeFs;
+    use futures::channel::oneshot;
+    use gpui::{App, TestAppContext, VisualTestContext};
+    use parking_lot::Mutex;
+    use project::Project;
+    use task::TaskTemplate;
+
+    use super::*;
+    use crate::{TerminalProvider, tests::init_test};
+
+    /// Records the tasks spawned, and lets the test decide when and how each of them exits.
+    #[derive(Clone, Default)]
+    struct FakeTerminalProvider {
+        runs: Arc<Mutex<Vec<(String, Option<oneshot::Sender<ExitStatus>>)>>>,
+    }
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let (exit_tx, exit_rx) = oneshot::channel();
+            self.runs.lock().push((task.label, Some(exit_tx)));
+            cx.background_spawn(async move { exit_rx.await.ok().map(Ok) })
+        }
+    }
+
+    impl FakeTerminalProvider {
+        fn spawned(&self) -> Vec<String> {
+            self.runs
+                .lock()
+                .iter()
+                .map(|(label, _)| label.clone())
+                .collect()
+        }
+
+        fn exit(&self, label: &str, code: i32) {
+            let exit_tx = self
+                .runs
+                .lock()
+                .iter_mut()
+                .find(|(spawned_label, exit_tx)| spawned_label == label && exit_tx.is_some())
+                .and_then(|(_, exit_tx)| exit_tx.take())
+                .unwrap_or_else(|| panic!("task {label:?} is not running"));
+            exit_tx.send(exit_status(code)).unwrap();
+        }
+    }
+
+    fn exit_status(code: i32) -> ExitStatus {
+        #[cfg(unix)]
+        {
+            std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::process::ExitStatusExt::from_raw(code as u32)
+        }
+    }
+
+    fn task(
+        label: &str,
+        command: &str,
+        depends_order: DependsOrder,
+        dependencies: Vec<TaskWithDependencies>,
+    ) -> TaskWithDependencies {
+        let template = TaskTemplate {
+            label: label.to_string(),
+            command: command.to_string(),
+            depends_on: dependencies
+                .iter()
+                .map(|dependency| dependency.task.original_task().label.clone())
+                .collect(),
+            depends_order,
+            ..TaskTemplate::default()
+        };
+        TaskWithDependencies {
+            task_source_kind: TaskSourceKind::UserInput,
+            task: template
+                .resolve_task("test", &TaskContext::default())
+                .unwrap(),
+            dependencies,
+        }
+    }
+
+    async fn workspace_with_fake_terminal(
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<Workspace>,
+        &mut VisualTestContext,
+        FakeTerminalProvider,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let terminal_provider = FakeTerminalProvider::default();
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(terminal_provider.clone());
+        });
+        (workspace, cx, terminal_provider)
+    }
+
+    fn run(
+        workspace: &Entity<Workspace>,
+        task_with_dependencies: TaskWithDependencies,
+        cx: &mut VisualTestContext,
+    ) {
+        workspace.update_in(cx, |workspace, window, cx| {
+            let task = run_with_dependencies(task_with_dependencies, window, cx);
+            workspace.scheduled_tasks.push(task);
+        });
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_dependencies_in_sequence(cx: &mut TestAppContext) {
+        let (workspace, cx, terminal) = workspace_with_fake_terminal(cx).await;
+
+        let dependencies = vec![
+            task("first", "echo first", DependsOrder::Parallel, Vec::new()),
+            task("second", "echo second", DependsOrder::Parallel, Vec::new()),
+        ];
+        run(
+            &workspace,
+            task("all", "echo all", DependsOrder::Sequence, dependencies),
+            cx,
+        );
+        assert_eq!(terminal.spawned(), ["first"]);
+
+        terminal.exit("first", 0);
+        cx.run_until_parked();
+        assert_eq!(terminal.spawned(), ["first", "second"]);
+
+        terminal.exit("second", 0);
+        cx.run_until_parked();
+        assert_eq!(terminal.spawned(), ["first", "second", "all"]);
+    }
+
+    #[gpui::test]
+    async fn test_failed_dependency_stops_dependent_tasks(cx: &mut TestAppContext) {
+        let (workspace, cx, terminal) = workspace_with_fake_terminal(cx).await;
+
+        let dependencies = vec![
+            task("first", "echo first", DependsOrder::Parallel, Vec::new()),
+            task("second", "echo second", DependsOrder::Parallel, Vec::new()),
+        ];
+        run(
+            &workspace,
+            task("all", "echo all", DependsOrder::Sequence, dependencies),
+            cx,
+        );
+        terminal.exit("first", 1);
+        cx.run_until_parked();
+        assert_eq!(
+            terminal.spawned(),
+            ["first"],
+            "neither the next dependency nor the task should run after a failure"
+        );
+
+        let dependencies = vec![
+            task("lint", "echo lint", DependsOrder::Parallel, Vec::new()),
+            task("test", "echo test", DependsOrder::Parallel, Vec::new()),
+        ];
+        run(
+            &workspace,
+            task("check", "echo check", DependsOrder::Parallel, dependencies),
+            cx,
+        );
+        let mut spawned = terminal.spawned();
+        spawned.sort();
+        assert_eq!(spawned, ["first", "lint", "test"]);
+        terminal.exit("test", 0);
+        terminal.exit("lint", 2);
+        cx.run_until_parked();
+        assert_eq!(
+            terminal.spawned().len(),
+            3,
+            "the task should not run when one of its dependencies failed"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_shared_dependency_runs_once(cx: &mut TestAppContext) {
+        let (workspace, cx, terminal) = workspace_with_fake_terminal(cx).await;
+
+        let setup = || task("setup", "echo setup", DependsOrder::Parallel, Vec::new());
+        let dependencies = vec![
+            task(
+                "client",
+                "echo client",
+                DependsOrder::Parallel,
+                vec![setup()],
+            ),
+            task(
+                "server",
+                "echo server",
+                DependsOrder::Parallel,
+                vec![setup()],
+            ),
+        ];
+        run(
+            &workspace,
+            task("all", "", DependsOrder::Parallel, dependencies),
+            cx,
+        );
+        assert_eq!(terminal.spawned(), ["setup"]);
+
+        terminal.exit("setup", 0);
+        cx.run_until_parked();
+        let mut spawned = terminal.spawned();
+        spawned.sort();
+        assert_eq!(spawned, ["client", "server", "setup"]);
+
+        terminal.exit("client", 0);
+        terminal.exit("server", 0);
+        cx.run_until_parked();
+        assert_eq!(
+            terminal.spawned().len(),
+            3,
+            "a task without a command only runs its dependencies"
+        );
+    }
+}
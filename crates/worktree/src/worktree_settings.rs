@@ -14,6 +14,7 @@ pub struct WorktreeSettings {
     pub file_scan_inclusions: PathMatcher,
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    pub search_index: bool,
 }
 
 impl WorktreeSettings {
@@ -62,6 +63,7 @@ impl Settings for WorktreeSettings {
             private_files: path_matchers(private_files, "private_files")
                 .log_err()
                 .unwrap_or_default(),
+            search_index: worktree.search_index.unwrap(),
         }
     }
 
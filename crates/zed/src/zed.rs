@@ -33,6 +33,7 @@ use gpui::{
     TitlebarOptions, UpdateGlobal, Window, WindowKind, WindowOptions, actions, image_cache, point,
     px, retain_all,
 };
+use hierarchy_panel::HierarchyPanel;
 use image_viewer::ImageInfo;
 use language::Capability;
 use language_onboarding::BasedPyrightBanner;
@@ -72,6 +73,7 @@ use std::{
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use theme::{ActiveTheme, GlobalTheme, SystemAppearance, ThemeRegistry, ThemeSettings};
 use ui::{PopoverMenuHandle, prelude::*};
+use undo_tree_panel::UndoTreePanel;
 use util::markdown::MarkdownString;
 use util::rel_path::RelPath;
 use util::{ResultExt, asset_str};
@@ -392,6 +394,7 @@ pub fn initialize_workspace(
         let search_button = cx.new(|_| search::search_status_button::SearchButton::new());
         let diagnostic_summary =
             cx.new(|cx| diagnostics::items::DiagnosticIndicator::new(workspace, cx));
+        let bisect_status = cx.new(|cx| git_ui::bisect::BisectStatusItem::new(workspace, cx));
         let activity_indicator = activity_indicator::ActivityIndicator::new(
             workspace,
             workspace.project().read(cx).languages().clone(),
@@ -421,6 +424,7 @@ pub fn initialize_workspace(
             status_bar.add_left_item(lsp_button, window, cx);
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
+            status_bar.add_left_item(bisect_status, window, cx);
             status_bar.add_right_item(edit_prediction_button, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
@@ -569,6 +573,8 @@ fn initialize_panels(
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let hierarchy_panel = HierarchyPanel::load(workspace_handle.clone(), cx.clone());
+        let undo_tree_panel = UndoTreePanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
@@ -582,6 +588,8 @@ fn initialize_panels(
         let (
             project_panel,
             outline_panel,
+            hierarchy_panel,
+            undo_tree_panel,
             terminal_panel,
             git_panel,
             channels_panel,
@@ -590,6 +598,8 @@ fn initialize_panels(
         ) = futures::try_join!(
             project_panel,
             outline_panel,
+            hierarchy_panel,
+            undo_tree_panel,
             git_panel,
             terminal_panel,
             channels_panel,
@@ -600,6 +610,8 @@ fn initialize_panels(
         workspace_handle.update_in(cx, |workspace, window, cx| {
             workspace.add_panel(project_panel, window, cx);
             workspace.add_panel(outline_panel, window, cx);
+            workspace.add_panel(hierarchy_panel, window, cx);
+            workspace.add_panel(undo_tree_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
             workspace.add_panel(git_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
@@ -4551,6 +4563,7 @@ mod tests {
                     | "vim::PushHelixPrevious"
                     | "vim::Number"
                     | "vim::SelectRegister"
+                    | "emacs::DigitArgument"
                     | "git::StageAndNext"
                     | "git::UnstageAndNext"
                     | "terminal::SendText"
@@ -4660,6 +4673,7 @@ mod tests {
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hierarchy_panel",
                 "icon_theme_selector",
                 "journal",
                 "keymap_editor",
@@ -4698,6 +4712,7 @@ mod tests {
                 "theme_selector",
                 "toast",
                 "toolchain",
+                "undo_tree_panel",
                 "variable_list",
                 "vim",
                 "window",
@@ -4839,6 +4854,8 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            hierarchy_panel::init(cx);
+            undo_tree_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
@@ -494,6 +494,31 @@ actions!(
     ]
 );
 
+pub mod container_actions {
+    use gpui::Action;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    /// Opens a project inside a running Docker or Podman container.
+    #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+    #[action(namespace = projects)]
+    #[serde(deny_unknown_fields)]
+    pub struct OpenContainer {
+        #[serde(default)]
+        pub create_new_window: bool,
+    }
+
+    /// Reopens the current project inside its dev container, starting the
+    /// container described by its `devcontainer.json` first.
+    #[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+    #[action(namespace = projects)]
+    #[serde(deny_unknown_fields)]
+    pub struct ReopenInDevContainer {
+        #[serde(default)]
+        pub create_new_window: bool,
+    }
+}
+
 #[cfg(target_os = "windows")]
 pub mod wsl_actions {
     use gpui::Action;